@@ -0,0 +1,84 @@
+#[macro_use]
+extern crate rocket;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rocket::{http::ContentType, local::asynchronous::Client, tokio::runtime::Runtime, Data};
+use rocket_multipart_form_data::{
+    MultipartFormData, MultipartFormDataField, MultipartFormDataOptions,
+};
+
+const FILE_SIZE: usize = 64 * 1024 * 1024;
+
+#[post("/?<read>&<write>", data = "<data>")]
+async fn upload(
+    read: usize,
+    write: usize,
+    content_type: &ContentType,
+    data: Data<'_>,
+) -> &'static str {
+    let options = MultipartFormDataOptions {
+        read_chunk_size: read,
+        write_chunk_size: write,
+        ..MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+            MultipartFormDataField::file("file").size_limit(FILE_SIZE as u64),
+        ])
+    };
+
+    MultipartFormData::parse(content_type, data, options).await.unwrap();
+
+    "ok"
+}
+
+fn body() -> Vec<u8> {
+    let mut body = Vec::with_capacity(FILE_SIZE + 256);
+
+    body.extend_from_slice(
+        b"--BOUNDARY\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a.bin\"\r\n\r\n",
+    );
+    body.extend((0..FILE_SIZE).map(|i| (i % 251) as u8));
+    body.extend_from_slice(b"\r\n--BOUNDARY--\r\n");
+
+    body
+}
+
+fn chunk_size(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+
+    let client =
+        runtime.block_on(Client::untracked(rocket::build().mount("/", routes![upload]))).unwrap();
+
+    let body = body();
+
+    let content_type =
+        ContentType::new("multipart", "form-data").with_params(("boundary", "BOUNDARY"));
+
+    let mut group = c.benchmark_group("chunk_size");
+
+    group.throughput(Throughput::Bytes(FILE_SIZE as u64)).sample_size(10);
+
+    for (read, write) in
+        [(4096, 0), (64 * 1024, 0), (64 * 1024, 256 * 1024), (1024 * 1024, 1024 * 1024)]
+    {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("read {} write {}", read, write)),
+            &(read, write),
+            |b, &(read, write)| {
+                b.to_async(&runtime).iter(|| async {
+                    let response = client
+                        .post(format!("/?read={}&write={}", read, write))
+                        .header(content_type.clone())
+                        .body(&body)
+                        .dispatch()
+                        .await;
+
+                    assert_eq!(Some("ok".to_string()), response.into_string().await);
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, chunk_size);
+criterion_main!(benches);
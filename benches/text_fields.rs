@@ -0,0 +1,92 @@
+#[macro_use]
+extern crate rocket;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rocket::{http::ContentType, local::asynchronous::Client, tokio::runtime::Runtime, Data};
+use rocket_multipart_form_data::{
+    MultipartFormData, MultipartFormDataField, MultipartFormDataOptions,
+};
+
+const TEXT_SIZE: usize = 16 * 1024 * 1024;
+
+#[post("/", data = "<data>")]
+async fn upload(content_type: &ContentType, data: Data<'_>) -> String {
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("text").size_limit(TEXT_SIZE as u64),
+    ]);
+
+    let mut multipart_form_data =
+        MultipartFormData::parse(content_type, data, options).await.unwrap();
+
+    multipart_form_data.remove_one_text("text").unwrap().text.len().to_string()
+}
+
+fn body(with_content_length: bool) -> Vec<u8> {
+    let mut body = Vec::with_capacity(TEXT_SIZE + 256);
+
+    body.extend_from_slice(b"--BOUNDARY\r\nContent-Disposition: form-data; name=\"text\"\r\n");
+
+    if with_content_length {
+        body.extend_from_slice(format!("Content-Length: {}\r\n", TEXT_SIZE).as_bytes());
+    }
+
+    body.extend_from_slice(b"\r\n");
+
+    // Mix ASCII and multi-byte characters, so that some of them are split between chunks
+    let pattern = "Lorem ipsum dolor sit amet, 天地玄黃，宇宙洪荒。🦀\n";
+
+    let mut text = String::with_capacity(TEXT_SIZE);
+
+    for c in pattern.chars().cycle() {
+        if text.len() + c.len_utf8() > TEXT_SIZE {
+            break;
+        }
+
+        text.push(c);
+    }
+
+    while text.len() < TEXT_SIZE {
+        text.push(' ');
+    }
+
+    body.extend_from_slice(text.as_bytes());
+    body.extend_from_slice(b"\r\n--BOUNDARY--\r\n");
+
+    body
+}
+
+fn text_fields(c: &mut Criterion) {
+    let runtime = Runtime::new().unwrap();
+
+    let client =
+        runtime.block_on(Client::untracked(rocket::build().mount("/", routes![upload]))).unwrap();
+
+    let content_type =
+        ContentType::new("multipart", "form-data").with_params(("boundary", "BOUNDARY"));
+
+    let mut group = c.benchmark_group("text_fields");
+
+    group.throughput(Throughput::Bytes(TEXT_SIZE as u64)).sample_size(10);
+
+    for with_content_length in [false, true] {
+        let body = body(with_content_length);
+
+        group.bench_with_input(
+            BenchmarkId::new("Content-Length", with_content_length),
+            &body,
+            |b, body| {
+                b.to_async(&runtime).iter(|| async {
+                    let response =
+                        client.post("/").header(content_type.clone()).body(body).dispatch().await;
+
+                    assert_eq!(Some(TEXT_SIZE.to_string()), response.into_string().await);
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, text_fields);
+criterion_main!(benches);
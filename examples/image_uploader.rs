@@ -4,21 +4,27 @@ extern crate rocket_include_static_resources;
 #[macro_use]
 extern crate rocket;
 
-use rocket::{http::ContentType, Data};
+use rocket::{
+    data::{Limits, ToByteUnit},
+    http::ContentType,
+    Data,
+};
 use rocket_multipart_form_data::{
-    mime, multer, MultipartFormData, MultipartFormDataError, MultipartFormDataField,
-    MultipartFormDataOptions,
+    mime, MultipartFormData, MultipartFormDataError, MultipartFormDataField,
+    MultipartFormDataOptions, RawFieldResponse,
 };
-use rocket_raw_response::RawResponse;
 
 static_response_handler! {
     "/" => index => "html-image-uploader",
 }
 
 #[post("/upload", data = "<data>")]
-async fn upload(content_type: &ContentType, data: Data<'_>) -> Result<RawResponse, &'static str> {
+async fn upload(
+    content_type: &ContentType,
+    limits: &Limits,
+    data: Data<'_>,
+) -> Result<RawFieldResponse, &'static str> {
     let options = MultipartFormDataOptions {
-        max_data_bytes: 33 * 1024 * 1024,
         allowed_fields: vec![MultipartFormDataField::raw("image")
             .size_limit(32 * 1024 * 1024)
             .content_type_by_string(Some(mime::IMAGE_STAR))
@@ -26,28 +32,29 @@ async fn upload(content_type: &ContentType, data: Data<'_>) -> Result<RawRespons
         ..MultipartFormDataOptions::default()
     };
 
-    let mut multipart_form_data = match MultipartFormData::parse(content_type, data, options).await
+    let mut multipart_form_data = match MultipartFormData::parse_limited(
+        content_type,
+        data,
+        options,
+        limits,
+        Some(33.mebibytes()),
+    )
+    .await
     {
         Ok(multipart_form_data) => multipart_form_data,
-        Err(err) => {
-            match err {
-                MultipartFormDataError::DataTooLargeError(_) => {
-                    return Err("The file is too large.");
-                },
-                MultipartFormDataError::DataTypeError(_) => {
-                    return Err("The file is not an image.");
-                },
-                MultipartFormDataError::MulterError(multer::Error::IncompleteFieldData {
-                    ..
-                })
-                | MultipartFormDataError::MulterError(multer::Error::IncompleteHeaders {
-                    ..
-                }) => {
-                    // may happen when we set the max_data_bytes limitation
-                    return Err("The request body seems too large.");
-                },
-                _ => panic!("{:?}", err),
-            }
+        Err(err) => match err {
+            MultipartFormDataError::DataTooLargeError(_) => {
+                return Err("The file is too large.");
+            },
+            MultipartFormDataError::DataTypeError(_) => {
+                return Err("The file is not an image.");
+            },
+            MultipartFormDataError::DataLimitExceededError {
+                ..
+            } => {
+                return Err("The request body is too large.");
+            },
+            _ => panic!("{:?}", err),
         },
     };
 
@@ -57,11 +64,17 @@ async fn upload(content_type: &ContentType, data: Data<'_>) -> Result<RawRespons
         Some(mut image) => {
             let raw = image.remove(0);
 
-            let content_type = raw.content_type;
-            let file_name = raw.file_name.unwrap_or_else(|| "Image".to_string());
-            let data = raw.raw;
+            // An SVG image can run scripts when it is shown inline, so only raster images are allowed
+            let is_raster = raw.content_type.as_ref().map_or(false, |content_type| {
+                [mime::IMAGE_PNG, mime::IMAGE_JPEG, mime::IMAGE_GIF].contains(content_type)
+                    || content_type.essence_str() == "image/webp"
+            });
+
+            if !is_raster {
+                return Err("The image type is not supported.");
+            }
 
-            Ok(RawResponse::from_vec(data, Some(file_name), content_type))
+            Ok(raw.into_response_body().inline())
         },
         None => Err("Please input a file."),
     }
@@ -0,0 +1,29 @@
+use std::{env, path::Path, process};
+
+use rocket_multipart_form_data::inspect;
+
+#[rocket::main]
+async fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    if args.len() < 3 {
+        eprintln!("Usage: {} <body file> <boundary> [output directory]", args[0]);
+
+        process::exit(1);
+    }
+
+    let extract_to = args.get(3).map(Path::new);
+
+    match inspect::read_parts(&args[1], args[2].as_str(), extract_to).await {
+        Ok(parts) => {
+            for part in parts {
+                println!("{}", part);
+            }
+        },
+        Err(err) => {
+            eprintln!("{}", err);
+
+            process::exit(1);
+        },
+    }
+}
@@ -0,0 +1,444 @@
+/// What to do with active content, such as scripts, event handlers and references to external resources, in the markup (SVG, HTML or XML) uploaded to a field. If it is not `Allow`, the data of a file field is buffered in memory to be checked before being written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ActiveContentPolicy {
+    /// Do not check the data.
+    #[default]
+    Allow,
+    /// Fail with an `ActiveContentError`.
+    Reject,
+    /// Remove the active content. A `ParseWarning::ActiveContentStripped` warning is recorded.
+    Strip,
+}
+
+/// Elements which can run scripts or embed other documents.
+const ACTIVE_ELEMENTS: [&str; 7] =
+    ["script", "foreignobject", "iframe", "object", "embed", "handler", "listener"];
+
+/// SMIL elements which change the value of another attribute.
+const ANIMATION_ELEMENTS: [&str; 5] =
+    ["animate", "set", "animatecolor", "animatemotion", "animatetransform"];
+
+/// Attributes which can be animated by the SMIL elements. Animating any other one, such as `href` with a `javascript:` URL, makes the animation element active.
+const ANIMATABLE_ATTRIBUTES: [&str; 38] = [
+    "x",
+    "y",
+    "x1",
+    "y1",
+    "x2",
+    "y2",
+    "cx",
+    "cy",
+    "r",
+    "rx",
+    "ry",
+    "dx",
+    "dy",
+    "d",
+    "points",
+    "width",
+    "height",
+    "viewbox",
+    "transform",
+    "rotate",
+    "offset",
+    "opacity",
+    "fill",
+    "fill-opacity",
+    "stroke",
+    "stroke-opacity",
+    "stroke-width",
+    "stroke-dasharray",
+    "stroke-dashoffset",
+    "stop-color",
+    "stop-opacity",
+    "color",
+    "visibility",
+    "display",
+    "font-size",
+    "font-weight",
+    "letter-spacing",
+    "pathlength",
+];
+
+/// Attributes whose values are URLs. Namespace prefixes, such as `xlink:`, are ignored.
+const URL_ATTRIBUTES: [&str; 5] = ["href", "src", "action", "formaction", "data"];
+
+/// Embedded images which are allowed in URL attributes.
+const SAFE_DATA_URLS: [&str; 4] =
+    ["data:image/png", "data:image/jpeg", "data:image/gif", "data:image/webp"];
+
+/// Check whether the markup `data` contains active content.
+#[inline]
+pub(crate) fn has_active_content(data: &[u8]) -> bool {
+    scan(data, None)
+}
+
+/// Remove active content from the markup `data`. Return whether anything has been removed.
+pub(crate) fn strip_active_content(data: &mut Vec<u8>) -> bool {
+    let mut output = Vec::with_capacity(data.len());
+
+    let found = scan(data, Some(&mut output));
+
+    if found {
+        *data = output;
+    }
+
+    found
+}
+
+/// Scan the markup `data`. If `output` is given, the data without active content is written to it. Return whether active content is found.
+fn scan(data: &[u8], mut output: Option<&mut Vec<u8>>) -> bool {
+    let mut emit = |bytes: &[u8]| {
+        if let Some(output) = output.as_mut() {
+            output.extend_from_slice(bytes);
+        }
+    };
+
+    let mut found = false;
+    let mut i = 0;
+
+    while i < data.len() {
+        let rest = &data[i..];
+
+        if rest[0] != b'<' {
+            let end = find(data, i, b"<").unwrap_or(data.len());
+
+            emit(&data[i..end]);
+            i = end;
+
+            continue;
+        }
+
+        if rest.starts_with(b"<!--") {
+            let end = find(data, i + 4, b"-->").map_or(data.len(), |e| e + 3);
+
+            emit(&data[i..end]);
+            i = end;
+
+            continue;
+        }
+
+        if starts_with_ignore_case(rest, b"<![cdata[") {
+            let end = find(data, i + 9, b"]]>").map_or(data.len(), |e| e + 3);
+
+            emit(&data[i..end]);
+            i = end;
+
+            continue;
+        }
+
+        if starts_with_ignore_case(rest, b"<?xml-stylesheet") {
+            // A style sheet which is loaded from another resource
+            found = true;
+            i = find(data, i, b"?>").map_or(data.len(), |e| e + 2);
+
+            continue;
+        }
+
+        if starts_with_ignore_case(rest, b"<!doctype") {
+            // An internal subset can declare external entities
+            let subset = find(data, i, b"[").filter(|&s| s < find_tag_end(data, i));
+
+            let end = match subset {
+                Some(s) => find(data, s, b"]").map_or(data.len(), |e| find_tag_end(data, e)),
+                None => find_tag_end(data, i),
+            };
+
+            if subset.is_some() {
+                found = true;
+            } else {
+                emit(&data[i..end]);
+            }
+
+            i = end;
+
+            continue;
+        }
+
+        let closing = rest.get(1) == Some(&b'/');
+        let name_start = i + 1 + closing as usize;
+        let name_end = skip_name(data, name_start);
+
+        if name_end == name_start {
+            // Not a tag, such as `<?xml ... ?>`
+            emit(&data[i..i + 1]);
+            i += 1;
+
+            continue;
+        }
+
+        let name = local_name(&data[name_start..name_end]);
+        let tag_end = find_tag_end(data, name_end);
+
+        if ACTIVE_ELEMENTS.contains(&name.as_str()) {
+            found = true;
+            i = skip_element(data, closing, tag_end, &name);
+
+            continue;
+        }
+
+        if closing {
+            emit(&data[i..tag_end]);
+            i = tag_end;
+
+            continue;
+        }
+
+        if name == "style" && !data[..tag_end].ends_with(b"/>") {
+            let (content_end, end) =
+                find_closing_tag(data, tag_end, &name).unwrap_or((data.len(), data.len()));
+
+            if is_active_css(&data[tag_end..content_end]) {
+                found = true;
+                i = end;
+
+                continue;
+            }
+        }
+
+        let (attributes, rest) = parse_attributes(data, name_end, tag_end);
+
+        if ANIMATION_ELEMENTS.contains(&name.as_str())
+            && attributes.iter().any(|attribute| {
+                attribute.name == "attributename"
+                    && !ANIMATABLE_ATTRIBUTES.contains(
+                        &String::from_utf8_lossy(attribute.value)
+                            .trim()
+                            .to_ascii_lowercase()
+                            .as_str(),
+                    )
+            })
+        {
+            found = true;
+            i = skip_element(data, closing, tag_end, &name);
+
+            continue;
+        }
+
+        emit(&data[i..name_end]);
+
+        for attribute in attributes {
+            if is_active_attribute(&attribute.name, attribute.value) {
+                found = true;
+            } else {
+                emit(&data[attribute.start..attribute.end]);
+            }
+        }
+
+        emit(&data[rest..tag_end]);
+
+        i = tag_end;
+    }
+
+    found
+}
+
+/// An attribute of a tag. `start` includes the separators before the attribute.
+struct Attribute<'a> {
+    /// The lowercased name. It is empty for a byte which cannot begin an attribute, so that the byte is kept as it is.
+    name:  String,
+    value: &'a [u8],
+    start: usize,
+    end:   usize,
+}
+
+/// Parse the attributes of a tag between its name and `tag_end`. Return them with the index of the rest of the tag, which only contains separators and `>`.
+fn parse_attributes(data: &[u8], from: usize, tag_end: usize) -> (Vec<Attribute<'_>>, usize) {
+    let mut attributes = Vec::new();
+    let mut j = from;
+
+    loop {
+        let attribute_start = j;
+
+        // Browsers treat `/` between attributes like whitespace, such as `<svg/onload=...>`
+        while j < tag_end && (data[j].is_ascii_whitespace() || data[j] == b'/') {
+            j += 1;
+        }
+
+        if j >= tag_end || (data[j] == b'>' && j + 1 == tag_end) {
+            return (attributes, attribute_start);
+        }
+
+        let attribute_name_end = skip_name(data, j);
+
+        if attribute_name_end == j {
+            // Quoted bytes are skipped together, as `find_tag_end` does
+            let end = match data[j] {
+                quote @ (b'"' | b'\'') => find(data, j + 1, &[quote]).map_or(data.len(), |e| e + 1),
+                _ => j + 1,
+            };
+
+            attributes.push(Attribute {
+                name: String::new(),
+                value: b"",
+                start: attribute_start,
+                end,
+            });
+
+            j = end;
+
+            continue;
+        }
+
+        let name = String::from_utf8_lossy(&data[j..attribute_name_end]).to_ascii_lowercase();
+
+        j = attribute_name_end;
+
+        while j < tag_end && data[j].is_ascii_whitespace() {
+            j += 1;
+        }
+
+        let mut value: &[u8] = b"";
+
+        if j < tag_end && data[j] == b'=' {
+            j += 1;
+
+            while j < tag_end && data[j].is_ascii_whitespace() {
+                j += 1;
+            }
+
+            match data.get(j) {
+                Some(&quote) if quote == b'"' || quote == b'\'' => {
+                    let value_end = find(data, j + 1, &[quote]).unwrap_or(data.len());
+
+                    value = &data[j + 1..value_end];
+                    j = (value_end + 1).min(data.len());
+                },
+                _ => {
+                    let value_start = j;
+
+                    while j < tag_end
+                        && !data[j].is_ascii_whitespace()
+                        && data[j] != b'>'
+                        && data[j] != b'/'
+                    {
+                        j += 1;
+                    }
+
+                    value = &data[value_start..j];
+                },
+            }
+        } else {
+            // An attribute without a value, so the whitespace belongs to the next one
+            j = attribute_name_end;
+        }
+
+        attributes.push(Attribute {
+            name,
+            value,
+            start: attribute_start,
+            end: j,
+        });
+    }
+}
+
+/// Skip an active element from its tag which ends at `tag_end`, including its content. Return the index after it.
+#[inline]
+fn skip_element(data: &[u8], closing: bool, tag_end: usize, name: &str) -> usize {
+    if closing || data[..tag_end].ends_with(b"/>") {
+        tag_end
+    } else {
+        find_closing_tag(data, tag_end, name).map_or(data.len(), |(_, end)| end)
+    }
+}
+
+fn is_active_attribute(name: &str, value: &[u8]) -> bool {
+    let local = name.rsplit(':').next().unwrap_or(name);
+
+    if local.starts_with("on") {
+        return true;
+    }
+
+    let value = String::from_utf8_lossy(value).trim().to_ascii_lowercase();
+
+    if URL_ATTRIBUTES.contains(&local) {
+        return !value.starts_with('#') && !SAFE_DATA_URLS.iter().any(|u| value.starts_with(u));
+    }
+
+    if local == "style" {
+        return is_active_css(value.as_bytes());
+    }
+
+    false
+}
+
+fn is_active_css(css: &[u8]) -> bool {
+    let css = String::from_utf8_lossy(css).to_ascii_lowercase();
+
+    if css.contains("@import") || css.contains("javascript:") || css.contains("expression(") {
+        return true;
+    }
+
+    css.match_indices("url(").any(|(i, _)| {
+        let target =
+            css[i + 4..].trim_start_matches(|c: char| c.is_whitespace() || c == '"' || c == '\'');
+
+        !target.starts_with('#') && !SAFE_DATA_URLS.iter().any(|u| target.starts_with(u))
+    })
+}
+
+/// Find the end of the closing tag of `name` after `from`. Return the index of the closing tag and the index after it.
+fn find_closing_tag(data: &[u8], from: usize, name: &str) -> Option<(usize, usize)> {
+    let mut i = from;
+
+    while let Some(start) = find(data, i, b"</") {
+        let name_end = skip_name(data, start + 2);
+
+        if local_name(&data[start + 2..name_end]) == name {
+            return Some((start, find_tag_end(data, name_end)));
+        }
+
+        i = start + 2;
+    }
+
+    None
+}
+
+/// Find the index after the `>` which ends the tag, skipping quoted attribute values.
+fn find_tag_end(data: &[u8], from: usize) -> usize {
+    let mut quote = None;
+
+    for (i, &b) in data.iter().enumerate().skip(from) {
+        match quote {
+            Some(q) if b == q => quote = None,
+            Some(_) => (),
+            None if b == b'"' || b == b'\'' => quote = Some(b),
+            None if b == b'>' => return i + 1,
+            None => (),
+        }
+    }
+
+    data.len()
+}
+
+#[inline]
+fn skip_name(data: &[u8], from: usize) -> usize {
+    let mut i = from;
+
+    while i < data.len() && (data[i].is_ascii_alphanumeric() || b":-_.".contains(&data[i])) {
+        i += 1;
+    }
+
+    i
+}
+
+/// Lowercase a tag name and remove its namespace prefix.
+#[inline]
+fn local_name(name: &[u8]) -> String {
+    let name = String::from_utf8_lossy(name).to_ascii_lowercase();
+
+    match name.rsplit_once(':') {
+        Some((_, local)) => local.to_string(),
+        None => name,
+    }
+}
+
+#[inline]
+fn find(data: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    data.get(from..)?.windows(needle.len()).position(|w| w == needle).map(|p| p + from)
+}
+
+#[inline]
+fn starts_with_ignore_case(data: &[u8], prefix: &[u8]) -> bool {
+    data.len() >= prefix.len() && data[..prefix.len()].eq_ignore_ascii_case(prefix)
+}
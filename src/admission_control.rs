@@ -0,0 +1,96 @@
+use std::{
+    fmt::{self, Debug, Display, Formatter},
+    sync::Arc,
+};
+
+use rocket::http::Status;
+
+use crate::{BoxFuture, MultipartFormDataField};
+
+/// Why an upload is refused by an `AdmissionControl`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum AdmissionDenied {
+    /// The client is not allowed to upload, which should be reported with `403 Forbidden`.
+    Forbidden(String),
+    /// The client has sent too many requests or bytes, which should be reported with `429 Too Many Requests`.
+    TooManyRequests(String),
+}
+
+impl AdmissionDenied {
+    /// The reason of the refusal.
+    #[inline]
+    pub fn reason(&self) -> &str {
+        match self {
+            AdmissionDenied::Forbidden(reason) | AdmissionDenied::TooManyRequests(reason) => reason,
+        }
+    }
+
+    /// The HTTP status which should be responded.
+    #[inline]
+    pub fn status(&self) -> Status {
+        match self {
+            AdmissionDenied::Forbidden(_) => Status::Forbidden,
+            AdmissionDenied::TooManyRequests(_) => Status::TooManyRequests,
+        }
+    }
+}
+
+impl Display for AdmissionDenied {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str(self.reason())
+    }
+}
+
+/// What an `AdmissionControl` knows about an upload before its body is read.
+#[derive(Debug, Clone, Copy)]
+pub struct AdmissionRequest<'a> {
+    /// The allowed fields.
+    pub fields:         &'a [MultipartFormDataField<'a>],
+    /// The length of the HTTP body, if it is known. It is only known when parsing a request of hyper.
+    pub content_length: Option<u64>,
+}
+
+/// A hook of rate limiters and abuse detection systems, which can refuse an upload before its body is read, or abort it while it is being read.
+pub trait AdmissionControl: Send + Sync {
+    /// Decide whether to accept an upload. It is called once before the body is read.
+    fn admit<'a>(
+        &'a self,
+        request: AdmissionRequest<'a>,
+    ) -> BoxFuture<'a, Result<(), AdmissionDenied>>;
+
+    /// Decide whether to continue reading an upload after `bytes_read` bytes of the HTTP body have been read. It is called every `check_interval` bytes, if it is set. The default implementation accepts everything.
+    fn check(&self, bytes_read: u64) -> BoxFuture<'_, Result<(), AdmissionDenied>> {
+        let _ = bytes_read;
+
+        Box::pin(async { Ok(()) })
+    }
+}
+
+impl Debug for dyn AdmissionControl {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("AdmissionControl")
+    }
+}
+
+/// An admission control which is consulted while parsing.
+#[derive(Debug, Clone)]
+pub struct Admission {
+    /// The admission control.
+    pub control:        Arc<dyn AdmissionControl>,
+    /// If it is set, the `check` method of the admission control is called every time this number of bytes of data has been read. It must be bigger than `0`.
+    pub check_interval: Option<u64>,
+}
+
+impl Admission {
+    /// Create an `Admission` instance which is only consulted before the body is read.
+    #[inline]
+    pub fn new(control: Arc<dyn AdmissionControl>) -> Admission {
+        Admission {
+            control,
+            check_interval: None,
+        }
+    }
+}
@@ -0,0 +1,679 @@
+use std::fmt::{self, Display, Formatter};
+
+const ZIP_LOCAL_FILE_HEADER: u32 = 0x0403_4B50;
+const ZIP_CENTRAL_DIRECTORY_HEADER: u32 = 0x0201_4B50;
+const ZIP_END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4B50;
+const ZIP64_END_OF_CENTRAL_DIRECTORY: u32 = 0x0606_4B50;
+const ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR: u32 = 0x0706_4B50;
+const ZIP_DIGITAL_SIGNATURE: u32 = 0x0505_4B50;
+const ZIP_DATA_DESCRIPTOR: &[u8] = b"PK\x07\x08";
+
+const TAR_BLOCK_SIZE: usize = 512;
+/// The max size of a GNU long name or a PAX extended header which is buffered.
+const TAR_MAX_EXTENSION_SIZE: u64 = 64 * 1024;
+
+/// Limits for ZIP and tar archives uploaded to a file field. The archive is inspected while it is being received, so that zip bombs and path traversal (zip slip) attacks are rejected before the handler opens it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArchiveLimits {
+    /// The max number of entries. The default value is `10000`.
+    pub max_entries:            u64,
+    /// The max total number of bytes of the entries after being decompressed. The default value is 1 GiB.
+    pub max_uncompressed_bytes: u64,
+    /// The max ratio of the uncompressed size to the compressed size of an entry. The default value is `100`.
+    pub max_compression_ratio:  u64,
+}
+
+impl Default for ArchiveLimits {
+    #[inline]
+    fn default() -> Self {
+        ArchiveLimits {
+            max_entries:            10000,
+            max_uncompressed_bytes: 1024 * 1024 * 1024,
+            max_compression_ratio:  100,
+        }
+    }
+}
+
+/// The reason why an uploaded archive is rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ArchiveViolation {
+    /// The data is not a ZIP or tar archive, or it is broken.
+    Malformed,
+    /// The archive has more entries than `max_entries`.
+    TooManyEntries,
+    /// The entries are larger than `max_uncompressed_bytes` in total.
+    TooLarge,
+    /// An entry is compressed more than `max_compression_ratio`.
+    CompressionRatioTooHigh(String),
+    /// The name of an entry (or the target of a link) is absolute or contains `..`.
+    PathTraversal(String),
+    /// The central directory of a ZIP archive does not match its local file headers, so that extractors may see different entries.
+    CentralDirectoryMismatch(String),
+}
+
+impl Display for ArchiveViolation {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        match self {
+            ArchiveViolation::Malformed => f.write_str("The archive is malformed."),
+            ArchiveViolation::TooManyEntries => f.write_str("The archive has too many entries."),
+            ArchiveViolation::TooLarge => f.write_str("The archive is too large when extracted."),
+            ArchiveViolation::CompressionRatioTooHigh(name) => f.write_fmt(format_args!(
+                "The entry `{}` of the archive is compressed too much.",
+                name
+            )),
+            ArchiveViolation::PathTraversal(name) => f.write_fmt(format_args!(
+                "The entry `{}` of the archive points outside of it.",
+                name
+            )),
+            ArchiveViolation::CentralDirectoryMismatch(name) => f.write_fmt(format_args!(
+                "The entry `{}` of the archive does not match its central directory.",
+                name
+            )),
+        }
+    }
+}
+
+enum State {
+    /// Read enough bytes to recognize the format.
+    Detecting,
+    ZipHeader,
+    /// Look for the data descriptor of an entry whose sizes are written after its data. `consumed` is the number of bytes of its data which have been scanned.
+    ZipDescriptor {
+        name:     String,
+        consumed: u64,
+    },
+    /// Read the records of the central directory of a ZIP archive until the end of central directory record.
+    ZipCentralDirectory,
+    TarHeader,
+    /// Read the data of a GNU long name (`L`) or a PAX extended header (`x`) entry.
+    TarExtension {
+        pax:       bool,
+        remaining: usize,
+        padding:   u64,
+    },
+    /// The rest of the data is not inspected, such as the comment of a ZIP archive.
+    Done,
+}
+
+/// Inspect an archive chunk by chunk.
+pub(crate) struct ArchiveInspector {
+    limits:            ArchiveLimits,
+    state:             State,
+    /// Bytes which cannot be parsed yet.
+    buffer:            Vec<u8>,
+    /// The number of bytes to skip, such as the data of the current entry.
+    skip:              u64,
+    entries:           u64,
+    uncompressed:      u64,
+    /// The names and sizes of the ZIP entries read from the local file headers, in order.
+    zip_entries:       Vec<(String, u64, u64)>,
+    /// The number of the records of the central directory which have been read.
+    zip_directory:     usize,
+    /// The name of the next tar entry given by a GNU long name or a PAX extended header.
+    tar_override_name: Option<String>,
+    tar_extension:     Vec<u8>,
+}
+
+impl ArchiveInspector {
+    #[inline]
+    pub(crate) fn new(limits: ArchiveLimits) -> ArchiveInspector {
+        ArchiveInspector {
+            limits,
+            state: State::Detecting,
+            buffer: Vec::new(),
+            skip: 0,
+            entries: 0,
+            uncompressed: 0,
+            zip_entries: Vec::new(),
+            zip_directory: 0,
+            tar_override_name: None,
+            tar_extension: Vec::new(),
+        }
+    }
+
+    /// Inspect the next chunk.
+    pub(crate) fn update(&mut self, mut data: &[u8]) -> Result<(), ArchiveViolation> {
+        if matches!(self.state, State::Done) {
+            return Ok(());
+        }
+
+        if self.skip > 0 {
+            let n = self.skip.min(data.len() as u64) as usize;
+
+            self.skip -= n as u64;
+            data = &data[n..];
+        }
+
+        self.buffer.extend_from_slice(data);
+
+        loop {
+            if self.skip > 0 {
+                let n = self.skip.min(self.buffer.len() as u64) as usize;
+
+                self.skip -= n as u64;
+                self.buffer.drain(..n);
+
+                if self.skip > 0 {
+                    return Ok(());
+                }
+            }
+
+            if !self.step()? {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Check that the archive is complete.
+    pub(crate) fn finish(mut self) -> Result<(), ArchiveViolation> {
+        if matches!(self.state, State::Detecting) {
+            // A small archive may be shorter than the bytes required to recognize a tar archive
+            if self.buffer.starts_with(b"PK\x03\x04") {
+                self.state = State::ZipHeader;
+
+                self.update(&[])?;
+            } else {
+                return Err(ArchiveViolation::Malformed);
+            }
+        }
+
+        match self.state {
+            State::Done => Ok(()),
+            // A tar archive may end without the two zero blocks
+            State::TarHeader if self.skip == 0 && self.buffer.is_empty() => Ok(()),
+            _ => Err(ArchiveViolation::Malformed),
+        }
+    }
+
+    /// Parse the buffered bytes. Return `false` if more bytes are required.
+    fn step(&mut self) -> Result<bool, ArchiveViolation> {
+        match self.state {
+            State::Detecting => {
+                if self.buffer.starts_with(b"PK\x03\x04") {
+                    self.state = State::ZipHeader;
+                } else if self.buffer.len() < 262 {
+                    return Ok(false);
+                } else if &self.buffer[257..262] == b"ustar" {
+                    self.state = State::TarHeader;
+                } else {
+                    return Err(ArchiveViolation::Malformed);
+                }
+
+                Ok(true)
+            },
+            State::ZipHeader => self.step_zip_header(),
+            State::ZipDescriptor {
+                ..
+            } => self.step_zip_descriptor(),
+            State::ZipCentralDirectory => self.step_zip_central_directory(),
+            State::TarHeader => self.step_tar_header(),
+            State::TarExtension {
+                ..
+            } => self.step_tar_extension(),
+            State::Done => {
+                self.buffer.clear();
+
+                Ok(false)
+            },
+        }
+    }
+
+    fn step_zip_header(&mut self) -> Result<bool, ArchiveViolation> {
+        if self.buffer.len() < 4 {
+            return Ok(false);
+        }
+
+        match read_u32(&self.buffer, 0) {
+            ZIP_LOCAL_FILE_HEADER => (),
+            ZIP_CENTRAL_DIRECTORY_HEADER
+            | ZIP_END_OF_CENTRAL_DIRECTORY
+            | ZIP64_END_OF_CENTRAL_DIRECTORY => {
+                // The central directory repeats the local headers, and extractors usually trust it instead of them
+                self.state = State::ZipCentralDirectory;
+
+                return Ok(true);
+            },
+            _ => return Err(ArchiveViolation::Malformed),
+        }
+
+        if self.buffer.len() < 30 {
+            return Ok(false);
+        }
+
+        let flags = read_u16(&self.buffer, 6);
+        let mut compressed = read_u32(&self.buffer, 18) as u64;
+        let mut uncompressed = read_u32(&self.buffer, 22) as u64;
+        let name_length = read_u16(&self.buffer, 26) as usize;
+        let extra_length = read_u16(&self.buffer, 28) as usize;
+
+        let header_length = 30 + name_length + extra_length;
+
+        if self.buffer.len() < header_length {
+            return Ok(false);
+        }
+
+        let name = String::from_utf8_lossy(&self.buffer[30..30 + name_length]).into_owned();
+
+        read_zip64_sizes(
+            &self.buffer[30 + name_length..header_length],
+            &mut compressed,
+            &mut uncompressed,
+        );
+
+        self.buffer.drain(..header_length);
+
+        if flags & 0x0008 != 0 && compressed == 0 {
+            // The sizes are written in the data descriptor after the data
+            self.state = State::ZipDescriptor {
+                name,
+                consumed: 0,
+            };
+        } else {
+            self.check_entry(&name, Some(compressed), uncompressed)?;
+
+            self.skip = compressed;
+            self.zip_entries.push((name, compressed, uncompressed));
+        }
+
+        Ok(true)
+    }
+
+    fn step_zip_descriptor(&mut self) -> Result<bool, ArchiveViolation> {
+        let State::ZipDescriptor {
+            name,
+            consumed,
+        } = &mut self.state
+        else {
+            unreachable!()
+        };
+
+        let mut i = 0;
+
+        while let Some(p) = find(&self.buffer, i, ZIP_DATA_DESCRIPTOR) {
+            // Data descriptors may use 32-bit or 64-bit sizes. The compressed size must be equal to the length of the data.
+            let data_length = *consumed + p as u64;
+
+            let sizes = if self.buffer.len() >= p + 16
+                && read_u32(&self.buffer, p + 8) as u64 == data_length
+            {
+                Some((read_u32(&self.buffer, p + 12) as u64, 16))
+            } else if self.buffer.len() >= p + 24 && read_u64(&self.buffer, p + 8) == data_length {
+                Some((read_u64(&self.buffer, p + 16), 24))
+            } else if self.buffer.len() < p + 24 {
+                // Wait for the rest of the descriptor
+                return Ok(false);
+            } else {
+                None
+            };
+
+            if let Some((uncompressed, descriptor_length)) = sizes {
+                let name = std::mem::take(name);
+
+                self.buffer.drain(..p + descriptor_length);
+                self.state = State::ZipHeader;
+
+                self.check_entry(&name, Some(data_length), uncompressed)?;
+
+                self.zip_entries.push((name, data_length, uncompressed));
+
+                return Ok(true);
+            }
+
+            i = p + 1;
+        }
+
+        // Keep the bytes which may be the beginning of a descriptor
+        let n = self.buffer.len().saturating_sub(ZIP_DATA_DESCRIPTOR.len() - 1);
+
+        *consumed += n as u64;
+        self.buffer.drain(..n);
+
+        Ok(false)
+    }
+
+    fn step_zip_central_directory(&mut self) -> Result<bool, ArchiveViolation> {
+        if self.buffer.len() < 4 {
+            return Ok(false);
+        }
+
+        match read_u32(&self.buffer, 0) {
+            ZIP_CENTRAL_DIRECTORY_HEADER => {
+                if self.buffer.len() < 46 {
+                    return Ok(false);
+                }
+
+                let mut compressed = read_u32(&self.buffer, 20) as u64;
+                let mut uncompressed = read_u32(&self.buffer, 24) as u64;
+                let name_length = read_u16(&self.buffer, 28) as usize;
+                let extra_length = read_u16(&self.buffer, 30) as usize;
+                let comment_length = read_u16(&self.buffer, 32) as usize;
+
+                let record_length = 46 + name_length + extra_length + comment_length;
+
+                if self.buffer.len() < record_length {
+                    return Ok(false);
+                }
+
+                let name = String::from_utf8_lossy(&self.buffer[46..46 + name_length]).into_owned();
+
+                read_zip64_sizes(
+                    &self.buffer[46 + name_length..46 + name_length + extra_length],
+                    &mut compressed,
+                    &mut uncompressed,
+                );
+
+                self.buffer.drain(..record_length);
+
+                // The records are checked like the local headers, and they must describe the same entries in the same order
+                self.zip_directory += 1;
+
+                if self.zip_directory as u64 > self.limits.max_entries {
+                    return Err(ArchiveViolation::TooManyEntries);
+                }
+
+                if is_traversal(&name) {
+                    return Err(ArchiveViolation::PathTraversal(name));
+                }
+
+                self.check_ratio(&name, compressed, uncompressed)?;
+
+                match self.zip_entries.get(self.zip_directory - 1) {
+                    Some((local_name, local_compressed, local_uncompressed))
+                        if *local_name == name
+                            && *local_compressed == compressed
+                            && *local_uncompressed == uncompressed =>
+                    {
+                        Ok(true)
+                    },
+                    _ => Err(ArchiveViolation::CentralDirectoryMismatch(name)),
+                }
+            },
+            ZIP64_END_OF_CENTRAL_DIRECTORY => {
+                if self.buffer.len() < 12 {
+                    return Ok(false);
+                }
+
+                self.skip = 12u64.saturating_add(read_u64(&self.buffer, 4));
+
+                Ok(true)
+            },
+            ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR => {
+                self.skip = 20;
+
+                Ok(true)
+            },
+            ZIP_DIGITAL_SIGNATURE => {
+                if self.buffer.len() < 6 {
+                    return Ok(false);
+                }
+
+                self.skip = 6 + read_u16(&self.buffer, 4) as u64;
+
+                Ok(true)
+            },
+            ZIP_END_OF_CENTRAL_DIRECTORY => {
+                if self.buffer.len() < 22 {
+                    return Ok(false);
+                }
+
+                // 0xFFFF means that the number is in the ZIP64 record
+                let total = read_u16(&self.buffer, 10);
+
+                if self.zip_directory < self.zip_entries.len()
+                    || (total != u16::MAX && total as usize != self.zip_directory)
+                {
+                    // A local entry is hidden from the extractors which read the central directory
+                    let name = self
+                        .zip_entries
+                        .get(self.zip_directory)
+                        .map(|(name, ..)| name.clone())
+                        .unwrap_or_default();
+
+                    return Err(ArchiveViolation::CentralDirectoryMismatch(name));
+                }
+
+                self.state = State::Done;
+
+                Ok(true)
+            },
+            _ => Err(ArchiveViolation::Malformed),
+        }
+    }
+
+    fn step_tar_header(&mut self) -> Result<bool, ArchiveViolation> {
+        if self.buffer.len() < TAR_BLOCK_SIZE {
+            return Ok(false);
+        }
+
+        let header: Vec<u8> = self.buffer.drain(..TAR_BLOCK_SIZE).collect();
+
+        if header.iter().all(|b| *b == 0) {
+            // The end of the archive
+            self.state = State::Done;
+
+            return Ok(true);
+        }
+
+        let size = parse_octal(&header[124..136]).ok_or(ArchiveViolation::Malformed)?;
+        let padding =
+            (TAR_BLOCK_SIZE as u64 - size % TAR_BLOCK_SIZE as u64) % TAR_BLOCK_SIZE as u64;
+
+        match header[156] {
+            typ @ (b'L' | b'x') => {
+                if size > TAR_MAX_EXTENSION_SIZE {
+                    return Err(ArchiveViolation::Malformed);
+                }
+
+                self.tar_extension.clear();
+                self.state = State::TarExtension {
+                    pax: typ == b'x',
+                    remaining: size as usize,
+                    padding,
+                };
+
+                return Ok(true);
+            },
+            // Global PAX headers and other metadata do not describe entries
+            b'g' | b'K' => {
+                self.skip = size + padding;
+
+                return Ok(true);
+            },
+            _ => (),
+        }
+
+        let name = match self.tar_override_name.take() {
+            Some(name) => name,
+            None => {
+                let name = c_string(&header[0..100]);
+
+                // The prefix field of the ustar format
+                let prefix = c_string(&header[345..500]);
+
+                if prefix.is_empty() {
+                    name
+                } else {
+                    format!("{}/{}", prefix, name)
+                }
+            },
+        };
+
+        // Hard links and symbolic links
+        if header[156] == b'1' || header[156] == b'2' {
+            let target = c_string(&header[157..257]);
+
+            if is_traversal(&target) {
+                return Err(ArchiveViolation::PathTraversal(target));
+            }
+        }
+
+        self.check_entry(&name, None, size)?;
+
+        self.skip = size + padding;
+
+        Ok(true)
+    }
+
+    fn step_tar_extension(&mut self) -> Result<bool, ArchiveViolation> {
+        let State::TarExtension {
+            pax,
+            remaining,
+            padding,
+        } = &mut self.state
+        else {
+            unreachable!()
+        };
+
+        let n = (*remaining).min(self.buffer.len());
+
+        self.tar_extension.extend(self.buffer.drain(..n));
+        *remaining -= n;
+
+        if *remaining > 0 {
+            return Ok(false);
+        }
+
+        let extension = String::from_utf8_lossy(&self.tar_extension);
+
+        let name = if *pax {
+            // Records are written as `<length> <key>=<value>\n`
+            extension.lines().find_map(|record| {
+                record
+                    .split_once(' ')
+                    .and_then(|(_, kv)| kv.strip_prefix("path="))
+                    .map(String::from)
+            })
+        } else {
+            Some(extension.trim_end_matches('\0').to_string())
+        };
+
+        if name.is_some() {
+            self.tar_override_name = name;
+        }
+
+        self.skip = *padding;
+        self.state = State::TarHeader;
+
+        Ok(true)
+    }
+
+    fn check_entry(
+        &mut self,
+        name: &str,
+        compressed: Option<u64>,
+        uncompressed: u64,
+    ) -> Result<(), ArchiveViolation> {
+        self.entries += 1;
+
+        if self.entries > self.limits.max_entries {
+            return Err(ArchiveViolation::TooManyEntries);
+        }
+
+        if is_traversal(name) {
+            return Err(ArchiveViolation::PathTraversal(name.to_string()));
+        }
+
+        self.uncompressed = self.uncompressed.saturating_add(uncompressed);
+
+        if self.uncompressed > self.limits.max_uncompressed_bytes {
+            return Err(ArchiveViolation::TooLarge);
+        }
+
+        if let Some(compressed) = compressed {
+            self.check_ratio(name, compressed, uncompressed)?;
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn check_ratio(
+        &self,
+        name: &str,
+        compressed: u64,
+        uncompressed: u64,
+    ) -> Result<(), ArchiveViolation> {
+        if uncompressed > compressed.max(1).saturating_mul(self.limits.max_compression_ratio) {
+            return Err(ArchiveViolation::CompressionRatioTooHigh(name.to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Read the sizes which do not fit in 32 bits from the ZIP64 extended information of the extra field of a ZIP header.
+fn read_zip64_sizes(mut extra: &[u8], compressed: &mut u64, uncompressed: &mut u64) {
+    while extra.len() >= 4 {
+        let id = read_u16(extra, 0);
+        let size = (read_u16(extra, 2) as usize).min(extra.len() - 4);
+
+        if id == 0x0001 {
+            let mut fields = &extra[4..4 + size];
+
+            if *uncompressed == u32::MAX as u64 && fields.len() >= 8 {
+                *uncompressed = read_u64(fields, 0);
+                fields = &fields[8..];
+            }
+
+            if *compressed == u32::MAX as u64 && fields.len() >= 8 {
+                *compressed = read_u64(fields, 0);
+            }
+        }
+
+        extra = &extra[4 + size..];
+    }
+}
+
+/// Check whether a path in an archive is absolute or contains `..`.
+fn is_traversal(path: &str) -> bool {
+    let path = path.replace('\\', "/");
+
+    path.starts_with('/')
+        || path.as_bytes().get(1) == Some(&b':')
+        || path.split('/').any(|component| component == "..")
+}
+
+#[inline]
+fn c_string(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+fn parse_octal(bytes: &[u8]) -> Option<u64> {
+    // The base-256 encoding of GNU tar
+    if bytes[0] & 0x80 != 0 {
+        return Some(bytes[1..].iter().fold(0u64, |n, b| (n << 8) | *b as u64));
+    }
+
+    let s = c_string(bytes);
+    let s = s.trim_matches(|c: char| c == ' ' || c == '\0');
+
+    if s.is_empty() {
+        Some(0)
+    } else {
+        u64::from_str_radix(s, 8).ok()
+    }
+}
+
+#[inline]
+fn read_u16(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+#[inline]
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+#[inline]
+fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+#[inline]
+fn find(data: &[u8], from: usize, needle: &[u8]) -> Option<usize> {
+    data.get(from..)?.windows(needle.len()).position(|w| w == needle).map(|p| p + from)
+}
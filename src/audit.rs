@@ -0,0 +1,383 @@
+use std::{
+    fmt::{self, Debug, Formatter},
+    io::Write,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use rocket::tokio::sync::mpsc::UnboundedSender;
+
+use crate::{
+    manifest::{escape, escape_option},
+    mime::Mime,
+    MultipartFormData, MultipartFormDataError, MultipartFormDataType,
+};
+
+/// How parsing a request ends.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuditOutcome {
+    Success,
+    /// Parsing fails. The code and the message are the ones of the `ErrorReport` of the error.
+    Failure {
+        code:    &'static str,
+        message: String,
+    },
+}
+
+/// A parsed field recorded in an `AuditEntry`.
+#[derive(Debug, Clone)]
+pub struct AuditField {
+    pub name:         Arc<str>,
+    /// The position of the part in the HTTP body.
+    pub index:        usize,
+    /// The type of the field. The files stored by a storage backend are recorded as `File`.
+    pub typ:          MultipartFormDataType,
+    pub content_type: Option<Mime>,
+    pub file_name:    Option<String>,
+    /// The number of bytes of the data. It is unknown for number, date-time, CSV and XML fields.
+    pub size:         Option<u64>,
+    /// The SHA-256 hash of the stored data, if `hash_fields` of the `Audit` is enabled. The files stored by a storage backend are not hashed.
+    #[cfg(feature = "sha2")]
+    pub sha256:       Option<[u8; 32]>,
+}
+
+/// The record of a parsed request.
+#[derive(Debug, Clone)]
+pub struct AuditEntry {
+    pub outcome:     AuditOutcome,
+    /// The parsed fields in the order in which they arrived, without checkbox fields and sub-forms. It is empty if parsing fails.
+    pub fields:      Vec<AuditField>,
+    /// The time spent on parsing.
+    pub duration:    Duration,
+    /// The digest of the whole HTTP body, if it is computed as set by the `body_digest` option.
+    #[cfg(feature = "sha2")]
+    pub body_digest: Option<[u8; 32]>,
+}
+
+/// A destination of the records of parsed requests, such as an audit log.
+pub trait AuditSink: Send + Sync {
+    /// Record a parsed request. It is called once at the end of parsing, whether it succeeds or fails.
+    fn record(&self, entry: &AuditEntry);
+}
+
+impl Debug for dyn AuditSink {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("AuditSink")
+    }
+}
+
+impl<F: Fn(&AuditEntry) + Send + Sync> AuditSink for F {
+    #[inline]
+    fn record(&self, entry: &AuditEntry) {
+        self(entry)
+    }
+}
+
+/// The entries are sent to the receiver. They are dropped if the receiver has been closed.
+impl AuditSink for UnboundedSender<AuditEntry> {
+    #[inline]
+    fn record(&self, entry: &AuditEntry) {
+        let _ = self.send(entry.clone());
+    }
+}
+
+/// An `AuditSink` which writes the entries to a writer, such as a log file. Every entry is written as a `request` line followed by a `field` line per field. The values in a line are separated by tabs, escaped in the same way as the manifest files. Errors of the writer are ignored so that they do not fail the requests.
+///
+/// ```text
+/// request  <outcome>  <error code>  <duration in milliseconds>
+/// field    <name>  <index>  <type>  <content type>  <file name>  <size>  <SHA-256>
+/// ```
+#[derive(Debug)]
+pub struct AuditWriter<W> {
+    writer: Mutex<W>,
+}
+
+impl<W: Write + Send> AuditWriter<W> {
+    /// Create an `AuditWriter` instance.
+    #[inline]
+    pub fn new(writer: W) -> AuditWriter<W> {
+        AuditWriter {
+            writer: Mutex::new(writer)
+        }
+    }
+
+    /// Get the writer back.
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl<W: Write + Send> AuditSink for AuditWriter<W> {
+    fn record(&self, entry: &AuditEntry) {
+        let (outcome, code) = match &entry.outcome {
+            AuditOutcome::Success => ("success", None),
+            AuditOutcome::Failure {
+                code, ..
+            } => ("failure", Some(*code)),
+        };
+
+        let mut lines = format!(
+            "request\t{}\t{}\t{}\n",
+            outcome,
+            escape_option(code),
+            entry.duration.as_millis()
+        );
+
+        for field in entry.fields.iter() {
+            #[cfg(feature = "sha2")]
+            let sha256: Option<String> =
+                field.sha256.map(|hash| hash.iter().map(|b| format!("{:02x}", b)).collect());
+            #[cfg(not(feature = "sha2"))]
+            let sha256: Option<String> = None;
+
+            lines.push_str(&format!(
+                "field\t{}\t{}\t{:?}\t{}\t{}\t{}\t{}\n",
+                escape(&field.name),
+                field.index,
+                field.typ,
+                escape_option(field.content_type.as_ref().map(|m| m.as_ref())),
+                escape_option(field.file_name.as_deref()),
+                escape_option(field.size.map(|size| size.to_string()).as_deref()),
+                escape_option(sha256.as_deref()),
+            ));
+        }
+
+        let mut writer = self.writer.lock().unwrap_or_else(|err| err.into_inner());
+
+        let _ = writer.write_all(lines.as_bytes()).and_then(|_| writer.flush());
+    }
+}
+
+/// An audit sink which records every parsed request.
+#[derive(Debug, Clone)]
+pub struct Audit {
+    /// The audit sink.
+    pub sink:        Arc<dyn AuditSink>,
+    /// Whether to compute the SHA-256 hashes of the fields. The uploaded files are read again to be hashed. The `sha2` feature is required.
+    #[cfg(feature = "sha2")]
+    pub hash_fields: bool,
+}
+
+impl Audit {
+    /// Create an `Audit` instance which does not hash the fields.
+    #[inline]
+    pub fn new(sink: Arc<dyn AuditSink>) -> Audit {
+        Audit {
+            sink,
+            #[cfg(feature = "sha2")]
+            hash_fields: false,
+        }
+    }
+
+    /// Record the result of parsing a request.
+    pub(crate) async fn record(
+        &self,
+        result: &Result<MultipartFormData, MultipartFormDataError>,
+        duration: Duration,
+    ) {
+        let entry = match result {
+            Ok(multipart_form_data) => AuditEntry {
+                outcome: AuditOutcome::Success,
+                fields: self.fields(multipart_form_data).await,
+                duration,
+                #[cfg(feature = "sha2")]
+                body_digest: multipart_form_data.body_digest,
+            },
+            Err(err) => AuditEntry {
+                outcome: AuditOutcome::Failure {
+                    code:    err.to_report().code,
+                    message: err.to_string(),
+                },
+                fields: Vec::new(),
+                duration,
+                #[cfg(feature = "sha2")]
+                body_digest: None,
+            },
+        };
+
+        self.sink.record(&entry);
+    }
+
+    async fn fields(&self, multipart_form_data: &MultipartFormData) -> Vec<AuditField> {
+        let mut fields = Vec::new();
+
+        let mut push = |name: &Arc<str>,
+                        index: usize,
+                        typ: MultipartFormDataType,
+                        content_type: &Option<Mime>,
+                        file_name: &Option<String>,
+                        size: Option<u64>| {
+            fields.push(AuditField {
+                name: name.clone(),
+                index,
+                typ,
+                content_type: content_type.clone(),
+                file_name: file_name.clone(),
+                size,
+                #[cfg(feature = "sha2")]
+                sha256: None,
+            })
+        };
+
+        for (name, files) in multipart_form_data.files.iter() {
+            for f in files {
+                let typ = MultipartFormDataType::File;
+
+                push(name, f.index, typ, &f.content_type, &f.file_name, Some(f.size));
+            }
+        }
+
+        for (name, raw) in multipart_form_data.raw.iter() {
+            for f in raw {
+                let size = Some(f.raw.len() as u64);
+
+                push(
+                    name,
+                    f.index,
+                    MultipartFormDataType::Raw,
+                    &f.content_type,
+                    &f.file_name,
+                    size,
+                );
+            }
+        }
+
+        for (name, texts) in multipart_form_data.texts.iter() {
+            for f in texts {
+                let size = Some(f.text.len() as u64);
+
+                push(
+                    name,
+                    f.index,
+                    MultipartFormDataType::Text,
+                    &f.content_type,
+                    &f.file_name,
+                    size,
+                );
+            }
+        }
+
+        for (name, large_texts) in multipart_form_data.large_texts.iter() {
+            for f in large_texts {
+                let size = Some(f.len);
+
+                push(
+                    name,
+                    f.index,
+                    MultipartFormDataType::Text,
+                    &f.content_type,
+                    &f.file_name,
+                    size,
+                );
+            }
+        }
+
+        for (name, numbers) in multipart_form_data.numbers.iter() {
+            for f in numbers {
+                let typ = MultipartFormDataType::Number;
+
+                push(name, f.index, typ, &f.content_type, &f.file_name, None);
+            }
+        }
+
+        #[cfg(feature = "chrono")]
+        for (name, datetimes) in multipart_form_data.datetimes.iter() {
+            for f in datetimes {
+                let typ = MultipartFormDataType::DateTime;
+
+                push(name, f.index, typ, &f.content_type, &f.file_name, None);
+            }
+        }
+
+        #[cfg(feature = "csv")]
+        for (name, csv) in multipart_form_data.csv.iter() {
+            for f in csv {
+                let typ = MultipartFormDataType::Csv;
+
+                push(name, f.index, typ, &f.content_type, &f.file_name, None);
+            }
+        }
+
+        #[cfg(feature = "xml")]
+        for (name, xml) in multipart_form_data.xml.iter() {
+            for f in xml {
+                let typ = MultipartFormDataType::Xml;
+
+                push(name, f.index, typ, &f.content_type, &f.file_name, None);
+            }
+        }
+
+        for (name, stored) in multipart_form_data.stored.iter() {
+            for f in stored {
+                let typ = MultipartFormDataType::File;
+
+                push(name, f.index, typ, &f.content_type, &f.file_name, Some(f.size));
+            }
+        }
+
+        fields.sort_by_key(|f| f.index);
+
+        #[cfg(feature = "sha2")]
+        if self.hash_fields {
+            for field in fields.iter_mut() {
+                field.sha256 = hash_field(multipart_form_data, field).await;
+            }
+        }
+
+        fields
+    }
+}
+
+/// Compute the SHA-256 hash of the stored data of a field.
+#[cfg(feature = "sha2")]
+async fn hash_field(
+    multipart_form_data: &MultipartFormData,
+    field: &AuditField,
+) -> Option<[u8; 32]> {
+    use rocket::tokio::{fs::File, io::AsyncReadExt};
+    use sha2::{Digest, Sha256};
+
+    match field.typ {
+        MultipartFormDataType::File => {
+            let f = multipart_form_data
+                .files
+                .get(&field.name)?
+                .iter()
+                .find(|f| f.index == field.index)?;
+
+            let mut file = File::open(&f.path).await.ok()?;
+            let mut hasher = Sha256::new();
+            let mut buffer = vec![0u8; 64 * 1024];
+
+            loop {
+                match file.read(&mut buffer).await.ok()? {
+                    0 => break,
+                    n => hasher.update(&buffer[..n]),
+                }
+            }
+
+            Some(hasher.finalize().into())
+        },
+        MultipartFormDataType::Raw => {
+            let f = multipart_form_data
+                .raw
+                .get(&field.name)?
+                .iter()
+                .find(|f| f.index == field.index)?;
+
+            Some(Sha256::digest(&f.raw).into())
+        },
+        MultipartFormDataType::Text => {
+            let f = multipart_form_data
+                .texts
+                .get(&field.name)?
+                .iter()
+                .find(|f| f.index == field.index)?;
+
+            Some(Sha256::digest(f.text.as_bytes()).into())
+        },
+        _ => None,
+    }
+}
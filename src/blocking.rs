@@ -0,0 +1,54 @@
+use std::{
+    io::{self, Read},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use rocket::{
+    http::ContentType,
+    tokio::{
+        io::{AsyncRead, ReadBuf},
+        runtime::{Builder, Handle},
+    },
+};
+
+use crate::{MultipartFormData, MultipartFormDataError, MultipartFormDataOptions};
+
+impl MultipartFormData {
+    /// Parse multipart/form-data from a synchronous reader. The same limits are applied as the `parse` method.
+    ///
+    /// It blocks the current thread. It can be called in a `spawn_blocking` closure, in which case the current Tokio runtime is reused, or in a thread without any Tokio runtime, in which case a temporary one is created. It must not be called in an asynchronous context.
+    pub fn parse_blocking<R: Read + Send + Unpin>(
+        content_type: &ContentType,
+        reader: R,
+        options: MultipartFormDataOptions<'_>,
+    ) -> Result<MultipartFormData, MultipartFormDataError> {
+        let boundary = MultipartFormData::boundary_from_content_type(content_type)?;
+
+        let reader = BlockingReader(reader.take(options.read_limit()));
+
+        let future = MultipartFormData::parse_reader(reader, boundary, options);
+
+        match Handle::try_current() {
+            Ok(handle) => handle.block_on(future),
+            Err(_) => Builder::new_current_thread().enable_all().build()?.block_on(future),
+        }
+    }
+}
+
+/// Adapt a synchronous reader to `AsyncRead`. Reading blocks the current thread.
+struct BlockingReader<R>(R);
+
+impl<R: Read + Unpin> AsyncRead for BlockingReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<(), io::Error>> {
+        let n = self.get_mut().0.read(buf.initialize_unfilled())?;
+
+        buf.advance(n);
+
+        Poll::Ready(Ok(()))
+    }
+}
@@ -0,0 +1,146 @@
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use rocket::tokio::io::{self as tokio_io, AsyncRead, ReadBuf};
+use sha2::{Digest, Sha256};
+
+use crate::MultipartFormData;
+
+const HMAC_BLOCK_SIZE: usize = 64;
+
+/// A digest which is computed over the whole HTTP body while it is being parsed. The `sha2` feature is required.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BodyDigest {
+    /// SHA-256.
+    Sha256,
+    /// HMAC-SHA256 with a key, such as the secret shared with the sender of signed webhooks.
+    HmacSha256(Vec<u8>),
+}
+
+/// The state of a `BodyDigest` being computed.
+enum Hasher {
+    Sha256(Sha256),
+    HmacSha256 { inner: Sha256, outer_key: [u8; HMAC_BLOCK_SIZE] },
+}
+
+impl Hasher {
+    fn new(digest: BodyDigest) -> Hasher {
+        match digest {
+            BodyDigest::Sha256 => Hasher::Sha256(Sha256::new()),
+            BodyDigest::HmacSha256(key) => {
+                let mut block = [0u8; HMAC_BLOCK_SIZE];
+
+                if key.len() > HMAC_BLOCK_SIZE {
+                    block[..32].copy_from_slice(&Sha256::digest(&key));
+                } else {
+                    block[..key.len()].copy_from_slice(&key);
+                }
+
+                let mut inner = Sha256::new();
+                inner.update(block.map(|b| b ^ 0x36));
+
+                Hasher::HmacSha256 {
+                    inner,
+                    outer_key: block.map(|b| b ^ 0x5C),
+                }
+            },
+        }
+    }
+
+    #[inline]
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha256(hasher) => hasher.update(data),
+            Hasher::HmacSha256 {
+                inner, ..
+            } => inner.update(data),
+        }
+    }
+
+    fn finalize(self) -> [u8; 32] {
+        match self {
+            Hasher::Sha256(hasher) => hasher.finalize().into(),
+            Hasher::HmacSha256 {
+                inner,
+                outer_key,
+            } => {
+                let mut outer = Sha256::new();
+                outer.update(outer_key);
+                outer.update(inner.finalize());
+
+                outer.finalize().into()
+            },
+        }
+    }
+}
+
+/// A reader which computes a `BodyDigest` over the data it reads.
+pub(crate) struct DigestReader<R> {
+    inner:  R,
+    hasher: Option<Hasher>,
+}
+
+impl<R: AsyncRead + Unpin> DigestReader<R> {
+    #[inline]
+    pub(crate) fn new(inner: R, digest: Option<BodyDigest>) -> DigestReader<R> {
+        DigestReader {
+            inner,
+            hasher: digest.map(Hasher::new),
+        }
+    }
+
+    /// Read the rest of the data, such as the epilogue after the closing delimiter, and get the digest.
+    pub(crate) async fn finish(&mut self) -> Result<Option<[u8; 32]>, io::Error> {
+        if self.hasher.is_none() {
+            return Ok(None);
+        }
+
+        tokio_io::copy(self, &mut tokio_io::sink()).await?;
+
+        Ok(self.hasher.take().map(Hasher::finalize))
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for DigestReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let filled = buf.filled().len();
+
+        let result = Pin::new(&mut self.inner).poll_read(cx, buf);
+
+        if let Some(hasher) = self.hasher.as_mut() {
+            hasher.update(&buf.filled()[filled..]);
+        }
+
+        result
+    }
+}
+
+impl MultipartFormData {
+    /// Finish the digest of the body. The uploaded files are deleted if the rest of the body cannot be read.
+    #[inline]
+    pub(crate) async fn with_body_digest<R: AsyncRead + Unpin>(
+        mut self,
+        reader: &mut DigestReader<R>,
+    ) -> Result<MultipartFormData, io::Error> {
+        self.body_digest = reader.finish().await?;
+
+        Ok(self)
+    }
+
+    /// Check whether the digest of the HTTP body, which is computed as set by the `body_digest` option, equals `expected`, such as the decoded value of a signature header. The comparison takes the same time wherever the digests differ.
+    pub fn verify_body_digest(&self, expected: &[u8]) -> bool {
+        match self.body_digest.as_ref() {
+            Some(digest) if digest.len() == expected.len() => {
+                digest.iter().zip(expected).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+            },
+            _ => false,
+        }
+    }
+}
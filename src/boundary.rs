@@ -0,0 +1,64 @@
+use std::borrow::Cow;
+
+use rocket::http::ContentType;
+
+use crate::{MultipartFormData, MultipartFormDataError};
+
+impl MultipartFormData {
+    /// Extract the boundary from the content type of a multipart/form-data request. The name of the parameter is case-insensitive, and the value can be quoted.
+    pub fn boundary_from_content_type(
+        content_type: &ContentType,
+    ) -> Result<Cow<'_, str>, MultipartFormDataError> {
+        if !content_type.is_form_data() {
+            return Err(MultipartFormDataError::WrongContentTypeError(Box::new(
+                content_type.clone(),
+            )));
+        }
+
+        content_type
+            .params()
+            .filter(|(k, _)| *k == "boundary")
+            .find_map(|(_, v)| unquote(v.trim()))
+            .ok_or(MultipartFormDataError::MissingBoundaryError)
+    }
+}
+
+/// Check whether a boundary conforms to RFC 2046, which allows 1 to 70 characters of `bchars` and does not allow a space at the end.
+pub(crate) fn is_valid_boundary(boundary: &str) -> bool {
+    !boundary.is_empty()
+        && boundary.len() <= 70
+        && !boundary.ends_with(' ')
+        && boundary.bytes().all(|b| b.is_ascii_alphanumeric() || b"'()+_,-./:=? ".contains(&b))
+}
+
+/// Remove the quotes and the escapes of a parameter value. Return `None` if it is empty.
+fn unquote(value: &str) -> Option<Cow<'_, str>> {
+    // The quotes are usually removed by Rocket already
+    let value = match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        Some(v) => v,
+        None => value,
+    };
+
+    if value.is_empty() {
+        return None;
+    }
+
+    if !value.contains('\\') {
+        return Some(Cow::Borrowed(value));
+    }
+
+    let mut s = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(c) = chars.next() {
+                s.push(c);
+            }
+        } else {
+            s.push(c);
+        }
+    }
+
+    Some(Cow::Owned(s))
+}
@@ -0,0 +1,4 @@
+use std::{future::Future, pin::Pin};
+
+/// A boxed future which is used by the asynchronous hooks of this crate.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
@@ -0,0 +1,120 @@
+use std::ops::Deref;
+
+use rocket::{
+    data::{self, Data, FromData},
+    http::{ContentType, Status},
+    log::private as log,
+    outcome::Outcome,
+    tokio::sync::OnceCell,
+    Request,
+};
+
+use crate::{MultipartFormData, MultipartFormDataError, MultipartFormDataSchema};
+
+/// The result of parsing the body of a request, which is kept in the request-local state.
+#[derive(Default)]
+struct Cached(OnceCell<Result<MultipartFormData, MultipartFormDataError>>);
+
+/// A data guard which parses multipart/form-data from the HTTP body with the `MultipartFormDataSchema<'static>` managed by Rocket, and caches the result in the request-local state.
+///
+/// The body is read only once in a request. The cached result can be accessed by the `cached` method from anything which runs after the data guard and has the request, such as fairings, catchers and the data guards of the routes the request is forwarded to. The uploaded files are deleted when the request is dropped.
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use rocket_multipart_form_data::{
+///     CachedMultipart, MultipartFormDataField, MultipartFormDataOptions,
+///     MultipartFormDataSchema,
+/// };
+///
+/// #[post("/", data = "<data>")]
+/// fn index(data: CachedMultipart<'_>) -> String {
+///     match data.texts.get("title") {
+///         Some(fields) => fields[0].text.clone(),
+///         None => String::new(),
+///     }
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let schema = MultipartFormDataSchema::new(
+///         MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+///             MultipartFormDataField::text("title"),
+///         ]),
+///     )
+///     .unwrap();
+///
+///     rocket::build().manage(schema).mount("/", routes![index])
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct CachedMultipart<'r>(&'r MultipartFormData);
+
+impl<'r> CachedMultipart<'r> {
+    /// Get the result of parsing the body of `request`, if the body has been parsed by a `CachedMultipart` data guard.
+    #[inline]
+    pub fn cached(
+        request: &'r Request<'_>,
+    ) -> Option<Result<CachedMultipart<'r>, &'r MultipartFormDataError>> {
+        let result = request.local_cache(Cached::default).0.get()?;
+
+        Some(result.as_ref().map(CachedMultipart))
+    }
+
+    /// Get the parsed data.
+    #[inline]
+    pub fn into_inner(self) -> &'r MultipartFormData {
+        self.0
+    }
+}
+
+impl<'r> Deref for CachedMultipart<'r> {
+    type Target = MultipartFormData;
+
+    #[inline]
+    fn deref(&self) -> &MultipartFormData {
+        self.0
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> FromData<'r> for CachedMultipart<'r> {
+    type Error = &'r MultipartFormDataError;
+
+    async fn from_data(request: &'r Request<'_>, data: Data<'r>) -> data::Outcome<'r, Self> {
+        let schema = match request.rocket().state::<MultipartFormDataSchema<'static>>() {
+            Some(schema) => schema,
+            None => {
+                log::error!(
+                    "A `MultipartFormDataSchema<'static>` should be managed to use \
+                     `CachedMultipart`."
+                );
+
+                return Outcome::Forward((data, Status::InternalServerError));
+            },
+        };
+
+        let result = request
+            .local_cache(Cached::default)
+            .0
+            .get_or_init(|| async {
+                let any = ContentType::Any;
+                let content_type = request.content_type().unwrap_or(&any);
+
+                MultipartFormData::parse_schema(content_type, data, schema).await
+            })
+            .await;
+
+        match result {
+            Ok(multipart_form_data) => Outcome::Success(CachedMultipart(multipart_form_data)),
+            Err(err) => {
+                let status = if err.is_client_error() {
+                    Status::BadRequest
+                } else {
+                    Status::InternalServerError
+                };
+
+                Outcome::Error((status, err))
+            },
+        }
+    }
+}
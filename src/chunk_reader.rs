@@ -0,0 +1,221 @@
+use std::{
+    io,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use rocket::tokio::{
+    io::{AsyncRead, ReadBuf},
+    time::{self, Instant},
+};
+
+use crate::{
+    multer::{bytes::Bytes, Field},
+    Admission, MultipartFormDataError, Quota,
+};
+
+/// Reads chunks of fields and does the per-request accounting. Every byte read from the HTTP body is accounted, including the ones of the parts which are skipped.
+pub(crate) struct ChunkReader<'a> {
+    /// The number of bytes read from the HTTP body, counted by a `CountingReader`.
+    pulled:     Arc<AtomicU64>,
+    quota:      Option<&'a Quota>,
+    reserved:   u64,
+    used:       u64,
+    throttle:   Option<Throttle>,
+    admission:  Option<&'a Admission>,
+    next_check: u64,
+    /// When the current part must have been received, with its field name.
+    deadline:   Option<(Instant, Arc<str>)>,
+}
+
+/// A token bucket which holds at most one second of tokens.
+struct Throttle {
+    rate:   f64,
+    tokens: f64,
+    last:   Instant,
+}
+
+impl<'a> ChunkReader<'a> {
+    #[inline]
+    pub(crate) fn new(
+        pulled: Arc<AtomicU64>,
+        quota: Option<&'a Quota>,
+        max_bytes_per_second: Option<u64>,
+        admission: Option<&'a Admission>,
+    ) -> ChunkReader<'a> {
+        let throttle = max_bytes_per_second.map(|rate| Throttle {
+            rate:   rate as f64,
+            tokens: rate as f64,
+            last:   Instant::now(),
+        });
+
+        let next_check = admission.and_then(|a| a.check_interval).unwrap_or(u64::MAX);
+
+        ChunkReader {
+            pulled,
+            quota,
+            reserved: 0,
+            used: 0,
+            throttle,
+            admission,
+            next_check,
+            deadline: None,
+        }
+    }
+
+    /// The number of bytes which have been accounted.
+    #[inline]
+    pub(crate) fn used(&self) -> u64 {
+        self.used
+    }
+
+    /// Start reading a part of a field which must be received within `max_duration`.
+    #[inline]
+    pub(crate) fn start_part(&mut self, field_name: &Arc<str>, max_duration: Option<Duration>) {
+        self.deadline = max_duration.map(|d| (Instant::now() + d, field_name.clone()));
+    }
+
+    /// Read the next chunk of a field.
+    pub(crate) async fn next_chunk(
+        &mut self,
+        entry: &mut Field<'_>,
+    ) -> Result<Option<Bytes>, MultipartFormDataError> {
+        let chunk = match self.deadline.as_ref() {
+            Some((deadline, field_name)) => {
+                match time::timeout_at(*deadline, entry.chunk()).await {
+                    Ok(chunk) => chunk,
+                    Err(_) => {
+                        return Err(MultipartFormDataError::FieldTimeoutError(field_name.clone()))
+                    },
+                }
+            },
+            None => entry.chunk().await,
+        };
+
+        let bytes = match chunk? {
+            Some(bytes) => bytes,
+            None => return Ok(None),
+        };
+
+        self.charge().await?;
+
+        Ok(Some(bytes))
+    }
+
+    /// Account the bytes which have been read from the HTTP body since the last time. It should also be called after moving to the next part, because the rest of the previous part may have been skipped.
+    pub(crate) async fn charge(&mut self) -> Result<(), MultipartFormDataError> {
+        let pulled = self.pulled.load(Ordering::Acquire);
+
+        if pulled <= self.used {
+            return Ok(());
+        }
+
+        let n = pulled - self.used;
+
+        self.used = pulled;
+
+        if let Some(throttle) = self.throttle.as_mut() {
+            let now = Instant::now();
+
+            throttle.tokens = (throttle.tokens
+                + now.duration_since(throttle.last).as_secs_f64() * throttle.rate)
+                .min(throttle.rate);
+            throttle.last = now;
+            throttle.tokens -= n as f64;
+
+            if throttle.tokens < 0.0 {
+                time::sleep(Duration::from_secs_f64(-throttle.tokens / throttle.rate)).await;
+            }
+        }
+
+        if let Some(quota) = self.quota {
+            if self.used > self.reserved {
+                let needed = self.used - self.reserved;
+
+                match quota
+                    .provider
+                    .check_and_reserve(&quota.identity, quota.reservation_size.max(needed))
+                    .await
+                {
+                    Some(reservation) => {
+                        self.reserved += reservation.bytes;
+
+                        // A grant which cannot cover the data which has been read means the quota is exhausted
+                        if reservation.bytes < needed {
+                            return Err(MultipartFormDataError::QuotaExceededError);
+                        }
+                    },
+                    None => return Err(MultipartFormDataError::QuotaExceededError),
+                }
+            }
+        }
+
+        if let Some(admission) = self.admission {
+            if self.used >= self.next_check {
+                admission
+                    .control
+                    .check(self.used)
+                    .await
+                    .map_err(MultipartFormDataError::AdmissionDeniedError)?;
+
+                let interval = admission.check_interval.unwrap();
+
+                self.next_check = (self.used / interval).saturating_add(1).saturating_mul(interval);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Finish reading. The unused reservation will be released.
+    pub(crate) async fn finish(self) {
+        if let Some(quota) = self.quota {
+            if self.reserved > self.used {
+                quota.provider.release(&quota.identity, self.reserved - self.used).await;
+            }
+        }
+    }
+}
+
+/// A reader which counts the bytes read from the HTTP body.
+pub(crate) struct CountingReader<R> {
+    inner: R,
+    count: Arc<AtomicU64>,
+}
+
+impl<R> CountingReader<R> {
+    #[inline]
+    pub(crate) fn new(inner: R) -> CountingReader<R> {
+        CountingReader {
+            inner,
+            count: Arc::default(),
+        }
+    }
+
+    /// The counter of the bytes which have been read.
+    #[inline]
+    pub(crate) fn count(&self) -> Arc<AtomicU64> {
+        self.count.clone()
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for CountingReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let filled = buf.filled().len();
+
+        let result = Pin::new(&mut self.inner).poll_read(cx, buf);
+
+        self.count.fetch_add((buf.filled().len() - filled) as u64, Ordering::Release);
+
+        result
+    }
+}
@@ -0,0 +1,57 @@
+use std::{fs, path::PathBuf};
+
+use crate::disk_budget::DiskCharge;
+
+/// A guard which deletes the uploaded files it holds when it is being dropped.
+#[derive(Debug, Default)]
+pub struct CleanupGuard {
+    paths:  Vec<PathBuf>,
+    charge: DiskCharge,
+}
+
+impl CleanupGuard {
+    /// Create an empty `CleanupGuard` instance.
+    #[inline]
+    pub fn new() -> CleanupGuard {
+        CleanupGuard::default()
+    }
+
+    /// Create an empty `CleanupGuard` instance which gives back the bytes taken from a `DiskBudget` when it is being dropped.
+    #[inline]
+    pub(crate) fn with_disk_charge(charge: DiskCharge) -> CleanupGuard {
+        CleanupGuard {
+            paths: Vec::new(),
+            charge,
+        }
+    }
+
+    /// Add a file which should be deleted when this guard is being dropped.
+    #[inline]
+    pub fn push<P: Into<PathBuf>>(&mut self, path: P) {
+        self.paths.push(path.into());
+    }
+
+    /// The files held by this guard.
+    #[inline]
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// Disarm this guard and return the files it holds. They will not be deleted automatically anymore.
+    #[inline]
+    pub fn keep(mut self) -> Vec<PathBuf> {
+        std::mem::take(&mut self.paths)
+    }
+}
+
+impl Drop for CleanupGuard {
+    #[inline]
+    fn drop(&mut self) {
+        for path in self.paths.iter() {
+            if fs::remove_file(path).is_err() {}
+        }
+
+        // Give back the bytes after the files are deleted
+        drop(std::mem::take(&mut self.charge));
+    }
+}
@@ -0,0 +1,37 @@
+use std::{
+    fmt::{self, Debug, Formatter},
+    time::SystemTime,
+};
+
+/// A source of the current time, which can be replaced to make the names of uploaded files reproducible in tests.
+///
+/// It is implemented for closures of type `Fn() -> SystemTime`.
+pub trait Clock: Send + Sync {
+    /// Get the current time.
+    fn now(&self) -> SystemTime;
+}
+
+impl<F: Fn() -> SystemTime + Send + Sync> Clock for F {
+    #[inline]
+    fn now(&self) -> SystemTime {
+        self()
+    }
+}
+
+impl Debug for dyn Clock {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("Clock")
+    }
+}
+
+/// The default clock, which reads the system time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    #[inline]
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use http::{header::CONTENT_DISPOSITION, HeaderMap};
+
+/// Get the parameters of the `Content-Disposition` header except `name` and `filename`. The names of the parameters are lowercased.
+pub(crate) fn content_disposition_params(headers: &HeaderMap) -> HashMap<String, String> {
+    let mut params = HashMap::new();
+
+    let value = match headers.get(CONTENT_DISPOSITION).and_then(|v| v.to_str().ok()) {
+        Some(value) => value,
+        None => return params,
+    };
+
+    // Skip the disposition type
+    let mut rest = match value.find(';') {
+        Some(index) => &value[index + 1..],
+        None => return params,
+    };
+
+    loop {
+        rest = rest.trim_start_matches(|c: char| c == ';' || c.is_ascii_whitespace());
+
+        if rest.is_empty() {
+            break;
+        }
+
+        let (key, after_key) = match rest.find(['=', ';']) {
+            Some(index) if rest.as_bytes()[index] == b'=' => (&rest[..index], &rest[index + 1..]),
+            Some(index) => {
+                // A parameter without a value
+                rest = &rest[index..];
+                continue;
+            },
+            None => break,
+        };
+
+        let after_key = after_key.trim_start();
+
+        let (value, after_value) = if let Some(quoted) = after_key.strip_prefix('"') {
+            let mut value = String::new();
+            let mut chars = quoted.char_indices();
+            let mut end = quoted.len();
+
+            while let Some((i, c)) = chars.next() {
+                match c {
+                    '\\' => {
+                        if let Some((_, c)) = chars.next() {
+                            value.push(c);
+                        }
+                    },
+                    '"' => {
+                        end = i + 1;
+                        break;
+                    },
+                    _ => value.push(c),
+                }
+            }
+
+            (value, &quoted[end..])
+        } else {
+            let end = after_key.find(';').unwrap_or(after_key.len());
+
+            (after_key[..end].trim_end().to_string(), &after_key[end..])
+        };
+
+        let key = key.trim().to_ascii_lowercase();
+
+        if !matches!(key.as_str(), "name" | "filename" | "filename*") {
+            params.insert(key, value);
+        }
+
+        rest = after_value;
+    }
+
+    params
+}
@@ -0,0 +1,56 @@
+use crate::mime::{self, Mime};
+
+/// Check whether `content_type` is accepted by the content type filter `filter`.
+///
+/// The type and the subtype of `filter` can be stars. If `filter` has a suffix, such as `application/*+json`, the suffix of `content_type` (or its subtype, as in `application/json`) must be the same. If `filter` has parameters, such as `charset=utf-8`, `content_type` must have the same parameters, whose values are compared case-insensitively.
+pub(crate) fn content_type_matches(filter: &Mime, content_type: &Mime) -> bool {
+    let top_ref = filter.type_();
+
+    if top_ref != mime::STAR && top_ref != content_type.type_() {
+        return false;
+    }
+
+    let sub_ref = filter.subtype();
+
+    if sub_ref != mime::STAR && sub_ref != content_type.subtype() {
+        return false;
+    }
+
+    if let Some(suffix_ref) = filter.suffix() {
+        let suffix = content_type.suffix().unwrap_or_else(|| content_type.subtype());
+
+        if suffix != suffix_ref {
+            return false;
+        }
+    }
+
+    params_match(filter, content_type)
+}
+
+/// Check whether `content_type` is accepted by the content type filter `filter`, either as it is or by its suffix. For example, `application/json` accepts `application/vnd.api+json` by its suffix `json`.
+pub(crate) fn content_type_matches_suffix(filter: &Mime, content_type: &Mime) -> bool {
+    if content_type_matches(filter, content_type) {
+        return true;
+    }
+
+    let suffix = match content_type.suffix() {
+        Some(suffix) if filter.suffix().is_none() => suffix,
+        _ => return false,
+    };
+
+    let top_ref = filter.type_();
+
+    if top_ref != mime::STAR && top_ref != content_type.type_() {
+        return false;
+    }
+
+    filter.subtype() == suffix && params_match(filter, content_type)
+}
+
+#[inline]
+fn params_match(filter: &Mime, content_type: &Mime) -> bool {
+    filter.params().all(|(name, value)| match content_type.get_param(name) {
+        Some(v) => v.as_str().eq_ignore_ascii_case(value.as_str()),
+        None => false,
+    })
+}
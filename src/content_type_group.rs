@@ -0,0 +1,55 @@
+use crate::mime::{self, Mime};
+
+/// The content types of `ContentTypeGroup::Documents`.
+const DOCUMENT_TYPES: [&str; 13] = [
+    "application/pdf",
+    "application/rtf",
+    "text/plain",
+    "text/csv",
+    "application/msword",
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    "application/vnd.ms-excel",
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    "application/vnd.ms-powerpoint",
+    "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+    "application/vnd.oasis.opendocument.text",
+    "application/vnd.oasis.opendocument.spreadsheet",
+    "application/vnd.oasis.opendocument.presentation",
+];
+
+/// A group of content types which can be accepted by a field at once with the `accept` method of `MultipartFormDataField`.
+///
+/// ```
+/// use rocket_multipart_form_data::{
+///     ContentTypeGroup, MultipartFormDataField,
+/// };
+///
+/// let field = MultipartFormDataField::file("attachment")
+///     .accept(ContentTypeGroup::Images)
+///     .accept(ContentTypeGroup::Documents);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContentTypeGroup {
+    /// `image/*`
+    Images,
+    /// `audio/*`
+    Audio,
+    /// `video/*`
+    Video,
+    /// PDF, plain text, CSV, RTF, Microsoft Office and OpenDocument files.
+    Documents,
+}
+
+impl ContentTypeGroup {
+    /// The content type filters of this group.
+    pub fn content_types(&self) -> Vec<Mime> {
+        match self {
+            ContentTypeGroup::Images => vec![mime::IMAGE_STAR],
+            ContentTypeGroup::Audio => vec!["audio/*".parse().unwrap()],
+            ContentTypeGroup::Video => vec!["video/*".parse().unwrap()],
+            ContentTypeGroup::Documents => {
+                DOCUMENT_TYPES.iter().map(|t| t.parse().unwrap()).collect()
+            },
+        }
+    }
+}
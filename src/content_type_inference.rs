@@ -0,0 +1,93 @@
+use std::str::FromStr;
+
+use crate::mime::Mime;
+
+/// The number of bytes at the start of the data which are needed to recognize its format.
+pub(crate) const MAGIC_BYTES_LENGTH: usize = 16;
+
+/// Infer the content type of a part which does not have a `Content-Type` header from the extension of its file name, or from the first bytes of its data.
+pub(crate) fn infer_content_type(file_name: Option<&str>, head: &[u8]) -> Option<Mime> {
+    let essence = file_name.and_then(from_extension).or_else(|| from_magic_bytes(head))?;
+
+    Mime::from_str(essence).ok()
+}
+
+fn from_extension(file_name: &str) -> Option<&'static str> {
+    let (_, extension) = file_name.rsplit_once('.')?;
+
+    let essence = match extension.to_ascii_lowercase().as_str() {
+        "txt" => "text/plain",
+        "htm" | "html" => "text/html",
+        "css" => "text/css",
+        "csv" => "text/csv",
+        "js" | "mjs" => "text/javascript",
+        "md" => "text/markdown",
+        "json" => "application/json",
+        "xml" => "application/xml",
+        "pdf" => "application/pdf",
+        "zip" => "application/zip",
+        "gz" => "application/gzip",
+        "tar" => "application/x-tar",
+        "wasm" => "application/wasm",
+        "doc" => "application/msword",
+        "docx" => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        "xls" => "application/vnd.ms-excel",
+        "xlsx" => "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+        "ppt" => "application/vnd.ms-powerpoint",
+        "pptx" => "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "svg" => "image/svg+xml",
+        "bmp" => "image/bmp",
+        "ico" => "image/vnd.microsoft.icon",
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "ogg" => "audio/ogg",
+        "flac" => "audio/flac",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "mov" => "video/quicktime",
+        _ => return None,
+    };
+
+    Some(essence)
+}
+
+fn from_magic_bytes(head: &[u8]) -> Option<&'static str> {
+    let essence = if head.starts_with(b"\x89PNG\r\n\x1A\n") {
+        "image/png"
+    } else if head.starts_with(b"\xFF\xD8\xFF") {
+        "image/jpeg"
+    } else if head.starts_with(b"GIF87a") || head.starts_with(b"GIF89a") {
+        "image/gif"
+    } else if head.starts_with(b"RIFF") && head.get(8..12) == Some(b"WEBP") {
+        "image/webp"
+    } else if head.starts_with(b"RIFF") && head.get(8..12) == Some(b"WAVE") {
+        "audio/wav"
+    } else if head.starts_with(b"BM") {
+        "image/bmp"
+    } else if head.starts_with(b"%PDF-") {
+        "application/pdf"
+    } else if head.starts_with(b"PK\x03\x04") {
+        "application/zip"
+    } else if head.starts_with(b"\x1F\x8B") {
+        "application/gzip"
+    } else if head.starts_with(b"\0asm") {
+        "application/wasm"
+    } else if head.starts_with(b"ID3") {
+        "audio/mpeg"
+    } else if head.starts_with(b"OggS") {
+        "audio/ogg"
+    } else if head.starts_with(b"fLaC") {
+        "audio/flac"
+    } else if head.get(4..8) == Some(b"ftyp") {
+        "video/mp4"
+    } else {
+        return None;
+    };
+
+    Some(essence)
+}
@@ -0,0 +1,277 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Debug, Display, Formatter},
+    sync::Arc,
+};
+
+use csv::StringRecord;
+use csv_core::ReadRecordResult;
+use serde::de::DeserializeOwned;
+
+use crate::mime::Mime;
+
+/// A record of a CSV field. The first record of the data is the header, so the values are associated with the column names.
+#[derive(Debug, Clone)]
+pub struct CsvRecord {
+    headers: Arc<[String]>,
+    values:  Vec<String>,
+    row:     usize,
+}
+
+impl CsvRecord {
+    /// The position of this record, starting from `1` for the first record after the header.
+    #[inline]
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    /// The column names.
+    #[inline]
+    pub fn headers(&self) -> &[String] {
+        &self.headers
+    }
+
+    /// The values, in the order of the columns.
+    #[inline]
+    pub fn values(&self) -> &[String] {
+        &self.values
+    }
+
+    /// Get the value of a column.
+    #[inline]
+    pub fn get(&self, column: &str) -> Option<&str> {
+        let i = self.headers.iter().position(|h| h == column)?;
+
+        self.values.get(i).map(String::as_str)
+    }
+
+    /// Deserialize this record by the `csv` crate. Structs and maps are filled by the column names and sequences by the positions. An empty value is `None` for an `Option`.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, CsvError> {
+        let headers = StringRecord::from(self.headers.as_ref());
+
+        StringRecord::from(self.values.as_slice()).deserialize(Some(&headers)).map_err(|err| {
+            CsvError {
+                row: self.row, message: err.to_string()
+            }
+        })
+    }
+}
+
+/// An error of a record of a CSV field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvError {
+    /// The position of the record, starting from `1` for the first record after the header. It is `0` for the header.
+    pub row:     usize,
+    pub message: String,
+}
+
+impl Display for CsvError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_fmt(format_args!("row {}: {}", self.row, self.message))
+    }
+}
+
+impl Error for CsvError {}
+
+/// A parsed CSV field.
+#[derive(Debug, Clone)]
+pub struct CsvField {
+    pub content_type:       Option<Mime>,
+    pub file_name:          Option<String>,
+    /// The extra parameters of the `Content-Disposition` header.
+    pub disposition_params: HashMap<String, String>,
+    /// The field name sent by the client, if it has been changed by the key normalization.
+    pub original_name:      Option<String>,
+    /// The position of this part in the HTTP body, starting from `0`.
+    pub index:              usize,
+    /// The column names.
+    pub headers:            Vec<String>,
+    /// The records after the header. It is empty if the records are consumed by the `on_csv_row` handler of the field.
+    pub records:            Vec<CsvRecord>,
+}
+
+impl CsvField {
+    /// Deserialize all records. The errors of every record which cannot be deserialized are returned.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<Vec<T>, Vec<CsvError>> {
+        let mut rows = Vec::with_capacity(self.records.len());
+        let mut errors = Vec::new();
+
+        for record in self.records.iter() {
+            match record.deserialize() {
+                Ok(row) => rows.push(row),
+                Err(error) => errors.push(error),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(rows)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// A consumer of the records of a CSV field, which receives every record as soon as it is parsed so that the records are not kept in memory.
+pub trait CsvRowHandler: Send + Sync {
+    /// Handle a record. Return an error message to reject the record, which fails the parsing.
+    fn handle(&self, record: &CsvRecord) -> Result<(), String>;
+}
+
+impl Debug for dyn CsvRowHandler {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("CsvRowHandler")
+    }
+}
+
+impl<F: Fn(&CsvRecord) -> Result<(), String> + Send + Sync> CsvRowHandler for F {
+    #[inline]
+    fn handle(&self, record: &CsvRecord) -> Result<(), String> {
+        self(record)
+    }
+}
+
+/// An incremental parser of CSV data, which is fed with the chunks of a part. The data is split into values by `csv-core`, so quotes are handled as leniently as the `csv` crate does.
+#[derive(Debug)]
+pub(crate) struct CsvParser {
+    reader:     csv_core::Reader,
+    headers:    Option<Arc<[String]>>,
+    /// The data of the record which is being parsed.
+    output:     Vec<u8>,
+    output_len: usize,
+    /// The end positions of the values of the record in `output`.
+    ends:       Vec<usize>,
+    ends_len:   usize,
+    row:        usize,
+}
+
+impl CsvParser {
+    #[inline]
+    pub(crate) fn new() -> CsvParser {
+        CsvParser {
+            reader:     csv_core::Reader::new(),
+            headers:    None,
+            output:     vec![0; 1024],
+            output_len: 0,
+            ends:       vec![0; 16],
+            ends_len:   0,
+            row:        0,
+        }
+    }
+
+    /// Parse a chunk. `on_record` is called for every complete record after the header.
+    #[inline]
+    pub(crate) fn push(
+        &mut self,
+        chunk: &[u8],
+        on_record: &mut dyn FnMut(CsvRecord) -> Result<(), CsvError>,
+    ) -> Result<(), CsvError> {
+        self.read(chunk, on_record)
+    }
+
+    /// Parse the last record which is not ended by a line break. Return the column names.
+    pub(crate) fn finish(
+        mut self,
+        on_record: &mut dyn FnMut(CsvRecord) -> Result<(), CsvError>,
+    ) -> Result<Vec<String>, CsvError> {
+        // Empty input is the end of the data for `csv-core`
+        self.read(&[], on_record)?;
+
+        Ok(self.headers.map(|headers| headers.to_vec()).unwrap_or_default())
+    }
+
+    fn read(
+        &mut self,
+        mut input: &[u8],
+        on_record: &mut dyn FnMut(CsvRecord) -> Result<(), CsvError>,
+    ) -> Result<(), CsvError> {
+        loop {
+            let (result, n_in, n_out, n_ends) = self.reader.read_record(
+                input,
+                &mut self.output[self.output_len..],
+                &mut self.ends[self.ends_len..],
+            );
+
+            input = &input[n_in..];
+            self.output_len += n_out;
+            self.ends_len += n_ends;
+
+            match result {
+                ReadRecordResult::InputEmpty | ReadRecordResult::End => return Ok(()),
+                ReadRecordResult::OutputFull => self.output.resize(self.output.len() * 2, 0),
+                ReadRecordResult::OutputEndsFull => self.ends.resize(self.ends.len() * 2, 0),
+                ReadRecordResult::Record => self.end_record(on_record)?,
+            }
+        }
+    }
+
+    /// An error of the record which is being parsed.
+    #[inline]
+    fn error(&self, message: &str) -> CsvError {
+        CsvError {
+            row:     self.headers.as_ref().map_or(0, |_| self.row + 1),
+            message: message.to_string(),
+        }
+    }
+
+    fn end_record(
+        &mut self,
+        on_record: &mut dyn FnMut(CsvRecord) -> Result<(), CsvError>,
+    ) -> Result<(), CsvError> {
+        let data = &self.output[..std::mem::take(&mut self.output_len)];
+        let ends = &self.ends[..std::mem::take(&mut self.ends_len)];
+
+        let data = match std::str::from_utf8(data) {
+            Ok(data) => data,
+            Err(_) => return Err(self.error("The record is not valid UTF-8.")),
+        };
+
+        let mut values = Vec::with_capacity(ends.len());
+        let mut start = 0;
+
+        for &end in ends {
+            values.push(data[start..end].to_string());
+
+            start = end;
+        }
+
+        // Blank lines are skipped
+        if values.len() == 1 && values[0].is_empty() {
+            return Ok(());
+        }
+
+        match self.headers.as_ref() {
+            Some(headers) => {
+                if values.len() != headers.len() {
+                    return Err(self.error(&format!(
+                        "The record has {} values but there are {} columns.",
+                        values.len(),
+                        headers.len()
+                    )));
+                }
+
+                self.row += 1;
+
+                on_record(CsvRecord {
+                    headers: headers.clone(),
+                    values,
+                    row: self.row,
+                })
+            },
+            None => {
+                // The byte order mark which spreadsheet applications write at the start
+                if let Some(first) = values.first_mut() {
+                    if let Some(name) = first.strip_prefix('\u{FEFF}') {
+                        *first = name.to_string();
+                    }
+                }
+
+                self.headers = Some(values.into());
+
+                Ok(())
+            },
+        }
+    }
+}
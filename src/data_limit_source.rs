@@ -0,0 +1,24 @@
+use std::fmt::{self, Display, Formatter};
+
+/// Where the max number of bytes to read from the HTTP body comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DataLimitSource {
+    /// The `max_data_bytes` of the options.
+    #[default]
+    Options,
+    /// The `data-form` limit of the Rocket `Limits`.
+    RocketLimits,
+    /// The cap passed to `MultipartFormData::parse_limited`.
+    Cap,
+}
+
+impl Display for DataLimitSource {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            DataLimitSource::Options => f.write_str("`max_data_bytes`"),
+            DataLimitSource::RocketLimits => f.write_str("the `data-form` limit"),
+            DataLimitSource::Cap => f.write_str("the read cap"),
+        }
+    }
+}
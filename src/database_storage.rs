@@ -0,0 +1,120 @@
+use std::io;
+
+use sqlx::{
+    postgres::{types::Oid, PgPool},
+    Postgres, Transaction,
+};
+
+use crate::{BoxFuture, StorageBackend, StorageWriter};
+
+/// The `INV_WRITE` mode of `lo_open`.
+const INV_WRITE: i32 = 0x20000;
+
+/// A `StorageBackend` which streams uploaded files into PostgreSQL large objects. Every file is written within its own transaction, and the id of a stored file is the OID of its large object.
+#[derive(Debug, Clone)]
+pub struct DatabaseStorage {
+    pool: PgPool,
+}
+
+impl DatabaseStorage {
+    /// Create a `DatabaseStorage` instance.
+    #[inline]
+    pub fn new(pool: PgPool) -> DatabaseStorage {
+        DatabaseStorage {
+            pool,
+        }
+    }
+}
+
+impl StorageBackend for DatabaseStorage {
+    fn create<'a>(
+        &'a self,
+        _field_name: &'a str,
+    ) -> BoxFuture<'a, Result<Box<dyn StorageWriter>, io::Error>> {
+        Box::pin(async move {
+            let mut transaction = self.pool.begin().await.map_err(to_io_error)?;
+
+            let oid: Oid = sqlx::query_scalar("SELECT lo_create(0)")
+                .fetch_one(&mut *transaction)
+                .await
+                .map_err(to_io_error)?;
+
+            let fd: i32 = sqlx::query_scalar("SELECT lo_open($1, $2)")
+                .bind(oid)
+                .bind(INV_WRITE)
+                .fetch_one(&mut *transaction)
+                .await
+                .map_err(to_io_error)?;
+
+            Ok(Box::new(DatabaseWriter {
+                transaction,
+                oid,
+                fd,
+            }) as Box<dyn StorageWriter>)
+        })
+    }
+
+    fn remove<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), io::Error>> {
+        Box::pin(async move {
+            let oid = id
+                .parse()
+                .map(Oid)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+
+            sqlx::query("SELECT lo_unlink($1)")
+                .bind(oid)
+                .execute(&self.pool)
+                .await
+                .map_err(to_io_error)?;
+
+            Ok(())
+        })
+    }
+}
+
+/// A large object which is being written. The transaction is rolled back if it is dropped before being finished, so that the large object is discarded.
+struct DatabaseWriter {
+    transaction: Transaction<'static, Postgres>,
+    oid:         Oid,
+    fd:          i32,
+}
+
+impl StorageWriter for DatabaseWriter {
+    fn write<'a>(&'a mut self, data: &'a [u8]) -> BoxFuture<'a, Result<(), io::Error>> {
+        Box::pin(async move {
+            sqlx::query("SELECT lowrite($1, $2)")
+                .bind(self.fd)
+                .bind(data)
+                .execute(&mut *self.transaction)
+                .await
+                .map_err(to_io_error)?;
+
+            Ok(())
+        })
+    }
+
+    fn finish(self: Box<Self>) -> BoxFuture<'static, Result<String, io::Error>> {
+        Box::pin(async move {
+            let DatabaseWriter {
+                mut transaction,
+                oid,
+                fd,
+            } = *self;
+
+            sqlx::query("SELECT lo_close($1)")
+                .bind(fd)
+                .execute(&mut *transaction)
+                .await
+                .map_err(to_io_error)?;
+
+            transaction.commit().await.map_err(to_io_error)?;
+
+            Ok(oid.0.to_string())
+        })
+    }
+}
+
+#[inline]
+fn to_io_error(err: sqlx::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, err)
+}
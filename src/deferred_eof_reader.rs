@@ -0,0 +1,109 @@
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use rocket::tokio::io::{AsyncRead, ReadBuf};
+
+/// A reader which returns `Poll::Pending` once before reporting the end of the data, if some data has been read since the last pending read.
+///
+/// `multer` looks for the first boundary by polling the stream twice in a row. If the first poll is pending and the second one gets the whole body along with its end, the body is rejected as an incomplete stream although the boundary has been received. Deferring the end makes it be seen by a later poll, after the received data has been scanned.
+pub(crate) struct DeferredEofReader<R> {
+    inner:     R,
+    read_data: bool,
+}
+
+impl<R> DeferredEofReader<R> {
+    #[inline]
+    pub(crate) fn new(inner: R) -> DeferredEofReader<R> {
+        DeferredEofReader {
+            inner,
+            read_data: false,
+        }
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for DeferredEofReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let filled = buf.filled().len();
+
+        match Pin::new(&mut self.inner).poll_read(cx, buf) {
+            Poll::Ready(Ok(())) => {
+                if buf.filled().len() > filled {
+                    self.read_data = true;
+                } else if buf.remaining() > 0 && self.read_data {
+                    // the end of the data, which is reported by the next read
+                    self.read_data = false;
+
+                    cx.waker().wake_by_ref();
+
+                    return Poll::Pending;
+                }
+
+                Poll::Ready(Ok(()))
+            },
+            Poll::Pending => {
+                self.read_data = false;
+
+                Poll::Pending
+            },
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A reader which is pending at first, and then returns all of its data at once.
+    struct SlowStartReader {
+        started: bool,
+        data:    &'static [u8],
+    }
+
+    impl AsyncRead for SlowStartReader {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            if !self.started {
+                self.started = true;
+
+                cx.waker().wake_by_ref();
+
+                return Poll::Pending;
+            }
+
+            let n = self.data.len().min(buf.remaining());
+
+            buf.put_slice(&self.data[..n]);
+
+            self.data = &self.data[n..];
+
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[rocket::async_test]
+    async fn body_and_end_after_pending_read() {
+        let reader = DeferredEofReader::new(SlowStartReader {
+            started: false,
+            data:    b"--XB\r\nContent-Disposition: form-data; name=\"t\"\r\n\r\nx\r\n--XB--\r\n",
+        });
+
+        let mut multipart = multer::Multipart::new(tokio_util::io::ReaderStream::new(reader), "XB");
+
+        let field = multipart.next_field().await.unwrap().unwrap();
+
+        assert_eq!(Some("t"), field.name());
+        assert_eq!("x", field.text().await.unwrap());
+        assert!(multipart.next_field().await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,99 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+/// A ceiling on the total size of the uploaded files which are kept in the temporary directory by all of the requests in progress, in order to protect the volume from filling up. It should be shared by the options of all routes.
+///
+/// The bytes of a request are counted while its files are being written, and they are given back when the `MultipartFormData` instance (or the `CleanupGuard` instance created by `into_background`) is dropped, even if some files have been taken out of it. Files stored by a `StorageBackend` and the derivatives created by post-processors are not counted.
+#[derive(Debug)]
+pub struct DiskBudget {
+    limit: u64,
+    used:  AtomicU64,
+}
+
+impl DiskBudget {
+    /// Create a `DiskBudget` instance which allows at most `limit` bytes.
+    #[inline]
+    pub fn new(limit: u64) -> DiskBudget {
+        DiskBudget {
+            limit,
+            used: AtomicU64::new(0),
+        }
+    }
+
+    /// The max number of bytes.
+    #[inline]
+    pub fn limit(&self) -> u64 {
+        self.limit
+    }
+
+    /// The number of bytes which are currently in use.
+    #[inline]
+    pub fn used(&self) -> u64 {
+        self.used.load(Ordering::Acquire)
+    }
+
+    /// Take `bytes` bytes if they are available.
+    fn try_acquire(&self, bytes: u64) -> bool {
+        self.used
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |used| {
+                used.checked_add(bytes).filter(|&used| used <= self.limit)
+            })
+            .is_ok()
+    }
+
+    #[inline]
+    fn release(&self, bytes: u64) {
+        self.used.fetch_sub(bytes, Ordering::AcqRel);
+    }
+}
+
+/// The bytes taken from a `DiskBudget` by a request, which are given back when it is dropped.
+#[derive(Debug, Default)]
+pub(crate) struct DiskCharge {
+    budget: Option<Arc<DiskBudget>>,
+    bytes:  u64,
+}
+
+impl DiskCharge {
+    #[inline]
+    pub(crate) fn new(budget: Option<Arc<DiskBudget>>) -> DiskCharge {
+        DiskCharge {
+            budget,
+            bytes: 0,
+        }
+    }
+
+    /// Take `bytes` more bytes. Return `false` if the budget is exhausted.
+    #[inline]
+    pub(crate) fn add(&mut self, bytes: u64) -> bool {
+        match self.budget.as_ref() {
+            Some(budget) => {
+                if !budget.try_acquire(bytes) {
+                    return false;
+                }
+
+                self.bytes += bytes;
+
+                true
+            },
+            None => true,
+        }
+    }
+
+    /// Take over the bytes of `other`, which must be taken from the same budget.
+    #[inline]
+    pub(crate) fn absorb(&mut self, other: &mut DiskCharge) {
+        self.bytes += std::mem::take(&mut other.bytes);
+    }
+}
+
+impl Drop for DiskCharge {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(budget) = self.budget.as_ref() {
+            budget.release(self.bytes);
+        }
+    }
+}
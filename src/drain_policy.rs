@@ -0,0 +1,101 @@
+/// What to do with the rest of the HTTP body when parsing fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DrainPolicy {
+    /// Read and discard the whole rest of the body, so that the connection can be reused.
+    #[default]
+    Full,
+    /// Read and discard at most the specified number of bytes of the field data.
+    UpTo(u64),
+    /// Stop reading immediately. The connection will be closed by Rocket.
+    Abort,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io,
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    use rocket::tokio::io::{AsyncRead, ReadBuf};
+
+    use super::*;
+    use crate::{MultipartFormData, MultipartFormDataField, MultipartFormDataOptions};
+
+    /// A reader which is pending before every read, like a network connection, so that the parser does not read ahead of what it needs.
+    struct NetworkReader {
+        data:     Vec<u8>,
+        position: usize,
+        ready:    bool,
+    }
+
+    impl AsyncRead for NetworkReader {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<io::Result<()>> {
+            if !self.ready {
+                self.ready = true;
+
+                cx.waker().wake_by_ref();
+
+                return Poll::Pending;
+            }
+
+            self.ready = false;
+
+            let n = (self.data.len() - self.position).min(buf.remaining());
+
+            buf.put_slice(&self.data[self.position..self.position + n]);
+
+            self.position += n;
+
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    /// Parse a body whose first field is invalid, and return how many bytes of it have been read.
+    async fn bytes_read(drain_on_error: DrainPolicy) -> (usize, usize) {
+        let mut data = Vec::new();
+
+        data.extend_from_slice(b"--XB\r\nContent-Disposition: form-data; name=\"n\"\r\n\r\nx\r\n");
+        data.extend_from_slice(b"--XB\r\nContent-Disposition: form-data; name=\"skip\"\r\n\r\n");
+        data.extend_from_slice(&[b'a'; 1024 * 1024]);
+        data.extend_from_slice(b"\r\n--XB--\r\n");
+
+        let length = data.len();
+
+        let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+            MultipartFormDataField::number::<u8>("n"),
+        ]);
+
+        options.drain_on_error = drain_on_error;
+
+        let mut reader = NetworkReader {
+            data,
+            position: 0,
+            ready: false,
+        };
+
+        MultipartFormData::parse_reader(&mut reader, "XB", options).await.unwrap_err();
+
+        (reader.position, length)
+    }
+
+    #[rocket::async_test]
+    async fn drain_policies() {
+        let (read, length) = bytes_read(DrainPolicy::Full).await;
+
+        assert_eq!(length, read);
+
+        let (read, _) = bytes_read(DrainPolicy::UpTo(64 * 1024)).await;
+
+        assert!(read > 64 * 1024 && read < length / 2, "{}", read);
+
+        let (read, _) = bytes_read(DrainPolicy::Abort).await;
+
+        assert!(read < 64 * 1024, "{}", read);
+    }
+}
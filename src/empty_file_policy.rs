@@ -0,0 +1,11 @@
+/// What to do with a part which looks like an empty file input of an HTML form, i.e. a part whose file name is an empty string and whose data is empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EmptyFilePolicy {
+    /// Drop the part. A `ParseWarning::EmptyFileIgnored` warning is recorded.
+    #[default]
+    Ignore,
+    /// Keep the part as an empty field. Its content type is not checked.
+    KeepEmpty,
+    /// Fail with an `EmptyFileError`.
+    Error,
+}
@@ -0,0 +1,299 @@
+use std::{
+    fmt::{self, Debug, Formatter},
+    io,
+    path::Path,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use aes_gcm::{
+    aead::{rand_core::RngCore, Aead, KeyInit, OsRng, Payload},
+    Aes256Gcm, Key, Nonce,
+};
+use rocket::tokio::{
+    fs::File,
+    io::{AsyncRead, ReadBuf},
+};
+
+use crate::{multer::bytes::Bytes, FileField, KeyProvider, LargeTextField};
+
+/// The number of plaintext bytes in every encrypted frame.
+const FRAME_SIZE: usize = 64 * 1024;
+const PREFIX_SIZE: usize = 8;
+const FRAME_HEADER_SIZE: usize = 5;
+
+// An encrypted file consists of a random nonce prefix and frames. Every frame consists of a flag which marks the last frame, the length of the ciphertext in big-endian and the ciphertext. The nonce of a frame is the prefix followed by the index of the frame in big-endian, and the flag is authenticated as associated data so that truncated files can be detected.
+
+#[inline]
+fn nonce(prefix: &[u8; PREFIX_SIZE], counter: u32) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+
+    nonce[..PREFIX_SIZE].copy_from_slice(prefix);
+    nonce[PREFIX_SIZE..].copy_from_slice(&counter.to_be_bytes());
+
+    nonce
+}
+
+/// Get the length of the plaintext of an encrypted file from the length of the file.
+pub(crate) fn plaintext_len(encrypted_len: u64) -> u64 {
+    const FRAME_OVERHEAD: u64 = FRAME_HEADER_SIZE as u64 + 16;
+
+    let frames_len = encrypted_len.saturating_sub(PREFIX_SIZE as u64);
+
+    // Every frame is full except the last one, which is not empty unless it is the only one
+    let frames = ((frames_len + FRAME_SIZE as u64 + FRAME_OVERHEAD - 1)
+        / (FRAME_SIZE as u64 + FRAME_OVERHEAD))
+        .max(1);
+
+    frames_len.saturating_sub(frames * FRAME_OVERHEAD)
+}
+
+#[inline]
+fn aead_error() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "The encrypted file is corrupted.")
+}
+
+#[inline]
+fn trailing_data_error() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, "The encrypted file has data after its last frame.")
+}
+
+/// Encrypts the data of a file field on the fly.
+pub(crate) struct Encryptor {
+    cipher:  Aes256Gcm,
+    prefix:  [u8; PREFIX_SIZE],
+    counter: u32,
+    pending: Vec<u8>,
+    started: bool,
+}
+
+impl Encryptor {
+    pub(crate) fn new(key_provider: &dyn KeyProvider) -> Encryptor {
+        let mut prefix = [0u8; PREFIX_SIZE];
+
+        OsRng.fill_bytes(&mut prefix);
+
+        Encryptor {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key_provider.key())),
+            prefix,
+            counter: 0,
+            pending: Vec::with_capacity(FRAME_SIZE),
+            started: false,
+        }
+    }
+
+    /// Encrypt a chunk. Return the bytes which should be written to the file.
+    pub(crate) fn update(&mut self, mut bytes: &[u8]) -> Result<Bytes, io::Error> {
+        let mut output = self.start();
+
+        while !bytes.is_empty() {
+            // A full frame is kept pending until more data comes, because the last frame has to be marked
+            if self.pending.len() == FRAME_SIZE {
+                self.encrypt_pending(&mut output, false)?;
+            }
+
+            let n = (FRAME_SIZE - self.pending.len()).min(bytes.len());
+
+            self.pending.extend_from_slice(&bytes[..n]);
+            bytes = &bytes[n..];
+        }
+
+        Ok(Bytes::from(output))
+    }
+
+    /// Encrypt the pending data as the last frame.
+    pub(crate) fn finish(mut self) -> Result<Bytes, io::Error> {
+        let mut output = self.start();
+
+        self.encrypt_pending(&mut output, true)?;
+
+        Ok(Bytes::from(output))
+    }
+
+    fn start(&mut self) -> Vec<u8> {
+        if self.started {
+            Vec::new()
+        } else {
+            self.started = true;
+
+            self.prefix.to_vec()
+        }
+    }
+
+    fn encrypt_pending(&mut self, output: &mut Vec<u8>, last: bool) -> Result<(), io::Error> {
+        let flag = [last as u8];
+
+        let ciphertext = self
+            .cipher
+            .encrypt(Nonce::from_slice(&nonce(&self.prefix, self.counter)), Payload {
+                msg: &self.pending,
+                aad: &flag,
+            })
+            .map_err(|_| aead_error())?;
+
+        self.counter = self.counter.checked_add(1).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::Other, "The file is too large to be encrypted.")
+        })?;
+        self.pending.clear();
+
+        output.push(flag[0]);
+        output.extend_from_slice(&(ciphertext.len() as u32).to_be_bytes());
+        output.extend_from_slice(&ciphertext);
+
+        Ok(())
+    }
+}
+
+/// A reader which decrypts an encrypted uploaded file. It is created by `FileField::decrypting_reader`.
+pub struct DecryptingReader {
+    file:     File,
+    cipher:   Aes256Gcm,
+    prefix:   Option<[u8; PREFIX_SIZE]>,
+    counter:  u32,
+    input:    Vec<u8>,
+    output:   Vec<u8>,
+    position: usize,
+    finished: bool,
+}
+
+impl Debug for DecryptingReader {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("DecryptingReader").field("file", &self.file).finish()
+    }
+}
+
+impl DecryptingReader {
+    async fn open(
+        path: &Path,
+        key_provider: &dyn KeyProvider,
+    ) -> Result<DecryptingReader, io::Error> {
+        Ok(DecryptingReader {
+            file:     File::open(path).await?,
+            cipher:   Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key_provider.key())),
+            prefix:   None,
+            counter:  0,
+            input:    Vec::new(),
+            output:   Vec::new(),
+            position: 0,
+            finished: false,
+        })
+    }
+
+    /// Decrypt a complete frame in the input buffer if any. Return whether a frame has been decrypted.
+    fn decrypt_frame(&mut self) -> Result<bool, io::Error> {
+        let prefix = match self.prefix {
+            Some(prefix) => prefix,
+            None => {
+                if self.input.len() < PREFIX_SIZE {
+                    return Ok(false);
+                }
+
+                let mut prefix = [0u8; PREFIX_SIZE];
+
+                prefix.copy_from_slice(&self.input[..PREFIX_SIZE]);
+                self.input.drain(..PREFIX_SIZE);
+                self.prefix = Some(prefix);
+
+                prefix
+            },
+        };
+
+        if self.input.len() < FRAME_HEADER_SIZE {
+            return Ok(false);
+        }
+
+        let flag = self.input[0];
+        let length =
+            u32::from_be_bytes(self.input[1..FRAME_HEADER_SIZE].try_into().unwrap()) as usize;
+
+        if self.input.len() < FRAME_HEADER_SIZE + length {
+            return Ok(false);
+        }
+
+        self.output = self
+            .cipher
+            .decrypt(Nonce::from_slice(&nonce(&prefix, self.counter)), Payload {
+                msg: &self.input[FRAME_HEADER_SIZE..FRAME_HEADER_SIZE + length],
+                aad: &[flag],
+            })
+            .map_err(|_| aead_error())?;
+        self.position = 0;
+
+        self.input.drain(..FRAME_HEADER_SIZE + length);
+        self.counter = self.counter.checked_add(1).ok_or_else(aead_error)?;
+        self.finished = flag == 1;
+
+        Ok(true)
+    }
+}
+
+impl AsyncRead for DecryptingReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<(), io::Error>> {
+        let this = self.get_mut();
+
+        loop {
+            if this.position < this.output.len() {
+                let n = (this.output.len() - this.position).min(buf.remaining());
+
+                buf.put_slice(&this.output[this.position..this.position + n]);
+                this.position += n;
+
+                return Poll::Ready(Ok(()));
+            }
+
+            // Nothing may follow the last frame
+            if this.finished && !this.input.is_empty() {
+                return Poll::Ready(Err(trailing_data_error()));
+            }
+
+            if !this.finished && this.decrypt_frame()? {
+                continue;
+            }
+
+            let mut chunk = [0u8; 8192];
+            let mut chunk_buf = ReadBuf::new(&mut chunk);
+
+            ready!(Pin::new(&mut this.file).poll_read(cx, &mut chunk_buf))?;
+
+            if chunk_buf.filled().is_empty() {
+                if this.finished {
+                    return Poll::Ready(Ok(()));
+                }
+
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "The encrypted file is truncated.",
+                )));
+            }
+
+            this.input.extend_from_slice(chunk_buf.filled());
+        }
+    }
+}
+
+impl FileField {
+    /// Open the uploaded file and decrypt it on the fly. If the file is not encrypted, an error is returned.
+    pub async fn decrypting_reader(&self) -> Result<DecryptingReader, io::Error> {
+        let key_provider = self.encryption.as_ref().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "The uploaded file is not encrypted.")
+        })?;
+
+        DecryptingReader::open(&self.path, key_provider.as_ref()).await
+    }
+}
+
+impl LargeTextField {
+    /// Open the file of the text and decrypt it on the fly. If the file is not encrypted, an error is returned.
+    pub async fn decrypting_reader(&self) -> Result<DecryptingReader, io::Error> {
+        let key_provider = self.encryption.as_ref().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "The large text is not encrypted.")
+        })?;
+
+        DecryptingReader::open(&self.path, key_provider.as_ref()).await
+    }
+}
@@ -0,0 +1,170 @@
+#[cfg(feature = "serde")]
+use serde::Serialize;
+
+use crate::{MessageCatalog, MultipartFormDataError};
+
+/// A machine-readable report of a `MultipartFormDataError`, which can be returned by JSON APIs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+pub struct ErrorReport {
+    /// A stable identifier of the kind of the error, such as `data_too_large`.
+    pub code:    &'static str,
+    /// The name of the field which causes the error, if any.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub field:   Option<String>,
+    /// A human-readable message.
+    pub message: String,
+    /// Additional information, such as the rejected value or the underlying error.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub details: Option<String>,
+}
+
+impl MultipartFormDataError {
+    /// Create an `ErrorReport` of this error.
+    pub fn to_report(&self) -> ErrorReport {
+        #[allow(deprecated)]
+        let (code, field, details) = match self {
+            MultipartFormDataError::NotFormDataError => ("not_form_data", None, None),
+            MultipartFormDataError::WrongContentTypeError(content_type) => {
+                ("not_form_data", None, Some(content_type.to_string()))
+            },
+            MultipartFormDataError::MissingBoundaryError
+            | MultipartFormDataError::BoundaryNotFoundError => ("boundary_not_found", None, None),
+            MultipartFormDataError::InvalidBoundaryError => ("invalid_boundary", None, None),
+            MultipartFormDataError::IOError(err) => ("io", None, Some(err.to_string())),
+            MultipartFormDataError::MulterError(err) => ("malformed", None, Some(err.to_string())),
+            MultipartFormDataError::FromUtf8Error(err) => {
+                ("invalid_utf8", None, Some(err.to_string()))
+            },
+            MultipartFormDataError::DataTooLargeError(field) => {
+                ("data_too_large", Some(field.as_ref()), None)
+            },
+            MultipartFormDataError::DataLimitExceededError {
+                source, ..
+            } => ("data_limit_exceeded", None, Some(source.to_string())),
+            MultipartFormDataError::DataTypeError(field) => {
+                ("data_type", Some(field.as_ref()), None)
+            },
+            MultipartFormDataError::InvalidValueError {
+                field,
+                got,
+            } => ("invalid_value", Some(field.as_ref()), Some(got.clone())),
+            MultipartFormDataError::InvalidNumberError {
+                field,
+                got,
+            } => ("invalid_number", Some(field.as_ref()), Some(got.clone())),
+            MultipartFormDataError::NumberOutOfRangeError {
+                field,
+                got,
+            } => ("number_out_of_range", Some(field.as_ref()), Some(got.to_string())),
+            #[cfg(feature = "chrono")]
+            MultipartFormDataError::InvalidDateTimeError {
+                field,
+                got,
+            } => ("invalid_datetime", Some(field.as_ref()), Some(got.clone())),
+            #[cfg(feature = "csv")]
+            MultipartFormDataError::InvalidCsvError {
+                field,
+                error,
+            } => ("invalid_csv", Some(field.as_ref()), Some(error.to_string())),
+            #[cfg(feature = "xml")]
+            MultipartFormDataError::InvalidXmlError {
+                field,
+                error,
+            } => ("invalid_xml", Some(field.as_ref()), Some(error.to_string())),
+            MultipartFormDataError::OptionsError(err) => {
+                ("invalid_options", None, Some(err.to_string()))
+            },
+            MultipartFormDataError::PostProcessError {
+                field,
+                error,
+            } => ("post_process_failed", Some(field.as_ref()), Some(error.to_string())),
+            MultipartFormDataError::QuotaExceededError => ("quota_exceeded", None, None),
+            MultipartFormDataError::AdmissionDeniedError(denied) => {
+                ("admission_denied", None, Some(denied.reason().to_string()))
+            },
+            MultipartFormDataError::UnnamedPartError => ("unnamed_part", None, None),
+            MultipartFormDataError::FieldNameTooLongError(name) => {
+                ("field_name_too_long", Some(name.as_str()), None)
+            },
+            MultipartFormDataError::NonAsciiFieldNameError(name) => {
+                ("non_ascii_field_name", Some(name.as_str()), None)
+            },
+            MultipartFormDataError::InterruptedError {
+                error, ..
+            } => ("interrupted", None, Some(error.to_string())),
+            MultipartFormDataError::ServerStorageExhaustedError => {
+                ("server_storage_exhausted", None, None)
+            },
+            MultipartFormDataError::ShutdownError => ("shutdown", None, None),
+            MultipartFormDataError::PartHeadersTooLargeError => {
+                ("part_headers_too_large", None, None)
+            },
+            MultipartFormDataError::TrailingDataError(bytes) => {
+                ("trailing_data", None, Some(bytes.to_string()))
+            },
+            MultipartFormDataError::MemoryLimitExceededError => {
+                ("memory_limit_exceeded", None, None)
+            },
+            MultipartFormDataError::TooManyTextPartsError => ("too_many_text_parts", None, None),
+            MultipartFormDataError::TooManyFilePartsError => ("too_many_file_parts", None, None),
+            MultipartFormDataError::FieldRejectedError(field) => {
+                ("field_rejected", Some(field.as_ref()), None)
+            },
+            MultipartFormDataError::EmptyFileError(field) => {
+                ("empty_file", Some(field.as_ref()), None)
+            },
+            MultipartFormDataError::FieldTimeoutError(field) => {
+                ("field_timeout", Some(field.as_ref()), None)
+            },
+            MultipartFormDataError::NotEnoughRepetitionsError {
+                field,
+                min,
+            } => ("not_enough_repetitions", Some(field.as_ref()), Some(min.to_string())),
+            MultipartFormDataError::FieldCountError {
+                field,
+                got,
+                ..
+            } => ("wrong_field_count", Some(field.as_ref()), Some(got.to_string())),
+            MultipartFormDataError::MappingError {
+                field,
+                error,
+            } => ("mapping_failed", Some(field.as_ref()), Some(error.to_string())),
+            #[cfg(feature = "prost")]
+            MultipartFormDataError::InvalidProtobufError {
+                field,
+                error,
+            } => ("invalid_protobuf", Some(field.as_ref()), Some(error.to_string())),
+            MultipartFormDataError::TooManyFilesError(field) => {
+                ("too_many_files", Some(field.as_ref()), None)
+            },
+            MultipartFormDataError::NestingTooDeepError(field) => {
+                ("nesting_too_deep", Some(field.as_ref()), None)
+            },
+            MultipartFormDataError::ArchiveRejectedError {
+                field,
+                violation,
+            } => ("archive_rejected", Some(field.as_ref()), Some(violation.to_string())),
+            #[cfg(feature = "sanitize")]
+            MultipartFormDataError::ActiveContentError(field) => {
+                ("active_content", Some(field.as_ref()), None)
+            },
+        };
+
+        ErrorReport {
+            code,
+            field: field.map(String::from),
+            message: self.to_string(),
+            details,
+        }
+    }
+
+    /// Create an `ErrorReport` of this error, whose message is taken from `catalog`.
+    #[inline]
+    pub fn to_report_with(&self, catalog: &dyn MessageCatalog) -> ErrorReport {
+        ErrorReport {
+            message: self.message(catalog),
+            ..self.to_report()
+        }
+    }
+}
@@ -0,0 +1,152 @@
+use std::{collections::HashMap, sync::Arc};
+
+#[cfg(feature = "csv")]
+use crate::CsvField;
+#[cfg(feature = "chrono")]
+use crate::DateTimeField;
+#[cfg(feature = "xml")]
+use crate::XmlField;
+use crate::{
+    FileField, LargeTextField, MultipartFormData, NumberField, RawField, StoredField, TextField,
+};
+
+/// All parsed fields of a field name, regardless of which map of the `MultipartFormData` instance they are in.
+#[derive(Debug, Clone, Copy)]
+pub struct FieldEntry<'a> {
+    files:       &'a [FileField],
+    raws:        &'a [RawField],
+    texts:       &'a [TextField],
+    numbers:     &'a [NumberField],
+    stored:      &'a [StoredField],
+    large_texts: &'a [LargeTextField],
+    #[cfg(feature = "chrono")]
+    datetimes:   &'a [DateTimeField],
+    #[cfg(feature = "csv")]
+    csv:         &'a [CsvField],
+    #[cfg(feature = "xml")]
+    xml:         &'a [XmlField],
+}
+
+impl<'a> FieldEntry<'a> {
+    /// The file fields.
+    #[inline]
+    pub fn files(&self) -> &'a [FileField] {
+        self.files
+    }
+
+    /// The raw fields.
+    #[inline]
+    pub fn raws(&self) -> &'a [RawField] {
+        self.raws
+    }
+
+    /// The text fields.
+    #[inline]
+    pub fn texts(&self) -> &'a [TextField] {
+        self.texts
+    }
+
+    /// The number fields.
+    #[inline]
+    pub fn numbers(&self) -> &'a [NumberField] {
+        self.numbers
+    }
+
+    /// The fields stored by the `StorageBackend`.
+    #[inline]
+    pub fn stored(&self) -> &'a [StoredField] {
+        self.stored
+    }
+
+    /// The text fields which are stored in files.
+    #[inline]
+    pub fn large_texts(&self) -> &'a [LargeTextField] {
+        self.large_texts
+    }
+
+    /// The date-time fields.
+    #[cfg(feature = "chrono")]
+    #[inline]
+    pub fn datetimes(&self) -> &'a [DateTimeField] {
+        self.datetimes
+    }
+
+    /// The CSV fields.
+    #[cfg(feature = "csv")]
+    #[inline]
+    pub fn csv(&self) -> &'a [CsvField] {
+        self.csv
+    }
+
+    /// The XML fields.
+    #[cfg(feature = "xml")]
+    #[inline]
+    pub fn xml(&self) -> &'a [XmlField] {
+        self.xml
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        let is_empty = self.files.is_empty()
+            && self.raws.is_empty()
+            && self.texts.is_empty()
+            && self.numbers.is_empty()
+            && self.stored.is_empty()
+            && self.large_texts.is_empty();
+
+        #[cfg(feature = "chrono")]
+        let is_empty = is_empty && self.datetimes.is_empty();
+
+        #[cfg(feature = "csv")]
+        let is_empty = is_empty && self.csv.is_empty();
+
+        #[cfg(feature = "xml")]
+        let is_empty = is_empty && self.xml.is_empty();
+
+        is_empty
+    }
+}
+
+impl MultipartFormData {
+    /// Look up the fields of a specific field name at one point. It is useful when a field can be sent as a file by some clients and as a text by the others. Return `None` if there is no such field.
+    pub fn field(&self, field_name: &str) -> Option<FieldEntry<'_>> {
+        let files = slice(&self.files, field_name);
+        let raws = slice(&self.raw, field_name);
+        let texts = slice(&self.texts, field_name);
+        let numbers = slice(&self.numbers, field_name);
+        let stored = slice(&self.stored, field_name);
+        let large_texts = slice(&self.large_texts, field_name);
+        #[cfg(feature = "chrono")]
+        let datetimes = slice(&self.datetimes, field_name);
+        #[cfg(feature = "csv")]
+        let csv = slice(&self.csv, field_name);
+        #[cfg(feature = "xml")]
+        let xml = slice(&self.xml, field_name);
+
+        let entry = FieldEntry {
+            files,
+            raws,
+            texts,
+            numbers,
+            stored,
+            large_texts,
+            #[cfg(feature = "chrono")]
+            datetimes,
+            #[cfg(feature = "csv")]
+            csv,
+            #[cfg(feature = "xml")]
+            xml,
+        };
+
+        if entry.is_empty() {
+            None
+        } else {
+            Some(entry)
+        }
+    }
+}
+
+#[inline]
+fn slice<'a, T>(map: &'a HashMap<Arc<str>, Vec<T>>, field_name: &str) -> &'a [T] {
+    map.get(field_name).map(Vec::as_slice).unwrap_or_default()
+}
@@ -0,0 +1,48 @@
+use std::fmt::{self, Debug, Formatter};
+
+use http::HeaderMap;
+
+use crate::mime::Mime;
+
+/// The metadata of a part, which is available before its data is read.
+#[derive(Debug, Clone, Copy)]
+pub struct FieldMeta<'a> {
+    pub name:           &'a str,
+    pub file_name:      Option<&'a str>,
+    pub content_type:   Option<&'a Mime>,
+    /// The `Content-Length` header of the part, if the client sends one.
+    pub content_length: Option<u64>,
+    pub headers:        &'a HeaderMap,
+}
+
+/// What to do with a part.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldDecision {
+    /// Go on processing the part according to the allowed fields.
+    Accept,
+    /// Ignore the part without reading its data.
+    Skip,
+    /// Stop parsing and fail with a `FieldRejectedError`.
+    Reject,
+}
+
+/// A filter which is invoked before the data of every named part is read, in order to decide whether to accept it dynamically, such as rejecting `.exe` files.
+///
+/// It is implemented for closures of type `Fn(&FieldMeta) -> FieldDecision`.
+pub trait FieldFilter: Send + Sync {
+    fn on_field(&self, meta: &FieldMeta<'_>) -> FieldDecision;
+}
+
+impl<F: Fn(&FieldMeta<'_>) -> FieldDecision + Send + Sync> FieldFilter for F {
+    #[inline]
+    fn on_field(&self, meta: &FieldMeta<'_>) -> FieldDecision {
+        self(meta)
+    }
+}
+
+impl Debug for dyn FieldFilter {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("FieldFilter")
+    }
+}
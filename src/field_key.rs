@@ -0,0 +1,81 @@
+use std::{collections::HashMap, hash::Hash};
+
+use rocket::{http::ContentType, Data};
+
+use crate::{
+    MultipartFormData, MultipartFormDataError, MultipartFormDataField, MultipartFormDataOptions,
+    NamedField,
+};
+
+/// A key of the fields of a form, which is usually implemented for an enum, so that parsed fields can be looked up without string literals.
+///
+/// ```
+/// use rocket_multipart_form_data::{FieldKey, MultipartFormDataField};
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// enum Key {
+///     Photo,
+///     Title,
+/// }
+///
+/// impl FieldKey for Key {
+///     fn all() -> &'static [Key] {
+///         &[Key::Photo, Key::Title]
+///     }
+///
+///     fn field(&self) -> MultipartFormDataField<'static> {
+///         match self {
+///             Key::Photo => MultipartFormDataField::file("photo")
+///                 .size_limit(4 * 1024 * 1024),
+///             Key::Title => MultipartFormDataField::text("title"),
+///         }
+///     }
+/// }
+/// ```
+pub trait FieldKey: Copy + Eq + Hash + 'static {
+    /// All of the keys.
+    fn all() -> &'static [Self];
+
+    /// The declaration of the field of this key, which includes the field name, the type and the limits.
+    fn field(&self) -> MultipartFormDataField<'static>;
+
+    /// The field name of this key.
+    #[inline]
+    fn name(&self) -> &'static str {
+        self.field().field_name
+    }
+}
+
+impl MultipartFormData {
+    /// Parse multipart/form-data from the HTTP body with the fields declared by `K`, which are added to the allowed fields of `options`. The parsed fields are grouped by their keys in the order in which they arrived. Checkbox fields are not included, and the uploaded files will not be deleted automatically, as the `into_fields` method.
+    pub async fn parse_typed<K: FieldKey>(
+        content_type: &ContentType,
+        data: Data<'_>,
+        mut options: MultipartFormDataOptions<'_>,
+    ) -> Result<HashMap<K, Vec<NamedField>>, MultipartFormDataError> {
+        for key in K::all() {
+            options.allowed_fields.push(key.field());
+        }
+
+        let multipart_form_data = MultipartFormData::parse(content_type, data, options).await?;
+
+        Ok(group_by_key(multipart_form_data))
+    }
+}
+
+/// Group the fields of `multipart_form_data` by their keys. The fields whose names are not declared by `K` are dropped.
+fn group_by_key<K: FieldKey>(
+    multipart_form_data: MultipartFormData,
+) -> HashMap<K, Vec<NamedField>> {
+    let keys: HashMap<&'static str, K> = K::all().iter().map(|k| (k.name(), *k)).collect();
+
+    let mut map: HashMap<K, Vec<NamedField>> = HashMap::new();
+
+    for field in multipart_form_data.into_fields() {
+        if let Some(key) = keys.get(field.name().as_ref()) {
+            map.entry(*key).or_default().push(field);
+        }
+    }
+
+    map
+}
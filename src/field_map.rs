@@ -0,0 +1,46 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::MultipartFormDataError;
+
+/// Methods of the maps of parsed fields, such as `MultipartFormData::texts`.
+///
+/// ```
+/// use std::{collections::HashMap, sync::Arc};
+///
+/// use rocket_multipart_form_data::{FieldMap, TextField};
+///
+/// let mut texts: HashMap<Arc<str>, Vec<TextField>> = HashMap::new();
+///
+/// assert!(texts.remove_exactly::<2>("email").is_err());
+/// ```
+pub trait FieldMap<T> {
+    /// Remove the fields of a specific field name, which must have been sent exactly `N` times. Otherwise, a `FieldCountError` is returned and the fields are kept.
+    fn remove_exactly<const N: usize>(
+        &mut self,
+        field_name: &str,
+    ) -> Result<[T; N], MultipartFormDataError>;
+}
+
+impl<T> FieldMap<T> for HashMap<Arc<str>, Vec<T>> {
+    fn remove_exactly<const N: usize>(
+        &mut self,
+        field_name: &str,
+    ) -> Result<[T; N], MultipartFormDataError> {
+        let got = self.get(field_name).map_or(0, Vec::len);
+
+        if got != N {
+            return Err(MultipartFormDataError::FieldCountError {
+                field: Arc::from(field_name),
+                expected: N,
+                got,
+            });
+        }
+
+        let fields = self.remove(field_name).unwrap_or_default();
+
+        match fields.try_into() {
+            Ok(fields) => Ok(fields),
+            Err(_) => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use std::{
+    error::Error,
+    fmt::{self, Debug, Formatter},
+};
+
+/// A transformation of the text of a text-based field, which is applied before the text is checked and stored, such as formatting phone numbers.
+pub trait TextMapper: Send + Sync {
+    /// Transform the text. An error causes a `MappingError`.
+    fn map(&self, text: String) -> Result<String, Box<dyn Error + Send + Sync>>;
+}
+
+impl Debug for dyn TextMapper {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("TextMapper")
+    }
+}
+
+impl<F> TextMapper for F
+where
+    F: Fn(String) -> Result<String, Box<dyn Error + Send + Sync>> + Send + Sync,
+{
+    #[inline]
+    fn map(&self, text: String) -> Result<String, Box<dyn Error + Send + Sync>> {
+        self(text)
+    }
+}
+
+/// A transformation of the data of a raw field, which is applied before the data is stored, such as decoding base64.
+pub trait RawMapper: Send + Sync {
+    /// Transform the data. An error causes a `MappingError`.
+    fn map(&self, raw: Vec<u8>) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>>;
+}
+
+impl Debug for dyn RawMapper {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("RawMapper")
+    }
+}
+
+impl<F> RawMapper for F
+where
+    F: Fn(Vec<u8>) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> + Send + Sync,
+{
+    #[inline]
+    fn map(&self, raw: Vec<u8>) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> {
+        self(raw)
+    }
+}
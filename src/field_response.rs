@@ -0,0 +1,240 @@
+use std::{
+    io::{self, Cursor, SeekFrom},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use rocket::{
+    http::{ContentType, Status},
+    response::{self, Responder, Response},
+    tokio::{
+        fs::File,
+        io::{AsyncRead, AsyncSeek, ReadBuf},
+    },
+    Request,
+};
+
+#[cfg(feature = "encryption")]
+use crate::DecryptingReader;
+use crate::{fields::client_file_name, mime::Mime, CleanupGuard, FileField, RawField};
+
+/// A responder which sends the data of a raw field as it is, without copying it. It is created by the `into_response_body` method of `RawField`.
+///
+/// The data is sent as an attachment with `X-Content-Type-Options: nosniff`, so that the browser downloads it instead of rendering it, unless the `inline` method is called.
+#[derive(Debug)]
+pub struct RawFieldResponse {
+    content_type: Option<Mime>,
+    file_name:    Option<String>,
+    inline:       bool,
+    data:         Vec<u8>,
+}
+
+impl RawFieldResponse {
+    /// Let the browser show the data inline instead of downloading it.
+    ///
+    /// The content type and the data are from the client, so a document which is uploaded by someone can run scripts on the site of this server when it is shown inline (stored XSS). Note that `image/svg+xml` is an image type which can contain scripts, so `image/*` is not safe. Only call this method when the content type has been checked against an allowlist of raster image types, such as `image/png`, `image/jpeg`, `image/gif` and `image/webp`.
+    #[inline]
+    pub fn inline(mut self) -> RawFieldResponse {
+        self.inline = true;
+
+        self
+    }
+}
+
+impl<'r> Responder<'r, 'static> for RawFieldResponse {
+    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'static> {
+        let mut response = Response::build();
+
+        response
+            .header(content_type(self.content_type.as_ref()))
+            .raw_header(
+                "Content-Disposition",
+                content_disposition(self.inline, self.file_name.as_deref()),
+            )
+            .raw_header("X-Content-Type-Options", "nosniff");
+
+        response.sized_body(self.data.len(), Cursor::new(self.data));
+
+        response.ok()
+    }
+}
+
+impl RawField {
+    /// Turn this raw field into a responder which sends its data, along with its content type and file name. The data is moved into the response instead of being copied.
+    #[inline]
+    pub fn into_response_body(self) -> RawFieldResponse {
+        RawFieldResponse {
+            content_type: self.content_type,
+            file_name:    self.file_name,
+            inline:       false,
+            data:         self.raw,
+        }
+    }
+}
+
+/// The data of an uploaded file which is being sent.
+#[derive(Debug)]
+enum FileBody {
+    Plain(File),
+    #[cfg(feature = "encryption")]
+    Decrypted(Box<DecryptingReader>),
+}
+
+/// A responder which streams an uploaded file. The file and its derivatives are deleted after the response has been sent, or when the response is dropped. It is created by the `into_response` method of `FileField`.
+///
+/// The file is sent as an attachment with `X-Content-Type-Options: nosniff`, so that the browser downloads it instead of rendering it, unless the `inline` method is called.
+#[derive(Debug)]
+pub struct FileFieldResponse {
+    content_type: Option<Mime>,
+    file_name:    Option<String>,
+    inline:       bool,
+    size:         u64,
+    body:         FileBody,
+    guard:        CleanupGuard,
+}
+
+impl FileFieldResponse {
+    /// Let the browser show the file inline instead of downloading it.
+    ///
+    /// The content type and the file are from the client, so a document which is uploaded by someone can run scripts on the site of this server when it is shown inline (stored XSS). Note that `image/svg+xml` is an image type which can contain scripts, so `image/*` is not safe. Only call this method when the content type has been checked against an allowlist of raster image types, such as `image/png`, `image/jpeg`, `image/gif` and `image/webp`.
+    #[inline]
+    pub fn inline(mut self) -> FileFieldResponse {
+        self.inline = true;
+
+        self
+    }
+}
+
+impl<'r> Responder<'r, 'static> for FileFieldResponse {
+    fn respond_to(self, _: &'r Request<'_>) -> response::Result<'static> {
+        let mut response = Response::build();
+
+        response
+            .header(content_type(self.content_type.as_ref()))
+            .raw_header(
+                "Content-Disposition",
+                content_disposition(self.inline, self.file_name.as_deref()),
+            )
+            .raw_header("X-Content-Type-Options", "nosniff");
+
+        match self.body {
+            FileBody::Plain(file) => {
+                let size = usize::try_from(self.size).map_err(|_| Status::InternalServerError)?;
+
+                response.sized_body(size, GuardedBody {
+                    inner: file, _guard: self.guard
+                });
+            },
+            #[cfg(feature = "encryption")]
+            FileBody::Decrypted(reader) => {
+                response.streamed_body(GuardedBody {
+                    inner: reader, _guard: self.guard
+                });
+            },
+        }
+
+        response.ok()
+    }
+}
+
+impl FileField {
+    /// Turn this file field into a responder which streams the uploaded file, along with its content type and file name. An encrypted file is decrypted. The file and its derivatives are deleted after the response has been sent, unless the file is persistent.
+    ///
+    /// The file should have been removed from the `MultipartFormData` instance, such as by the `remove_one_file` method, so that it is not deleted while it is being sent.
+    pub async fn into_response(self) -> Result<FileFieldResponse, io::Error> {
+        #[cfg(feature = "encryption")]
+        let body = if self.encryption.is_some() {
+            FileBody::Decrypted(Box::new(self.decrypting_reader().await?))
+        } else {
+            FileBody::Plain(self.open().await?)
+        };
+        #[cfg(not(feature = "encryption"))]
+        let body = FileBody::Plain(self.open().await?);
+
+        let mut guard = CleanupGuard::new();
+
+        for path in self.paths() {
+            guard.push(path);
+        }
+
+        Ok(FileFieldResponse {
+            content_type: self.content_type,
+            file_name: self.file_name,
+            inline: false,
+            size: self.size,
+            body,
+            guard,
+        })
+    }
+}
+
+/// A body which keeps the files of a response until the body is dropped.
+struct GuardedBody<R> {
+    inner:  R,
+    _guard: CleanupGuard,
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for GuardedBody<R> {
+    #[inline]
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl<R: AsyncSeek + Unpin> AsyncSeek for GuardedBody<R> {
+    #[inline]
+    fn start_seek(mut self: Pin<&mut Self>, position: SeekFrom) -> io::Result<()> {
+        Pin::new(&mut self.inner).start_seek(position)
+    }
+
+    #[inline]
+    fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<u64>> {
+        Pin::new(&mut self.inner).poll_complete(cx)
+    }
+}
+
+/// The `Content-Type` header of a response. It is `application/octet-stream` if the content type is unknown.
+#[inline]
+fn content_type(content_type: Option<&Mime>) -> ContentType {
+    content_type
+        .and_then(|content_type| content_type.as_ref().parse().ok())
+        .unwrap_or(ContentType::Binary)
+}
+
+/// The `Content-Disposition` header of a response, which is `attachment` unless `inline` is set. The file name is given both in ASCII and in UTF-8 (RFC 6266).
+fn content_disposition(inline: bool, file_name: Option<&str>) -> String {
+    let disposition = if inline { "inline" } else { "attachment" };
+
+    let file_name = match file_name.and_then(client_file_name) {
+        Some(file_name) => file_name,
+        None => return disposition.to_string(),
+    };
+
+    let ascii: String =
+        file_name
+            .chars()
+            .map(|c| {
+                if c.is_ascii() && !c.is_ascii_control() && c != '"' && c != '\\' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+
+    let mut encoded = String::with_capacity(file_name.len());
+
+    for &b in file_name.as_bytes() {
+        if b.is_ascii_alphanumeric() || b"!#$&+-.^_`|~".contains(&b) {
+            encoded.push(b as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", b));
+        }
+    }
+
+    format!("{}; filename=\"{}\"; filename*=UTF-8''{}", disposition, ascii, encoded)
+}
@@ -1,24 +1,253 @@
-use std::path::PathBuf;
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
-use crate::mime::Mime;
+use rocket::tokio::fs::{self, File};
 
-#[derive(Debug)]
+use crate::{mime::Mime, multer::bytes::Bytes, FileData, KeyProvider, Number};
+
+#[derive(Debug, Clone)]
 pub struct FileField {
+    pub content_type:       Option<Mime>,
+    pub file_name:          Option<String>,
+    /// The extra parameters of the `Content-Disposition` header, such as `creation-date` or `size`. The names of the parameters are lowercased.
+    pub disposition_params: HashMap<String, String>,
+    /// The field name sent by the client, if it has been changed by the key normalization.
+    pub original_name:      Option<String>,
+    /// The position of this part in the HTTP body, starting from `0`.
+    pub index:              usize,
+    pub path:               PathBuf,
+    /// The number of bytes which have been received. The overhead of encryption is not included.
+    pub size:               u64,
+    /// The `Content-Length` header of the part, if the client sends one. It can be compared with `size` to detect truncated uploads.
+    pub content_length:     Option<u64>,
+    /// The artifacts derived from this file by a `FieldPostProcessor`.
+    pub derivatives:        Vec<Derivative>,
+    /// Whether the same content had already been stored, so the existing file is reused. It is only set in the content-addressed storage mode.
+    pub was_deduplicated:   bool,
+    /// Whether the file is kept when the `MultipartFormData` instance is being dropped. Files stored in the content-addressed storage mode are persistent because they can be shared by other uploads.
+    pub persistent:         bool,
+    /// The key provider which the file is encrypted with. Use the `decrypting_reader` method to read the plaintext.
+    pub encryption:         Option<Arc<dyn KeyProvider>>,
+}
+
+/// An artifact derived from an uploaded file, such as a thumbnail.
+#[derive(Debug, Clone)]
+pub struct Derivative {
+    /// The name of this derivative, such as `"thumbnail"`.
+    pub name:         String,
     pub content_type: Option<Mime>,
-    pub file_name:    Option<String>,
     pub path:         PathBuf,
 }
 
-#[derive(Debug)]
+impl FileField {
+    /// Open the uploaded file in read-only mode.
+    #[inline]
+    pub async fn open(&self) -> Result<File, io::Error> {
+        File::open(&self.path).await
+    }
+
+    /// Read the whole uploaded file into memory. An encrypted file is decrypted.
+    #[inline]
+    pub async fn read_to_bytes(&self) -> Result<Vec<u8>, io::Error> {
+        #[cfg(feature = "encryption")]
+        if self.encryption.is_some() {
+            use rocket::tokio::io::AsyncReadExt;
+
+            let mut data = Vec::new();
+
+            self.decrypting_reader().await?.read_to_end(&mut data).await?;
+
+            return Ok(data);
+        }
+
+        fs::read(&self.path).await
+    }
+
+    /// Get the size of the uploaded file. The size of an encrypted file includes the overhead of encryption.
+    #[inline]
+    pub async fn len(&self) -> Result<u64, io::Error> {
+        Ok(fs::metadata(&self.path).await?.len())
+    }
+
+    /// The paths of the uploaded file (unless it is persistent) and its derivatives, which should be deleted along with this field.
+    #[inline]
+    pub(crate) fn paths(&self) -> impl Iterator<Item = &Path> {
+        let path = if self.persistent { None } else { Some(self.path.as_path()) };
+
+        path.into_iter().chain(self.derivatives.iter().map(|d| d.path.as_path()))
+    }
+
+    /// Check whether the uploaded file is empty.
+    #[inline]
+    pub async fn is_empty(&self) -> Result<bool, io::Error> {
+        Ok(self.len().await? == 0)
+    }
+
+    /// The uploaded file as a `FileData`, so that it can be handled in the same way as the data kept in memory. The data of an encrypted file is the ciphertext, so use the `decrypting_reader` method to read the plaintext.
+    #[inline]
+    pub fn data(&self) -> FileData {
+        FileData::OnDisk(self.path.clone())
+    }
+
+    /// The last component of the file name sent by the client. Some old browsers, such as Internet Explorer, send the full path of the file on the client, such as `C:\Users\bob\photo.jpg`, so the directories and the drive letter are removed, whether they are separated by `\` or `/`. The raw value is still available in `file_name`. Return `None` if there is no file name or nothing is left.
+    #[inline]
+    pub fn client_file_name(&self) -> Option<&str> {
+        self.file_name.as_deref().and_then(client_file_name)
+    }
+}
+
+/// Remove the directories and the drive letter from a file name sent by a client.
+pub(crate) fn client_file_name(file_name: &str) -> Option<&str> {
+    let name = file_name.rsplit(['/', '\\']).next().unwrap_or(file_name);
+
+    // A drive-relative path, such as `C:photo.jpg`
+    let name = match name.as_bytes() {
+        [drive, b':', ..] if drive.is_ascii_alphabetic() => &name[2..],
+        _ => name,
+    };
+
+    if name.is_empty() {
+        None
+    } else {
+        Some(name)
+    }
+}
+
+#[derive(Clone)]
 pub struct RawField {
-    pub content_type: Option<Mime>,
-    pub file_name:    Option<String>,
-    pub raw:          Vec<u8>,
+    pub content_type:       Option<Mime>,
+    pub file_name:          Option<String>,
+    /// The extra parameters of the `Content-Disposition` header.
+    pub disposition_params: HashMap<String, String>,
+    /// The field name sent by the client, if it has been changed by the key normalization.
+    pub original_name:      Option<String>,
+    /// The position of this part in the HTTP body, starting from `0`.
+    pub index:              usize,
+    pub raw:                Vec<u8>,
 }
 
-#[derive(Debug)]
+impl RawField {
+    /// Move the data into a `FileData`, so that it can be handled in the same way as uploaded files stored on the disk.
+    #[inline]
+    pub fn into_data(self) -> FileData {
+        FileData::InMemory(Bytes::from(self.raw))
+    }
+}
+
+#[derive(Clone)]
 pub struct TextField {
-    pub content_type: Option<Mime>,
-    pub file_name:    Option<String>,
-    pub text:         String,
+    pub content_type:       Option<Mime>,
+    pub file_name:          Option<String>,
+    /// The extra parameters of the `Content-Disposition` header.
+    pub disposition_params: HashMap<String, String>,
+    /// The field name sent by the client, if it has been changed by the key normalization.
+    pub original_name:      Option<String>,
+    /// The position of this part in the HTTP body, starting from `0`.
+    pub index:              usize,
+    pub text:               String,
+    /// The exact bytes which have been received, before the byte order mark is removed, the line breaks are normalized or the active content is removed. It is only kept if `keep_raw_bytes` of the field is enabled, such as for verifying a signature over the submitted bytes.
+    pub raw_bytes:          Option<Vec<u8>>,
+}
+
+/// A text field which exceeds the `large_text` threshold of its field, so it is stored in a file in the temporary directory instead of memory. The file contains valid UTF-8, or its ciphertext if `encryption` is set, and is deleted when the `MultipartFormData` instance is being dropped.
+#[derive(Debug, Clone)]
+pub struct LargeTextField {
+    pub content_type:       Option<Mime>,
+    pub file_name:          Option<String>,
+    /// The extra parameters of the `Content-Disposition` header.
+    pub disposition_params: HashMap<String, String>,
+    /// The field name sent by the client, if it has been changed by the key normalization.
+    pub original_name:      Option<String>,
+    /// The position of this part in the HTTP body, starting from `0`.
+    pub index:              usize,
+    pub path:               PathBuf,
+    /// The number of bytes of the text.
+    pub len:                u64,
+    /// The key provider which the file is encrypted with, the same as the one of the `encryption` option.
+    pub encryption:         Option<Arc<dyn KeyProvider>>,
+}
+
+impl LargeTextField {
+    /// Open the file of the text in read-only mode. An encrypted file is not decrypted, so use the `decrypting_reader` method to read the plaintext.
+    #[inline]
+    pub async fn open(&self) -> Result<File, io::Error> {
+        File::open(&self.path).await
+    }
+
+    /// Read the whole text into memory. An encrypted file is decrypted.
+    #[inline]
+    pub async fn read_to_string(&self) -> Result<String, io::Error> {
+        #[cfg(feature = "encryption")]
+        if self.encryption.is_some() {
+            use rocket::tokio::io::AsyncReadExt;
+
+            let mut text = String::new();
+
+            self.decrypting_reader().await?.read_to_string(&mut text).await?;
+
+            return Ok(text);
+        }
+
+        fs::read_to_string(&self.path).await
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NumberField {
+    pub content_type:       Option<Mime>,
+    pub file_name:          Option<String>,
+    /// The extra parameters of the `Content-Disposition` header.
+    pub disposition_params: HashMap<String, String>,
+    /// The field name sent by the client, if it has been changed by the key normalization.
+    pub original_name:      Option<String>,
+    /// The position of this part in the HTTP body, starting from `0`.
+    pub index:              usize,
+    pub number:             Number,
+}
+
+#[cfg(feature = "chrono")]
+#[derive(Debug, Clone)]
+pub struct DateTimeField {
+    pub content_type:       Option<Mime>,
+    pub file_name:          Option<String>,
+    /// The extra parameters of the `Content-Disposition` header.
+    pub disposition_params: HashMap<String, String>,
+    /// The field name sent by the client, if it has been changed by the key normalization.
+    pub original_name:      Option<String>,
+    /// The position of this part in the HTTP body, starting from `0`.
+    pub index:              usize,
+    pub datetime:           chrono::NaiveDateTime,
+}
+
+/// A file which was being received when the connection was interrupted. It is kept with the `.partial` extension and is not deleted automatically.
+#[derive(Debug)]
+pub struct PartialFile {
+    pub field:     Arc<str>,
+    pub file_name: Option<String>,
+    pub path:      PathBuf,
+    /// The number of bytes which have been received.
+    pub size:      u64,
+}
+
+/// An uploaded file which is stored by a `StorageBackend`.
+#[derive(Debug, Clone)]
+pub struct StoredField {
+    pub content_type:       Option<Mime>,
+    pub file_name:          Option<String>,
+    /// The extra parameters of the `Content-Disposition` header.
+    pub disposition_params: HashMap<String, String>,
+    /// The field name sent by the client, if it has been changed by the key normalization.
+    pub original_name:      Option<String>,
+    /// The position of this part in the HTTP body, starting from `0`.
+    pub index:              usize,
+    /// The id of the file given by the storage backend.
+    pub id:                 String,
+    /// The number of bytes which have been stored.
+    pub size:               u64,
+    /// The `Content-Length` header of the part, if the client sends one.
+    pub content_length:     Option<u64>,
 }
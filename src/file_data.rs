@@ -0,0 +1,52 @@
+use std::{io, path::PathBuf, pin::Pin};
+
+use rocket::tokio::{
+    fs::{self, File},
+    io::AsyncRead,
+};
+
+use crate::multer::bytes::Bytes;
+
+/// The data of an uploaded file, which is either stored on the disk or kept in memory. It can be read in the same way wherever it is, so the code which handles it does not depend on how the file has been received.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileData {
+    /// A file stored on the disk, such as a file field.
+    OnDisk(PathBuf),
+    /// Data kept in memory, such as a raw field.
+    InMemory(Bytes),
+}
+
+impl FileData {
+    /// Get the size of the data.
+    #[inline]
+    pub async fn len(&self) -> Result<u64, io::Error> {
+        match self {
+            FileData::OnDisk(path) => Ok(fs::metadata(path).await?.len()),
+            FileData::InMemory(bytes) => Ok(bytes.len() as u64),
+        }
+    }
+
+    /// Check whether the data is empty.
+    #[inline]
+    pub async fn is_empty(&self) -> Result<bool, io::Error> {
+        Ok(self.len().await? == 0)
+    }
+
+    /// Create a reader of the data.
+    #[inline]
+    pub async fn reader(&self) -> Result<Pin<Box<dyn AsyncRead + Send + Sync>>, io::Error> {
+        match self {
+            FileData::OnDisk(path) => Ok(Box::pin(File::open(path).await?)),
+            FileData::InMemory(bytes) => Ok(Box::pin(io::Cursor::new(bytes.clone()))),
+        }
+    }
+
+    /// Read the whole data into memory.
+    #[inline]
+    pub async fn read_to_bytes(&self) -> Result<Bytes, io::Error> {
+        match self {
+            FileData::OnDisk(path) => Ok(Bytes::from(fs::read(path).await?)),
+            FileData::InMemory(bytes) => Ok(bytes.clone()),
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use std::{
+    fmt::{self, Debug, Formatter},
+    io,
+    path::{Component, Path, PathBuf},
+    time::SystemTime,
+};
+
+use rocket::tokio::fs::{self, File, OpenOptions};
+
+use crate::{retry_policy::Retry, Clock, RetryPolicy, Rng};
+
+/// The max number of names tried to create a file.
+const MAX_ATTEMPTS: u32 = 100;
+
+/// A generator of the names of the files created in the temporary directory, such as UUIDs. A file is never overwritten, because it is created only if the generated name is not taken, otherwise another name is generated.
+///
+/// It is implemented for closures of type `Fn(&str, u32) -> String`.
+pub trait FileNameGenerator: Send + Sync {
+    /// Generate a file name for the data of the field `field_name`. `attempt` starts from `0` and is increased every time the previous name is taken. The name must not contain path separators.
+    fn generate(&self, field_name: &str, attempt: u32) -> String;
+}
+
+impl<F: Fn(&str, u32) -> String + Send + Sync> FileNameGenerator for F {
+    #[inline]
+    fn generate(&self, field_name: &str, attempt: u32) -> String {
+        self(field_name, attempt)
+    }
+}
+
+impl Debug for dyn FileNameGenerator {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("FileNameGenerator")
+    }
+}
+
+/// The default generator, which names files after the current time in nanoseconds, such as `rs-1700000000000000000`. A counter is appended if the name is taken.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TimestampFileNameGenerator;
+
+impl FileNameGenerator for TimestampFileNameGenerator {
+    #[inline]
+    fn generate(&self, _field_name: &str, attempt: u32) -> String {
+        let nanos = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_nanos();
+
+        if attempt == 0 {
+            format!("rs-{}", nanos)
+        } else {
+            format!("rs-{}-{}", nanos, attempt)
+        }
+    }
+}
+
+/// The generator which is used if no `file_name_generator` is set. It names files after the time of `clock` in the same way as `TimestampFileNameGenerator`, but a random number from `rng` is appended instead of a counter if the name is taken, so that concurrent uploads do not keep trying the same names.
+pub(crate) struct DefaultFileNameGenerator<'a> {
+    pub(crate) clock: &'a dyn Clock,
+    pub(crate) rng:   &'a dyn Rng,
+}
+
+impl<'a> FileNameGenerator for DefaultFileNameGenerator<'a> {
+    #[inline]
+    fn generate(&self, _field_name: &str, attempt: u32) -> String {
+        let nanos = self
+            .clock
+            .now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+
+        if attempt == 0 {
+            format!("rs-{}", nanos)
+        } else {
+            format!("rs-{}-{:016x}", nanos, self.rng.next_u64())
+        }
+    }
+}
+
+/// Create a new file in `dir` atomically, with a name generated by `generator`.
+pub(crate) async fn create_new_file(
+    dir: &Path,
+    generator: &dyn FileNameGenerator,
+    field_name: &str,
+    retry: Option<RetryPolicy>,
+) -> Result<(File, PathBuf), io::Error> {
+    let mut retry = Retry::new(retry);
+
+    for attempt in 0..MAX_ATTEMPTS {
+        let file_name = generator.generate(field_name, attempt);
+
+        let mut components = Path::new(&file_name).components();
+
+        if !matches!((components.next(), components.next()), (Some(Component::Normal(_)), None)) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("The generated file name `{}` is not a single path component.", file_name),
+            ));
+        }
+
+        let path = dir.join(file_name);
+
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path).await {
+                Ok(file) => return Ok((file, path)),
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => break,
+                Err(err) => retry.wait(err).await?,
+            }
+        }
+    }
+
+    Err(io::Error::new(io::ErrorKind::AlreadyExists, "All of the generated file names are taken."))
+}
+
+/// Create a file in `dir` whose name is derived from an idempotency key, the field name and the position of the part, or truncate it if it exists, so that a retried request writes to the same file. The `.partial` file left by an interrupted attempt is removed.
+pub(crate) async fn create_idempotent_file(
+    dir: &Path,
+    idempotency_key: &str,
+    field_name: &str,
+    index: usize,
+    retry: Option<RetryPolicy>,
+) -> Result<(File, PathBuf), io::Error> {
+    let mut file_name = format!("idem-{}-", idempotency_key);
+
+    // Escape the field name so that it is always a single path component
+    for b in field_name.bytes() {
+        if b.is_ascii_alphanumeric() || b == b'_' {
+            file_name.push(b as char);
+        } else {
+            file_name.push_str(&format!("%{:02X}", b));
+        }
+    }
+
+    file_name.push_str(&format!("-{}", index));
+
+    let path = dir.join(&file_name);
+
+    let _ = fs::remove_file(dir.join(file_name + ".partial")).await;
+
+    let mut retry = Retry::new(retry);
+
+    loop {
+        match OpenOptions::new().write(true).create(true).truncate(true).open(&path).await {
+            Ok(file) => return Ok((file, path)),
+            Err(err) => retry.wait(err).await?,
+        }
+    }
+}
@@ -0,0 +1,10 @@
+/// How uploaded files are named and stored in the temporary directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FileStorageMode {
+    /// Every uploaded file is stored as a new file with a unique name. It is deleted automatically.
+    #[default]
+    Unique,
+    /// Every uploaded file is stored as a file named by the hex-encoded SHA-256 hash of its content. If a file with the same hash already exists, the existing file is reused and the uploaded data is discarded. These files are shared and are not deleted automatically.
+    #[cfg(feature = "sha2")]
+    ContentAddressed,
+}
@@ -0,0 +1,253 @@
+use std::io;
+
+use rocket::tokio::{
+    fs::File,
+    io::{AsyncWriteExt, BufWriter},
+    sync::mpsc,
+    task::{self, JoinHandle},
+};
+
+#[cfg(feature = "encryption")]
+use crate::encryption::Encryptor;
+use crate::{multer::bytes::Bytes, retry_policy::Retry, RetryPolicy, StorageWriter};
+
+/// The writer of an uploaded file.
+pub(crate) struct FileWriter {
+    sink:      Sink,
+    #[cfg(feature = "encryption")]
+    encryptor: Option<Encryptor>,
+}
+
+enum Sink {
+    /// Write chunks in the current task.
+    Direct(BufWriter<File>, Option<RetryPolicy>),
+    /// Send chunks to a background task which writes them, so that the next chunk can be read from the network while the previous one is being written. The handle is taken once the task has been joined.
+    Pipelined { sender: mpsc::Sender<Bytes>, handle: Option<JoinHandle<Result<(), io::Error>>> },
+    /// Write chunks to a storage backend.
+    Backend(Box<dyn StorageWriter>),
+}
+
+impl FileWriter {
+    /// Create a `FileWriter` instance. If `pipeline_depth` is bigger than `0`, at most `pipeline_depth` chunks can be queued for writing. The writes which fail with transient errors are retried according to `retry`.
+    pub(crate) fn new(
+        file: File,
+        write_chunk_size: usize,
+        pipeline_depth: usize,
+        retry: Option<RetryPolicy>,
+    ) -> FileWriter {
+        let mut writer = BufWriter::with_capacity(write_chunk_size, file);
+
+        let sink = if pipeline_depth == 0 {
+            Sink::Direct(writer, retry)
+        } else {
+            let (sender, mut receiver) = mpsc::channel::<Bytes>(pipeline_depth);
+
+            let handle = task::spawn(async move {
+                while let Some(bytes) = receiver.recv().await {
+                    write_all(&mut writer, &bytes, retry).await?;
+                }
+
+                flush(&mut writer, retry).await
+            });
+
+            Sink::Pipelined {
+                sender,
+                handle: Some(handle),
+            }
+        };
+
+        FileWriter {
+            sink,
+            #[cfg(feature = "encryption")]
+            encryptor: None,
+        }
+    }
+
+    /// Create a `FileWriter` instance which writes chunks to a storage backend.
+    pub(crate) fn with_backend(writer: Box<dyn StorageWriter>) -> FileWriter {
+        let sink = Sink::Backend(writer);
+
+        FileWriter {
+            sink,
+            #[cfg(feature = "encryption")]
+            encryptor: None,
+        }
+    }
+
+    /// Encrypt the data before writing it.
+    #[cfg(feature = "encryption")]
+    #[inline]
+    pub(crate) fn set_encryptor(&mut self, encryptor: Option<Encryptor>) {
+        self.encryptor = encryptor;
+    }
+
+    /// Write a chunk.
+    pub(crate) async fn write(&mut self, bytes: Bytes) -> Result<(), io::Error> {
+        #[cfg(feature = "encryption")]
+        let bytes = match self.encryptor.as_mut() {
+            Some(encryptor) => encryptor.update(&bytes)?,
+            None => bytes,
+        };
+
+        self.write_to_sink(bytes).await
+    }
+
+    /// Write the remaining data to the file. Return the id of the file if it is written to a storage backend.
+    pub(crate) async fn finish(mut self) -> Result<Option<String>, io::Error> {
+        self.finish_encryption().await?;
+
+        match self.sink {
+            Sink::Direct(mut writer, retry) => flush(&mut writer, retry).await.map(|_| None),
+            Sink::Pipelined {
+                sender,
+                handle,
+            } => {
+                drop(sender);
+
+                match handle {
+                    Some(handle) => handle
+                        .await
+                        .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?
+                        .map(|_| None),
+                    None => Err(stopped()),
+                }
+            },
+            Sink::Backend(writer) => writer.finish().await.map(Some),
+        }
+    }
+
+    /// Stop writing and wait for the file to be closed, so that it can be deleted.
+    pub(crate) async fn discard(self) {
+        match self.sink {
+            Sink::Direct(writer, _) => drop(writer),
+            Sink::Pipelined {
+                sender,
+                handle,
+            } => {
+                drop(sender);
+
+                if let Some(handle) = handle {
+                    handle.abort();
+
+                    let _ = handle.await;
+                }
+            },
+            Sink::Backend(writer) => drop(writer),
+        }
+    }
+
+    /// Write the last encrypted frame if the data is being encrypted.
+    async fn finish_encryption(&mut self) -> Result<(), io::Error> {
+        #[cfg(feature = "encryption")]
+        if let Some(encryptor) = self.encryptor.take() {
+            let bytes = encryptor.finish()?;
+
+            self.write_to_sink(bytes).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn write_to_sink(&mut self, bytes: Bytes) -> Result<(), io::Error> {
+        match &mut self.sink {
+            Sink::Direct(writer, retry) => write_all(writer, &bytes, *retry).await,
+            Sink::Pipelined {
+                sender, ..
+            } => {
+                if sender.send(bytes).await.is_err() {
+                    // The writing task has stopped, so there must be an error
+                    return Err(self.abort().await.unwrap_or_else(stopped));
+                }
+
+                Ok(())
+            },
+            Sink::Backend(writer) => writer.write(&bytes).await,
+        }
+    }
+
+    /// Wait for the writing task to stop. Return its error if any. The task is joined only once.
+    async fn abort(&mut self) -> Option<io::Error> {
+        match &mut self.sink {
+            Sink::Direct(..) | Sink::Backend(_) => None,
+            Sink::Pipelined {
+                handle, ..
+            } => match handle.take()?.await {
+                Ok(Ok(())) => None,
+                Ok(Err(err)) => Some(err),
+                Err(err) => Some(io::Error::new(io::ErrorKind::Other, err)),
+            },
+        }
+    }
+}
+
+/// The error of writing to a writing task which has stopped.
+#[inline]
+fn stopped() -> io::Error {
+    io::Error::new(io::ErrorKind::BrokenPipe, "The file writer has stopped.")
+}
+
+/// Write all of `bytes`. Only the failed writes are retried, so no data is written twice.
+async fn write_all(
+    writer: &mut BufWriter<File>,
+    mut bytes: &[u8],
+    retry: Option<RetryPolicy>,
+) -> Result<(), io::Error> {
+    let mut retry = Retry::new(retry);
+
+    while !bytes.is_empty() {
+        match writer.write(bytes).await {
+            Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+            Ok(n) => bytes = &bytes[n..],
+            Err(err) => retry.wait(err).await?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Flush the buffered data. The data which has not been written is kept in the buffer when flushing fails, so it can be retried.
+async fn flush(writer: &mut BufWriter<File>, retry: Option<RetryPolicy>) -> Result<(), io::Error> {
+    let mut retry = Retry::new(retry);
+
+    loop {
+        match writer.flush().await {
+            Ok(()) => return Ok(()),
+            Err(err) => retry.wait(err).await?,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rocket::async_test]
+    async fn pipelined_write_error_then_discard() {
+        let path = std::env::temp_dir().join("rocket-multipart-form-data-file-writer-test");
+
+        std::fs::write(&path, b"").unwrap();
+
+        // a read-only file, so every write fails
+        let file = File::open(&path).await.unwrap();
+
+        let mut writer = FileWriter::new(file, 1, 1, None);
+
+        let mut err = None;
+
+        for _ in 0..16 {
+            if let Err(e) = writer.write(Bytes::from_static(b"data")).await {
+                err = Some(e);
+                break;
+            }
+        }
+
+        let err = err.expect("the write error should be reported");
+
+        assert_ne!(io::ErrorKind::BrokenPipe, err.kind());
+
+        // must not join the writing task again
+        writer.discard().await;
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,228 @@
+use std::{borrow::Cow, sync::Arc};
+
+use rocket::{
+    form::{error::ErrorKind, Error, Errors},
+    http::Status,
+};
+
+use crate::{MessageCatalog, MultipartFormDataError};
+
+impl MultipartFormDataError {
+    /// Convert this error to `rocket::form::Errors`, so that it can be reported in the same way as the errors of Rocket's `FromForm` validation.
+    pub fn into_form_errors(self) -> Errors<'static> {
+        Errors::from(self.into_form_error())
+    }
+
+    /// Convert this error to a `rocket::form::Error`, whose validation message is taken from `catalog`.
+    pub fn into_form_error_with(self, catalog: &dyn MessageCatalog) -> Error<'static> {
+        let message = self.message(catalog);
+
+        let mut error = self.into_form_error();
+
+        if let ErrorKind::Validation(_) = error.kind {
+            error.kind = ErrorKind::Validation(Cow::Owned(message));
+        }
+
+        error
+    }
+
+    /// Convert this error to a `rocket::form::Error`.
+    pub fn into_form_error(self) -> Error<'static> {
+        #[allow(deprecated)]
+        let (kind, name, value): (ErrorKind<'static>, _, _) = match self {
+            MultipartFormDataError::NotFormDataError => {
+                (ErrorKind::Custom(Status::UnsupportedMediaType, Box::new(self)), None, None)
+            },
+            MultipartFormDataError::WrongContentTypeError(ref content_type) => {
+                let content_type = content_type.to_string();
+
+                (
+                    ErrorKind::Custom(Status::UnsupportedMediaType, Box::new(self)),
+                    None,
+                    Some(content_type),
+                )
+            },
+            MultipartFormDataError::MissingBoundaryError
+            | MultipartFormDataError::BoundaryNotFoundError
+            | MultipartFormDataError::InvalidBoundaryError
+            | MultipartFormDataError::TrailingDataError(_) => {
+                (ErrorKind::Custom(Status::BadRequest, Box::new(self)), None, None)
+            },
+            MultipartFormDataError::IOError(err) => (ErrorKind::Io(err), None, None),
+            // The `multer` crate used by Rocket may not be the same version as ours
+            MultipartFormDataError::MulterError(err) => {
+                (ErrorKind::Custom(Status::BadRequest, Box::new(err)), None, None)
+            },
+            MultipartFormDataError::FromUtf8Error(err) => {
+                (ErrorKind::Utf8(err.utf8_error()), None, None)
+            },
+            MultipartFormDataError::DataTooLargeError(field) => (
+                ErrorKind::InvalidLength {
+                    min: None, max: None
+                },
+                Some(field),
+                None,
+            ),
+            MultipartFormDataError::DataLimitExceededError {
+                limit, ..
+            } => (
+                ErrorKind::InvalidLength {
+                    min: None, max: Some(limit)
+                },
+                None,
+                None,
+            ),
+            MultipartFormDataError::DataTypeError(ref field) => {
+                let field = field.clone();
+
+                (ErrorKind::Validation(Cow::Owned(self.to_string())), Some(field), None)
+            },
+            MultipartFormDataError::InvalidValueError {
+                ref field,
+                ref got,
+            }
+            | MultipartFormDataError::InvalidNumberError {
+                ref field,
+                ref got,
+            } => {
+                let (field, got) = (field.clone(), got.clone());
+
+                (ErrorKind::Validation(Cow::Owned(self.to_string())), Some(field), Some(got))
+            },
+            MultipartFormDataError::NumberOutOfRangeError {
+                field,
+                got,
+            } => (
+                ErrorKind::OutOfRange {
+                    start: None, end: None
+                },
+                Some(field),
+                Some(got.to_string()),
+            ),
+            #[cfg(feature = "chrono")]
+            MultipartFormDataError::InvalidDateTimeError {
+                ref field,
+                ref got,
+            } => {
+                let (field, got) = (field.clone(), got.clone());
+
+                (ErrorKind::Validation(Cow::Owned(self.to_string())), Some(field), Some(got))
+            },
+            #[cfg(feature = "csv")]
+            MultipartFormDataError::InvalidCsvError {
+                ref field, ..
+            } => {
+                let field = field.clone();
+
+                (ErrorKind::Validation(Cow::Owned(self.to_string())), Some(field), None)
+            },
+            #[cfg(feature = "xml")]
+            MultipartFormDataError::InvalidXmlError {
+                ref field, ..
+            } => {
+                let field = field.clone();
+
+                (ErrorKind::Validation(Cow::Owned(self.to_string())), Some(field), None)
+            },
+            MultipartFormDataError::OptionsError(_) => {
+                (ErrorKind::Custom(Status::InternalServerError, Box::new(self)), None, None)
+            },
+            MultipartFormDataError::QuotaExceededError => {
+                (ErrorKind::Custom(Status::PayloadTooLarge, Box::new(self)), None, None)
+            },
+            MultipartFormDataError::PartHeadersTooLargeError
+            | MultipartFormDataError::MemoryLimitExceededError
+            | MultipartFormDataError::TooManyTextPartsError
+            | MultipartFormDataError::TooManyFilePartsError => {
+                (ErrorKind::Custom(Status::PayloadTooLarge, Box::new(self)), None, None)
+            },
+            MultipartFormDataError::AdmissionDeniedError(ref denied) => {
+                (ErrorKind::Custom(denied.status(), Box::new(self)), None, None)
+            },
+            MultipartFormDataError::ServerStorageExhaustedError => {
+                (ErrorKind::Custom(Status::InsufficientStorage, Box::new(self)), None, None)
+            },
+            MultipartFormDataError::ShutdownError => {
+                (ErrorKind::Custom(Status::ServiceUnavailable, Box::new(self)), None, None)
+            },
+            MultipartFormDataError::FieldTimeoutError(ref field) => {
+                let field = field.clone();
+
+                (ErrorKind::Custom(Status::RequestTimeout, Box::new(self)), Some(field), None)
+            },
+            MultipartFormDataError::UnnamedPartError
+            | MultipartFormDataError::InterruptedError {
+                ..
+            } => (ErrorKind::Custom(Status::BadRequest, Box::new(self)), None, None),
+            MultipartFormDataError::FieldRejectedError(ref field)
+            | MultipartFormDataError::EmptyFileError(ref field)
+            | MultipartFormDataError::TooManyFilesError(ref field)
+            | MultipartFormDataError::NestingTooDeepError(ref field) => {
+                let field = field.clone();
+
+                (ErrorKind::Validation(Cow::Owned(self.to_string())), Some(field), None)
+            },
+            MultipartFormDataError::FieldNameTooLongError(ref name)
+            | MultipartFormDataError::NonAsciiFieldNameError(ref name) => {
+                let name: Arc<str> = Arc::from(name.as_str());
+
+                (ErrorKind::Validation(Cow::Owned(self.to_string())), Some(name), None)
+            },
+            MultipartFormDataError::NotEnoughRepetitionsError {
+                ref field, ..
+            }
+            | MultipartFormDataError::FieldCountError {
+                ref field, ..
+            }
+            | MultipartFormDataError::MappingError {
+                ref field, ..
+            } => {
+                let field = field.clone();
+
+                (ErrorKind::Validation(Cow::Owned(self.to_string())), Some(field), None)
+            },
+            #[cfg(feature = "prost")]
+            MultipartFormDataError::InvalidProtobufError {
+                ref field, ..
+            } => {
+                let field = field.clone();
+
+                (ErrorKind::Validation(Cow::Owned(self.to_string())), Some(field), None)
+            },
+            #[cfg(feature = "sanitize")]
+            MultipartFormDataError::ActiveContentError(ref field) => {
+                let field = field.clone();
+
+                (ErrorKind::Validation(Cow::Owned(self.to_string())), Some(field), None)
+            },
+            MultipartFormDataError::ArchiveRejectedError {
+                ref field, ..
+            } => {
+                let field = field.clone();
+
+                (ErrorKind::Validation(Cow::Owned(self.to_string())), Some(field), None)
+            },
+            MultipartFormDataError::PostProcessError {
+                field,
+                error,
+            } => (ErrorKind::Custom(Status::InternalServerError, error), Some(field), None),
+        };
+
+        let mut error = Error::from(kind);
+
+        if let Some(name) = name {
+            error = error.with_name(name.to_string());
+        }
+
+        error.value = value.map(Cow::Owned);
+
+        error
+    }
+}
+
+impl From<MultipartFormDataError> for Errors<'static> {
+    #[inline]
+    fn from(err: MultipartFormDataError) -> Errors<'static> {
+        err.into_form_errors()
+    }
+}
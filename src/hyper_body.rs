@@ -0,0 +1,104 @@
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use http::{
+    header::{CONTENT_LENGTH, CONTENT_TYPE},
+    HeaderMap,
+};
+use hyper::body::{Buf, Bytes, HttpBody};
+use rocket::{
+    futures::Stream,
+    http::ContentType,
+    tokio::io::{AsyncReadExt, Take},
+};
+use tokio_util::io::StreamReader;
+
+use crate::{
+    request_body::RequestBody, MultipartFormData, MultipartFormDataError, MultipartFormDataOptions,
+};
+
+impl MultipartFormData {
+    /// Parse multipart/form-data from the headers and the body of a hyper request, such as the ones taken apart from an axum request. The same options, limits and temporary files are used as the `parse` method. The `hyper-body` feature is required.
+    ///
+    /// If the `Content-Type` header is missing or cannot be parsed, a `WrongContentTypeError` with `*/*` is returned.
+    #[inline]
+    pub async fn parse_hyper<B>(
+        headers: &HeaderMap,
+        body: B,
+        options: MultipartFormDataOptions<'_>,
+    ) -> Result<MultipartFormData, MultipartFormDataError>
+    where
+        B: HttpBody + Send + Unpin,
+        B::Data: Send,
+        B::Error: Into<Box<dyn std::error::Error + Send + Sync>>, {
+        MultipartFormData::parse_body(
+            HyperBody {
+                headers,
+                body,
+            },
+            options,
+        )
+        .await
+    }
+}
+
+/// The headers and the body of a hyper request.
+pub(crate) struct HyperBody<'a, B> {
+    headers: &'a HeaderMap,
+    body:    B,
+}
+
+impl<'a, B> RequestBody for HyperBody<'a, B>
+where
+    B: HttpBody + Send + Unpin,
+    B::Data: Send,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    type Reader = Take<StreamReader<BodyStream<B>, Bytes>>;
+
+    fn boundary(&self) -> Result<String, MultipartFormDataError> {
+        let content_type = self
+            .headers
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(ContentType::parse_flexible)
+            .ok_or_else(|| {
+                MultipartFormDataError::WrongContentTypeError(Box::new(ContentType::Any))
+            })?;
+
+        MultipartFormData::boundary_from_content_type(&content_type).map(|b| b.into_owned())
+    }
+
+    #[inline]
+    fn content_length(&self) -> Option<u64> {
+        self.headers.get(CONTENT_LENGTH)?.to_str().ok()?.trim().parse().ok()
+    }
+
+    #[inline]
+    fn open(self, max_data_bytes: u64) -> Self::Reader {
+        StreamReader::new(BodyStream(self.body)).take(max_data_bytes)
+    }
+}
+
+/// Adapt the data frames of a hyper body to a stream of bytes.
+pub(crate) struct BodyStream<B>(B);
+
+impl<B> Stream for BodyStream<B>
+where
+    B: HttpBody + Unpin,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    type Item = Result<Bytes, io::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.0).poll_data(cx).map(|data| {
+            data.map(|data| match data {
+                Ok(mut data) => Ok(data.copy_to_bytes(data.remaining())),
+                Err(err) => Err(io::Error::new(io::ErrorKind::Other, err)),
+            })
+        })
+    }
+}
@@ -0,0 +1,177 @@
+/*!
+Tools for debugging captured multipart/form-data bodies, such as the failing payloads submitted by customers.
+
+The parts of a captured body are read by the same parser as the one used by `MultipartFormData`, including every part which would be skipped. Use `MultipartFormData::parse_file` with the production options to replay the parsing.
+
+```rust,no_run
+use rocket_multipart_form_data::inspect;
+
+# async fn run() -> Result<(), rocket_multipart_form_data::MultipartFormDataError> {
+let parts = inspect::read_parts("body.bin", "boundary", Some("parts".as_ref())).await?;
+
+for part in parts {
+    println!("{}", part);
+}
+# Ok(())
+# }
+```
+*/
+
+use std::{
+    fmt::{self, Display, Formatter},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use rocket::tokio::{
+    fs::{self, File},
+    io::AsyncWriteExt,
+};
+
+use crate::{
+    mime::Mime, multer::Multipart, part_scanner::PartScanner, MultipartFormDataError, PartOffsets,
+};
+
+/// A part of a captured body.
+#[derive(Debug, Clone)]
+pub struct CapturedPart {
+    /// The position of this part in the body, starting from `0`.
+    pub index:        usize,
+    pub name:         Option<String>,
+    pub file_name:    Option<String>,
+    pub content_type: Option<Mime>,
+    /// The headers of this part. The values which are not valid UTF-8 are converted lossily.
+    pub headers:      Vec<(String, String)>,
+    /// The number of bytes of the data.
+    pub size:         u64,
+    /// The byte ranges of this part in the body.
+    pub offsets:      Option<PartOffsets>,
+    /// The file which the data has been extracted to.
+    pub extracted:    Option<PathBuf>,
+}
+
+impl Display for CapturedPart {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_fmt(format_args!("#{} name={:?}", self.index, self.name))?;
+
+        if let Some(file_name) = self.file_name.as_ref() {
+            f.write_fmt(format_args!(" file_name={:?}", file_name))?;
+        }
+
+        if let Some(content_type) = self.content_type.as_ref() {
+            f.write_fmt(format_args!(" content_type={}", content_type))?;
+        }
+
+        f.write_fmt(format_args!(" size={}", self.size))?;
+
+        if let Some(offsets) = self.offsets.as_ref() {
+            f.write_fmt(format_args!(" data={}..{}", offsets.data.start, offsets.data.end))?;
+        }
+
+        if let Some(extracted) = self.extracted.as_ref() {
+            f.write_fmt(format_args!(" extracted={}", extracted.display()))?;
+        }
+
+        for (name, value) in self.headers.iter() {
+            f.write_fmt(format_args!("\n    {}: {}", name, value))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Read all parts of a body captured in a file. If `extract_to` is set, the data of every part is written to a file named `part-<index>` in that directory, which is created if it does not exist.
+///
+/// The parts which have been read are returned even if the body is broken afterwards, as long as there is no I/O error. The error of the broken body is not reported, so replay the parsing to get it.
+pub async fn read_parts<P: AsRef<Path>, S: Into<String>>(
+    path: P,
+    boundary: S,
+    extract_to: Option<&Path>,
+) -> Result<Vec<CapturedPart>, MultipartFormDataError> {
+    let boundary = boundary.into();
+
+    if let Some(dir) = extract_to {
+        fs::create_dir_all(dir).await?;
+    }
+
+    let offsets = Arc::new(Mutex::new(Vec::new()));
+
+    let mut reader =
+        PartScanner::new(File::open(path).await?, &boundary, None, Some(offsets.clone()));
+
+    let mut multipart =
+        Multipart::new(tokio_util::io::ReaderStream::new(&mut reader), boundary.as_str());
+
+    let mut parts = Vec::new();
+
+    while let Ok(Some(mut entry)) = multipart.next_field().await {
+        let mut part = CapturedPart {
+            index:        entry.index(),
+            name:         entry.name().map(String::from),
+            file_name:    entry.file_name().map(String::from),
+            content_type: entry.content_type().cloned(),
+            headers:      entry
+                .headers()
+                .iter()
+                .map(|(name, value)| {
+                    (name.to_string(), String::from_utf8_lossy(value.as_bytes()).into_owned())
+                })
+                .collect(),
+            size:         0,
+            offsets:      None,
+            extracted:    None,
+        };
+
+        let mut file = match extract_to {
+            Some(dir) => {
+                let path = dir.join(format!("part-{}", part.index));
+
+                let file = File::create(&path).await?;
+
+                part.extracted = Some(path);
+
+                Some(file)
+            },
+            None => None,
+        };
+
+        let mut broken = false;
+
+        loop {
+            match entry.chunk().await {
+                Ok(Some(bytes)) => {
+                    part.size += bytes.len() as u64;
+
+                    if let Some(file) = file.as_mut() {
+                        file.write_all(&bytes).await?;
+                    }
+                },
+                Ok(None) => break,
+                Err(_) => {
+                    broken = true;
+                    break;
+                },
+            }
+        }
+
+        if let Some(mut file) = file {
+            file.flush().await?;
+        }
+
+        parts.push(part);
+
+        if broken {
+            break;
+        }
+    }
+
+    drop(multipart);
+
+    let offsets = std::mem::take(&mut *offsets.lock().unwrap());
+
+    for (part, offsets) in parts.iter_mut().zip(offsets) {
+        part.offsets = Some(offsets);
+    }
+
+    Ok(parts)
+}
@@ -0,0 +1,38 @@
+use std::borrow::Cow;
+
+/// How the field names sent by clients are normalized before they are matched with the allowed fields and used as the keys of the parsed data.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum KeyNormalization {
+    /// Use the field names as they are.
+    #[default]
+    None,
+    /// Convert the field names to lowercase.
+    Lowercase,
+    /// Convert the field names with a function.
+    Custom(fn(&str) -> String),
+}
+
+impl KeyNormalization {
+    /// Normalize a field name. A borrowed name is returned if it is not changed.
+    pub fn normalize<'a>(&self, name: &'a str) -> Cow<'a, str> {
+        match self {
+            KeyNormalization::None => Cow::Borrowed(name),
+            KeyNormalization::Lowercase => {
+                if name.chars().any(char::is_uppercase) {
+                    Cow::Owned(name.to_lowercase())
+                } else {
+                    Cow::Borrowed(name)
+                }
+            },
+            KeyNormalization::Custom(f) => {
+                let normalized = f(name);
+
+                if normalized == name {
+                    Cow::Borrowed(name)
+                } else {
+                    Cow::Owned(normalized)
+                }
+            },
+        }
+    }
+}
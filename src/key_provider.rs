@@ -0,0 +1,22 @@
+use std::fmt::{self, Debug, Formatter};
+
+/// A provider of the AES-256 key used to encrypt uploaded files at rest.
+///
+/// It is implemented for `[u8; 32]`, so a key can be supplied directly. The same key has to be returned as long as the encrypted files are in use.
+pub trait KeyProvider: Send + Sync {
+    fn key(&self) -> [u8; 32];
+}
+
+impl KeyProvider for [u8; 32] {
+    #[inline]
+    fn key(&self) -> [u8; 32] {
+        *self
+    }
+}
+
+impl Debug for dyn KeyProvider {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("KeyProvider")
+    }
+}
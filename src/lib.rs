@@ -79,21 +79,175 @@ async fn index(content_type: &ContentType, data: Data<'_>) -> &'static str {
 Also see `examples`.
  */
 
+#[cfg(feature = "chrono")]
+pub extern crate chrono;
 pub extern crate mime;
 pub extern crate multer;
-
+#[cfg(feature = "prost")]
+pub extern crate prost;
+
+#[cfg(feature = "sanitize")]
+mod active_content;
+mod admission_control;
+mod archive_inspector;
+mod audit;
+mod blocking;
+#[cfg(feature = "sha2")]
+mod body_digest;
+mod boundary;
+mod boxed_future;
+mod cached_multipart;
+mod chunk_reader;
+mod cleanup_guard;
+mod clock;
+mod content_disposition;
+mod content_type_filter;
+mod content_type_group;
+mod content_type_inference;
+#[cfg(feature = "csv")]
+mod csv_field;
+mod data_limit_source;
+#[cfg(feature = "sqlx")]
+mod database_storage;
+mod deferred_eof_reader;
+mod disk_budget;
+mod drain_policy;
+mod empty_file_policy;
+#[cfg(feature = "encryption")]
+mod encryption;
+mod error_report;
+mod field_entry;
+mod field_filter;
+mod field_key;
+mod field_map;
+mod field_mapper;
+mod field_response;
 mod fields;
+mod file_data;
+mod file_name_generator;
+mod file_storage_mode;
+mod file_writer;
+mod form_errors;
+#[cfg(feature = "hyper-body")]
+mod hyper_body;
+pub mod inspect;
+mod key_normalization;
+mod key_provider;
+mod manifest;
+mod message_catalog;
 mod multipart_form_data;
 mod multipart_form_data_errors;
 mod multipart_form_data_field;
 mod multipart_form_data_options;
+mod multipart_form_data_schema;
 mod multipart_form_data_type;
+mod named_field;
+mod newline;
+mod number;
+mod oversize_policy;
+mod parse_stats;
+mod parse_warning;
+mod part_handler;
+mod part_offsets;
+mod part_scanner;
+mod part_sinks;
+mod post_processor;
+mod preset;
+mod progressive;
+#[cfg(feature = "prost")]
+mod protobuf;
+mod quota;
+mod redaction;
 mod repetition;
-
+mod request_body;
+mod retry_policy;
+mod rng;
+#[cfg(feature = "serde")]
+mod serialize;
+mod shutdown_reader;
+mod spec;
+mod storage_backend;
+mod temp_dir_handle;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+mod text_decoder;
+mod text_file_name_policy;
+mod unnamed_part_policy;
+#[cfg(feature = "serde")]
+mod upload_session;
+mod upload_tracker;
+#[cfg(feature = "xml")]
+mod value_deserializer;
+#[cfg(feature = "xml")]
+mod xml;
+
+#[cfg(feature = "sanitize")]
+pub use active_content::ActiveContentPolicy;
+pub use admission_control::*;
+pub use archive_inspector::{ArchiveLimits, ArchiveViolation};
+pub use audit::*;
+#[cfg(feature = "sha2")]
+pub use body_digest::BodyDigest;
+pub use boxed_future::*;
+pub use cached_multipart::CachedMultipart;
+pub use cleanup_guard::*;
+pub use clock::{Clock, SystemClock};
+pub use content_type_group::ContentTypeGroup;
+#[cfg(feature = "csv")]
+pub use csv_field::{CsvError, CsvField, CsvRecord, CsvRowHandler};
+pub use data_limit_source::DataLimitSource;
+#[cfg(feature = "sqlx")]
+pub use database_storage::*;
+pub use disk_budget::DiskBudget;
+pub use drain_policy::*;
+pub use empty_file_policy::*;
+#[cfg(feature = "encryption")]
+pub use encryption::DecryptingReader;
+pub use error_report::*;
+pub use field_entry::*;
+pub use field_filter::*;
+pub use field_key::FieldKey;
+pub use field_map::FieldMap;
+pub use field_mapper::*;
+pub use field_response::{FileFieldResponse, RawFieldResponse};
 pub use fields::*;
+pub use file_data::FileData;
+pub use file_name_generator::{FileNameGenerator, TimestampFileNameGenerator};
+pub use file_storage_mode::*;
+pub use key_normalization::*;
+pub use key_provider::*;
+pub use message_catalog::*;
 pub use multipart_form_data::*;
 pub use multipart_form_data_errors::*;
 pub use multipart_form_data_field::*;
 pub use multipart_form_data_options::*;
+pub use multipart_form_data_schema::MultipartFormDataSchema;
 pub use multipart_form_data_type::*;
+pub use named_field::*;
+pub use newline::*;
+pub use number::{Number, Numeric};
+pub use oversize_policy::*;
+pub use parse_stats::*;
+pub use parse_warning::*;
+pub use part_handler::{DefaultPartHandler, PartHandler, PartReader};
+pub use part_offsets::PartOffsets;
+pub use part_sinks::*;
+pub use post_processor::*;
+pub use preset::*;
+pub use progressive::*;
+pub use quota::*;
 pub use repetition::*;
+pub use retry_policy::RetryPolicy;
+pub use rng::{Rng, SystemRng};
+#[cfg(feature = "serde")]
+pub use serialize::Base64RawField;
+pub use spec::SpecError;
+pub use storage_backend::*;
+pub use temp_dir_handle::TempDirHandle;
+pub use text_file_name_policy::*;
+pub use unnamed_part_policy::*;
+#[cfg(feature = "serde")]
+pub use upload_session::*;
+pub use upload_tracker::*;
+#[cfg(feature = "xml")]
+pub use xml::{XmlElement, XmlError, XmlField, XmlNode};
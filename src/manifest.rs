@@ -0,0 +1,870 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    io::{BufRead, BufReader, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
+
+#[cfg(feature = "chrono")]
+use crate::DateTimeField;
+use crate::{
+    mime::Mime, Derivative, FileField, KeyProvider, LargeTextField, MultipartFormData, Number,
+    NumberField, ParseWarning, PartOffsets, RawField, StoredField, TextField,
+};
+
+const MANIFEST_FILE_NAME: &str = "manifest";
+const MANIFEST_HEADER: &str = "rocket-multipart-form-data manifest 2";
+const NULL: &str = "\\N";
+
+/// The columns of a manifest line of a field, which are followed by the names and the values of its `Content-Disposition` parameters.
+const FIELD_COLUMNS: usize = 10;
+
+/// The metadata shared by all kinds of fields.
+struct Meta<'a> {
+    content_type:       &'a Option<Mime>,
+    file_name:          &'a Option<String>,
+    disposition_params: &'a HashMap<String, String>,
+    original_name:      &'a Option<String>,
+    index:              usize,
+}
+
+macro_rules! meta {
+    ($f:expr) => {
+        Meta {
+            content_type:       &$f.content_type,
+            file_name:          &$f.file_name,
+            disposition_params: &$f.disposition_params,
+            original_name:      &$f.original_name,
+            index:              $f.index,
+        }
+    };
+}
+
+/// The columns which only some kinds of fields have.
+#[derive(Default)]
+struct Extra<'a> {
+    content_length: Option<u64>,
+    flags:          Vec<&'a str>,
+    /// The payload file of the raw bytes of a text field.
+    raw_bytes:      Option<String>,
+}
+
+impl MultipartFormData {
+    /// Persist this `MultipartFormData` instance to a directory as a manifest file plus payload files, so that it can be reloaded by the `load` method later. The uploaded files are moved into the directory, and the sub-forms are saved in its subdirectories.
+    ///
+    /// CSV and XML fields cannot be saved, so an `Unsupported` error is returned before anything is moved if there are any.
+    ///
+    /// This method does blocking I/O, so it should be called in `rocket::tokio::task::spawn_blocking` in an async context.
+    pub fn save<P: AsRef<Path>>(mut self, dir: P) -> Result<(), io::Error> {
+        self.check_savable()?;
+
+        let dir = dir.as_ref();
+
+        fs::create_dir_all(dir)?;
+
+        let mut manifest = String::from(MANIFEST_HEADER);
+        manifest.push('\n');
+
+        let mut counter = 0usize;
+
+        let mut next_payload_name = |kind: &str| {
+            counter += 1;
+
+            format!("{}-{}", kind, counter)
+        };
+
+        let mut files = std::mem::take(&mut self.files);
+        let mut move_result = Ok(());
+
+        for (name, fields) in files.iter_mut() {
+            let mut moved = 0;
+
+            for f in fields.iter() {
+                let payload = next_payload_name("file");
+
+                // A persistent file may be shared by other uploads, so copy it instead
+                let result = if f.persistent {
+                    fs::copy(&f.path, dir.join(&payload)).map(|_| ())
+                } else {
+                    move_file(&f.path, dir.join(&payload))
+                };
+
+                if let Err(err) = result {
+                    move_result = Err(err);
+                    break;
+                }
+
+                let mut flags = Vec::new();
+
+                if f.was_deduplicated {
+                    flags.push("deduplicated");
+                }
+
+                if f.persistent {
+                    flags.push("persistent");
+                }
+
+                if f.encryption.is_some() {
+                    flags.push("encrypted");
+                }
+
+                push_line(&mut manifest, "file", name, meta!(f), &payload, Extra {
+                    content_length: f.content_length,
+                    flags,
+                    ..Extra::default()
+                });
+
+                for d in f.derivatives.iter() {
+                    let payload = next_payload_name("derivative");
+
+                    if let Err(err) = move_file(&d.path, dir.join(&payload)) {
+                        move_result = Err(err);
+                        break;
+                    }
+
+                    push_line(
+                        &mut manifest,
+                        "derivative",
+                        name,
+                        Meta {
+                            content_type:       &d.content_type,
+                            file_name:          &Some(d.name.clone()),
+                            disposition_params: &HashMap::new(),
+                            original_name:      &None,
+                            index:              f.index,
+                        },
+                        &payload,
+                        Extra::default(),
+                    );
+                }
+
+                if move_result.is_err() {
+                    break;
+                }
+
+                moved += 1;
+            }
+
+            fields.drain(..moved);
+
+            if move_result.is_err() {
+                break;
+            }
+        }
+
+        // Put the files which have not been moved back, so that they can be deleted when `self` is dropped.
+        self.files = files;
+
+        move_result?;
+
+        let mut large_texts = std::mem::take(&mut self.large_texts);
+        let mut move_result = Ok(());
+
+        for (name, fields) in large_texts.iter_mut() {
+            let mut moved = 0;
+
+            for f in fields.iter() {
+                let payload = next_payload_name("large_text");
+
+                if let Err(err) = move_file(&f.path, dir.join(&payload)) {
+                    move_result = Err(err);
+                    break;
+                }
+
+                let flags = if f.encryption.is_some() { vec!["encrypted"] } else { Vec::new() };
+
+                push_line(&mut manifest, "large_text", name, meta!(f), &payload, Extra {
+                    flags,
+                    ..Extra::default()
+                });
+
+                moved += 1;
+            }
+
+            fields.drain(..moved);
+
+            if move_result.is_err() {
+                break;
+            }
+        }
+
+        self.large_texts = large_texts;
+
+        move_result?;
+
+        for (name, fields) in self.raw.iter() {
+            for f in fields {
+                let payload = next_payload_name("raw");
+
+                fs::write(dir.join(&payload), &f.raw)?;
+
+                push_line(&mut manifest, "raw", name, meta!(f), &payload, Extra::default());
+            }
+        }
+
+        for (name, fields) in self.texts.iter() {
+            for f in fields {
+                let payload = next_payload_name("text");
+
+                fs::write(dir.join(&payload), &f.text)?;
+
+                let raw_bytes = match f.raw_bytes.as_ref() {
+                    Some(raw_bytes) => {
+                        let payload = next_payload_name("raw_bytes");
+
+                        fs::write(dir.join(&payload), raw_bytes)?;
+
+                        Some(payload)
+                    },
+                    None => None,
+                };
+
+                push_line(&mut manifest, "text", name, meta!(f), &payload, Extra {
+                    raw_bytes,
+                    ..Extra::default()
+                });
+            }
+        }
+
+        for (name, fields) in self.numbers.iter() {
+            for f in fields {
+                let number = match f.number {
+                    Number::Integer(n) => format!("i:{}", n),
+                    Number::Unsigned(n) => format!("u:{}", n),
+                    Number::Float(n) => format!("f:{}", n),
+                };
+
+                push_line(&mut manifest, "number", name, meta!(f), &number, Extra::default());
+            }
+        }
+
+        for (name, fields) in self.stored.iter() {
+            for f in fields {
+                let stored = format!("{}:{}", f.size, f.id);
+
+                push_line(&mut manifest, "stored", name, meta!(f), &stored, Extra {
+                    content_length: f.content_length,
+                    ..Extra::default()
+                });
+            }
+        }
+
+        #[cfg(feature = "chrono")]
+        for (name, fields) in self.datetimes.iter() {
+            for f in fields {
+                let datetime = f.datetime.format("%Y-%m-%dT%H:%M:%S%.f").to_string();
+
+                push_line(&mut manifest, "datetime", name, meta!(f), &datetime, Extra::default());
+            }
+        }
+
+        for (name, value) in self.bools.iter() {
+            push_columns(&mut manifest, &[
+                "bool",
+                &escape(name),
+                if *value { "true" } else { "false" },
+            ]);
+        }
+
+        for (name, forms) in std::mem::take(&mut self.nested) {
+            for form in forms {
+                let payload = next_payload_name("nested");
+
+                form.save(dir.join(&payload))?;
+
+                push_columns(&mut manifest, &["nested", &escape(&name), &payload]);
+            }
+        }
+
+        for warning in self.warnings.iter() {
+            let (kind, field) = match warning {
+                ParseWarning::UnnamedPart => ("UnnamedPart", None),
+                ParseWarning::UnknownField(field) => ("UnknownField", Some(field.as_str())),
+                ParseWarning::EmptyFileIgnored(field) => ("EmptyFileIgnored", Some(field.as_ref())),
+                ParseWarning::ContentTypeMismatchOnEmptyFile(field) => {
+                    ("ContentTypeMismatchOnEmptyFile", Some(field.as_ref()))
+                },
+                ParseWarning::ExtraRepetitionDropped(field) => {
+                    ("ExtraRepetitionDropped", Some(field.as_ref()))
+                },
+                ParseWarning::DataTruncated(field) => ("DataTruncated", Some(field.as_ref())),
+                #[cfg(feature = "sanitize")]
+                ParseWarning::ActiveContentStripped(field) => {
+                    ("ActiveContentStripped", Some(field.as_ref()))
+                },
+            };
+
+            push_columns(&mut manifest, &["warning", kind, &escape_option(field)]);
+        }
+
+        for name in self.redacted.iter() {
+            push_columns(&mut manifest, &["redacted", &escape(name)]);
+        }
+
+        push_columns(&mut manifest, &[
+            "stats",
+            &self.stats.bytes_read.to_string(),
+            &self.stats.text_parts.to_string(),
+            &self.stats.file_parts.to_string(),
+            &self.stats.elapsed.as_nanos().to_string(),
+        ]);
+
+        for offsets in self.part_offsets.iter() {
+            push_columns(&mut manifest, &[
+                "offsets",
+                &offsets.part.start.to_string(),
+                &offsets.part.end.to_string(),
+                &offsets.data.start.to_string(),
+                &offsets.data.end.to_string(),
+            ]);
+        }
+
+        #[cfg(feature = "sha2")]
+        if let Some(digest) = self.body_digest.as_ref() {
+            let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+
+            push_columns(&mut manifest, &["digest", &hex]);
+        }
+
+        let mut file = fs::File::create(dir.join(MANIFEST_FILE_NAME))?;
+
+        file.write_all(manifest.as_bytes())?;
+        file.sync_all()?;
+
+        Ok(())
+    }
+
+    /// Return an `Unsupported` error if this instance or its sub-forms have fields which cannot be saved.
+    fn check_savable(&self) -> Result<(), io::Error> {
+        #[cfg(feature = "csv")]
+        if self.csv.values().any(|fields| !fields.is_empty()) {
+            return Err(io::Error::new(io::ErrorKind::Unsupported, "CSV fields cannot be saved."));
+        }
+
+        #[cfg(feature = "xml")]
+        if self.xml.values().any(|fields| !fields.is_empty()) {
+            return Err(io::Error::new(io::ErrorKind::Unsupported, "XML fields cannot be saved."));
+        }
+
+        for form in self.nested.values().flatten() {
+            form.check_savable()?;
+        }
+
+        Ok(())
+    }
+
+    /// Reload a `MultipartFormData` instance persisted by the `save` method. The uploaded files are left in the directory and will be deleted when the reloaded instance is dropped, unless they are persistent.
+    ///
+    /// An `InvalidData` error is returned if any of the files is encrypted. Use the `load_with_key_provider` method for them instead.
+    ///
+    /// This method does blocking I/O, so it should be called in `rocket::tokio::task::spawn_blocking` in an async context.
+    #[inline]
+    pub fn load<P: AsRef<Path>>(dir: P) -> Result<MultipartFormData, io::Error> {
+        MultipartFormData::load_inner(dir.as_ref(), None)
+    }
+
+    /// Reload a `MultipartFormData` instance persisted by the `save` method, like the `load` method. The encrypted files are decrypted with the key from `encryption`, which has to be the one they were encrypted with.
+    ///
+    /// This method does blocking I/O, so it should be called in `rocket::tokio::task::spawn_blocking` in an async context.
+    #[inline]
+    pub fn load_with_key_provider<P: AsRef<Path>>(
+        dir: P,
+        encryption: Arc<dyn KeyProvider>,
+    ) -> Result<MultipartFormData, io::Error> {
+        MultipartFormData::load_inner(dir.as_ref(), Some(&encryption))
+    }
+
+    fn load_inner(
+        dir: &Path,
+        encryption: Option<&Arc<dyn KeyProvider>>,
+    ) -> Result<MultipartFormData, io::Error> {
+        let mut lines = BufReader::new(fs::File::open(dir.join(MANIFEST_FILE_NAME))?).lines();
+
+        if lines.next().transpose()?.as_deref() != Some(MANIFEST_HEADER) {
+            return Err(invalid_data("The manifest header is incorrect."));
+        }
+
+        let mut multipart_form_data = MultipartFormData::default();
+
+        if let Err(err) = multipart_form_data.load_lines(dir, lines, encryption) {
+            // The files which have been loaded must not be deleted when the instance is dropped
+            multipart_form_data.leave_files();
+
+            return Err(err);
+        }
+
+        Ok(multipart_form_data)
+    }
+
+    fn load_lines(
+        &mut self,
+        dir: &Path,
+        lines: impl Iterator<Item = Result<String, io::Error>>,
+        encryption: Option<&Arc<dyn KeyProvider>>,
+    ) -> Result<(), io::Error> {
+        let multipart_form_data = self;
+
+        for line in lines {
+            let line = line?;
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let columns: Vec<&str> = line.split('\t').collect();
+
+            let kind = columns[0];
+
+            match kind {
+                "bool" => {
+                    let (name, value) = match columns[1..] {
+                        [name, "true"] => (name, true),
+                        [name, "false"] => (name, false),
+                        _ => return Err(invalid_line(&line)),
+                    };
+
+                    multipart_form_data.bools.insert(Arc::from(unescape(name)), value);
+
+                    continue;
+                },
+                "nested" => {
+                    let (name, payload) = match columns[1..] {
+                        [name, payload] => (name, payload),
+                        _ => return Err(invalid_line(&line)),
+                    };
+
+                    let form =
+                        MultipartFormData::load_inner(&dir.join(unescape(payload)), encryption)?;
+
+                    multipart_form_data
+                        .nested
+                        .entry(Arc::from(unescape(name)))
+                        .or_default()
+                        .push(form);
+
+                    continue;
+                },
+                "warning" => {
+                    let field = match columns[1..] {
+                        [_, field] => unescape_option(field),
+                        _ => return Err(invalid_line(&line)),
+                    };
+
+                    let warning = match (columns[1], field) {
+                        ("UnnamedPart", None) => ParseWarning::UnnamedPart,
+                        ("UnknownField", Some(field)) => ParseWarning::UnknownField(field),
+                        ("EmptyFileIgnored", Some(field)) => {
+                            ParseWarning::EmptyFileIgnored(Arc::from(field))
+                        },
+                        ("ContentTypeMismatchOnEmptyFile", Some(field)) => {
+                            ParseWarning::ContentTypeMismatchOnEmptyFile(Arc::from(field))
+                        },
+                        ("ExtraRepetitionDropped", Some(field)) => {
+                            ParseWarning::ExtraRepetitionDropped(Arc::from(field))
+                        },
+                        ("DataTruncated", Some(field)) => {
+                            ParseWarning::DataTruncated(Arc::from(field))
+                        },
+                        #[cfg(feature = "sanitize")]
+                        ("ActiveContentStripped", Some(field)) => {
+                            ParseWarning::ActiveContentStripped(Arc::from(field))
+                        },
+                        _ => return Err(invalid_line(&line)),
+                    };
+
+                    multipart_form_data.warnings.push(warning);
+
+                    continue;
+                },
+                "redacted" => {
+                    let name = match columns[1..] {
+                        [name] => name,
+                        _ => return Err(invalid_line(&line)),
+                    };
+
+                    multipart_form_data.redacted.push(Arc::from(unescape(name)));
+
+                    continue;
+                },
+                "stats" => {
+                    let numbers = parse_numbers(&columns[1..], &line)?;
+
+                    match numbers[..] {
+                        [bytes_read, text_parts, file_parts, elapsed] => {
+                            let stats = &mut multipart_form_data.stats;
+
+                            stats.bytes_read = bytes_read;
+                            stats.text_parts = text_parts as usize;
+                            stats.file_parts = file_parts as usize;
+                            stats.elapsed = Duration::from_nanos(elapsed);
+                        },
+                        _ => return Err(invalid_line(&line)),
+                    }
+
+                    continue;
+                },
+                "offsets" => {
+                    let numbers = parse_numbers(&columns[1..], &line)?;
+
+                    match numbers[..] {
+                        [part_start, part_end, data_start, data_end] => {
+                            multipart_form_data.part_offsets.push(PartOffsets {
+                                part: Range {
+                                    start: part_start, end: part_end
+                                },
+                                data: Range {
+                                    start: data_start, end: data_end
+                                },
+                            })
+                        },
+                        _ => return Err(invalid_line(&line)),
+                    }
+
+                    continue;
+                },
+                #[cfg(feature = "sha2")]
+                "digest" => {
+                    let hex = match columns[1..] {
+                        [hex] if hex.len() == 64 => hex,
+                        _ => return Err(invalid_line(&line)),
+                    };
+
+                    let mut digest = [0u8; 32];
+
+                    for (i, b) in digest.iter_mut().enumerate() {
+                        *b = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+                            .map_err(|_| invalid_line(&line))?;
+                    }
+
+                    multipart_form_data.body_digest = Some(digest);
+
+                    continue;
+                },
+                _ => (),
+            }
+
+            if columns.len() < FIELD_COLUMNS || (columns.len() - FIELD_COLUMNS) % 2 != 0 {
+                return Err(invalid_line(&line));
+            }
+
+            let name: Arc<str> = Arc::from(unescape(columns[1]));
+            let content_type = match unescape_option(columns[2]) {
+                Some(content_type) => {
+                    Some(Mime::from_str(&content_type).map_err(|_| invalid_line(&line))?)
+                },
+                None => None,
+            };
+            let file_name = unescape_option(columns[3]);
+            let original_name = unescape_option(columns[4]);
+            let index: usize = columns[5].parse().map_err(|_| invalid_line(&line))?;
+            let value = unescape(columns[6]);
+            let content_length = match unescape_option(columns[7]) {
+                Some(content_length) => {
+                    Some(content_length.parse().map_err(|_| invalid_line(&line))?)
+                },
+                None => None,
+            };
+            let flags: Vec<&str> = columns[8].split(',').filter(|f| !f.is_empty()).collect();
+            let raw_bytes = unescape_option(columns[9]);
+            let disposition_params: HashMap<String, String> = columns[FIELD_COLUMNS..]
+                .chunks(2)
+                .map(|pair| (unescape(pair[0]), unescape(pair[1])))
+                .collect();
+
+            match kind {
+                "file" => {
+                    let encryption = encrypted_with(&flags, encryption)?;
+
+                    let path = dir.join(value);
+                    let size = fs::metadata(&path)?.len();
+
+                    let f = FileField {
+                        content_type,
+                        file_name,
+                        disposition_params,
+                        original_name,
+                        index,
+                        path,
+                        size,
+                        content_length,
+                        derivatives: Vec::new(),
+                        was_deduplicated: flags.contains(&"deduplicated"),
+                        persistent: flags.contains(&"persistent"),
+                        encryption,
+                    };
+
+                    multipart_form_data.files.entry(name).or_default().push(f);
+                },
+                "derivative" => {
+                    let d = Derivative {
+                        name: file_name.ok_or_else(|| invalid_line(&line))?,
+                        content_type,
+                        path: dir.join(value),
+                    };
+
+                    // A derivative belongs to the file which is listed right before it
+                    multipart_form_data
+                        .files
+                        .get_mut(&name)
+                        .and_then(|fields| fields.last_mut())
+                        .ok_or_else(|| invalid_line(&line))?
+                        .derivatives
+                        .push(d);
+                },
+                "raw" => {
+                    let f = RawField {
+                        content_type,
+                        file_name,
+                        disposition_params,
+                        original_name,
+                        index,
+                        raw: fs::read(dir.join(value))?,
+                    };
+
+                    multipart_form_data.raw.entry(name).or_default().push(f);
+                },
+                "text" => {
+                    let raw_bytes = match raw_bytes {
+                        Some(payload) => Some(fs::read(dir.join(payload))?),
+                        None => None,
+                    };
+
+                    let f = TextField {
+                        content_type,
+                        file_name,
+                        disposition_params,
+                        original_name,
+                        index,
+                        text: fs::read_to_string(dir.join(value))?,
+                        raw_bytes,
+                    };
+
+                    multipart_form_data.texts.entry(name).or_default().push(f);
+                },
+                "large_text" => {
+                    let encryption = encrypted_with(&flags, encryption)?;
+
+                    let path = dir.join(value);
+                    let len = fs::metadata(&path)?.len();
+
+                    #[cfg(feature = "encryption")]
+                    let len = if encryption.is_some() {
+                        crate::encryption::plaintext_len(len)
+                    } else {
+                        len
+                    };
+
+                    let f = LargeTextField {
+                        content_type,
+                        file_name,
+                        disposition_params,
+                        original_name,
+                        index,
+                        path,
+                        len,
+                        encryption,
+                    };
+
+                    multipart_form_data.large_texts.entry(name).or_default().push(f);
+                },
+                "number" => {
+                    let number = match value.split_once(':') {
+                        Some(("i", n)) => n.parse().ok().map(Number::Integer),
+                        Some(("u", n)) => n.parse().ok().map(Number::Unsigned),
+                        Some(("f", n)) => n.parse().ok().map(Number::Float),
+                        _ => None,
+                    }
+                    .ok_or_else(|| invalid_line(&line))?;
+
+                    let f = NumberField {
+                        content_type,
+                        file_name,
+                        disposition_params,
+                        original_name,
+                        index,
+                        number,
+                    };
+
+                    multipart_form_data.numbers.entry(name).or_default().push(f);
+                },
+                "stored" => {
+                    let (size, id) = value.split_once(':').ok_or_else(|| invalid_line(&line))?;
+
+                    let f = StoredField {
+                        content_type,
+                        file_name,
+                        disposition_params,
+                        original_name,
+                        index,
+                        id: id.to_string(),
+                        size: size.parse().map_err(|_| invalid_line(&line))?,
+                        content_length,
+                    };
+
+                    multipart_form_data.stored.entry(name).or_default().push(f);
+                },
+                #[cfg(feature = "chrono")]
+                "datetime" => {
+                    let datetime =
+                        chrono::NaiveDateTime::parse_from_str(&value, "%Y-%m-%dT%H:%M:%S%.f")
+                            .map_err(|_| invalid_line(&line))?;
+
+                    let f = DateTimeField {
+                        content_type,
+                        file_name,
+                        disposition_params,
+                        original_name,
+                        index,
+                        datetime,
+                    };
+
+                    multipart_form_data.datetimes.entry(name).or_default().push(f);
+                },
+                _ => return Err(invalid_line(&line)),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Take the files out of this instance and its sub-forms without deleting them.
+    fn leave_files(&mut self) {
+        self.files.clear();
+        self.large_texts.clear();
+
+        for form in self.nested.values_mut().flatten() {
+            form.leave_files();
+        }
+    }
+}
+
+fn push_line(manifest: &mut String, kind: &str, name: &str, meta: Meta, value: &str, extra: Extra) {
+    let mut columns = vec![
+        kind.to_string(),
+        escape(name),
+        escape_option(meta.content_type.as_ref().map(|m| m.as_ref())),
+        escape_option(meta.file_name.as_deref()),
+        escape_option(meta.original_name.as_deref()),
+        meta.index.to_string(),
+        escape(value),
+        escape_option(extra.content_length.map(|n| n.to_string()).as_deref()),
+        extra.flags.join(","),
+        escape_option(extra.raw_bytes.as_deref()),
+    ];
+
+    for (name, value) in meta.disposition_params.iter() {
+        columns.push(escape(name));
+        columns.push(escape(value));
+    }
+
+    manifest.push_str(&columns.join("\t"));
+    manifest.push('\n');
+}
+
+/// Push a line of columns which have been escaped.
+fn push_columns(manifest: &mut String, columns: &[&str]) {
+    manifest.push_str(&columns.join("\t"));
+    manifest.push('\n');
+}
+
+fn parse_numbers(columns: &[&str], line: &str) -> Result<Vec<u64>, io::Error> {
+    columns.iter().map(|n| n.parse().map_err(|_| invalid_line(line))).collect()
+}
+
+pub(crate) fn escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[inline]
+pub(crate) fn escape_option(s: Option<&str>) -> String {
+    match s {
+        Some(s) => escape(s),
+        None => String::from(NULL),
+    }
+}
+
+pub(crate) fn unescape(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('t') => unescaped.push('\t'),
+                Some('n') => unescaped.push('\n'),
+                Some('r') => unescaped.push('\r'),
+                Some(c) => unescaped.push(c),
+                None => unescaped.push('\\'),
+            }
+        } else {
+            unescaped.push(c);
+        }
+    }
+
+    unescaped
+}
+
+#[inline]
+pub(crate) fn unescape_option(s: &str) -> Option<String> {
+    if s == NULL {
+        None
+    } else {
+        Some(unescape(s))
+    }
+}
+
+#[inline]
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Get the key provider of a file whose line has the `encrypted` flag.
+fn encrypted_with(
+    flags: &[&str],
+    encryption: Option<&Arc<dyn KeyProvider>>,
+) -> Result<Option<Arc<dyn KeyProvider>>, io::Error> {
+    if !flags.contains(&"encrypted") {
+        return Ok(None);
+    }
+
+    match encryption {
+        Some(encryption) => Ok(Some(encryption.clone())),
+        None => {
+            Err(invalid_data("The manifest has encrypted files, but no key provider is given."))
+        },
+    }
+}
+
+#[inline]
+pub(crate) fn invalid_line(line: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("Invalid manifest line: {:?}", line))
+}
+
+pub(crate) fn move_file<P: AsRef<Path>>(from: P, to: PathBuf) -> Result<(), io::Error> {
+    let from = from.as_ref();
+
+    if fs::rename(from, &to).is_err() {
+        // Maybe they are on different filesystems
+        fs::copy(from, &to)?;
+        fs::remove_file(from)?;
+    }
+
+    Ok(())
+}
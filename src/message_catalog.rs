@@ -0,0 +1,48 @@
+use std::fmt::{self, Debug, Formatter};
+
+use crate::MultipartFormDataError;
+
+/// A catalog of the messages of errors, in order to localize or rebrand the errors which are shown to end users.
+///
+/// It is implemented for closures of type `Fn(&MultipartFormDataError) -> Option<String>`.
+///
+/// ```rust
+/// use rocket_multipart_form_data::{MessageCatalog, MultipartFormDataError};
+///
+/// let catalog = |error: &MultipartFormDataError| match error {
+///     MultipartFormDataError::DataTooLargeError(field) => {
+///         Some(format!("「{}」のファイルが大きすぎます。", field))
+///     },
+///     _ => None,
+/// };
+///
+/// let error = MultipartFormDataError::DataTooLargeError("photo".into());
+///
+/// assert_eq!("「photo」のファイルが大きすぎます。", error.message(&catalog));
+/// ```
+pub trait MessageCatalog: Send + Sync {
+    /// The message of an error. Return `None` to use its `Display` output.
+    fn message(&self, error: &MultipartFormDataError) -> Option<String>;
+}
+
+impl<F: Fn(&MultipartFormDataError) -> Option<String> + Send + Sync> MessageCatalog for F {
+    #[inline]
+    fn message(&self, error: &MultipartFormDataError) -> Option<String> {
+        self(error)
+    }
+}
+
+impl Debug for dyn MessageCatalog {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("MessageCatalog")
+    }
+}
+
+impl MultipartFormDataError {
+    /// The message of this error in `catalog`, or its `Display` output if `catalog` does not have one.
+    #[inline]
+    pub fn message(&self, catalog: &dyn MessageCatalog) -> String {
+        catalog.message(self).unwrap_or_else(|| self.to_string())
+    }
+}
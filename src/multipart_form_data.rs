@@ -1,67 +1,535 @@
-use std::{collections::HashMap, fs, path::Path, sync::Arc, time::SystemTime};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fs,
+    ops::{Deref, RangeBounds},
+    path::{Path, PathBuf},
+    sync::{atomic::Ordering, Arc},
+    time::Instant,
+};
 
+use http::{header::CONTENT_LENGTH, HeaderMap};
 use rocket::{
+    data::{ByteUnit, Limits},
     http::ContentType,
-    tokio::{fs::File, io::AsyncWriteExt},
+    tokio::{
+        fs::File,
+        io::{AsyncRead, AsyncReadExt},
+        sync::mpsc::UnboundedSender,
+        task,
+    },
     Data,
 };
-
+#[cfg(feature = "sha2")]
+use sha2::{Digest, Sha256};
+
+#[cfg(feature = "sha2")]
+use crate::body_digest::DigestReader;
+#[cfg(feature = "encryption")]
+use crate::encryption::Encryptor;
+#[cfg(feature = "chrono")]
+use crate::DateTimeField;
+#[cfg(feature = "sha2")]
+use crate::FileStorageMode;
+#[cfg(feature = "sanitize")]
+use crate::{
+    active_content::{has_active_content, strip_active_content},
+    ActiveContentPolicy,
+};
 use crate::{
-    mime, multer::Multipart, FileField, MultipartFormDataError, MultipartFormDataOptions,
-    MultipartFormDataType, RawField, TextField,
+    archive_inspector::ArchiveInspector,
+    boundary::is_valid_boundary,
+    chunk_reader::{ChunkReader, CountingReader},
+    content_disposition::content_disposition_params,
+    content_type_filter::{content_type_matches, content_type_matches_suffix},
+    content_type_inference::{infer_content_type, MAGIC_BYTES_LENGTH},
+    deferred_eof_reader::DeferredEofReader,
+    disk_budget::DiskCharge,
+    file_name_generator::{create_idempotent_file, create_new_file, DefaultFileNameGenerator},
+    file_writer::FileWriter,
+    mime::Mime,
+    multer::{self, bytes::Bytes, Constraints, Field, Multipart},
+    multipart_form_data_schema::intern,
+    part_handler::PartSource,
+    part_scanner::PartScanner,
+    request_body::{RequestBody, RocketBody},
+    shutdown_reader::ShutdownReader,
+    text_decoder::TextDecoder,
+    AdmissionRequest, BoxFuture, CleanupGuard, DataLimitSource, DrainPolicy, EmptyFilePolicy,
+    FieldDecision, FieldMeta, FileField, LargeTextField, MultipartFormDataError,
+    MultipartFormDataField, MultipartFormDataOptions, MultipartFormDataSchema,
+    MultipartFormDataType, NumberField, OversizePolicy, ParseStats, ParseWarning, ParsedField,
+    PartHandler, PartOffsets, PartReader, PartialFile, RawField, Repetition, StoredField,
+    SystemClock, SystemRng, TextField, TextFileNamePolicy, UnnamedPartPolicy,
+};
+#[cfg(feature = "csv")]
+use crate::{csv_field::CsvParser, CsvError, CsvField, CsvRecord};
+#[cfg(feature = "xml")]
+use crate::{
+    xml::{parse_xml, XmlError},
+    XmlField,
 };
 
+const UTF8_BOM: &str = "\u{FEFF}";
+const DATA_FORM_LIMIT: &str = "data-form";
+
 /// Parsed multipart/form-data.
-#[derive(Debug)]
+#[derive(Default)]
+#[non_exhaustive]
 pub struct MultipartFormData {
-    pub files: HashMap<Arc<str>, Vec<FileField>>,
-    pub raw:   HashMap<Arc<str>, Vec<RawField>>,
-    pub texts: HashMap<Arc<str>, Vec<TextField>>,
+    pub files:           HashMap<Arc<str>, Vec<FileField>>,
+    pub raw:             HashMap<Arc<str>, Vec<RawField>>,
+    pub texts:           HashMap<Arc<str>, Vec<TextField>>,
+    pub bools:           HashMap<Arc<str>, bool>,
+    pub numbers:         HashMap<Arc<str>, Vec<NumberField>>,
+    /// The uploaded files which are stored by the `StorageBackend` set in the options.
+    pub stored:          HashMap<Arc<str>, Vec<StoredField>>,
+    /// The text fields which exceed the `large_text` thresholds of their fields, so they are stored in files.
+    pub large_texts:     HashMap<Arc<str>, Vec<LargeTextField>>,
+    #[cfg(feature = "chrono")]
+    pub datetimes:       HashMap<Arc<str>, Vec<DateTimeField>>,
+    #[cfg(feature = "csv")]
+    pub csv:             HashMap<Arc<str>, Vec<CsvField>>,
+    #[cfg(feature = "xml")]
+    pub xml:             HashMap<Arc<str>, Vec<XmlField>>,
+    /// Non-fatal anomalies found while parsing.
+    pub warnings:        Vec<ParseWarning>,
+    /// The sub-forms which are parsed from the multipart bodies nested in the raw fields declared by the `nested` method of `MultipartFormDataField`.
+    pub nested:          HashMap<Arc<str>, Vec<MultipartFormData>>,
+    /// The digest of the whole HTTP body, which is computed as set by the `body_digest` option.
+    #[cfg(feature = "sha2")]
+    pub body_digest:     Option<[u8; 32]>,
+    /// Statistics of parsing, such as the measured reading rate.
+    pub stats:           ParseStats,
+    /// The byte ranges of the parts in the HTTP body, which are indexed by the `index` of the fields. It is only filled if `record_offsets` is enabled.
+    pub part_offsets:    Vec<PartOffsets>,
+    /// The names of the fields whose contents are left out of the `Debug` output.
+    pub(crate) redacted: Vec<Arc<str>>,
+    disk_charge:         DiskCharge,
 }
 
 impl MultipartFormData {
     /// Parse multipart/form-data from the HTTP body.
     pub async fn parse(
+        content_type: &ContentType,
+        data: Data<'_>,
+        options: MultipartFormDataOptions<'_>,
+    ) -> Result<MultipartFormData, MultipartFormDataError> {
+        MultipartFormData::parse_body(
+            RocketBody {
+                content_type,
+                data,
+            },
+            options,
+        )
+        .await
+    }
+
+    /// Parse multipart/form-data from the HTTP body, which is read at most `cap` bytes if it is set, or the `data-form` limit of `limits` (such as `request.limits()`), instead of the `max_data_bytes` of the options. The `size_limit` of the fields which is bigger than the limit is lowered to it. A body which exceeds the limit causes a `DataLimitExceededError` telling which one is used.
+    pub async fn parse_limited(
         content_type: &ContentType,
         data: Data<'_>,
         mut options: MultipartFormDataOptions<'_>,
+        limits: &Limits,
+        cap: Option<ByteUnit>,
     ) -> Result<MultipartFormData, MultipartFormDataError> {
-        if !content_type.is_form_data() {
-            return Err(MultipartFormDataError::NotFormDataError);
+        let (limit, source) = match cap {
+            Some(cap) => (cap, DataLimitSource::Cap),
+            None => (
+                limits.get(DATA_FORM_LIMIT).unwrap_or(Limits::DATA_FORM),
+                DataLimitSource::RocketLimits,
+            ),
+        };
+
+        let limit = limit.as_u64();
+
+        options.max_data_bytes = limit;
+
+        for field in options.allowed_fields.iter_mut() {
+            field.size_limit = field.size_limit.min(limit);
         }
 
-        let (_, boundary) = match content_type.params().find(|&(k, _)| k == "boundary") {
-            Some(s) => s,
-            None => return Err(MultipartFormDataError::BoundaryNotFoundError),
+        MultipartFormData::parse(content_type, data, options).await.map_err(|err| match err {
+            MultipartFormDataError::DataLimitExceededError {
+                limit, ..
+            } => MultipartFormDataError::DataLimitExceededError {
+                limit,
+                source,
+            },
+            err => err,
+        })
+    }
+
+    /// Parse multipart/form-data from the HTTP body with a `MultipartFormDataSchema`, which can be shared by many requests.
+    pub async fn parse_schema(
+        content_type: &ContentType,
+        data: Data<'_>,
+        schema: &MultipartFormDataSchema<'_>,
+    ) -> Result<MultipartFormData, MultipartFormDataError> {
+        let body = RocketBody {
+            content_type,
+            data,
+        };
+
+        let boundary = body.boundary()?;
+
+        let stream = body.open(schema.options.read_limit());
+
+        MultipartFormData::parse_reader_with(stream, boundary, None, schema, None, None).await
+    }
+
+    /// Parse multipart/form-data from a file which contains a captured HTTP body, such as the one saved by logging middleware. The same limits are applied as the `parse` method.
+    pub async fn parse_file<P: AsRef<Path>, S: Into<String>>(
+        path: P,
+        boundary: S,
+        options: MultipartFormDataOptions<'_>,
+    ) -> Result<MultipartFormData, MultipartFormDataError> {
+        let stream = File::open(path).await?.take(options.read_limit());
+
+        MultipartFormData::parse_reader(stream, boundary, options).await
+    }
+
+    /// Parse multipart/form-data from the HTTP body, and let `handler` take over the parts of some fields from the standard handling. The parts which are taken over are put into the `stored` map.
+    pub async fn parse_with(
+        content_type: &ContentType,
+        data: Data<'_>,
+        options: MultipartFormDataOptions<'_>,
+        handler: &dyn PartHandler,
+    ) -> Result<MultipartFormData, MultipartFormDataError> {
+        let body = RocketBody {
+            content_type,
+            data,
+        };
+
+        let boundary = body.boundary()?;
+
+        let stream = body.open(options.read_limit());
+
+        let schema = MultipartFormDataSchema::new(options)?;
+
+        MultipartFormData::parse_reader_with(stream, boundary, None, &schema, Some(handler), None)
+            .await
+    }
+
+    /// Parse multipart/form-data from the HTTP body, and send the fields to `progress` as soon as they have been completely received.
+    pub(crate) async fn parse_progressive_with(
+        content_type: &ContentType,
+        data: Data<'_>,
+        options: MultipartFormDataOptions<'_>,
+        progress: UnboundedSender<ParsedField>,
+    ) -> Result<MultipartFormData, MultipartFormDataError> {
+        let body = RocketBody {
+            content_type,
+            data,
         };
 
-        options.allowed_fields.sort_by_key(|e| e.field_name);
+        let boundary = body.boundary()?;
+
+        let stream = body.open(options.read_limit());
+
+        let schema = MultipartFormDataSchema::new(options)?;
+
+        MultipartFormData::parse_reader_with(stream, boundary, None, &schema, None, Some(&progress))
+            .await
+    }
+
+    /// Parse multipart/form-data from the body of a request of any supported web framework.
+    #[inline]
+    pub(crate) async fn parse_body<B: RequestBody>(
+        body: B,
+        options: MultipartFormDataOptions<'_>,
+    ) -> Result<MultipartFormData, MultipartFormDataError> {
+        let boundary = body.boundary()?;
+        let body_length = body.content_length();
+
+        let stream = body.open(options.read_limit());
+
+        let schema = MultipartFormDataSchema::new(options)?;
+
+        MultipartFormData::parse_reader_with(stream, boundary, body_length, &schema, None, None)
+            .await
+    }
+
+    #[inline]
+    pub(crate) async fn parse_reader<R: AsyncRead + Send + Unpin, S: Into<String>>(
+        reader: R,
+        boundary: S,
+        options: MultipartFormDataOptions<'_>,
+    ) -> Result<MultipartFormData, MultipartFormDataError> {
+        let schema = MultipartFormDataSchema::new(options)?;
+
+        MultipartFormData::parse_reader_with(reader, boundary, None, &schema, None, None).await
+    }
+
+    async fn parse_reader_with<R: AsyncRead + Send + Unpin, S: Into<String>>(
+        reader: R,
+        boundary: S,
+        body_length: Option<u64>,
+        schema: &MultipartFormDataSchema<'_>,
+        handler: Option<&dyn PartHandler>,
+        progress: Option<&UnboundedSender<ParsedField>>,
+    ) -> Result<MultipartFormData, MultipartFormDataError> {
+        let started = Instant::now();
+
+        let result = MultipartFormData::parse_reader_unaudited(
+            reader,
+            boundary,
+            body_length,
+            schema,
+            handler,
+            progress,
+        )
+        .await;
+
+        if let Some(audit) = schema.options.audit.as_ref() {
+            audit.record(&result, started.elapsed()).await;
+        }
+
+        result
+    }
+
+    async fn parse_reader_unaudited<R: AsyncRead + Send + Unpin, S: Into<String>>(
+        reader: R,
+        boundary: S,
+        body_length: Option<u64>,
+        schema: &MultipartFormDataSchema<'_>,
+        handler: Option<&dyn PartHandler>,
+        progress: Option<&UnboundedSender<ParsedField>>,
+    ) -> Result<MultipartFormData, MultipartFormDataError> {
+        let started = Instant::now();
+
+        let options = &schema.options;
+
+        let boundary = boundary.into();
+
+        if options.strict_boundary && !is_valid_boundary(&boundary) {
+            return Err(MultipartFormDataError::InvalidBoundaryError);
+        }
+
+        if let Some(admission) = options.admission.as_ref() {
+            let request = AdmissionRequest {
+                fields:         &options.allowed_fields,
+                content_length: body_length,
+            };
 
-        let stream = data.open(options.max_data_bytes.into());
+            admission
+                .control
+                .admit(request)
+                .await
+                .map_err(MultipartFormDataError::AdmissionDeniedError)?;
+        }
 
-        let mut multipart = Multipart::new(tokio_util::io::ReaderStream::new(stream), boundary);
+        // The fields which can still accept parts, with their remaining repetitions
+        let mut allowed_fields: Vec<AllowedField> = options
+            .allowed_fields
+            .iter()
+            .zip(schema.keys.iter())
+            .map(|(field, key)| AllowedField {
+                field,
+                key,
+                repetition: field.repetition,
+            })
+            .collect();
+
+        let reader = CountingReader::new(DeferredEofReader::new(reader));
+        let pulled = reader.count();
+
+        let reader = ShutdownReader::new(reader, options.shutdown.clone(), progress.cloned());
+        let shut_down = reader.aborted();
+
+        let part_offsets = if options.record_offsets { Some(Arc::default()) } else { None };
+
+        let mut reader = PartScanner::new(
+            reader,
+            &boundary,
+            options.max_part_header_bytes,
+            part_offsets.clone(),
+        );
+        let headers_too_large = reader.exceeded();
+        let trailing = if options.reject_trailing_data { Some(reader.trailing()) } else { None };
+
+        #[cfg(feature = "sha2")]
+        let mut reader = DigestReader::new(reader, options.body_digest.clone());
+        #[cfg(not(feature = "sha2"))]
+        let mut reader = reader;
+
+        let mut multipart = Multipart::with_constraints(
+            tokio_util::io::ReaderStream::with_capacity(
+                &mut reader,
+                options.read_chunk_size.max(1),
+            ),
+            boundary,
+            Constraints::new().size_limit(options.to_multer_size_limit()),
+        );
 
         let mut files: HashMap<Arc<str>, Vec<FileField>> = HashMap::new();
         let mut raw: HashMap<Arc<str>, Vec<RawField>> = HashMap::new();
         let mut texts: HashMap<Arc<str>, Vec<TextField>> = HashMap::new();
+        let mut bools: HashMap<Arc<str>, bool> = HashMap::new();
+        let mut numbers: HashMap<Arc<str>, Vec<NumberField>> = HashMap::new();
+        let mut stored: HashMap<Arc<str>, Vec<StoredField>> = HashMap::new();
+        let mut large_texts: HashMap<Arc<str>, Vec<LargeTextField>> = HashMap::new();
+        #[cfg(feature = "chrono")]
+        let mut datetimes: HashMap<Arc<str>, Vec<DateTimeField>> = HashMap::new();
+        #[cfg(feature = "csv")]
+        let mut csv: HashMap<Arc<str>, Vec<CsvField>> = HashMap::new();
+        #[cfg(feature = "xml")]
+        let mut xml: HashMap<Arc<str>, Vec<XmlField>> = HashMap::new();
+        let mut nested: HashMap<Arc<str>, Vec<MultipartFormData>> = HashMap::new();
+
+        let mut disk_charge = DiskCharge::new(options.disk_budget.clone());
+
+        let mut chunk_reader = ChunkReader::new(
+            pulled,
+            options.quota.as_ref(),
+            options.max_bytes_per_second,
+            options.admission.as_ref(),
+        );
+
+        let mut warnings: Vec<ParseWarning> = Vec::new();
+
+        // The ids of the parts which have been taken over by the handler
+        let mut handled_ids: Vec<String> = Vec::new();
+
+        // The multi-file fields which cannot accept more files
+        let mut exhausted_multiple: Vec<Arc<str>> = Vec::new();
+
+        let mut text_parts = 0usize;
+        let mut file_parts = 0usize;
+
+        // The number of bytes buffered in memory by raw and text-based fields
+        let mut in_memory = 0u64;
+
+        let default_generator = DefaultFileNameGenerator {
+            clock: options.clock.as_deref().unwrap_or(&SystemClock),
+            rng:   options.rng.as_deref().unwrap_or(&SystemRng),
+        };
+
+        let generator = options.file_name_generator.as_deref().unwrap_or(&default_generator);
 
         let mut output_err: Option<MultipartFormDataError> = None;
 
-        'outer: while let Some(mut entry) = multipart.next_field().await? {
-            let field_name = match entry.name() {
-                Some(name) => Arc::from(name),
-                None => continue,
+        'outer: loop {
+            let entry = multipart.next_field().await;
+
+            // The rest of the previous part and the headers of this one have been read
+            if let Err(err) = chunk_reader.charge().await {
+                output_err = Some(err);
+
+                break;
+            }
+
+            let mut entry = match entry {
+                Ok(Some(entry)) => entry,
+                Ok(None) => break,
+                Err(err) => {
+                    output_err = Some(err.into());
+
+                    break;
+                },
+            };
+
+            if entry.file_name().is_some() {
+                file_parts += 1;
+
+                if file_parts > options.max_file_parts {
+                    output_err = Some(MultipartFormDataError::TooManyFilePartsError);
+
+                    break;
+                }
+            } else {
+                text_parts += 1;
+
+                if text_parts > options.max_text_parts {
+                    output_err = Some(MultipartFormDataError::TooManyTextPartsError);
+
+                    break;
+                }
+            }
+
+            let name = match entry.name() {
+                Some(name) => name,
+                None => match options.unnamed_parts {
+                    UnnamedPartPolicy::Ignore => {
+                        warnings.push(ParseWarning::UnnamedPart);
+                        continue;
+                    },
+                    UnnamedPartPolicy::Error => {
+                        output_err = Some(MultipartFormDataError::UnnamedPartError);
+
+                        break;
+                    },
+                    UnnamedPartPolicy::CollectAs(name) => name,
+                },
+            };
+
+            let normalized_name = options.normalize_keys.normalize(name);
+
+            let original_name = match &normalized_name {
+                Cow::Owned(_) => Some(name.to_string()),
+                Cow::Borrowed(_) => None,
             };
 
-            if let Ok(vi) =
-                options.allowed_fields.binary_search_by(|f| f.field_name.cmp(&field_name))
+            let name = normalized_name.as_ref();
+
+            if options.max_field_name_len.map_or(false, |max| name.len() > max) {
+                output_err = Some(MultipartFormDataError::FieldNameTooLongError(name.to_string()));
+
+                break;
+            }
+
+            if options.ascii_only_field_names
+                && !name.bytes().all(|b| b.is_ascii_graphic() || b == b' ')
             {
+                output_err = Some(MultipartFormDataError::NonAsciiFieldNameError(name.to_string()));
+
+                break;
+            }
+
+            // The content type whose alias has been resolved
+            let content_type = entry.content_type().map(|content_type| {
+                options.mime_aliases.get(content_type.essence_str()).unwrap_or(content_type).clone()
+            });
+
+            if let Some(on_field) = options.on_field.as_ref() {
+                let meta = FieldMeta {
+                    name,
+                    file_name: entry.file_name(),
+                    content_type: content_type.as_ref(),
+                    content_length: content_length(entry.headers()),
+                    headers: entry.headers(),
+                };
+
+                match on_field.on_field(&meta) {
+                    FieldDecision::Accept => (),
+                    FieldDecision::Skip => continue,
+                    FieldDecision::Reject => {
+                        output_err =
+                            Some(MultipartFormDataError::FieldRejectedError(Arc::from(name)));
+
+                        break;
+                    },
+                }
+            }
+
+            let field_name = match intern(&schema.field_names, name) {
+                Some(field_name) => field_name,
+                None => {
+                    warnings.push(ParseWarning::UnknownField(name.to_string()));
+                    continue;
+                },
+            };
+
+            if let Ok(vi) = allowed_fields.binary_search_by(|f| f.field_name.cmp(&field_name)) {
+                // The key of the results, which is the field name unless the field is stored under another one
+                let key = allowed_fields[vi].key.clone();
+
                 // To deal with the weird behavior of web browsers
                 // If the client wants to upload an empty file, it should not set the filename to empty string.
                 let mut might_be_empty_file_input_in_html = false;
 
                 {
-                    let field_ref = &options.allowed_fields[vi];
+                    let field_ref = allowed_fields[vi].field;
 
                     // The HTTP request body of an empty file input in a HTML form sent by web browsers:
                     // Content-Disposition: form-data; name="???"; filename=""
@@ -74,30 +542,33 @@ impl MultipartFormData {
                     }
 
                     // Whether to check content type
-                    if let Some(content_type_ref) = &field_ref.content_type {
-                        let mut mat = false; // Is the content type matching?
-
-                        if let Some(content_type) = entry.content_type().as_ref() {
-                            let top = content_type.type_();
-                            let sub = content_type.subtype();
-
-                            for content_type_ref in content_type_ref {
-                                let top_ref = content_type_ref.type_();
-
-                                if top_ref != mime::STAR && top_ref != top {
-                                    continue;
-                                }
-
-                                let sub_ref = content_type_ref.subtype();
-
-                                if sub_ref != mime::STAR && sub_ref != sub {
-                                    continue;
-                                }
-
-                                mat = true;
-                                break;
-                            }
-                        }
+                    if field_ref.content_type.is_some()
+                        || !field_ref.denied_content_types.is_empty()
+                    {
+                        // Is the content type matching?
+                        let matches = if field_ref.match_suffix {
+                            content_type_matches_suffix
+                        } else {
+                            content_type_matches
+                        };
+
+                        let mat = match content_type.as_ref() {
+                            Some(content_type) => {
+                                let allowed = match &field_ref.content_type {
+                                    Some(content_type_ref) => {
+                                        content_type_ref.iter().any(|f| matches(f, content_type))
+                                    },
+                                    None => true,
+                                };
+
+                                allowed
+                                    && !field_ref
+                                        .denied_content_types
+                                        .iter()
+                                        .any(|f| matches(f, content_type))
+                            },
+                            None => field_ref.content_type.is_none(),
+                        };
 
                         if !mat {
                             if might_be_empty_file_input_in_html {
@@ -115,55 +586,204 @@ impl MultipartFormData {
                     }
                 }
 
+                // The field name, the fields, the boundary and the raw field of a nested multipart body, which is parsed after the field is released
+                let mut nested_part = None;
+
                 let drop_field = {
-                    let field = unsafe { options.allowed_fields.get_unchecked_mut(vi) };
+                    let field = unsafe { allowed_fields.get_unchecked_mut(vi) };
 
-                    match field.typ {
-                        MultipartFormDataType::File => {
-                            let target_file_name = format!(
-                                "rs-{}",
-                                SystemTime::now()
-                                    .duration_since(SystemTime::UNIX_EPOCH)
-                                    .unwrap()
-                                    .as_nanos()
-                            );
-
-                            let target_path = {
-                                let mut p = Path::join(&options.temporary_dir, &target_file_name);
-
-                                let mut i = 1usize;
-
-                                while p.exists() {
-                                    p = Path::join(
-                                        &options.temporary_dir,
-                                        format!("{}-{}", &target_file_name, i),
-                                    );
-
-                                    i += 1;
-                                }
+                    // The size limit may be reduced by the budget of the repetition
+                    let size_limit = field.repetition.size_limit(field.size_limit);
+
+                    chunk_reader.start_part(&field_name, field.max_duration);
+                    let size;
+
+                    let declared_length = content_length(entry.headers());
 
-                                p
+                    if options.reject_declared_oversize
+                        && field.on_oversize == OversizePolicy::Error
+                        && declared_length.map_or(false, |length| length > size_limit)
+                    {
+                        output_err = Some(MultipartFormDataError::DataTooLargeError(field_name));
+
+                        break 'outer;
+                    }
+
+                    if let (Some(handler), None) = (handler, output_err.as_ref()) {
+                        let file_name = entry.file_name().map(String::from);
+                        let content_type = content_type.clone();
+                        let headers = entry.headers().clone();
+
+                        let meta = FieldMeta {
+                            name:           &field_name,
+                            file_name:      file_name.as_deref(),
+                            content_type:   content_type.as_ref(),
+                            content_length: declared_length,
+                            headers:        &headers,
+                        };
+
+                        if handler.accepts(&meta) {
+                            let index = entry.index();
+
+                            let mut source = PartSource {
+                                entry:        &mut entry,
+                                chunk_reader: &mut chunk_reader,
                             };
 
-                            let mut file = match File::create(&target_path).await {
-                                Ok(f) => f,
+                            let mut reader =
+                                PartReader::new(&mut source, field_name.clone(), size_limit);
+
+                            let id = match handler.handle(&meta, &mut reader).await {
+                                Ok(id) => id,
                                 Err(err) => {
-                                    output_err = Some(err.into());
+                                    output_err = Some(err);
 
                                     break 'outer;
                                 },
                             };
 
+                            size = reader.size();
+
+                            handled_ids.push(id.clone());
+
+                            let f = StoredField {
+                                content_type,
+                                file_name,
+                                disposition_params: content_disposition_params(&headers),
+                                original_name: original_name.clone(),
+                                index,
+                                id,
+                                size,
+                                content_length: declared_length,
+                            };
+
+                            report(progress, || ParsedField::Stored(key.clone(), f.clone()));
+
+                            stored.entry(key).or_default().push(f);
+
+                            if field.repetition.decrease_check_is_over(size) {
+                                let field = allowed_fields.remove(vi);
+
+                                if field.multiple {
+                                    exhausted_multiple.push(Arc::from(field.field_name));
+                                }
+                            }
+
+                            continue;
+                        }
+                    }
+
+                    let mut typ = field.typ;
+
+                    if typ == MultipartFormDataType::Text && entry.file_name().is_some() {
+                        match field.on_text_with_file_name {
+                            TextFileNamePolicy::Text => (),
+                            TextFileNamePolicy::TreatAsFile => typ = MultipartFormDataType::File,
+                            TextFileNamePolicy::Error => {
+                                output_err =
+                                    Some(MultipartFormDataError::DataTypeError(field_name));
+
+                                break 'outer;
+                            },
+                        }
+                    }
+
+                    match typ {
+                        MultipartFormDataType::File => {
+                            let (mut file, target_path) = match options.storage.as_ref() {
+                                Some(storage) => match storage.create(&field_name).await {
+                                    Ok(writer) => (FileWriter::with_backend(writer), None),
+                                    Err(err) => {
+                                        output_err = Some(err.into());
+
+                                        break 'outer;
+                                    },
+                                },
+                                None => {
+                                    let result = match options.idempotency_key.as_deref() {
+                                        Some(key) => {
+                                            create_idempotent_file(
+                                                &options.temporary_dir,
+                                                key,
+                                                &field_name,
+                                                entry.index(),
+                                                options.io_retry_policy,
+                                            )
+                                            .await
+                                        },
+                                        None => {
+                                            create_new_file(
+                                                &options.temporary_dir,
+                                                generator,
+                                                &field_name,
+                                                options.io_retry_policy,
+                                            )
+                                            .await
+                                        },
+                                    };
+
+                                    match result {
+                                        Ok((f, target_path)) => (
+                                            FileWriter::new(
+                                                f,
+                                                options.write_chunk_size,
+                                                options.write_pipeline_depth,
+                                                options.io_retry_policy,
+                                            ),
+                                            Some(target_path),
+                                        ),
+                                        Err(err) => {
+                                            output_err = Some(err.into());
+
+                                            break 'outer;
+                                        },
+                                    }
+                                },
+                            };
+
+                            #[cfg(feature = "encryption")]
+                            file.set_encryptor(options.encryption.as_deref().map(Encryptor::new));
+
                             let mut sum_c = 0u64;
+                            let mut truncated = false;
+
+                            let mut inspector = field.archive_limits.map(ArchiveInspector::new);
+
+                            // The first bytes of the data, to infer its content type
+                            let mut head = Vec::new();
+
+                            #[cfg(feature = "sanitize")]
+                            let mut markup =
+                                (field.active_content != ActiveContentPolicy::Allow).then(Vec::new);
+
+                            #[cfg(feature = "sha2")]
+                            let mut hasher = (options.file_storage_mode
+                                == FileStorageMode::ContentAddressed
+                                && target_path.is_some())
+                            .then(Sha256::new);
 
                             loop {
-                                match entry.chunk().await {
+                                match chunk_reader.next_chunk(&mut entry).await {
                                     Ok(bytes) => match bytes {
-                                        Some(bytes) => {
+                                        Some(mut bytes) => {
+                                            if truncated {
+                                                continue;
+                                            }
+
                                             sum_c += bytes.len() as u64;
 
-                                            if sum_c > field.size_limit {
-                                                try_delete(&target_path);
+                                            if sum_c > size_limit
+                                                && field.on_oversize == OversizePolicy::Truncate
+                                            {
+                                                bytes.truncate(
+                                                    bytes.len() - (sum_c - size_limit) as usize,
+                                                );
+
+                                                sum_c = size_limit;
+                                                truncated = true;
+                                            } else if sum_c > size_limit {
+                                                file.discard().await;
+                                                target_path.iter().for_each(try_delete);
 
                                                 output_err = Some(
                                                     MultipartFormDataError::DataTooLargeError(
@@ -174,208 +794,1517 @@ impl MultipartFormData {
                                                 break 'outer;
                                             }
 
-                                            match file.write_all(bytes.as_ref()).await {
-                                                Ok(_) => (),
-                                                Err(err) => {
-                                                    try_delete(&target_path);
-
-                                                    output_err = Some(err.into());
+                                            if head.len() < MAGIC_BYTES_LENGTH {
+                                                let n = (MAGIC_BYTES_LENGTH - head.len())
+                                                    .min(bytes.len());
 
-                                                    break 'outer;
-                                                },
+                                                head.extend_from_slice(&bytes[..n]);
                                             }
-                                        },
-                                        None => break,
-                                    },
-                                    Err(err) => {
-                                        try_delete(&target_path);
 
-                                        output_err = Some(err.into());
+                                            if let Some(inspector) = inspector.as_mut() {
+                                                if let Err(violation) = inspector.update(&bytes) {
+                                                    file.discard().await;
+                                                    target_path.iter().for_each(try_delete);
 
-                                        break 'outer;
-                                    },
-                                }
-                            }
+                                                    output_err = Some(
+                                                        MultipartFormDataError::ArchiveRejectedError {
+                                                            field: field_name,
+                                                            violation,
+                                                        },
+                                                    );
 
-                            if might_be_empty_file_input_in_html {
-                                if sum_c == 0 {
-                                    // This file might be from an empty file input in the HTML form, so ignore it.
-                                    try_delete(&target_path);
+                                                    break 'outer;
+                                                }
+                                            }
 
-                                    output_err = None;
-                                    continue;
-                                } else if output_err.is_some() {
-                                    try_delete(&target_path);
+                                            // The markup is written after being checked, so it is buffered in memory
+                                            #[cfg(feature = "sanitize")]
+                                            if let Some(markup) = markup.as_mut() {
+                                                in_memory += bytes.len() as u64;
 
-                                    break 'outer;
-                                }
-                            }
+                                                if options
+                                                    .max_in_memory_bytes
+                                                    .map_or(false, |max| in_memory > max)
+                                                {
+                                                    file.discard().await;
+                                                    target_path.iter().for_each(try_delete);
 
-                            let file_name = entry.file_name().map(String::from);
+                                                    output_err = Some(
+                                                        MultipartFormDataError::MemoryLimitExceededError,
+                                                    );
 
-                            let f = FileField {
-                                content_type: entry.content_type().cloned(),
-                                file_name,
-                                path: target_path,
-                            };
+                                                    break 'outer;
+                                                }
 
-                            if let Some(fields) = files.get_mut(&field_name) {
-                                fields.push(f);
-                            } else {
-                                files.insert(field_name, vec![f]);
-                            }
-                        },
-                        MultipartFormDataType::Raw => {
-                            let mut raw_buffer = Vec::new();
+                                                markup.extend_from_slice(&bytes);
+                                                continue;
+                                            }
 
-                            loop {
-                                match entry.chunk().await {
-                                    Ok(bytes) => match bytes {
-                                        Some(bytes) => {
-                                            if raw_buffer.len() as u64 + bytes.len() as u64
-                                                > field.size_limit
+                                            #[cfg(feature = "sha2")]
+                                            if let Some(hasher) = hasher.as_mut() {
+                                                hasher.update(&bytes);
+                                            }
+
+                                            if target_path.is_some()
+                                                && !disk_charge.add(bytes.len() as u64)
                                             {
+                                                file.discard().await;
+                                                target_path.iter().for_each(try_delete);
+
                                                 output_err = Some(
-                                                    MultipartFormDataError::DataTooLargeError(
-                                                        field_name,
-                                                    ),
+                                                    MultipartFormDataError::ServerStorageExhaustedError,
                                                 );
 
                                                 break 'outer;
                                             }
 
-                                            raw_buffer.extend_from_slice(bytes.as_ref());
+                                            match file.write(bytes).await {
+                                                Ok(_) => (),
+                                                Err(err) => {
+                                                    file.discard().await;
+                                                    target_path.iter().for_each(try_delete);
+
+                                                    output_err = Some(err.into());
+
+                                                    break 'outer;
+                                                },
+                                            }
                                         },
                                         None => break,
                                     },
                                     Err(err) => {
-                                        output_err = Some(err.into());
+                                        let retained = match (&err, target_path.as_deref()) {
+                                            (
+                                                MultipartFormDataError::MulterError(error),
+                                                Some(path),
+                                            ) if options.retain_partial_files_on_disconnect
+                                                && options.encryption.is_none()
+                                                && is_disconnection(error)
+                                                && !shut_down.load(Ordering::Acquire)
+                                                && !headers_too_large.load(Ordering::Acquire) =>
+                                            {
+                                                retain_partial_file(file, path).await
+                                            },
+                                            _ => {
+                                                file.discard().await;
+                                                target_path.iter().for_each(try_delete);
+
+                                                None
+                                            },
+                                        };
+
+                                        output_err = Some(match (err, retained) {
+                                            (
+                                                MultipartFormDataError::MulterError(error),
+                                                Some(path),
+                                            ) => MultipartFormDataError::InterruptedError {
+                                                error,
+                                                partial_files: vec![PartialFile {
+                                                    field: field_name,
+                                                    file_name: entry.file_name().map(String::from),
+                                                    path,
+                                                    size: sum_c,
+                                                }],
+                                            },
+                                            (err, _) => err,
+                                        });
 
                                         break 'outer;
                                     },
                                 }
                             }
 
-                            if might_be_empty_file_input_in_html {
-                                if raw_buffer.is_empty() {
-                                    // This file might be from an empty file input in the HTML form, so ignore it.
-                                    output_err = None;
+                            if truncated {
+                                warnings.push(ParseWarning::DataTruncated(field_name.clone()));
+                            }
+
+                            match check_empty_part(
+                                might_be_empty_file_input_in_html,
+                                field.on_empty_file,
+                                sum_c,
+                                &field_name,
+                                &mut output_err,
+                                &mut warnings,
+                            ) {
+                                EmptyPart::Keep => (),
+                                EmptyPart::Ignore => {
+                                    file.discard().await;
+                                    target_path.iter().for_each(try_delete);
+
                                     continue;
-                                } else if output_err.is_some() {
+                                },
+                                EmptyPart::Fail => {
+                                    file.discard().await;
+                                    target_path.iter().for_each(try_delete);
+
                                     break 'outer;
-                                }
+                                },
                             }
 
-                            let file_name = entry.file_name().map(String::from);
+                            if let Some(inspector) = inspector {
+                                if let Err(violation) = inspector.finish() {
+                                    file.discard().await;
+                                    target_path.iter().for_each(try_delete);
 
-                            let f = RawField {
-                                content_type: entry.content_type().cloned(),
-                                file_name,
-                                raw: raw_buffer,
-                            };
+                                    output_err =
+                                        Some(MultipartFormDataError::ArchiveRejectedError {
+                                            field: field_name,
+                                            violation,
+                                        });
 
-                            if let Some(fields) = raw.get_mut(&field_name) {
-                                fields.push(f);
-                            } else {
-                                raw.insert(field_name, vec![f]);
+                                    break 'outer;
+                                }
+                            }
+
+                            #[cfg(feature = "sanitize")]
+                            if let Some(mut markup) = markup {
+                                if !check_active_content(
+                                    field.active_content,
+                                    &mut markup,
+                                    &field_name,
+                                    &mut warnings,
+                                ) {
+                                    file.discard().await;
+                                    target_path.iter().for_each(try_delete);
+
+                                    output_err = Some(MultipartFormDataError::ActiveContentError(
+                                        field_name,
+                                    ));
+
+                                    break 'outer;
+                                }
+
+                                // The buffer is released after being written
+                                in_memory -= sum_c;
+
+                                sum_c = markup.len() as u64;
+
+                                #[cfg(feature = "sha2")]
+                                if let Some(hasher) = hasher.as_mut() {
+                                    hasher.update(&markup);
+                                }
+
+                                if target_path.is_some() && !disk_charge.add(markup.len() as u64) {
+                                    file.discard().await;
+                                    target_path.iter().for_each(try_delete);
+
+                                    output_err =
+                                        Some(MultipartFormDataError::ServerStorageExhaustedError);
+
+                                    break 'outer;
+                                }
+
+                                if let Err(err) = file.write(Bytes::from(markup)).await {
+                                    file.discard().await;
+                                    target_path.iter().for_each(try_delete);
+
+                                    output_err = Some(err.into());
+
+                                    break 'outer;
+                                }
+                            }
+
+                            let id = match file.finish().await {
+                                Ok(id) => id,
+                                Err(err) => {
+                                    target_path.iter().for_each(try_delete);
+
+                                    output_err = Some(err.into());
+
+                                    break 'outer;
+                                },
+                            };
+
+                            let target_path = match target_path {
+                                Some(target_path) => target_path,
+                                None => {
+                                    // The file has been written to the storage backend
+                                    let f = StoredField {
+                                        content_type:       inferred(
+                                            &entry,
+                                            content_type.as_ref(),
+                                            options.infer_content_types,
+                                            &head,
+                                        ),
+                                        file_name:          entry.file_name().map(String::from),
+                                        disposition_params: content_disposition_params(
+                                            entry.headers(),
+                                        ),
+                                        original_name:      original_name.clone(),
+                                        index:              entry.index(),
+                                        id:                 id.unwrap_or_default(),
+                                        size:               sum_c,
+                                        content_length:     declared_length,
+                                    };
+
+                                    report(progress, || {
+                                        ParsedField::Stored(key.clone(), f.clone())
+                                    });
+
+                                    stored.entry(key).or_default().push(f);
+
+                                    if field.repetition.decrease_check_is_over(sum_c) {
+                                        let field = allowed_fields.remove(vi);
+
+                                        if field.multiple {
+                                            exhausted_multiple.push(Arc::from(field.field_name));
+                                        }
+                                    }
+
+                                    continue;
+                                },
+                            };
+
+                            #[cfg(feature = "sha2")]
+                            let (target_path, was_deduplicated, persistent) = match hasher {
+                                Some(hasher) => match store_content_addressed(
+                                    target_path,
+                                    &options.temporary_dir,
+                                    hasher,
+                                ) {
+                                    Ok((path, was_deduplicated)) => (path, was_deduplicated, true),
+                                    Err(err) => {
+                                        output_err = Some(err.into());
+
+                                        break 'outer;
+                                    },
+                                },
+                                None => (target_path, false, false),
+                            };
+
+                            #[cfg(not(feature = "sha2"))]
+                            let (was_deduplicated, persistent) = (false, false);
+
+                            let derivatives = match field.post_processor.clone() {
+                                Some(post_processor) => {
+                                    let source = target_path.clone();
+                                    let temporary_dir = options.temporary_dir.clone();
+
+                                    let result = task::spawn_blocking(move || {
+                                        post_processor.process(&source, &temporary_dir)
+                                    })
+                                    .await;
+
+                                    match result {
+                                        Ok(Ok(derivatives)) => derivatives,
+                                        Ok(Err(error)) => {
+                                            if !persistent {
+                                                try_delete(&target_path);
+                                            }
+
+                                            output_err =
+                                                Some(MultipartFormDataError::PostProcessError {
+                                                    field: field_name,
+                                                    error,
+                                                });
+
+                                            break 'outer;
+                                        },
+                                        Err(err) => {
+                                            if !persistent {
+                                                try_delete(&target_path);
+                                            }
+
+                                            output_err =
+                                                Some(MultipartFormDataError::PostProcessError {
+                                                    field: field_name,
+                                                    error: Box::new(err),
+                                                });
+
+                                            break 'outer;
+                                        },
+                                    }
+                                },
+                                None => Vec::new(),
+                            };
+
+                            let file_name = entry.file_name().map(String::from);
+                            let disposition_params = content_disposition_params(entry.headers());
+
+                            size = sum_c;
+
+                            let f = FileField {
+                                content_type: inferred(
+                                    &entry,
+                                    content_type.as_ref(),
+                                    options.infer_content_types,
+                                    &head,
+                                ),
+                                file_name,
+                                disposition_params,
+                                original_name: original_name.clone(),
+                                index: entry.index(),
+                                path: target_path,
+                                size: sum_c,
+                                content_length: declared_length,
+                                derivatives,
+                                was_deduplicated,
+                                persistent,
+                                encryption: options.encryption.clone(),
+                            };
+
+                            report(progress, || ParsedField::File(key.clone(), f.clone()));
+
+                            if let Some(fields) = files.get_mut(&key) {
+                                fields.push(f);
+                            } else {
+                                files.insert(key, vec![f]);
                             }
                         },
-                        MultipartFormDataType::Text => {
-                            let mut text_buffer = Vec::new();
+                        MultipartFormDataType::Raw => {
+                            let mut raw_buffer = Vec::new();
+                            let mut truncated = false;
 
                             loop {
-                                match entry.chunk().await {
+                                match chunk_reader.next_chunk(&mut entry).await {
                                     Ok(bytes) => match bytes {
                                         Some(bytes) => {
-                                            if text_buffer.len() as u64 + bytes.len() as u64
-                                                > field.size_limit
+                                            if truncated {
+                                                continue;
+                                            }
+
+                                            let mut bytes = bytes.as_ref();
+
+                                            if raw_buffer.len() as u64 + bytes.len() as u64
+                                                > size_limit
+                                            {
+                                                if field.on_oversize != OversizePolicy::Truncate {
+                                                    output_err = Some(
+                                                        MultipartFormDataError::DataTooLargeError(
+                                                            field_name,
+                                                        ),
+                                                    );
+
+                                                    break 'outer;
+                                                }
+
+                                                let remaining =
+                                                    (size_limit - raw_buffer.len() as u64) as usize;
+
+                                                bytes = &bytes[..remaining];
+                                                truncated = true;
+                                            }
+
+                                            in_memory += bytes.len() as u64;
+
+                                            if options
+                                                .max_in_memory_bytes
+                                                .map_or(false, |max| in_memory > max)
                                             {
                                                 output_err = Some(
-                                                    MultipartFormDataError::DataTooLargeError(
-                                                        field_name,
-                                                    ),
+                                                    MultipartFormDataError::MemoryLimitExceededError,
                                                 );
 
                                                 break 'outer;
                                             }
 
-                                            text_buffer.extend_from_slice(bytes.as_ref());
+                                            raw_buffer.extend_from_slice(bytes);
                                         },
                                         None => break,
                                     },
                                     Err(err) => {
-                                        output_err = Some(err.into());
+                                        output_err = Some(err);
 
                                         break 'outer;
                                     },
                                 }
                             }
 
-                            if might_be_empty_file_input_in_html {
-                                if text_buffer.is_empty() {
-                                    // This file might be from an empty file input in the HTML form, so ignore it.
-                                    output_err = None;
-                                    continue;
-                                } else if output_err.is_some() {
+                            if truncated {
+                                warnings.push(ParseWarning::DataTruncated(field_name.clone()));
+                            }
+
+                            match check_empty_part(
+                                might_be_empty_file_input_in_html,
+                                field.on_empty_file,
+                                raw_buffer.len() as u64,
+                                &field_name,
+                                &mut output_err,
+                                &mut warnings,
+                            ) {
+                                EmptyPart::Keep => (),
+                                EmptyPart::Ignore => continue,
+                                EmptyPart::Fail => break 'outer,
+                            }
+
+                            #[cfg(feature = "sanitize")]
+                            if !check_active_content(
+                                field.active_content,
+                                &mut raw_buffer,
+                                &field_name,
+                                &mut warnings,
+                            ) {
+                                output_err =
+                                    Some(MultipartFormDataError::ActiveContentError(field_name));
+
+                                break 'outer;
+                            }
+
+                            if let Some(raw_mapper) = field.raw_mapper.as_ref() {
+                                raw_buffer = match raw_mapper.map(raw_buffer) {
+                                    Ok(raw_buffer) => raw_buffer,
+                                    Err(error) => {
+                                        output_err = Some(MultipartFormDataError::MappingError {
+                                            field: field_name,
+                                            error,
+                                        });
+
+                                        break 'outer;
+                                    },
+                                };
+                            }
+
+                            #[cfg(feature = "prost")]
+                            if let Some(check) = field.protobuf_check {
+                                if let Err(error) = check(&raw_buffer) {
+                                    output_err =
+                                        Some(MultipartFormDataError::InvalidProtobufError {
+                                            field: field_name,
+                                            error,
+                                        });
+
                                     break 'outer;
                                 }
                             }
 
-                            let text = match String::from_utf8(text_buffer) {
-                                Ok(s) => s,
-                                Err(err) => {
-                                    output_err = Some(err.into());
+                            let file_name = entry.file_name().map(String::from);
+                            let disposition_params = content_disposition_params(entry.headers());
+
+                            size = raw_buffer.len() as u64;
+
+                            let f = RawField {
+                                content_type: inferred(
+                                    &entry,
+                                    content_type.as_ref(),
+                                    options.infer_content_types,
+                                    &raw_buffer,
+                                ),
+                                file_name,
+                                disposition_params,
+                                original_name: original_name.clone(),
+                                index: entry.index(),
+                                raw: raw_buffer,
+                            };
+
+                            let nested_content_type = f
+                                .content_type
+                                .as_ref()
+                                .filter(|content_type| content_type.type_() == mime::MULTIPART);
+
+                            if let (Some(nested_fields), Some(content_type)) =
+                                (field.nested_fields.as_ref(), nested_content_type)
+                            {
+                                if options.max_nesting_depth == 0 {
+                                    output_err = Some(MultipartFormDataError::NestingTooDeepError(
+                                        field_name,
+                                    ));
+
+                                    break 'outer;
+                                }
+
+                                let boundary = match content_type.get_param(mime::BOUNDARY) {
+                                    Some(boundary) => boundary.as_str().to_string(),
+                                    None => {
+                                        output_err =
+                                            Some(MultipartFormDataError::MissingBoundaryError);
+
+                                        break 'outer;
+                                    },
+                                };
+
+                                nested_part = Some((key, nested_fields.clone(), boundary, f));
+                            } else {
+                                report(progress, || ParsedField::Raw(key.clone(), f.clone()));
+
+                                if let Some(fields) = raw.get_mut(&key) {
+                                    fields.push(f);
+                                } else {
+                                    raw.insert(key, vec![f]);
+                                }
+                            }
+                        },
+                        #[cfg(feature = "csv")]
+                        MultipartFormDataType::Csv => {
+                            let mut parser = CsvParser::new();
+                            let mut records = Vec::new();
+                            let mut received = 0u64;
+
+                            let handler = field.csv_row_handler.as_deref();
+
+                            let mut on_record = |record: CsvRecord| match handler {
+                                Some(handler) => {
+                                    handler.handle(&record).map_err(|message| CsvError {
+                                        row: record.row(),
+                                        message,
+                                    })
+                                },
+                                None => {
+                                    records.push(record);
+
+                                    Ok(())
+                                },
+                            };
+
+                            loop {
+                                match chunk_reader.next_chunk(&mut entry).await {
+                                    Ok(Some(bytes)) => {
+                                        received += bytes.len() as u64;
+
+                                        // The records cannot be truncated
+                                        if received > size_limit {
+                                            output_err =
+                                                Some(MultipartFormDataError::DataTooLargeError(
+                                                    field_name,
+                                                ));
+
+                                            break 'outer;
+                                        }
+
+                                        if handler.is_none() {
+                                            in_memory += bytes.len() as u64;
+
+                                            if options
+                                                .max_in_memory_bytes
+                                                .map_or(false, |max| in_memory > max)
+                                            {
+                                                output_err = Some(
+                                                    MultipartFormDataError::MemoryLimitExceededError,
+                                                );
+
+                                                break 'outer;
+                                            }
+                                        }
+
+                                        if let Err(error) = parser.push(&bytes, &mut on_record) {
+                                            output_err =
+                                                Some(MultipartFormDataError::InvalidCsvError {
+                                                    field: field_name,
+                                                    error,
+                                                });
+
+                                            break 'outer;
+                                        }
+                                    },
+                                    Ok(None) => break,
+                                    Err(err) => {
+                                        output_err = Some(err);
+
+                                        break 'outer;
+                                    },
+                                }
+                            }
+
+                            let headers = match parser.finish(&mut on_record) {
+                                Ok(headers) => headers,
+                                Err(error) => {
+                                    output_err = Some(MultipartFormDataError::InvalidCsvError {
+                                        field: field_name,
+                                        error,
+                                    });
 
                                     break 'outer;
                                 },
                             };
 
+                            match check_empty_part(
+                                might_be_empty_file_input_in_html,
+                                field.on_empty_file,
+                                received,
+                                &field_name,
+                                &mut output_err,
+                                &mut warnings,
+                            ) {
+                                EmptyPart::Keep => (),
+                                EmptyPart::Ignore => continue,
+                                EmptyPart::Fail => break 'outer,
+                            }
+
                             let file_name = entry.file_name().map(String::from);
+                            let disposition_params = content_disposition_params(entry.headers());
+
+                            size = received;
 
-                            let f = TextField {
-                                content_type: entry.content_type().cloned(),
+                            let f = CsvField {
+                                content_type: content_type.clone(),
                                 file_name,
-                                text,
+                                disposition_params,
+                                original_name: original_name.clone(),
+                                index: entry.index(),
+                                headers,
+                                records,
                             };
 
-                            if let Some(fields) = texts.get_mut(&field_name) {
+                            report(progress, || ParsedField::Csv(key.clone(), f.clone()));
+
+                            if let Some(fields) = csv.get_mut(&key) {
                                 fields.push(f);
                             } else {
-                                texts.insert(field_name, vec![f]);
+                                csv.insert(key, vec![f]);
+                            }
+                        },
+                        #[cfg(feature = "xml")]
+                        MultipartFormDataType::Xml => {
+                            let mut buffer = Vec::new();
+
+                            loop {
+                                match chunk_reader.next_chunk(&mut entry).await {
+                                    Ok(Some(bytes)) => {
+                                        // A document cannot be truncated
+                                        if buffer.len() as u64 + bytes.len() as u64 > size_limit {
+                                            output_err =
+                                                Some(MultipartFormDataError::DataTooLargeError(
+                                                    field_name,
+                                                ));
+
+                                            break 'outer;
+                                        }
+
+                                        in_memory += bytes.len() as u64;
+
+                                        if options
+                                            .max_in_memory_bytes
+                                            .map_or(false, |max| in_memory > max)
+                                        {
+                                            output_err = Some(
+                                                MultipartFormDataError::MemoryLimitExceededError,
+                                            );
+
+                                            break 'outer;
+                                        }
+
+                                        buffer.extend_from_slice(&bytes);
+                                    },
+                                    Ok(None) => break,
+                                    Err(err) => {
+                                        output_err = Some(err);
+
+                                        break 'outer;
+                                    },
+                                }
+                            }
+
+                            match check_empty_part(
+                                might_be_empty_file_input_in_html,
+                                field.on_empty_file,
+                                buffer.len() as u64,
+                                &field_name,
+                                &mut output_err,
+                                &mut warnings,
+                            ) {
+                                EmptyPart::Keep => (),
+                                EmptyPart::Ignore => continue,
+                                EmptyPart::Fail => break 'outer,
+                            }
+
+                            let parsed = match std::str::from_utf8(&buffer) {
+                                Ok(text) => parse_xml(text),
+                                Err(_) => Err(XmlError {
+                                    line:    None,
+                                    message: "The data is not valid UTF-8.".to_string(),
+                                }),
+                            };
+
+                            let root = match parsed {
+                                Ok(root) => root,
+                                Err(error) => {
+                                    output_err = Some(MultipartFormDataError::InvalidXmlError {
+                                        field: field_name,
+                                        error,
+                                    });
+
+                                    break 'outer;
+                                },
+                            };
+
+                            let file_name = entry.file_name().map(String::from);
+                            let disposition_params = content_disposition_params(entry.headers());
+
+                            size = buffer.len() as u64;
+
+                            let f = XmlField {
+                                content_type: content_type.clone(),
+                                file_name,
+                                disposition_params,
+                                original_name: original_name.clone(),
+                                index: entry.index(),
+                                root,
+                            };
+
+                            report(progress, || ParsedField::Xml(key.clone(), f.clone()));
+
+                            if let Some(fields) = xml.get_mut(&key) {
+                                fields.push(f);
+                            } else {
+                                xml.insert(key, vec![f]);
+                            }
+                        },
+                        // Text-based types
+                        _ => 'text: {
+                            // Reserve the declared length so that the text does not need to be reallocated
+                            let mut decoder =
+                                TextDecoder::with_capacity(declared_length.map_or(0, |length| {
+                                    length.min(size_limit).min(
+                                        options
+                                            .max_in_memory_bytes
+                                            .map_or(u64::MAX, |max| max.saturating_sub(in_memory)),
+                                    )
+                                })
+                                    as usize);
+                            let mut truncated = false;
+
+                            let mut raw_bytes = (field.keep_raw_bytes
+                                && field.typ == MultipartFormDataType::Text)
+                                .then(Vec::new);
+
+                            let large_text = field
+                                .large_text
+                                .filter(|_| field.typ == MultipartFormDataType::Text);
+
+                            // The file which the text is moved to after it exceeds the threshold, and the number of bytes which have been written to it
+                            let mut spill: Option<(FileWriter, PathBuf)> = None;
+                            let mut spilled = 0u64;
+
+                            let mut failed = None;
+
+                            loop {
+                                match chunk_reader.next_chunk(&mut entry).await {
+                                    Ok(bytes) => match bytes {
+                                        Some(bytes) => {
+                                            if truncated {
+                                                continue;
+                                            }
+
+                                            let mut bytes = bytes.as_ref();
+
+                                            let received = spilled + decoder.len() as u64;
+
+                                            if received + bytes.len() as u64 > size_limit {
+                                                if field.on_oversize != OversizePolicy::Truncate {
+                                                    failed = Some(
+                                                        MultipartFormDataError::DataTooLargeError(
+                                                            field_name.clone(),
+                                                        ),
+                                                    );
+
+                                                    break;
+                                                }
+
+                                                let remaining = (size_limit - received) as usize;
+
+                                                bytes = &bytes[..remaining];
+                                                truncated = true;
+                                            }
+
+                                            if spill.is_none()
+                                                && large_text.map_or(false, |threshold| {
+                                                    received + bytes.len() as u64 > threshold
+                                                })
+                                            {
+                                                match create_new_file(
+                                                    &options.temporary_dir,
+                                                    generator,
+                                                    &field_name,
+                                                    options.io_retry_policy,
+                                                )
+                                                .await
+                                                {
+                                                    Ok((file, path)) => {
+                                                        #[cfg_attr(
+                                                            not(feature = "encryption"),
+                                                            allow(unused_mut)
+                                                        )]
+                                                        let mut file = FileWriter::new(
+                                                            file,
+                                                            options.write_chunk_size,
+                                                            options.write_pipeline_depth,
+                                                            options.io_retry_policy,
+                                                        );
+
+                                                        #[cfg(feature = "encryption")]
+                                                        file.set_encryptor(
+                                                            options
+                                                                .encryption
+                                                                .as_deref()
+                                                                .map(Encryptor::new),
+                                                        );
+
+                                                        spill = Some((file, path));
+                                                    },
+                                                    Err(err) => {
+                                                        failed = Some(err.into());
+
+                                                        break;
+                                                    },
+                                                }
+
+                                                // The buffered data is moved to the file. The raw bytes cannot be kept along with a large text, which is checked by the validation of the options.
+                                                in_memory -= received;
+                                            }
+
+                                            if spill.is_none() {
+                                                // The raw bytes are kept besides the text
+                                                in_memory += bytes.len() as u64
+                                                    * (1 + raw_bytes.is_some() as u64);
+
+                                                if options
+                                                    .max_in_memory_bytes
+                                                    .map_or(false, |max| in_memory > max)
+                                                {
+                                                    failed = Some(
+                                                        MultipartFormDataError::MemoryLimitExceededError,
+                                                    );
+
+                                                    break;
+                                                }
+
+                                                if let Some(raw_bytes) = raw_bytes.as_mut() {
+                                                    raw_bytes.extend_from_slice(bytes);
+                                                }
+                                            }
+
+                                            if let Err(err) = decoder.push(bytes) {
+                                                failed = Some(err.into());
+
+                                                break;
+                                            }
+
+                                            if let Some((file, _)) = spill.as_mut() {
+                                                let text = decoder.take_text();
+
+                                                spilled += text.len() as u64;
+
+                                                if !disk_charge.add(text.len() as u64) {
+                                                    failed = Some(
+                                                        MultipartFormDataError::ServerStorageExhaustedError,
+                                                    );
+
+                                                    break;
+                                                }
+
+                                                if let Err(err) =
+                                                    file.write(Bytes::from(text)).await
+                                                {
+                                                    failed = Some(err.into());
+
+                                                    break;
+                                                }
+                                            }
+                                        },
+                                        None => break,
+                                    },
+                                    Err(err) => {
+                                        failed = Some(err);
+
+                                        break;
+                                    },
+                                }
+                            }
+
+                            if let Some((mut file, path)) = spill {
+                                if failed.is_none() {
+                                    // Do not cut a character in the middle
+                                    match decoder.finish(truncated) {
+                                        Ok(rest) => {
+                                            spilled += rest.len() as u64;
+
+                                            if !disk_charge.add(rest.len() as u64) {
+                                                failed = Some(
+                                                    MultipartFormDataError::ServerStorageExhaustedError,
+                                                );
+                                            } else if let Err(err) =
+                                                file.write(Bytes::from(rest)).await
+                                            {
+                                                failed = Some(err.into());
+                                            }
+                                        },
+                                        Err(err) => failed = Some(err.into()),
+                                    }
+                                }
+
+                                // The content type of a part which is not empty has to match
+                                if let Some(err) = failed.or_else(|| output_err.take()) {
+                                    file.discard().await;
+                                    try_delete(&path);
+
+                                    output_err = Some(err);
+
+                                    break 'outer;
+                                }
+
+                                if let Err(err) = file.finish().await {
+                                    try_delete(&path);
+
+                                    output_err = Some(err.into());
+
+                                    break 'outer;
+                                }
+
+                                if truncated {
+                                    warnings.push(ParseWarning::DataTruncated(field_name.clone()));
+                                }
+
+                                size = spilled;
+
+                                let f = LargeTextField {
+                                    content_type: content_type.clone(),
+                                    file_name: entry.file_name().map(String::from),
+                                    disposition_params: content_disposition_params(entry.headers()),
+                                    original_name: original_name.clone(),
+                                    index: entry.index(),
+                                    path,
+                                    len: spilled,
+                                    encryption: options.encryption.clone(),
+                                };
+
+                                report(progress, || ParsedField::LargeText(key.clone(), f.clone()));
+
+                                large_texts.entry(key).or_default().push(f);
+
+                                break 'text;
+                            }
+
+                            if let Some(err) = failed {
+                                output_err = Some(err);
+
+                                break 'outer;
+                            }
+
+                            // Do not cut a character in the middle
+                            let mut text = match decoder.finish(truncated) {
+                                Ok(text) => text,
+                                Err(err) => {
+                                    output_err = Some(err.into());
+
+                                    break 'outer;
+                                },
+                            };
+
+                            if truncated {
+                                warnings.push(ParseWarning::DataTruncated(field_name.clone()));
+                            }
+
+                            match check_empty_part(
+                                might_be_empty_file_input_in_html,
+                                field.on_empty_file,
+                                text.len() as u64,
+                                &field_name,
+                                &mut output_err,
+                                &mut warnings,
+                            ) {
+                                EmptyPart::Keep => (),
+                                EmptyPart::Ignore => continue 'outer,
+                                EmptyPart::Fail => break 'outer,
+                            }
+
+                            if field.strip_bom && text.starts_with(UTF8_BOM) {
+                                text.drain(..UTF8_BOM.len());
+                            }
+
+                            field.newline_normalization.normalize(&mut text);
+
+                            #[cfg(feature = "sanitize")]
+                            if field.active_content != ActiveContentPolicy::Allow {
+                                let mut data = text.into_bytes();
+
+                                if !check_active_content(
+                                    field.active_content,
+                                    &mut data,
+                                    &field_name,
+                                    &mut warnings,
+                                ) {
+                                    output_err = Some(MultipartFormDataError::ActiveContentError(
+                                        field_name,
+                                    ));
+
+                                    break 'outer;
+                                }
+
+                                text = match String::from_utf8(data) {
+                                    Ok(text) => text,
+                                    Err(err) => {
+                                        output_err = Some(err.into());
+
+                                        break 'outer;
+                                    },
+                                };
+                            }
+
+                            if let Some(text_mapper) = field.text_mapper.as_ref() {
+                                text = match text_mapper.map(text) {
+                                    Ok(text) => text,
+                                    Err(error) => {
+                                        output_err = Some(MultipartFormDataError::MappingError {
+                                            field: field_name,
+                                            error,
+                                        });
+
+                                        break 'outer;
+                                    },
+                                };
+                            }
+
+                            size = text.len() as u64;
+
+                            if let Some(allowed_values) = &field.allowed_values {
+                                if !allowed_values.iter().any(|v| *v == text) {
+                                    output_err = Some(MultipartFormDataError::InvalidValueError {
+                                        field: field_name,
+                                        got:   text,
+                                    });
+
+                                    break 'outer;
+                                }
+                            }
+
+                            match field.typ {
+                                MultipartFormDataType::Checkbox => {
+                                    let checked = is_checked(&text);
+
+                                    report(progress, || {
+                                        ParsedField::Checkbox(key.clone(), checked)
+                                    });
+
+                                    bools.insert(key, checked);
+                                },
+                                MultipartFormDataType::Number => {
+                                    let number = match field.number_parser.and_then(|p| p(&text)) {
+                                        Some(number) => number,
+                                        None => {
+                                            output_err =
+                                                Some(MultipartFormDataError::InvalidNumberError {
+                                                    field: field_name,
+                                                    got:   text,
+                                                });
+
+                                            break 'outer;
+                                        },
+                                    };
+
+                                    if !field.number_range.contains(&number) {
+                                        output_err =
+                                            Some(MultipartFormDataError::NumberOutOfRangeError {
+                                                field: field_name,
+                                                got:   number,
+                                            });
+
+                                        break 'outer;
+                                    }
+
+                                    let file_name = entry.file_name().map(String::from);
+                                    let disposition_params =
+                                        content_disposition_params(entry.headers());
+
+                                    let f = NumberField {
+                                        content_type: content_type.clone(),
+                                        file_name,
+                                        disposition_params,
+                                        original_name: original_name.clone(),
+                                        index: entry.index(),
+                                        number,
+                                    };
+
+                                    report(progress, || {
+                                        ParsedField::Number(key.clone(), f.clone())
+                                    });
+
+                                    if let Some(fields) = numbers.get_mut(&key) {
+                                        fields.push(f);
+                                    } else {
+                                        numbers.insert(key, vec![f]);
+                                    }
+                                },
+                                #[cfg(feature = "chrono")]
+                                MultipartFormDataType::DateTime => {
+                                    let datetime =
+                                        match parse_datetime(&text, field.datetime_format) {
+                                            Some(datetime) => datetime,
+                                            None => {
+                                                output_err = Some(
+                                                    MultipartFormDataError::InvalidDateTimeError {
+                                                        field: field_name,
+                                                        got:   text,
+                                                    },
+                                                );
+
+                                                break 'outer;
+                                            },
+                                        };
+
+                                    let file_name = entry.file_name().map(String::from);
+                                    let disposition_params =
+                                        content_disposition_params(entry.headers());
+
+                                    let f = DateTimeField {
+                                        content_type: content_type.clone(),
+                                        file_name,
+                                        disposition_params,
+                                        original_name: original_name.clone(),
+                                        index: entry.index(),
+                                        datetime,
+                                    };
+
+                                    report(progress, || {
+                                        ParsedField::DateTime(key.clone(), f.clone())
+                                    });
+
+                                    if let Some(fields) = datetimes.get_mut(&key) {
+                                        fields.push(f);
+                                    } else {
+                                        datetimes.insert(key, vec![f]);
+                                    }
+                                },
+                                _ => {
+                                    let file_name = entry.file_name().map(String::from);
+                                    let disposition_params =
+                                        content_disposition_params(entry.headers());
+
+                                    let f = TextField {
+                                        content_type: content_type.clone(),
+                                        file_name,
+                                        disposition_params,
+                                        original_name: original_name.clone(),
+                                        index: entry.index(),
+                                        text,
+                                        raw_bytes,
+                                    };
+
+                                    report(progress, || ParsedField::Text(key.clone(), f.clone()));
+
+                                    if let Some(fields) = texts.get_mut(&key) {
+                                        fields.push(f);
+                                    } else {
+                                        texts.insert(key, vec![f]);
+                                    }
+                                },
                             }
                         },
                     }
 
-                    field.repetition.decrease_check_is_over()
+                    field.repetition.decrease_check_is_over(size)
                 };
 
                 if drop_field {
-                    options.allowed_fields.remove(vi);
+                    let field = allowed_fields.remove(vi);
+
+                    if field.multiple {
+                        exhausted_multiple.push(Arc::from(field.field_name));
+                    }
+                }
+
+                if let Some((key, nested_fields, boundary, f)) = nested_part {
+                    let nested_form = parse_nested(
+                        &f.raw,
+                        boundary,
+                        options,
+                        nested_fields,
+                        (text_parts, file_parts),
+                        in_memory,
+                    );
+
+                    match nested_form.await {
+                        Ok(mut form) => {
+                            text_parts += form.stats.text_parts;
+                            file_parts += form.stats.file_parts;
+
+                            disk_charge.absorb(&mut form.disk_charge);
+
+                            nested.entry(key).or_default().push(form);
+                        },
+                        Err(err) => {
+                            output_err = Some(err);
+
+                            break;
+                        },
+                    }
+                }
+            } else if exhausted_multiple.contains(&field_name) {
+                output_err = Some(MultipartFormDataError::TooManyFilesError(field_name));
+
+                break;
+            } else {
+                warnings.push(ParseWarning::ExtraRepetitionDropped(field_name));
+            }
+        }
+
+        let stats = ParseStats {
+            bytes_read: chunk_reader.used(),
+            text_parts,
+            file_parts,
+            elapsed: started.elapsed(),
+        };
+
+        chunk_reader.finish().await;
+
+        if output_err.is_none() {
+            for (field_name, min) in schema.minimums.iter().cloned() {
+                let count = files.get(&field_name).map_or(0, Vec::len)
+                    + raw.get(&field_name).map_or(0, Vec::len)
+                    + texts.get(&field_name).map_or(0, Vec::len)
+                    + numbers.get(&field_name).map_or(0, Vec::len)
+                    + stored.get(&field_name).map_or(0, Vec::len)
+                    + large_texts.get(&field_name).map_or(0, Vec::len)
+                    + nested.get(&field_name).map_or(0, Vec::len)
+                    + bools.contains_key(&field_name) as usize;
+
+                #[cfg(feature = "chrono")]
+                let count = count + datetimes.get(&field_name).map_or(0, Vec::len);
+
+                #[cfg(feature = "csv")]
+                let count = count + csv.get(&field_name).map_or(0, Vec::len);
+
+                #[cfg(feature = "xml")]
+                let count = count + xml.get(&field_name).map_or(0, Vec::len);
+
+                if count < min as usize {
+                    output_err = Some(MultipartFormDataError::NotEnoughRepetitionsError {
+                        field: field_name,
+                        min,
+                    });
+
+                    break;
                 }
             }
         }
 
+        if let Some(err) = output_err.as_mut() {
+            if shut_down.load(Ordering::Acquire) && !progress.map_or(false, |p| p.is_closed()) {
+                *err = MultipartFormDataError::ShutdownError;
+            } else if headers_too_large.load(Ordering::Acquire) {
+                *err = MultipartFormDataError::PartHeadersTooLargeError;
+            }
+
+            match options.drain_on_error {
+                // The body may be broken, so stop draining on errors and report the original one
+                DrainPolicy::Full => while let Ok(Some(_)) = multipart.next_field().await {},
+                DrainPolicy::UpTo(limit) => {
+                    let mut drained = 0u64;
+
+                    'drain: while let Ok(Some(mut entry)) = multipart.next_field().await {
+                        while let Ok(Some(bytes)) = entry.chunk().await {
+                            drained += bytes.len() as u64;
+
+                            if drained > limit {
+                                break 'drain;
+                            }
+                        }
+                    }
+                },
+                DrainPolicy::Abort => (),
+            }
+        }
+
+        drop(multipart);
+
+        if let (None, Some(trailing)) = (output_err.as_ref(), trailing) {
+            // Read the rest of the body, so that the whole epilogue is checked
+            match rocket::tokio::io::copy(&mut reader, &mut rocket::tokio::io::sink()).await {
+                Ok(_) => {
+                    let bytes = trailing.load(Ordering::Acquire);
+
+                    if bytes > 0 {
+                        output_err = Some(MultipartFormDataError::TrailingDataError(bytes));
+                    }
+                },
+                Err(err) => output_err = Some(err.into()),
+            }
+        }
+
         if let Some(err) = output_err {
+            let err = err.redact(options.redact_fields);
+
             for (_, fields) in files {
                 for f in fields {
-                    try_delete(f.path);
+                    for path in f.paths() {
+                        try_delete(path);
+                    }
                 }
             }
 
-            loop {
-                if multipart.next_field().await?.is_none() {
-                    break;
+            for f in large_texts.values().flatten() {
+                try_delete(&f.path);
+            }
+
+            if let Some(storage) = options.storage.as_ref() {
+                for f in stored.values().flatten() {
+                    if handled_ids.contains(&f.id) {
+                        continue;
+                    }
+
+                    if storage.remove(&f.id).await.is_err() {}
+                }
+            }
+
+            if let Some(handler) = handler {
+                for id in handled_ids.iter() {
+                    if handler.remove(id).await.is_err() {}
                 }
             }
 
             Err(err)
         } else {
-            Ok(MultipartFormData {
+            for checkbox_name in schema.checkbox_names.iter().cloned() {
+                bools.entry(checkbox_name).or_insert(false);
+            }
+
+            #[cfg(feature = "sha2")]
+            let body_digest = None;
+
+            let part_offsets = part_offsets
+                .map(|offsets| std::mem::take(&mut *offsets.lock().unwrap()))
+                .unwrap_or_default();
+
+            let multipart_form_data = MultipartFormData {
                 files,
                 raw,
                 texts,
-            })
+                bools,
+                numbers,
+                stored,
+                large_texts,
+                #[cfg(feature = "chrono")]
+                datetimes,
+                #[cfg(feature = "csv")]
+                csv,
+                #[cfg(feature = "xml")]
+                xml,
+                warnings,
+                nested,
+                #[cfg(feature = "sha2")]
+                body_digest,
+                stats,
+                part_offsets,
+                redacted: options
+                    .allowed_fields
+                    .iter()
+                    .zip(schema.keys.iter())
+                    .filter(|(field, _)| options.redact_fields.contains(&field.field_name))
+                    .map(|(_, key)| key.clone())
+                    .collect(),
+                disk_charge,
+            };
+
+            #[cfg(feature = "sha2")]
+            let multipart_form_data = multipart_form_data.with_body_digest(&mut reader).await?;
+
+            Ok(multipart_form_data)
+        }
+    }
+}
+
+/// Parsed multipart/form-data whose uploaded files are not deleted automatically. It is created by the `MultipartFormData::into_background` method.
+#[derive(Default)]
+pub struct OwnedMultipartFormData {
+    pub files:           HashMap<Arc<str>, Vec<FileField>>,
+    pub raw:             HashMap<Arc<str>, Vec<RawField>>,
+    pub texts:           HashMap<Arc<str>, Vec<TextField>>,
+    pub bools:           HashMap<Arc<str>, bool>,
+    pub numbers:         HashMap<Arc<str>, Vec<NumberField>>,
+    /// The uploaded files which are stored by the `StorageBackend` set in the options.
+    pub stored:          HashMap<Arc<str>, Vec<StoredField>>,
+    /// The text fields which exceed the `large_text` thresholds of their fields, so they are stored in files.
+    pub large_texts:     HashMap<Arc<str>, Vec<LargeTextField>>,
+    #[cfg(feature = "chrono")]
+    pub datetimes:       HashMap<Arc<str>, Vec<DateTimeField>>,
+    #[cfg(feature = "csv")]
+    pub csv:             HashMap<Arc<str>, Vec<CsvField>>,
+    #[cfg(feature = "xml")]
+    pub xml:             HashMap<Arc<str>, Vec<XmlField>>,
+    /// Non-fatal anomalies found while parsing.
+    pub warnings:        Vec<ParseWarning>,
+    /// The sub-forms which are parsed from the multipart bodies nested in the raw fields.
+    pub nested:          HashMap<Arc<str>, Vec<OwnedMultipartFormData>>,
+    /// The digest of the whole HTTP body, which is computed as set by the `body_digest` option.
+    #[cfg(feature = "sha2")]
+    pub body_digest:     Option<[u8; 32]>,
+    /// Statistics of parsing, such as the measured reading rate.
+    pub stats:           ParseStats,
+    /// The byte ranges of the parts in the HTTP body, which are indexed by the `index` of the fields. It is only filled if `record_offsets` is enabled.
+    pub part_offsets:    Vec<PartOffsets>,
+    /// The names of the fields whose contents are left out of the `Debug` output.
+    pub(crate) redacted: Vec<Arc<str>>,
+}
+
+impl MultipartFormData {
+    /// Split this `MultipartFormData` instance into the parsed data and a `CleanupGuard` instance which owns the uploaded files, so that the data can be moved into a background task and the files can live as long as the guard.
+    pub fn into_background(mut self) -> (OwnedMultipartFormData, CleanupGuard) {
+        let mut guard = CleanupGuard::with_disk_charge(std::mem::take(&mut self.disk_charge));
+
+        for fields in self.files.values() {
+            for f in fields {
+                for path in f.paths() {
+                    guard.push(path);
+                }
+            }
+        }
+
+        for f in self.large_texts.values().flatten() {
+            guard.push(&f.path);
+        }
+
+        let mut data = OwnedMultipartFormData::default();
+
+        std::mem::swap(&mut data.files, &mut self.files);
+        std::mem::swap(&mut data.raw, &mut self.raw);
+        std::mem::swap(&mut data.texts, &mut self.texts);
+        std::mem::swap(&mut data.bools, &mut self.bools);
+        std::mem::swap(&mut data.numbers, &mut self.numbers);
+        std::mem::swap(&mut data.stored, &mut self.stored);
+        std::mem::swap(&mut data.large_texts, &mut self.large_texts);
+        #[cfg(feature = "chrono")]
+        std::mem::swap(&mut data.datetimes, &mut self.datetimes);
+        #[cfg(feature = "csv")]
+        std::mem::swap(&mut data.csv, &mut self.csv);
+        #[cfg(feature = "xml")]
+        std::mem::swap(&mut data.xml, &mut self.xml);
+        std::mem::swap(&mut data.warnings, &mut self.warnings);
+        data.stats = self.stats;
+        std::mem::swap(&mut data.part_offsets, &mut self.part_offsets);
+        std::mem::swap(&mut data.redacted, &mut self.redacted);
+        #[cfg(feature = "sha2")]
+        {
+            data.body_digest = self.body_digest;
         }
+
+        for (field_name, forms) in self.nested.drain() {
+            let forms = forms
+                .into_iter()
+                .map(|form| {
+                    let (form, nested_guard) = form.into_background();
+
+                    for path in nested_guard.keep() {
+                        guard.push(path);
+                    }
+
+                    form
+                })
+                .collect();
+
+            data.nested.insert(field_name, forms);
+        }
+
+        (data, guard)
+    }
+}
+
+impl MultipartFormData {
+    /// Remove the first file field of a specific field name. The file and its derivatives will not be deleted automatically when this `MultipartFormData` instance is being dropped.
+    pub fn remove_one_file(&mut self, field_name: &str) -> Option<FileField> {
+        remove_one(&mut self.files, field_name)
+    }
+
+    /// Remove the first raw field of a specific field name.
+    pub fn remove_one_raw(&mut self, field_name: &str) -> Option<RawField> {
+        remove_one(&mut self.raw, field_name)
+    }
+
+    /// Remove the first text field of a specific field name.
+    pub fn remove_one_text(&mut self, field_name: &str) -> Option<TextField> {
+        remove_one(&mut self.texts, field_name)
+    }
+
+    /// Remove the first number field of a specific field name.
+    pub fn remove_one_number(&mut self, field_name: &str) -> Option<NumberField> {
+        remove_one(&mut self.numbers, field_name)
     }
 }
 
@@ -386,13 +2315,280 @@ impl Drop for MultipartFormData {
 
         for fields in files.values() {
             for f in fields {
-                try_delete(&f.path);
+                for path in f.paths() {
+                    try_delete(path);
+                }
             }
         }
+
+        for f in self.large_texts.values().flatten() {
+            try_delete(&f.path);
+        }
+    }
+}
+
+/// An allowed field of a `MultipartFormDataSchema` and the number of parts it can still accept in a request. The settings of the field can be read through it.
+struct AllowedField<'s, 'a> {
+    field:      &'s MultipartFormDataField<'a>,
+    key:        &'s Arc<str>,
+    repetition: Repetition,
+}
+
+impl<'s, 'a> Deref for AllowedField<'s, 'a> {
+    type Target = MultipartFormDataField<'a>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.field
     }
 }
 
+/// Parse the data of a multipart body nested in a raw field as a sub-form with `fields`, which shares the limits of `options` after `parts` (the numbers of text parts and file parts) and `in_memory` bytes have been read. The future is boxed because parsing is recursive.
+fn parse_nested<'a>(
+    data: &'a [u8],
+    boundary: String,
+    options: &MultipartFormDataOptions<'a>,
+    fields: Vec<MultipartFormDataField<'a>>,
+    parts: (usize, usize),
+    in_memory: u64,
+) -> BoxFuture<'a, Result<MultipartFormData, MultipartFormDataError>> {
+    let nested_options = MultipartFormDataOptions {
+        max_data_bytes: options.max_data_bytes,
+        temporary_dir: options.temporary_dir.clone(),
+        allowed_fields: fields,
+        file_storage_mode: options.file_storage_mode,
+        on_field: options.on_field.clone(),
+        encryption: options.encryption.clone(),
+        unnamed_parts: options.unnamed_parts,
+        max_text_parts: options.max_text_parts - parts.0,
+        max_file_parts: options.max_file_parts - parts.1,
+        max_in_memory_bytes: options.max_in_memory_bytes.map(|max| max.saturating_sub(in_memory)),
+        file_name_generator: options.file_name_generator.clone(),
+        clock: options.clock.clone(),
+        rng: options.rng.clone(),
+        disk_budget: options.disk_budget.clone(),
+        max_field_name_len: options.max_field_name_len,
+        ascii_only_field_names: options.ascii_only_field_names,
+        infer_content_types: options.infer_content_types,
+        max_nesting_depth: options.max_nesting_depth - 1,
+        reject_trailing_data: options.reject_trailing_data,
+        strict_boundary: options.strict_boundary,
+        redact_fields: options.redact_fields,
+        ..MultipartFormDataOptions::new()
+    };
+
+    Box::pin(async move {
+        let schema = MultipartFormDataSchema::new(nested_options)?;
+
+        MultipartFormData::parse_reader_with(
+            data,
+            boundary,
+            Some(data.len() as u64),
+            &schema,
+            None,
+            None,
+        )
+        .await
+    })
+}
+
+/// The content type of a part, which is inferred from its file name and the first bytes of its data if it is missing and `infer` is `true`.
+#[inline]
+fn inferred(entry: &Field, content_type: Option<&Mime>, infer: bool, head: &[u8]) -> Option<Mime> {
+    match content_type {
+        Some(content_type) => Some(content_type.clone()),
+        None if infer => infer_content_type(entry.file_name(), head),
+        None => None,
+    }
+}
+
+/// Get the `Content-Length` header of a part.
+#[inline]
+fn content_length(headers: &HeaderMap) -> Option<u64> {
+    headers.get(CONTENT_LENGTH)?.to_str().ok()?.trim().parse().ok()
+}
+
+/// Whether the error is caused by an interrupted connection.
+#[inline]
+fn is_disconnection(error: &multer::Error) -> bool {
+    matches!(
+        error,
+        multer::Error::IncompleteFieldData { .. }
+            | multer::Error::IncompleteStream
+            | multer::Error::StreamReadFailed(_)
+    )
+}
+
+/// Close a partially written file and rename it with the `.partial` extension. The file is deleted if it cannot be kept.
+async fn retain_partial_file(file: FileWriter, path: &Path) -> Option<PathBuf> {
+    let mut partial_path = path.as_os_str().to_owned();
+    partial_path.push(".partial");
+
+    let partial_path = PathBuf::from(partial_path);
+
+    if file.finish().await.is_ok() && fs::rename(path, &partial_path).is_ok() {
+        Some(partial_path)
+    } else {
+        try_delete(path);
+
+        None
+    }
+}
+
+/// Send a field which has been completely received to the consumer of `parse_progressive`, if any.
+#[inline]
+fn report(progress: Option<&UnboundedSender<ParsedField>>, field: impl FnOnce() -> ParsedField) {
+    if let Some(progress) = progress {
+        // The consumer may have given up
+        let _ = progress.send(field());
+    }
+}
+
+fn remove_one<T>(map: &mut HashMap<Arc<str>, Vec<T>>, field_name: &str) -> Option<T> {
+    let fields = map.get_mut(field_name)?;
+
+    let field = if fields.is_empty() { None } else { Some(fields.remove(0)) };
+
+    if fields.is_empty() {
+        map.remove(field_name);
+    }
+
+    field
+}
+
+/// Move an uploaded file to `temporary_dir/<sha256>`. If the file already exists, the uploaded file is deleted. Return the new path and whether the file is deduplicated.
+#[cfg(feature = "sha2")]
+fn store_content_addressed(
+    path: PathBuf,
+    temporary_dir: &Path,
+    hasher: Sha256,
+) -> Result<(PathBuf, bool), std::io::Error> {
+    use std::fmt::Write;
+
+    let mut hash = String::with_capacity(64);
+
+    for b in hasher.finalize() {
+        write!(hash, "{:02x}", b).unwrap();
+    }
+
+    let store_path = temporary_dir.join(hash);
+
+    if store_path.exists() {
+        try_delete(path);
+
+        Ok((store_path, true))
+    } else {
+        if let Err(err) = fs::rename(&path, &store_path) {
+            try_delete(path);
+
+            return Err(err);
+        }
+
+        Ok((store_path, false))
+    }
+}
+
+/// Apply the active content policy of a field to its data. Return `false` if the data is rejected.
+#[cfg(feature = "sanitize")]
+fn check_active_content(
+    policy: ActiveContentPolicy,
+    data: &mut Vec<u8>,
+    field_name: &Arc<str>,
+    warnings: &mut Vec<ParseWarning>,
+) -> bool {
+    match policy {
+        ActiveContentPolicy::Allow => true,
+        ActiveContentPolicy::Reject => !has_active_content(data),
+        ActiveContentPolicy::Strip => {
+            if strip_active_content(data) {
+                warnings.push(ParseWarning::ActiveContentStripped(field_name.clone()));
+            }
+
+            true
+        },
+    }
+}
+
+/// Whether the value of a checkbox field means it is checked. A web browser only sends a checked checkbox, with the value `on` by default, but other clients and the hidden inputs used as fallbacks can send an unchecked one explicitly.
+#[inline]
+fn is_checked(text: &str) -> bool {
+    let text = text.trim();
+
+    !(text.is_empty()
+        || ["off", "false", "0", "no"].iter().any(|value| text.eq_ignore_ascii_case(value)))
+}
+
+/// What to do with a part after the empty file policy is applied.
+enum EmptyPart {
+    Keep,
+    Ignore,
+    Fail,
+}
+
+/// Apply the empty file policy to a part with `size` bytes of data, if it might be from an empty file input in an HTML form. A content type mismatch which has been reserved in `output_err` is turned into a warning if the part is empty, or fails the parsing otherwise.
+fn check_empty_part(
+    might_be_empty_file_input_in_html: bool,
+    policy: EmptyFilePolicy,
+    size: u64,
+    field_name: &Arc<str>,
+    output_err: &mut Option<MultipartFormDataError>,
+    warnings: &mut Vec<ParseWarning>,
+) -> EmptyPart {
+    if !might_be_empty_file_input_in_html {
+        return EmptyPart::Keep;
+    }
+
+    if size > 0 {
+        return if output_err.is_some() { EmptyPart::Fail } else { EmptyPart::Keep };
+    }
+
+    if policy == EmptyFilePolicy::Error {
+        *output_err = Some(MultipartFormDataError::EmptyFileError(field_name.clone()));
+
+        return EmptyPart::Fail;
+    }
+
+    if output_err.take().is_some() {
+        warnings.push(ParseWarning::ContentTypeMismatchOnEmptyFile(field_name.clone()));
+    }
+
+    if policy == EmptyFilePolicy::Ignore {
+        // This file might be from an empty file input in the HTML form, so ignore it.
+        warnings.push(ParseWarning::EmptyFileIgnored(field_name.clone()));
+
+        return EmptyPart::Ignore;
+    }
+
+    EmptyPart::Keep
+}
+
 #[inline]
 fn try_delete<P: AsRef<Path>>(path: P) {
     if fs::remove_file(path.as_ref()).is_err() {}
 }
+
+#[cfg(feature = "chrono")]
+fn parse_datetime(text: &str, format: Option<&str>) -> Option<chrono::NaiveDateTime> {
+    use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+    let text = text.trim();
+
+    match format {
+        // The format may only have a date, which is at midnight, or a time, which is on 1970-01-01
+        Some(format) => NaiveDateTime::parse_from_str(text, format)
+            .ok()
+            .or_else(|| {
+                NaiveDate::parse_from_str(text, format)
+                    .ok()
+                    .map(|date| date.and_time(NaiveTime::from_hms_opt(0, 0, 0).unwrap()))
+            })
+            .or_else(|| {
+                NaiveTime::parse_from_str(text, format)
+                    .ok()
+                    .map(|time| NaiveDate::from_ymd_opt(1970, 1, 1).unwrap().and_time(time))
+            }),
+        None => NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M")
+            .or_else(|_| NaiveDateTime::parse_from_str(text, "%Y-%m-%dT%H:%M:%S%.f"))
+            .ok(),
+    }
+}
@@ -2,21 +2,140 @@ use std::{
     error::Error,
     fmt::{self, Display, Formatter},
     io,
+    path::PathBuf,
     string::FromUtf8Error,
     sync::Arc,
 };
 
-use crate::multer;
+use rocket::http::ContentType;
+
+#[cfg(feature = "csv")]
+use crate::CsvError;
+#[cfg(feature = "xml")]
+use crate::XmlError;
+use crate::{multer, AdmissionDenied, ArchiveViolation, DataLimitSource, Number, PartialFile};
 
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum MultipartFormDataError {
+    /// This error is no longer returned. A request whose content type is not `multipart/form-data` fails with `WrongContentTypeError`.
+    #[deprecated(since = "0.11.0", note = "use `WrongContentTypeError` instead")]
     NotFormDataError,
+    /// This error is no longer returned. A `multipart/form-data` content type without a `boundary` parameter fails with `MissingBoundaryError`.
+    #[deprecated(since = "0.11.0", note = "use `MissingBoundaryError` instead")]
     BoundaryNotFoundError,
+    /// The content type of the request is not `multipart/form-data`.
+    WrongContentTypeError(Box<ContentType>),
+    /// The content type of the request is `multipart/form-data` but it does not have a `boundary` parameter.
+    MissingBoundaryError,
+    /// The boundary is longer than 70 characters, contains characters which are not allowed by RFC 2046 or ends with a space, while `strict_boundary` is enabled.
+    InvalidBoundaryError,
     IOError(io::Error),
     MulterError(multer::Error),
     FromUtf8Error(FromUtf8Error),
     DataTooLargeError(Arc<str>),
+    /// The HTTP body is larger than the max number of bytes to read.
+    DataLimitExceededError {
+        limit:  u64,
+        source: DataLimitSource,
+    },
     DataTypeError(Arc<str>),
+    InvalidValueError {
+        field: Arc<str>,
+        got:   String,
+    },
+    InvalidNumberError {
+        field: Arc<str>,
+        got:   String,
+    },
+    NumberOutOfRangeError {
+        field: Arc<str>,
+        got:   Number,
+    },
+    #[cfg(feature = "chrono")]
+    InvalidDateTimeError {
+        field: Arc<str>,
+        got:   String,
+    },
+    /// The data of a CSV field is broken, or a record is rejected by the `on_csv_row` handler of the field.
+    #[cfg(feature = "csv")]
+    InvalidCsvError {
+        field: Arc<str>,
+        error: CsvError,
+    },
+    /// The data of an XML field is malformed or declares a document type.
+    #[cfg(feature = "xml")]
+    InvalidXmlError {
+        field: Arc<str>,
+        error: XmlError,
+    },
+    OptionsError(OptionsValidationError),
+    PostProcessError {
+        field: Arc<str>,
+        error: Box<dyn Error + Send + Sync>,
+    },
+    QuotaExceededError,
+    /// The upload is refused by the `AdmissionControl` set in the options.
+    AdmissionDeniedError(AdmissionDenied),
+    FieldRejectedError(Arc<str>),
+    UnnamedPartError,
+    /// The name of a part is longer than `max_field_name_len`.
+    FieldNameTooLongError(String),
+    /// The name of a part contains characters which are not printable ASCII characters while `ascii_only_field_names` is enabled.
+    NonAsciiFieldNameError(String),
+    /// The headers of a part are longer than `max_part_header_bytes`.
+    PartHeadersTooLargeError,
+    /// There is data other than whitespace after the closing boundary, while `reject_trailing_data` is enabled. It has the number of bytes after the closing boundary.
+    TrailingDataError(u64),
+    /// The raw and text-based fields buffered in memory are larger than `max_in_memory_bytes` in total.
+    MemoryLimitExceededError,
+    TooManyTextPartsError,
+    TooManyFilePartsError,
+    EmptyFileError(Arc<str>),
+    /// A field is not sent the exact number of times required by the `remove_exactly` method of `FieldMap`.
+    FieldCountError {
+        field:    Arc<str>,
+        expected: usize,
+        got:      usize,
+    },
+    /// A part is still being received after the max duration of its field.
+    FieldTimeoutError(Arc<str>),
+    /// A field is sent fewer times than the minimum of its repetition.
+    NotEnoughRepetitionsError {
+        field: Arc<str>,
+        min:   u32,
+    },
+    /// The transformation set by the `map_text` or `map_raw` method of a field fails.
+    MappingError {
+        field: Arc<str>,
+        error: Box<dyn Error + Send + Sync>,
+    },
+    /// The data of a raw field cannot be decoded as the protobuf message declared by the `protobuf` method of the field.
+    #[cfg(feature = "prost")]
+    InvalidProtobufError {
+        field: Arc<str>,
+        error: prost::DecodeError,
+    },
+    /// A multi-file field receives more files than its max number of files.
+    TooManyFilesError(Arc<str>),
+    /// A raw field contains a nested multipart body which is deeper than `max_nesting_depth`.
+    NestingTooDeepError(Arc<str>),
+    /// An archive uploaded to a file field exceeds its `archive_limits`, or it is malformed.
+    ArchiveRejectedError {
+        field:     Arc<str>,
+        violation: ArchiveViolation,
+    },
+    #[cfg(feature = "sanitize")]
+    ActiveContentError(Arc<str>),
+    /// The `DiskBudget` shared by all requests is exhausted.
+    ServerStorageExhaustedError,
+    /// The server begins shutting down while the HTTP body is being read.
+    ShutdownError,
+    /// The connection is interrupted while a file is being received. The partially received file is kept if `retain_partial_files_on_disconnect` is enabled.
+    InterruptedError {
+        error:         multer::Error,
+        partial_files: Vec<PartialFile>,
+    },
 }
 
 impl From<io::Error> for MultipartFormDataError {
@@ -29,7 +148,30 @@ impl From<io::Error> for MultipartFormDataError {
 impl From<multer::Error> for MultipartFormDataError {
     #[inline]
     fn from(err: multer::Error) -> MultipartFormDataError {
-        MultipartFormDataError::MulterError(err)
+        if let Some(limit) = stream_size_exceeded(&err) {
+            return MultipartFormDataError::DataLimitExceededError {
+                limit,
+                source: DataLimitSource::Options,
+            };
+        }
+
+        match err {
+            multer::Error::FieldSizeExceeded {
+                field_name: Some(field_name), ..
+            } => MultipartFormDataError::DataTooLargeError(Arc::from(field_name)),
+            _ => MultipartFormDataError::MulterError(err),
+        }
+    }
+}
+
+/// Get the limit of the whole stream if `err` is caused by exceeding it. `multer` wraps the error when it is returned from reading a field.
+fn stream_size_exceeded(err: &multer::Error) -> Option<u64> {
+    match err {
+        multer::Error::StreamSizeExceeded {
+            limit,
+        } => Some(*limit),
+        multer::Error::StreamReadFailed(err) => stream_size_exceeded(err.downcast_ref()?),
+        _ => None,
     }
 }
 
@@ -40,27 +182,291 @@ impl From<FromUtf8Error> for MultipartFormDataError {
     }
 }
 
+impl From<OptionsValidationError> for MultipartFormDataError {
+    #[inline]
+    fn from(err: OptionsValidationError) -> MultipartFormDataError {
+        MultipartFormDataError::OptionsError(err)
+    }
+}
+
 impl Display for MultipartFormDataError {
     #[inline]
     fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        #[allow(deprecated)]
         match self {
             MultipartFormDataError::NotFormDataError => {
                 f.write_str("The content type is not `multipart/form-data`.")
             },
-            MultipartFormDataError::BoundaryNotFoundError => f.write_str(
-                "The boundary cannot be found. Maybe the multipart form data is incorrect.",
+            MultipartFormDataError::BoundaryNotFoundError => {
+                f.write_str("The `multipart/form-data` content type does not have a boundary.")
+            },
+            MultipartFormDataError::WrongContentTypeError(content_type) => f.write_fmt(
+                format_args!("The content type `{}` is not `multipart/form-data`.", content_type),
             ),
+            MultipartFormDataError::MissingBoundaryError => {
+                f.write_str("The `multipart/form-data` content type does not have a boundary.")
+            },
+            MultipartFormDataError::InvalidBoundaryError => f.write_str("The boundary is invalid."),
             MultipartFormDataError::IOError(err) => Display::fmt(err, f),
             MultipartFormDataError::MulterError(err) => Display::fmt(err, f),
             MultipartFormDataError::FromUtf8Error(err) => Display::fmt(err, f),
             MultipartFormDataError::DataTooLargeError(field) => {
                 f.write_fmt(format_args!("The data of field `{}` is too large.", field))
             },
+            MultipartFormDataError::DataLimitExceededError {
+                limit,
+                source,
+            } => f.write_fmt(format_args!(
+                "The HTTP body is larger than {} ({} bytes).",
+                source, limit
+            )),
             MultipartFormDataError::DataTypeError(field) => {
                 f.write_fmt(format_args!("The data type of field `{}` is incorrect.", field))
             },
+            MultipartFormDataError::InvalidValueError {
+                field,
+                got,
+            } => f.write_fmt(format_args!(
+                "The value `{}` of field `{}` is not allowed.",
+                got, field
+            )),
+            MultipartFormDataError::InvalidNumberError {
+                field,
+                got,
+            } => f.write_fmt(format_args!(
+                "The value `{}` of field `{}` is not a number.",
+                got, field
+            )),
+            MultipartFormDataError::NumberOutOfRangeError {
+                field,
+                got,
+            } => f.write_fmt(format_args!(
+                "The number `{}` of field `{}` is out of range.",
+                got, field
+            )),
+            #[cfg(feature = "chrono")]
+            MultipartFormDataError::InvalidDateTimeError {
+                field,
+                got,
+            } => f.write_fmt(format_args!(
+                "The value `{}` of field `{}` is not a valid date and time.",
+                got, field
+            )),
+            #[cfg(feature = "csv")]
+            MultipartFormDataError::InvalidCsvError {
+                field,
+                error,
+            } => f.write_fmt(format_args!(
+                "The record {} of the CSV field `{}` is invalid. {}",
+                error.row, field, error.message
+            )),
+            #[cfg(feature = "xml")]
+            MultipartFormDataError::InvalidXmlError {
+                field,
+                error,
+            } => f.write_fmt(format_args!("The XML field `{}` is invalid. {}", field, error)),
+            MultipartFormDataError::OptionsError(err) => Display::fmt(err, f),
+            MultipartFormDataError::PostProcessError {
+                field,
+                error,
+            } => f.write_fmt(format_args!(
+                "The post-processing of field `{}` failed: {}",
+                field, error
+            )),
+            MultipartFormDataError::QuotaExceededError => {
+                f.write_str("The upload quota is exceeded.")
+            },
+            MultipartFormDataError::AdmissionDeniedError(denied) => {
+                f.write_fmt(format_args!("The upload is refused: {}", denied))
+            },
+            MultipartFormDataError::InterruptedError {
+                error, ..
+            } => f.write_fmt(format_args!("The connection is interrupted: {}", error)),
+            MultipartFormDataError::FieldRejectedError(field) => {
+                f.write_fmt(format_args!("The field `{}` is rejected.", field))
+            },
+            MultipartFormDataError::FieldNameTooLongError(name) => {
+                f.write_fmt(format_args!("The field name `{}` is too long.", name))
+            },
+            MultipartFormDataError::NonAsciiFieldNameError(name) => f.write_fmt(format_args!(
+                "The field name `{}` contains disallowed characters.",
+                name.escape_debug()
+            )),
+            MultipartFormDataError::UnnamedPartError => {
+                f.write_str("A part does not have a field name.")
+            },
+            MultipartFormDataError::PartHeadersTooLargeError => {
+                f.write_str("The headers of a part are too large.")
+            },
+            MultipartFormDataError::TrailingDataError(bytes) => f.write_fmt(format_args!(
+                "There are {} bytes of data after the closing boundary.",
+                bytes
+            )),
+            MultipartFormDataError::MemoryLimitExceededError => {
+                f.write_str("The fields buffered in memory are too large in total.")
+            },
+            MultipartFormDataError::TooManyTextPartsError => {
+                f.write_str("There are too many parts without file names.")
+            },
+            MultipartFormDataError::TooManyFilePartsError => {
+                f.write_str("There are too many parts with file names.")
+            },
+            MultipartFormDataError::EmptyFileError(field) => {
+                f.write_fmt(format_args!("The file of field `{}` is empty.", field))
+            },
+            MultipartFormDataError::FieldCountError {
+                field,
+                expected,
+                got,
+            } => f.write_fmt(format_args!(
+                "The field `{}` should be sent exactly {} times, but it is sent {} times.",
+                field, expected, got
+            )),
+            MultipartFormDataError::FieldTimeoutError(field) => {
+                f.write_fmt(format_args!("The field `{}` is not received in time.", field))
+            },
+            MultipartFormDataError::NotEnoughRepetitionsError {
+                field,
+                min,
+            } => f.write_fmt(format_args!(
+                "The field `{}` should be sent at least {} times.",
+                field, min
+            )),
+            MultipartFormDataError::MappingError {
+                field,
+                error,
+            } => f.write_fmt(format_args!(
+                "The data of field `{}` cannot be transformed: {}",
+                field, error
+            )),
+            #[cfg(feature = "prost")]
+            MultipartFormDataError::InvalidProtobufError {
+                field,
+                error,
+            } => f.write_fmt(format_args!(
+                "The data of field `{}` is not a valid protobuf message: {}",
+                field, error
+            )),
+            MultipartFormDataError::TooManyFilesError(field) => {
+                f.write_fmt(format_args!("There are too many files in field `{}`.", field))
+            },
+            MultipartFormDataError::NestingTooDeepError(field) => f.write_fmt(format_args!(
+                "The multipart body nested in field `{}` is too deep.",
+                field
+            )),
+            MultipartFormDataError::ArchiveRejectedError {
+                field,
+                violation,
+            } => f.write_fmt(format_args!(
+                "The archive of field `{}` is rejected: {}",
+                field, violation
+            )),
+            #[cfg(feature = "sanitize")]
+            MultipartFormDataError::ActiveContentError(field) => {
+                f.write_fmt(format_args!("The data of field `{}` contains active content.", field))
+            },
+            MultipartFormDataError::ServerStorageExhaustedError => {
+                f.write_str("The storage for uploaded files on the server is exhausted.")
+            },
+            MultipartFormDataError::ShutdownError => f.write_str("The server is shutting down."),
         }
     }
 }
 
 impl Error for MultipartFormDataError {}
+
+impl MultipartFormDataError {
+    /// Whether this error is caused by the request, rather than by the server (such as I/O errors, misconfigured options and failed post-processing). It can be used to choose between 4xx and 5xx status codes.
+    #[inline]
+    pub fn is_client_error(&self) -> bool {
+        !matches!(
+            self,
+            MultipartFormDataError::IOError(_)
+                | MultipartFormDataError::OptionsError(_)
+                | MultipartFormDataError::PostProcessError { .. }
+                | MultipartFormDataError::ServerStorageExhaustedError
+                | MultipartFormDataError::ShutdownError
+        )
+    }
+}
+
+/// Errors of misconfigured `MultipartFormDataOptions` instances.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OptionsValidationError {
+    /// A field is declared more than once with different types.
+    ConflictingTypesError(String),
+    /// A field is declared more than once with content type filters which have nothing in common.
+    EmptyContentTypeError(String),
+    /// A field is allowed to be used zero times.
+    ZeroRepetitionError(String),
+    /// The size limit of a field is larger than the max number of bytes to read.
+    SizeLimitTooLargeError(String),
+    /// The temporary directory does not exist or is not writable.
+    TemporaryDirectoryError(PathBuf),
+    /// Encryption is requested but the `encryption` feature is not enabled.
+    EncryptionUnavailableError,
+    /// A field has a post-processor but uploaded files are stored by a storage backend.
+    PostProcessorUnavailableError(String),
+    /// A text field keeps its raw bytes but its large text is stored in a file, where the raw bytes cannot be kept.
+    RawBytesUnavailableError(String),
+    /// The max number of bytes per second is `0`.
+    ZeroBandwidthError,
+    /// The idempotency key is empty, too long or contains disallowed characters.
+    InvalidIdempotencyKeyError,
+    /// The interval of the checks of the admission control is `0`.
+    ZeroCheckIntervalError,
+}
+
+impl Display for OptionsValidationError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        match self {
+            OptionsValidationError::ConflictingTypesError(field) => f.write_fmt(format_args!(
+                "The field `{}` is declared more than once with different types.",
+                field
+            )),
+            OptionsValidationError::EmptyContentTypeError(field) => f.write_fmt(format_args!(
+                "The field `{}` is declared more than once and no content type is acceptable by \
+                 all of its declarations.",
+                field
+            )),
+            OptionsValidationError::ZeroRepetitionError(field) => {
+                f.write_fmt(format_args!("The field `{}` is allowed to be used zero times.", field))
+            },
+            OptionsValidationError::SizeLimitTooLargeError(field) => f.write_fmt(format_args!(
+                "The size limit of the field `{}` is larger than the max number of bytes to read.",
+                field
+            )),
+            OptionsValidationError::TemporaryDirectoryError(path) => f.write_fmt(format_args!(
+                "The temporary directory `{}` does not exist or is not writable.",
+                path.display()
+            )),
+            OptionsValidationError::EncryptionUnavailableError => {
+                f.write_str("Encryption is requested but the `encryption` feature is not enabled.")
+            },
+            OptionsValidationError::PostProcessorUnavailableError(field) => {
+                f.write_fmt(format_args!(
+                    "The field `{}` has a post-processor but uploaded files are stored by a \
+                     storage backend.",
+                    field
+                ))
+            },
+            OptionsValidationError::RawBytesUnavailableError(field) => f.write_fmt(format_args!(
+                "The field `{}` keeps its raw bytes but its large text is stored in a file.",
+                field
+            )),
+            OptionsValidationError::ZeroBandwidthError => {
+                f.write_str("The max number of bytes per second is zero.")
+            },
+            OptionsValidationError::InvalidIdempotencyKeyError => {
+                f.write_str("The idempotency key is invalid.")
+            },
+            OptionsValidationError::ZeroCheckIntervalError => {
+                f.write_str("The interval of the checks of the admission control is zero.")
+            },
+        }
+    }
+}
+
+impl Error for OptionsValidationError {}
@@ -1,66 +1,274 @@
-use std::str::FromStr;
+use std::{
+    error::Error,
+    ops::{Bound, RangeBounds},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
 
-use crate::{mime::Mime, MultipartFormDataType, Repetition};
+#[cfg(feature = "prost")]
+use crate::protobuf::{check_protobuf, ProtobufCheck};
+#[cfg(feature = "sanitize")]
+use crate::ActiveContentPolicy;
+#[cfg(feature = "csv")]
+use crate::CsvRowHandler;
+use crate::{
+    mime::Mime, number::parse_number, ArchiveLimits, ContentTypeGroup, EmptyFilePolicy,
+    FieldPostProcessor, MultipartFormDataType, Newline, Number, Numeric, OversizePolicy, RawMapper,
+    Repetition, TextFileNamePolicy, TextMapper,
+};
 
 const DEFAULT_IN_MEMORY_DATA_LIMIT: u64 = 1024 * 1024;
 const DEFAULT_FILE_DATA_LIMIT: u64 = 8 * 1024 * 1024;
 
 /// The guarder for fields.
 #[derive(Debug, Clone)]
+#[non_exhaustive]
 pub struct MultipartFormDataField<'a> {
     /// The type of this field.
-    pub typ:          MultipartFormDataType,
+    pub typ:                    MultipartFormDataType,
     /// The name of this field.
-    pub field_name:   &'a str,
+    pub field_name:             &'a str,
+    /// The key of the results of this field. If it is `None`, the field name is used.
+    pub store_as:               Option<&'a str>,
     /// The size limit for this field.
-    pub size_limit:   u64,
-    /// To filter the content types. It supports stars.
-    pub content_type: Option<Vec<Mime>>,
+    pub size_limit:             u64,
+    /// The max time to receive the data of each part of this field.
+    pub max_duration:           Option<Duration>,
+    /// What to do when the data of this field exceeds the size limit.
+    pub on_oversize:            OversizePolicy,
+    /// What to do when this field looks like an empty file input of an HTML form.
+    pub on_empty_file:          EmptyFilePolicy,
+    /// What to do when a part of this text field declares a file name.
+    pub on_text_with_file_name: TextFileNamePolicy,
+    /// To filter the content types. It supports stars, suffixes (such as `application/*+json`) and parameters (such as `text/plain; charset=utf-8`).
+    pub content_type:           Option<Vec<Mime>>,
+    /// The content types which are not allowed even if they pass the `content_type` filters. They are matched in the same way as `content_type`.
+    pub denied_content_types:   Vec<Mime>,
+    /// Whether the content type filters also accept the content types whose suffixes match them, such as `application/vnd.api+json` for `application/json`. The denied content types are matched in the same way.
+    pub match_suffix:           bool,
     /// To define this `MultipartFormDataField` instance can be used how many times.
-    pub repetition:   Repetition,
+    pub repetition:             Repetition,
+    /// Whether this field is created by the `files` method. The files which exceed the max number of files cause a `TooManyFilesError` instead of being dropped.
+    pub multiple:               bool,
+    /// Whether to remove the UTF-8 byte order mark at the start of the data of a text field.
+    pub strip_bom:              bool,
+    /// Whether to keep the bytes of a text field as they are received, besides the decoded text.
+    pub keep_raw_bytes:         bool,
+    /// If it is set, the data of a text field which exceeds this number of bytes is stored in a file in the temporary directory as a `LargeTextField`, instead of being kept in memory.
+    pub large_text:             Option<u64>,
+    /// How the line breaks in the data of a text field are normalized.
+    pub newline_normalization:  Newline,
+    /// To restrict the value of a text field to one of these values.
+    pub allowed_values:         Option<Vec<&'a str>>,
+    /// The parser of a number field.
+    pub number_parser:          Option<fn(&str) -> Option<Number>>,
+    /// The allowed range of a number field.
+    pub number_range:           (Bound<Number>, Bound<Number>),
+    /// The format of a date-time field, which can also have only a date or only a time. If it is `None`, the formats used by the HTML `datetime-local` input are accepted.
+    #[cfg(feature = "chrono")]
+    pub datetime_format:        Option<&'a str>,
+    /// The consumer of the records of a CSV field. If it is set, the records are not kept in the `CsvField`.
+    #[cfg(feature = "csv")]
+    pub csv_row_handler:        Option<Arc<dyn CsvRowHandler>>,
+    /// The limits of the archives uploaded to this file field. If it is set, the data must be a ZIP or tar archive.
+    pub archive_limits:         Option<ArchiveLimits>,
+    /// What to do with active content in the markup uploaded to this field.
+    #[cfg(feature = "sanitize")]
+    pub active_content:         ActiveContentPolicy,
+    /// The processor which is invoked after a file field has been completely received.
+    pub post_processor:         Option<Arc<dyn FieldPostProcessor>>,
+    /// The fields of the sub-form which is parsed from the multipart body nested in this raw field.
+    pub nested_fields:          Option<Vec<MultipartFormDataField<'a>>>,
+    /// The transformation of the text of this text-based field.
+    pub text_mapper:            Option<Arc<dyn TextMapper>>,
+    /// The transformation of the data of this raw field.
+    pub raw_mapper:             Option<Arc<dyn RawMapper>>,
+    /// The check of the protobuf message declared for this raw field, which fails if the data cannot be decoded.
+    #[cfg(feature = "prost")]
+    pub protobuf_check:         Option<ProtobufCheck>,
 }
 
 impl<'a> MultipartFormDataField<'a> {
-    /// Create a text field, the default size_limit is 1 MiB.
     #[inline]
-    pub fn text<S: ?Sized + AsRef<str>>(field_name: &S) -> MultipartFormDataField {
+    fn new(
+        typ: MultipartFormDataType,
+        field_name: &'a str,
+        size_limit: u64,
+    ) -> MultipartFormDataField<'a> {
         MultipartFormDataField {
-            typ:          MultipartFormDataType::Text,
-            field_name:   field_name.as_ref(),
-            size_limit:   DEFAULT_IN_MEMORY_DATA_LIMIT,
+            typ,
+            field_name,
+            store_as: None,
+            size_limit,
+            max_duration: None,
+            on_oversize: OversizePolicy::default(),
+            on_empty_file: EmptyFilePolicy::default(),
+            on_text_with_file_name: TextFileNamePolicy::default(),
             content_type: None,
-            repetition:   Repetition::default(),
+            denied_content_types: Vec::new(),
+            match_suffix: false,
+            repetition: Repetition::default(),
+            multiple: false,
+            strip_bom: false,
+            keep_raw_bytes: false,
+            large_text: None,
+            newline_normalization: Newline::default(),
+            allowed_values: None,
+            number_parser: None,
+            number_range: (Bound::Unbounded, Bound::Unbounded),
+            #[cfg(feature = "chrono")]
+            datetime_format: None,
+            #[cfg(feature = "csv")]
+            csv_row_handler: None,
+            archive_limits: None,
+            #[cfg(feature = "sanitize")]
+            active_content: ActiveContentPolicy::default(),
+            post_processor: None,
+            nested_fields: None,
+            text_mapper: None,
+            raw_mapper: None,
+            #[cfg(feature = "prost")]
+            protobuf_check: None,
         }
     }
 
+    /// Create a text field, the default size_limit is 1 MiB.
+    #[inline]
+    pub fn text<S: ?Sized + AsRef<str>>(field_name: &S) -> MultipartFormDataField<'_> {
+        MultipartFormDataField::new(
+            MultipartFormDataType::Text,
+            field_name.as_ref(),
+            DEFAULT_IN_MEMORY_DATA_LIMIT,
+        )
+    }
+
     /// Create a raw field, the default size_limit is 1 MiB.
     #[inline]
-    pub fn bytes<S: ?Sized + AsRef<str>>(field_name: &S) -> MultipartFormDataField {
+    pub fn bytes<S: ?Sized + AsRef<str>>(field_name: &S) -> MultipartFormDataField<'_> {
         Self::raw(field_name.as_ref())
     }
 
     /// Create a raw field, the default size_limit is 1 MiB.
     #[inline]
-    pub fn raw<S: ?Sized + AsRef<str>>(field_name: &S) -> MultipartFormDataField {
-        MultipartFormDataField {
-            typ:          MultipartFormDataType::Raw,
-            field_name:   field_name.as_ref(),
-            size_limit:   DEFAULT_IN_MEMORY_DATA_LIMIT,
-            content_type: None,
-            repetition:   Repetition::default(),
-        }
+    pub fn raw<S: ?Sized + AsRef<str>>(field_name: &S) -> MultipartFormDataField<'_> {
+        MultipartFormDataField::new(
+            MultipartFormDataType::Raw,
+            field_name.as_ref(),
+            DEFAULT_IN_MEMORY_DATA_LIMIT,
+        )
+    }
+
+    /// Create a checkbox field, the default size_limit is 1 MiB. An absent checkbox field is parsed as `false`, and so is one whose value is empty, `off`, `false`, `0` or `no` (case-insensitively). Any other value, such as the `on` sent by web browsers, is parsed as `true`.
+    #[inline]
+    pub fn checkbox<S: ?Sized + AsRef<str>>(field_name: &S) -> MultipartFormDataField<'_> {
+        MultipartFormDataField::new(
+            MultipartFormDataType::Checkbox,
+            field_name.as_ref(),
+            DEFAULT_IN_MEMORY_DATA_LIMIT,
+        )
+    }
+
+    /// Create a number field, the default size_limit is 1 MiB. The text of this field will be parsed as `T`.
+    #[inline]
+    pub fn number<T: Numeric>(
+        field_name: &(impl ?Sized + AsRef<str>),
+    ) -> MultipartFormDataField<'_> {
+        let mut field = MultipartFormDataField::new(
+            MultipartFormDataType::Number,
+            field_name.as_ref(),
+            DEFAULT_IN_MEMORY_DATA_LIMIT,
+        );
+
+        field.number_parser = Some(parse_number::<T>);
+
+        field
+    }
+
+    /// Create a date-time field, the default size_limit is 1 MiB. The text of this field will be parsed as a `chrono::NaiveDateTime`.
+    #[cfg(feature = "chrono")]
+    #[inline]
+    pub fn datetime<S: ?Sized + AsRef<str>>(field_name: &S) -> MultipartFormDataField<'_> {
+        MultipartFormDataField::new(
+            MultipartFormDataType::DateTime,
+            field_name.as_ref(),
+            DEFAULT_IN_MEMORY_DATA_LIMIT,
+        )
+    }
+
+    /// Create a CSV field, the default size_limit is 1 MiB. The first record of the data is the header, and the others are parsed into `CsvRecord`s which can be deserialized into typed rows.
+    #[cfg(feature = "csv")]
+    #[inline]
+    pub fn csv<S: ?Sized + AsRef<str>>(field_name: &S) -> MultipartFormDataField<'_> {
+        MultipartFormDataField::new(
+            MultipartFormDataType::Csv,
+            field_name.as_ref(),
+            DEFAULT_IN_MEMORY_DATA_LIMIT,
+        )
+    }
+
+    /// Create an XML field, the default size_limit is 1 MiB. The data is parsed into an `XmlElement` which can be deserialized. Document type declarations are rejected, so entities cannot be expanded or loaded from outside.
+    #[cfg(feature = "xml")]
+    #[inline]
+    pub fn xml<S: ?Sized + AsRef<str>>(field_name: &S) -> MultipartFormDataField<'_> {
+        MultipartFormDataField::new(
+            MultipartFormDataType::Xml,
+            field_name.as_ref(),
+            DEFAULT_IN_MEMORY_DATA_LIMIT,
+        )
     }
 
     /// Create a file field, the default size_limit is 8 MiB.
     #[inline]
-    pub fn file<S: ?Sized + AsRef<str>>(field_name: &S) -> MultipartFormDataField {
-        MultipartFormDataField {
-            typ:          MultipartFormDataType::File,
-            field_name:   field_name.as_ref(),
-            size_limit:   DEFAULT_FILE_DATA_LIMIT,
-            content_type: None,
-            repetition:   Repetition::default(),
-        }
+    pub fn file<S: ?Sized + AsRef<str>>(field_name: &S) -> MultipartFormDataField<'_> {
+        MultipartFormDataField::new(
+            MultipartFormDataType::File,
+            field_name.as_ref(),
+            DEFAULT_FILE_DATA_LIMIT,
+        )
+    }
+
+    /// Create a field which accepts any number of files, such as the ones sent by `<input type="file" multiple>`. The default size limit of each file is 8 MiB. Use the `max_files`, `per_file_limit` and `total_limit` methods to restrict them.
+    #[inline]
+    pub fn files<S: ?Sized + AsRef<str>>(field_name: &S) -> MultipartFormDataField<'_> {
+        let mut field = MultipartFormDataField::new(
+            MultipartFormDataType::File,
+            field_name.as_ref(),
+            DEFAULT_FILE_DATA_LIMIT,
+        );
+
+        field.repetition = Repetition::infinite();
+        field.multiple = true;
+
+        field
+    }
+
+    /// Put the results of this field under `key` instead of the field name, such as for mapping the field names sent by legacy clients to the ones used by the application. Several fields can be stored under the same key. The errors about the parts still refer to the field name sent by the client, while a `NotEnoughRepetitionsError` refers to the key.
+    #[inline]
+    pub fn store_as(mut self, key: &'a str) -> MultipartFormDataField<'a> {
+        self.store_as = Some(key);
+
+        self
+    }
+
+    /// Set the max number of files of this field.
+    #[inline]
+    pub fn max_files(mut self, max_files: u32) -> MultipartFormDataField<'a> {
+        self.repetition = self.repetition.with_max_count(max_files);
+        self
+    }
+
+    /// Set the size limit of each file of this field. It is the same as the `size_limit` method.
+    #[inline]
+    pub fn per_file_limit(self, per_file_limit: u64) -> MultipartFormDataField<'a> {
+        self.size_limit(per_file_limit)
+    }
+
+    /// Set the max total size of all files of this field.
+    #[inline]
+    pub fn total_limit(mut self, total_limit: u64) -> MultipartFormDataField<'a> {
+        self.repetition = self.repetition.with_max_total_bytes(total_limit);
+        self
     }
 
     /// Set the size_limit for this field.
@@ -70,6 +278,44 @@ impl<'a> MultipartFormDataField<'a> {
         self
     }
 
+    /// Set the max time to receive the data of each part of this field, counted from the end of its headers. A part which is still being received after that, such as a slowly trickling file, causes a `FieldTimeoutError`, while the other fields are not affected.
+    #[inline]
+    pub fn max_duration(mut self, max_duration: Duration) -> MultipartFormDataField<'a> {
+        self.max_duration = Some(max_duration);
+        self
+    }
+
+    /// Set what to do when the data of this field exceeds the size limit.
+    #[inline]
+    pub fn on_oversize(mut self, on_oversize: OversizePolicy) -> MultipartFormDataField<'a> {
+        self.on_oversize = on_oversize;
+        self
+    }
+
+    /// Set what to do when this field looks like an empty file input of an HTML form.
+    #[inline]
+    pub fn on_empty_file(mut self, on_empty_file: EmptyFilePolicy) -> MultipartFormDataField<'a> {
+        self.on_empty_file = on_empty_file;
+        self
+    }
+
+    /// Parse the data of this raw field as a sub-form with `fields` if its content type is `multipart/*`. The sub-form is put into `MultipartFormData::nested` instead of the raw data. `max_nesting_depth` in the options must be bigger than `0`.
+    #[inline]
+    pub fn nested(mut self, fields: Vec<MultipartFormDataField<'a>>) -> MultipartFormDataField<'a> {
+        self.nested_fields = Some(fields);
+        self
+    }
+
+    /// Set what to do when a part of this text field declares a file name, such as a file uploaded to a field which only expects text.
+    #[inline]
+    pub fn on_text_with_file_name(
+        mut self,
+        on_text_with_file_name: TextFileNamePolicy,
+    ) -> MultipartFormDataField<'a> {
+        self.on_text_with_file_name = on_text_with_file_name;
+        self
+    }
+
     /// Add a content type filter for this field. This method can be used multiple times to use multiple content type filters.
     #[inline]
     pub fn content_type(mut self, content_type: Option<Mime>) -> MultipartFormDataField<'a> {
@@ -110,10 +356,156 @@ impl<'a> MultipartFormDataField<'a> {
         Ok(self)
     }
 
+    /// Add the content type filters of a group for this field. This method can be used multiple times to accept multiple groups, and it can be combined with the `content_type` method.
+    #[inline]
+    pub fn accept(mut self, group: ContentTypeGroup) -> MultipartFormDataField<'a> {
+        self.content_type.get_or_insert_with(Vec::new).extend(group.content_types());
+        self
+    }
+
+    /// Add a content type which is not allowed for this field, such as `image/svg+xml` for a field which accepts `image/*`. This method can be used multiple times to deny multiple content types.
+    #[inline]
+    pub fn deny_content_type(mut self, content_type: Mime) -> MultipartFormDataField<'a> {
+        self.denied_content_types.push(content_type);
+        self
+    }
+
+    /// Set whether the content type filters also accept the content types whose suffixes match them, such as `application/vnd.api+json` for `application/json`.
+    #[inline]
+    pub fn match_suffix(mut self, match_suffix: bool) -> MultipartFormDataField<'a> {
+        self.match_suffix = match_suffix;
+        self
+    }
+
+    /// Inspect the archives uploaded to this file field while they are being received, and reject the ones which exceed `archive_limits` or contain entries pointing outside of them.
+    #[inline]
+    pub fn archive_limits(mut self, archive_limits: ArchiveLimits) -> MultipartFormDataField<'a> {
+        self.archive_limits = Some(archive_limits);
+        self
+    }
+
+    /// Set what to do with active content, such as scripts, event handlers and references to external resources, in the markup uploaded to this field. It is useful for fields which accept SVG images.
+    #[cfg(feature = "sanitize")]
+    #[inline]
+    pub fn active_content(
+        mut self,
+        active_content: ActiveContentPolicy,
+    ) -> MultipartFormDataField<'a> {
+        self.active_content = active_content;
+        self
+    }
+
     /// Set the repetition for this field.
     #[inline]
     pub fn repetition(mut self, repetition: Repetition) -> MultipartFormDataField<'a> {
         self.repetition = repetition;
         self
     }
+
+    /// Set whether to remove the UTF-8 byte order mark at the start of the data of this text field. Text pasted from some Windows tools starts with it.
+    #[inline]
+    pub fn strip_bom(mut self, strip_bom: bool) -> MultipartFormDataField<'a> {
+        self.strip_bom = strip_bom;
+        self
+    }
+
+    /// Set whether to keep the bytes of this text field as they are received in `TextField::raw_bytes`, so that they can be verified without switching the field to a raw field. It cannot be used with `large_text`.
+    #[inline]
+    pub fn keep_raw_bytes(mut self, keep_raw_bytes: bool) -> MultipartFormDataField<'a> {
+        self.keep_raw_bytes = keep_raw_bytes;
+        self
+    }
+
+    /// Store the data of this text field in a file in the temporary directory as a `LargeTextField` if it exceeds `threshold` bytes, such as pasted logs. The text in the file is validated as UTF-8, but it is not processed in any other way, so the byte order mark, the line breaks, the text mapper, the allowed values and the active content policy are not applied. It cannot be used with `keep_raw_bytes`. The size limit of the field still applies.
+    #[inline]
+    pub fn large_text(mut self, threshold: u64) -> MultipartFormDataField<'a> {
+        self.large_text = Some(threshold);
+        self
+    }
+
+    /// Set how the line breaks in the data of this text field are normalized, such as the text submitted from a `textarea`.
+    #[inline]
+    pub fn newline_normalization(mut self, newline: Newline) -> MultipartFormDataField<'a> {
+        self.newline_normalization = newline;
+        self
+    }
+
+    /// Restrict the value of this text field to one of the given values.
+    #[inline]
+    pub fn one_of<I: IntoIterator<Item = &'a str>>(
+        mut self,
+        allowed_values: I,
+    ) -> MultipartFormDataField<'a> {
+        self.allowed_values = Some(allowed_values.into_iter().collect());
+        self
+    }
+
+    /// Set the allowed range for this number field.
+    #[inline]
+    pub fn range<T: Numeric, R: RangeBounds<T>>(mut self, range: R) -> MultipartFormDataField<'a> {
+        let convert = |bound: Bound<&T>| match bound {
+            Bound::Included(n) => Bound::Included(n.into_number()),
+            Bound::Excluded(n) => Bound::Excluded(n.into_number()),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+
+        self.number_range = (convert(range.start_bound()), convert(range.end_bound()));
+        self
+    }
+
+    /// Set the format (in `chrono`'s strftime syntax) for this date-time field. A format with only a date, such as `%Y-%m-%d`, gives the date at midnight, and a format with only a time, such as `%H:%M`, gives the time on 1970-01-01.
+    #[cfg(feature = "chrono")]
+    #[inline]
+    pub fn format(mut self, format: &'a str) -> MultipartFormDataField<'a> {
+        self.datetime_format = Some(format);
+        self
+    }
+
+    /// Set a consumer which receives every record of this CSV field as soon as it is parsed, so that large uploads are not kept in memory. An error returned by it fails the parsing with an `InvalidCsvError`.
+    #[cfg(feature = "csv")]
+    #[inline]
+    pub fn on_csv_row<H: CsvRowHandler + 'static>(
+        mut self,
+        handler: H,
+    ) -> MultipartFormDataField<'a> {
+        self.csv_row_handler = Some(Arc::new(handler));
+        self
+    }
+
+    /// Set a processor which is invoked after this file field has been completely received, in order to generate derived artifacts.
+    #[inline]
+    pub fn post_process(
+        mut self,
+        post_processor: Box<dyn FieldPostProcessor>,
+    ) -> MultipartFormDataField<'a> {
+        self.post_processor = Some(Arc::from(post_processor));
+        self
+    }
+
+    /// Set a transformation of the text of this text-based field, such as formatting phone numbers. It is applied after the newlines are normalized, and before the text is checked against the allowed values or parsed as a number or a date-time. The text which is returned is stored.
+    #[inline]
+    pub fn map_text<F>(mut self, mapper: F) -> MultipartFormDataField<'a>
+    where
+        F: Fn(String) -> Result<String, Box<dyn Error + Send + Sync>> + Send + Sync + 'static, {
+        self.text_mapper = Some(Arc::new(mapper));
+        self
+    }
+
+    /// Set a transformation of the data of this raw field, such as decoding base64. It is applied after the data has been completely received, and the data which is returned is stored.
+    #[inline]
+    pub fn map_raw<F>(mut self, mapper: F) -> MultipartFormDataField<'a>
+    where
+        F: Fn(Vec<u8>) -> Result<Vec<u8>, Box<dyn Error + Send + Sync>> + Send + Sync + 'static,
+    {
+        self.raw_mapper = Some(Arc::new(mapper));
+        self
+    }
+
+    /// Declare the protobuf message generated by `prost` which is carried by this raw field. The data which cannot be decoded as `M` fails the parsing with an `InvalidProtobufError`, so it can be decoded by the `decode_protobuf` method of the `RawField` later.
+    #[cfg(feature = "prost")]
+    #[inline]
+    pub fn protobuf<M: prost::Message + Default>(mut self) -> MultipartFormDataField<'a> {
+        self.protobuf_check = Some(check_protobuf::<M>);
+        self
+    }
 }
@@ -1,16 +1,108 @@
-use std::{env, path::PathBuf};
+use std::{collections::HashMap, env, fs, path::PathBuf, str::FromStr, sync::Arc};
 
-use crate::MultipartFormDataField;
+use rocket::{log::private as log, Shutdown};
+
+#[cfg(feature = "sha2")]
+use crate::BodyDigest;
+use crate::{
+    content_type_filter::content_type_matches,
+    mime::{self, Mime},
+    multer::{Constraints, SizeLimit},
+    Admission, Audit, Clock, DiskBudget, DrainPolicy, FieldFilter, FileNameGenerator,
+    FileStorageMode, KeyNormalization, KeyProvider, MultipartFormDataField, MultipartFormDataType,
+    OptionsValidationError, OversizePolicy, Preset, Quota, RetryPolicy, Rng, StorageBackend,
+    UnnamedPartPolicy,
+};
+
+const DEFAULT_READ_CHUNK_SIZE: usize = 4096;
+const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 64;
 
 /// Options for parsing multipart/form-data.
 #[derive(Debug)]
 pub struct MultipartFormDataOptions<'a> {
     /// The max number of bytes to read.
-    pub max_data_bytes: u64,
+    pub max_data_bytes:                     u64,
     /// A path of directory where the uploaded files will be stored. It should be created before parsing.
-    pub temporary_dir:  PathBuf,
+    pub temporary_dir:                      PathBuf,
     /// Allowed fields of data.
-    pub allowed_fields: Vec<MultipartFormDataField<'a>>,
+    pub allowed_fields:                     Vec<MultipartFormDataField<'a>>,
+    /// The capacity of the buffer used to read the HTTP body. The default value is 4 KiB.
+    pub read_chunk_size:                    usize,
+    /// The capacity of the buffer used to write uploaded files. If it is `0`, the data will be written to files as soon as it is read. The default value is `0`.
+    pub write_chunk_size:                   usize,
+    /// If it is bigger than `0`, uploaded files are written by background tasks, so that the next chunk can be read from the network while the previous one is being written. At most `write_pipeline_depth` chunks can be queued for writing per file. The default value is `0`.
+    pub write_pipeline_depth:               usize,
+    /// If it is set, creating and writing the files in the temporary directory are retried when they fail with transient errors, which network filesystems such as NFS commonly return, instead of failing the whole upload. The writes are retried without writing any data twice.
+    pub io_retry_policy:                    Option<RetryPolicy>,
+    /// How uploaded files are named and stored in the temporary directory.
+    pub file_storage_mode:                  FileStorageMode,
+    /// The upload quota of the caller, which is enforced while the HTTP body is being read.
+    pub quota:                              Option<Quota>,
+    /// The admission control which can refuse the upload before the HTTP body is read, or abort it while the HTTP body is being read, with an `AdmissionDeniedError`.
+    pub admission:                          Option<Admission>,
+    /// What to do with the rest of the HTTP body when parsing fails. The default value is `DrainPolicy::Full`.
+    pub drain_on_error:                     DrainPolicy,
+    /// A filter which is invoked before the data of every named part is read.
+    pub on_field:                           Option<Arc<dyn FieldFilter>>,
+    /// If it is set, uploaded files are encrypted with AES-256-GCM on the fly, so that their plaintext is never stored in the temporary directory. Post-processors receive the encrypted files. The `encryption` feature is required.
+    pub encryption:                         Option<Arc<dyn KeyProvider>>,
+    /// What to do with parts which do not have field names. The default value is `UnnamedPartPolicy::Ignore`.
+    pub unnamed_parts:                      UnnamedPartPolicy<'a>,
+    /// If it is set, uploaded files are streamed to this backend instead of the temporary directory, and they are put in `MultipartFormData::stored`. `file_storage_mode`, `write_chunk_size` and `write_pipeline_depth` are ignored.
+    pub storage:                            Option<Arc<dyn StorageBackend>>,
+    /// The max number of parts without file names, whether their fields are allowed or not. The default value is `usize::MAX`.
+    pub max_text_parts:                     usize,
+    /// The max number of parts with file names, whether their fields are allowed or not. The default value is `usize::MAX`.
+    pub max_file_parts:                     usize,
+    /// The generator of the names of uploaded files in the temporary directory. If it is `None`, the files are named after the time of the `clock` in the same way as `TimestampFileNameGenerator`, and a random number from the `rng` is appended if the name is taken.
+    pub file_name_generator:                Option<Arc<dyn FileNameGenerator>>,
+    /// The source of the current time for naming uploaded files. If it is `None`, `SystemClock` is used. Replace it with a fixed clock to get reproducible file names in tests.
+    pub clock:                              Option<Arc<dyn Clock>>,
+    /// The source of random numbers for naming uploaded files. If it is `None`, `SystemRng` is used. Replace it with a seeded one to get reproducible file names in tests.
+    pub rng:                                Option<Arc<dyn Rng>>,
+    /// If it is set, the HTTP body, including the parts which are skipped, is read at most this number of bytes per second on average, with bursts of up to one second of data, so that a single request cannot saturate the disk or the storage backend. It must be bigger than `0`.
+    pub max_bytes_per_second:               Option<u64>,
+    /// The presets which are applied to the file fields when parsing starts, in order.
+    pub presets:                            Vec<Preset>,
+    /// A ceiling on the total size of the uploaded files in the temporary directory, shared with other requests. If it would be exceeded, parsing fails with a `ServerStorageExhaustedError`.
+    pub disk_budget:                        Option<Arc<DiskBudget>>,
+    /// Whether to keep the file which is being received when the connection is interrupted, instead of deleting it. The file is renamed with the `.partial` extension and reported in an `InterruptedError`. It does not apply to encrypted files or files written to a storage backend.
+    pub retain_partial_files_on_disconnect: bool,
+    /// If it is set, the names of the files created in the temporary directory are derived from this key, the field name and the position of the part, instead of being generated by the `file_name_generator`. A retried request with the same key overwrites the files of the previous attempt, including the `.partial` ones, rather than creating new ones. The temporary directory should not be shared with untrusted processes. The key must be made of at most 64 ASCII letters, digits, `-` and `_`, such as the value of an `Idempotency-Key` header.
+    pub idempotency_key:                    Option<String>,
+    /// Whether to reject a part whose `Content-Length` header exceeds the size limit of its field with a `DataTooLargeError` before reading its data. It does not apply to the fields which truncate oversized data.
+    pub reject_declared_oversize:           bool,
+    /// The max length of field names in bytes. A part whose name is longer causes a `FieldNameTooLongError`, even if the field is not allowed.
+    pub max_field_name_len:                 Option<usize>,
+    /// Whether to record the byte ranges of the parts in the HTTP body into `MultipartFormData::part_offsets`, such as for audit pipelines which keep the raw body and need to point at a part later.
+    pub record_offsets:                     bool,
+    /// The max number of bytes of the headers of a part. It is checked while the HTTP body is being read, so headers which are split across many reads or never end cause a `PartHeadersTooLargeError` instead of being buffered without bounds. The default value is 8 KiB.
+    pub max_part_header_bytes:              Option<u64>,
+    /// Whether to reject the body which has data other than whitespace after the closing boundary with a `TrailingDataError`, such as the bodies concatenated by broken clients. The rest of the body is read to the end to check it.
+    pub reject_trailing_data:               bool,
+    /// Whether to reject a boundary which does not conform to RFC 2046 with an `InvalidBoundaryError` before the HTTP body is read. A conforming boundary has 1 to 70 characters, which are ASCII letters, digits, spaces and `'()+_,-./:=?`, and does not end with a space. Overlong or exotic boundaries can make scanning the body slow and usually come from broken or malicious clients.
+    pub strict_boundary:                    bool,
+    /// The max number of bytes which the raw and text-based fields of a request can buffer in memory in total, regardless of the size limits of the fields. Exceeding it causes a `MemoryLimitExceededError`. The raw bytes kept by `keep_raw_bytes` are counted besides the texts, and so is the markup which file fields with an `active_content` policy buffer to check it.
+    pub max_in_memory_bytes:                Option<u64>,
+    /// Whether field names must be made of printable ASCII characters. A part whose name is not causes a `NonAsciiFieldNameError`, even if the field is not allowed.
+    pub ascii_only_field_names:             bool,
+    /// The aliases of nonstandard content types sent by some clients, such as `image/jpg` for `image/jpeg`. The content type of a part is replaced with the one its type and subtype are mapped to, before it is checked by the content type filters of the fields and put into the results. The keys should be in lowercase and without parameters.
+    pub mime_aliases:                       HashMap<String, Mime>,
+    /// Whether to infer the content types of the file and raw parts which do not have a `Content-Type` header, from the extensions of their file names or the first bytes of their data. The inferred content types are put into the results, but they are not checked by the content type filters of the fields.
+    pub infer_content_types:                bool,
+    /// The max depth of the sub-forms parsed from the multipart bodies nested in the raw fields declared by the `nested` method of `MultipartFormDataField`. A sub-form shares the limits of parts and the disk budget with its parent, and its data cannot be larger than the raw field. A body nested deeper causes a `NestingTooDeepError`. The default value is `0`, which means that nested bodies are not parsed at all.
+    pub max_nesting_depth:                  usize,
+    /// If it is set, parsing is aborted with a `ShutdownError` as soon as the server begins shutting down, and the uploaded files are deleted, so that the shutdown does not wait for slow uploads. It can be obtained by the `Shutdown` request guard.
+    pub shutdown:                           Option<Shutdown>,
+    /// How the field names sent by clients are normalized, so that the inconsistent casing of different clients can be tolerated. The names of the allowed fields should be declared in the normalized form. The original names are kept in the `original_name` of the fields. The default value is `KeyNormalization::None`.
+    pub normalize_keys:                     KeyNormalization,
+    /// The names of the fields whose contents must not be shown, such as passwords. Their values are left out of the `Debug` output of `MultipartFormData` and replaced with `[redacted]` in the errors. A number of them which is out of range is reported as an `InvalidValueError`. The errors returned by the mappers and the post-processors of the fields are not changed.
+    pub redact_fields:                      &'a [&'a str],
+    /// If it is set, every parsed request is recorded to its audit sink, with the fields, the outcome and the time spent. The requests which are rejected before their bodies are read, such as the ones whose content types are wrong, are not recorded.
+    pub audit:                              Option<Audit>,
+    /// If it is set, a digest is computed over the whole HTTP body while it is being parsed, and put into `MultipartFormData::body_digest`, so that a signed request can be verified without buffering its body. The `sha2` feature is required.
+    #[cfg(feature = "sha2")]
+    pub body_digest:                        Option<BodyDigest>,
 }
 
 impl<'a> MultipartFormDataOptions<'a> {
@@ -18,9 +110,48 @@ impl<'a> MultipartFormDataOptions<'a> {
     #[inline]
     pub fn new() -> MultipartFormDataOptions<'a> {
         MultipartFormDataOptions {
-            max_data_bytes: u64::MAX,
-            temporary_dir:  env::temp_dir(),
-            allowed_fields: Vec::new(),
+            max_data_bytes:                       u64::MAX,
+            temporary_dir:                        env::temp_dir(),
+            allowed_fields:                       Vec::new(),
+            read_chunk_size:                      DEFAULT_READ_CHUNK_SIZE,
+            write_chunk_size:                     0,
+            write_pipeline_depth:                 0,
+            io_retry_policy:                      None,
+            file_storage_mode:                    FileStorageMode::default(),
+            quota:                                None,
+            admission:                            None,
+            drain_on_error:                       DrainPolicy::default(),
+            on_field:                             None,
+            encryption:                           None,
+            unnamed_parts:                        UnnamedPartPolicy::default(),
+            storage:                              None,
+            max_text_parts:                       usize::MAX,
+            max_file_parts:                       usize::MAX,
+            file_name_generator:                  None,
+            clock:                                None,
+            rng:                                  None,
+            max_bytes_per_second:                 None,
+            presets:                              Vec::new(),
+            disk_budget:                          None,
+            retain_partial_files_on_disconnect:   false,
+            idempotency_key:                      None,
+            reject_declared_oversize:             false,
+            max_field_name_len:                   None,
+            record_offsets:                       false,
+            max_part_header_bytes:                Some(8 * 1024),
+            reject_trailing_data:                 false,
+            strict_boundary:                      false,
+            max_in_memory_bytes:                  None,
+            ascii_only_field_names:               false,
+            mime_aliases:                         HashMap::new(),
+            infer_content_types:                  false,
+            max_nesting_depth:                    0,
+            shutdown:                             None,
+            normalize_keys:                       KeyNormalization::None,
+            redact_fields:                        &[],
+            audit:                                None,
+            #[cfg(feature = "sha2")]
+            body_digest:                          None,
         }
     }
 
@@ -30,11 +161,239 @@ impl<'a> MultipartFormDataOptions<'a> {
         allowed_fields: Vec<MultipartFormDataField<'a>>,
     ) -> MultipartFormDataOptions<'a> {
         MultipartFormDataOptions {
-            max_data_bytes: u64::MAX,
-            temporary_dir: env::temp_dir(),
             allowed_fields,
+            ..MultipartFormDataOptions::new()
+        }
+    }
+}
+
+impl<'a> MultipartFormDataOptions<'a> {
+    /// Check this `MultipartFormDataOptions` instance for misconfiguration. This method is called automatically at the start of parsing.
+    pub fn validate(&self) -> Result<(), OptionsValidationError> {
+        let mut has_file_fields = false;
+
+        for (i, field) in self.allowed_fields.iter().enumerate() {
+            if field.repetition.is_zero() {
+                return Err(OptionsValidationError::ZeroRepetitionError(
+                    field.field_name.to_string(),
+                ));
+            }
+
+            if field.size_limit > self.max_data_bytes {
+                return Err(OptionsValidationError::SizeLimitTooLargeError(
+                    field.field_name.to_string(),
+                ));
+            }
+
+            if self.allowed_fields[..i]
+                .iter()
+                .any(|f| f.field_name == field.field_name && f.typ != field.typ)
+            {
+                return Err(OptionsValidationError::ConflictingTypesError(
+                    field.field_name.to_string(),
+                ));
+            }
+
+            if self.storage.is_some() && field.post_processor.is_some() {
+                return Err(OptionsValidationError::PostProcessorUnavailableError(
+                    field.field_name.to_string(),
+                ));
+            }
+
+            if field.typ == MultipartFormDataType::Text
+                && field.keep_raw_bytes
+                && field.large_text.is_some()
+            {
+                return Err(OptionsValidationError::RawBytesUnavailableError(
+                    field.field_name.to_string(),
+                ));
+            }
+
+            has_file_fields |= field.typ == MultipartFormDataType::File;
+        }
+
+        if self.max_bytes_per_second == Some(0) {
+            return Err(OptionsValidationError::ZeroBandwidthError);
+        }
+
+        if self.admission.as_ref().map_or(false, |a| a.check_interval == Some(0)) {
+            return Err(OptionsValidationError::ZeroCheckIntervalError);
+        }
+
+        if let Some(key) = self.idempotency_key.as_ref() {
+            if key.is_empty()
+                || key.len() > MAX_IDEMPOTENCY_KEY_LENGTH
+                || !key.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+            {
+                return Err(OptionsValidationError::InvalidIdempotencyKeyError);
+            }
+        }
+
+        #[cfg(not(feature = "encryption"))]
+        if self.encryption.is_some() {
+            return Err(OptionsValidationError::EncryptionUnavailableError);
+        }
+
+        if has_file_fields && self.storage.is_none() {
+            let writable = match fs::metadata(&self.temporary_dir) {
+                Ok(metadata) => metadata.is_dir() && !metadata.permissions().readonly(),
+                Err(_) => false,
+            };
+
+            if !writable {
+                return Err(OptionsValidationError::TemporaryDirectoryError(
+                    self.temporary_dir.clone(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Merge the fields which are declared more than once into one field. The repetitions are summed up, the content type filters are intersected, the denied content types are united, the allowed values are intersected, the smallest size limit is taken, and the data is truncated only if all of the declarations allow it. Other settings are taken from the first declaration. This method is called automatically at the start of parsing, before the options are validated, and it sorts the fields by their names.
+    pub fn merge_duplicate_fields(&mut self) -> Result<(), OptionsValidationError> {
+        self.allowed_fields.sort_by_key(|f| f.field_name);
+
+        let mut merged: Vec<MultipartFormDataField<'a>> =
+            Vec::with_capacity(self.allowed_fields.len());
+
+        for field in self.allowed_fields.drain(..) {
+            let last = match merged.last_mut() {
+                Some(last) if last.field_name == field.field_name => last,
+                _ => {
+                    merged.push(field);
+                    continue;
+                },
+            };
+
+            log::debug!(
+                "The field `{}` is declared more than once. Merge its declarations.",
+                field.field_name
+            );
+
+            if last.typ != field.typ {
+                return Err(OptionsValidationError::ConflictingTypesError(
+                    field.field_name.to_string(),
+                ));
+            }
+
+            last.repetition = last.repetition.merge(field.repetition);
+            last.size_limit = last.size_limit.min(field.size_limit);
+
+            if field.on_oversize == OversizePolicy::Error {
+                last.on_oversize = OversizePolicy::Error;
+            }
+
+            last.content_type = match (last.content_type.take(), field.content_type) {
+                (Some(a), Some(b)) => {
+                    let mut intersection: Vec<Mime> = Vec::new();
+
+                    for a in a.iter() {
+                        for b in b.iter() {
+                            if let Some(m) = intersect_content_type(a, b) {
+                                if !intersection.contains(&m) {
+                                    intersection.push(m);
+                                }
+                            }
+                        }
+                    }
+
+                    if intersection.is_empty() {
+                        return Err(OptionsValidationError::EmptyContentTypeError(
+                            field.field_name.to_string(),
+                        ));
+                    }
+
+                    Some(intersection)
+                },
+                (a, b) => a.or(b),
+            };
+
+            for content_type in field.denied_content_types {
+                if !last.denied_content_types.contains(&content_type) {
+                    last.denied_content_types.push(content_type);
+                }
+            }
+
+            last.allowed_values = match (last.allowed_values.take(), field.allowed_values) {
+                (Some(a), Some(b)) => Some(a.into_iter().filter(|v| b.contains(v)).collect()),
+                (a, b) => a.or(b),
+            };
+        }
+
+        self.allowed_fields = merged;
+
+        Ok(())
+    }
+}
+
+impl<'a> MultipartFormDataOptions<'a> {
+    /// The max number of bytes to read from the HTTP body. One more byte than `max_data_bytes` is read, so that a body which exceeds it can be told from a truncated one.
+    #[inline]
+    pub(crate) fn read_limit(&self) -> u64 {
+        self.max_data_bytes.saturating_add(1)
+    }
+
+    /// Create a `multer::SizeLimit` instance which enforces `max_data_bytes` and the size limits of the allowed fields. Fields which are not allowed are not limited, because they are skipped.
+    pub fn to_multer_size_limit(&self) -> SizeLimit {
+        let mut field_limits: HashMap<&str, u64> = HashMap::new();
+
+        for field in self.allowed_fields.iter() {
+            let limit = field_limits.entry(field.field_name).or_insert(0);
+
+            // Truncated fields have to be read completely
+            let size_limit = match field.on_oversize {
+                OversizePolicy::Error => field.size_limit,
+                OversizePolicy::Truncate => u64::MAX,
+            };
+
+            *limit = (*limit).max(size_limit);
         }
+
+        field_limits.into_iter().fold(
+            SizeLimit::new().whole_stream(self.max_data_bytes).per_field(u64::MAX),
+            |size_limit, (field_name, limit)| size_limit.for_field(field_name, limit),
+        )
+    }
+
+    /// Create a `multer::Constraints` instance which enforces the size limits and the allowed field names of these options at the `multer` layer.
+    ///
+    /// The `parse` method only applies the size limits, so that fields which are not allowed are skipped instead of being rejected.
+    pub fn to_multer_constraints(&self) -> Constraints {
+        let mut field_names: Vec<&str> = self.allowed_fields.iter().map(|f| f.field_name).collect();
+
+        field_names.sort_unstable();
+        field_names.dedup();
+
+        Constraints::new().size_limit(self.to_multer_size_limit()).allowed_fields(field_names)
+    }
+}
+
+fn intersect_content_type(a: &Mime, b: &Mime) -> Option<Mime> {
+    // One of the filters may be more specific than the other one
+    if content_type_matches(b, a) {
+        return Some(a.clone());
+    } else if content_type_matches(a, b) {
+        return Some(b.clone());
     }
+
+    let top = if a.type_() == mime::STAR {
+        b.type_()
+    } else if b.type_() == mime::STAR || a.type_() == b.type_() {
+        a.type_()
+    } else {
+        return None;
+    };
+
+    let sub = if a.subtype() == mime::STAR {
+        b.subtype()
+    } else if b.subtype() == mime::STAR || a.subtype() == b.subtype() {
+        a.subtype()
+    } else {
+        return None;
+    };
+
+    Mime::from_str(&format!("{}/{}", top, sub)).ok()
 }
 
 impl<'a> Default for MultipartFormDataOptions<'a> {
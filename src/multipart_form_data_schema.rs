@@ -0,0 +1,126 @@
+use std::sync::Arc;
+
+use crate::{MultipartFormDataOptions, MultipartFormDataType, OptionsValidationError};
+
+/// `MultipartFormDataOptions` which have been prepared for parsing, so that they can be built once, such as at startup, and shared by many requests without being cloned.
+///
+/// The presets are applied, the options are validated, and the allowed fields are sorted when it is created. The state of a request, such as how many times a field has been sent, is kept by the parser, so the schema is never modified. The settings which differ between requests, such as `quota` and `idempotency_key`, should be set in the options passed to the `parse` method instead.
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use rocket::{http::ContentType, Data, State};
+/// use rocket_multipart_form_data::{
+///     MultipartFormData, MultipartFormDataField, MultipartFormDataOptions,
+///     MultipartFormDataSchema,
+/// };
+///
+/// #[post("/", data = "<data>")]
+/// async fn index(
+///     schema: &State<MultipartFormDataSchema<'static>>,
+///     content_type: &ContentType,
+///     data: Data<'_>,
+/// ) -> &'static str {
+///     let _multipart_form_data =
+///         MultipartFormData::parse_schema(content_type, data, schema)
+///             .await
+///             .unwrap();
+///
+///     "ok"
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let schema = MultipartFormDataSchema::new(
+///         MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+///             MultipartFormDataField::file("photo"),
+///             MultipartFormDataField::text("title"),
+///         ]),
+///     )
+///     .unwrap();
+///
+///     rocket::build().manage(schema).mount("/", routes![index])
+/// }
+/// ```
+#[derive(Debug)]
+pub struct MultipartFormDataSchema<'a> {
+    pub(crate) options:        MultipartFormDataOptions<'a>,
+    /// The interned names of the allowed fields, sorted and deduplicated, so that every part of the same field shares one `Arc<str>`.
+    pub(crate) field_names:    Vec<Arc<str>>,
+    /// The keys of the results of the allowed fields, in the same order as the fields. The interned field name is used unless a field is stored under another key.
+    pub(crate) keys:           Vec<Arc<str>>,
+    pub(crate) checkbox_names: Vec<Arc<str>>,
+    /// The keys of the fields which must be sent at least a number of times.
+    pub(crate) minimums:       Vec<(Arc<str>, u32)>,
+}
+
+impl<'a> MultipartFormDataSchema<'a> {
+    /// Prepare `options` for parsing.
+    pub fn new(
+        mut options: MultipartFormDataOptions<'a>,
+    ) -> Result<MultipartFormDataSchema<'a>, OptionsValidationError> {
+        for preset in std::mem::take(&mut options.presets) {
+            preset.apply(&mut options);
+        }
+
+        // Duplicate declarations are merged before the validation, so conflicting ones fail here
+        options.merge_duplicate_fields()?;
+        options.validate()?;
+
+        let mut field_names: Vec<Arc<str>> =
+            options.allowed_fields.iter().map(|f| Arc::from(f.field_name)).collect();
+        field_names.dedup();
+
+        let keys: Vec<Arc<str>> = options
+            .allowed_fields
+            .iter()
+            .map(|f| match f.store_as {
+                Some(key) => Arc::from(key),
+                None => intern(&field_names, f.field_name).unwrap(),
+            })
+            .collect();
+
+        let checkbox_names: Vec<Arc<str>> = options
+            .allowed_fields
+            .iter()
+            .zip(keys.iter())
+            .filter(|(f, _)| f.typ == MultipartFormDataType::Checkbox)
+            .map(|(_, key)| key.clone())
+            .collect();
+
+        let mut minimums: Vec<(Arc<str>, u32)> = Vec::new();
+
+        for (field, key) in options.allowed_fields.iter().zip(keys.iter()) {
+            if field.repetition.min() == 0 {
+                continue;
+            }
+
+            match minimums.iter_mut().find(|(name, _)| name == key) {
+                Some((_, min)) => *min = min.saturating_add(field.repetition.min()),
+                None => minimums.push((key.clone(), field.repetition.min())),
+            }
+        }
+
+        Ok(MultipartFormDataSchema {
+            options,
+            field_names,
+            keys,
+            checkbox_names,
+            minimums,
+        })
+    }
+
+    /// The prepared options.
+    #[inline]
+    pub fn options(&self) -> &MultipartFormDataOptions<'a> {
+        &self.options
+    }
+}
+
+/// Get the interned name of a field.
+#[inline]
+pub(crate) fn intern(field_names: &[Arc<str>], field_name: &str) -> Option<Arc<str>> {
+    field_names
+        .binary_search_by(|n| n.as_ref().cmp(field_name))
+        .ok()
+        .map(|i| field_names[i].clone())
+}
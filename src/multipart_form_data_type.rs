@@ -1,4 +1,5 @@
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
 pub enum MultipartFormDataType {
     /// Stored the parsed data as a string.
     Text,
@@ -6,4 +7,17 @@ pub enum MultipartFormDataType {
     Raw,
     /// Stored the parsed data as a file.
     File,
+    /// Stored the parsed data as a boolean. Any submitted value is regarded as checked.
+    Checkbox,
+    /// Stored the parsed data as a number.
+    Number,
+    /// Stored the parsed data as a date and time.
+    #[cfg(feature = "chrono")]
+    DateTime,
+    /// Stored the parsed data as CSV records.
+    #[cfg(feature = "csv")]
+    Csv,
+    /// Stored the parsed data as an XML document.
+    #[cfg(feature = "xml")]
+    Xml,
 }
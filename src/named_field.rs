@@ -0,0 +1,118 @@
+use std::sync::Arc;
+
+#[cfg(feature = "csv")]
+use crate::CsvField;
+#[cfg(feature = "chrono")]
+use crate::DateTimeField;
+#[cfg(feature = "xml")]
+use crate::XmlField;
+use crate::{
+    FileField, LargeTextField, MultipartFormData, NumberField, RawField, StoredField, TextField,
+};
+
+/// A parsed field along with its field name.
+#[derive(Debug)]
+pub enum NamedField {
+    File(Arc<str>, FileField),
+    Raw(Arc<str>, RawField),
+    Text(Arc<str>, TextField),
+    Number(Arc<str>, NumberField),
+    Stored(Arc<str>, StoredField),
+    LargeText(Arc<str>, LargeTextField),
+    #[cfg(feature = "chrono")]
+    DateTime(Arc<str>, DateTimeField),
+    #[cfg(feature = "csv")]
+    Csv(Arc<str>, CsvField),
+    #[cfg(feature = "xml")]
+    Xml(Arc<str>, XmlField),
+}
+
+impl NamedField {
+    /// The field name.
+    #[inline]
+    pub fn name(&self) -> &Arc<str> {
+        match self {
+            NamedField::File(name, _)
+            | NamedField::Raw(name, _)
+            | NamedField::Text(name, _)
+            | NamedField::Number(name, _)
+            | NamedField::Stored(name, _)
+            | NamedField::LargeText(name, _) => name,
+            #[cfg(feature = "chrono")]
+            NamedField::DateTime(name, _) => name,
+            #[cfg(feature = "csv")]
+            NamedField::Csv(name, _) => name,
+            #[cfg(feature = "xml")]
+            NamedField::Xml(name, _) => name,
+        }
+    }
+
+    /// The position of the part in the HTTP body.
+    #[inline]
+    pub fn index(&self) -> usize {
+        match self {
+            NamedField::File(_, f) => f.index,
+            NamedField::Raw(_, f) => f.index,
+            NamedField::Text(_, f) => f.index,
+            NamedField::Number(_, f) => f.index,
+            NamedField::Stored(_, f) => f.index,
+            NamedField::LargeText(_, f) => f.index,
+            #[cfg(feature = "chrono")]
+            NamedField::DateTime(_, f) => f.index,
+            #[cfg(feature = "csv")]
+            NamedField::Csv(_, f) => f.index,
+            #[cfg(feature = "xml")]
+            NamedField::Xml(_, f) => f.index,
+        }
+    }
+}
+
+impl MultipartFormData {
+    /// Flatten this `MultipartFormData` instance into a list of fields in the order in which they arrived. Boolean fields are not included because they do not keep their parts. The uploaded files will not be deleted automatically anymore.
+    pub fn into_fields(mut self) -> Vec<NamedField> {
+        let mut fields = Vec::new();
+
+        for (name, files) in std::mem::take(&mut self.files) {
+            fields.extend(files.into_iter().map(|f| NamedField::File(name.clone(), f)));
+        }
+
+        for (name, raw) in std::mem::take(&mut self.raw) {
+            fields.extend(raw.into_iter().map(|f| NamedField::Raw(name.clone(), f)));
+        }
+
+        for (name, texts) in std::mem::take(&mut self.texts) {
+            fields.extend(texts.into_iter().map(|f| NamedField::Text(name.clone(), f)));
+        }
+
+        for (name, numbers) in std::mem::take(&mut self.numbers) {
+            fields.extend(numbers.into_iter().map(|f| NamedField::Number(name.clone(), f)));
+        }
+
+        for (name, stored) in std::mem::take(&mut self.stored) {
+            fields.extend(stored.into_iter().map(|f| NamedField::Stored(name.clone(), f)));
+        }
+
+        for (name, large_texts) in std::mem::take(&mut self.large_texts) {
+            fields.extend(large_texts.into_iter().map(|f| NamedField::LargeText(name.clone(), f)));
+        }
+
+        #[cfg(feature = "chrono")]
+        for (name, datetimes) in std::mem::take(&mut self.datetimes) {
+            fields.extend(datetimes.into_iter().map(|f| NamedField::DateTime(name.clone(), f)));
+        }
+
+        #[cfg(feature = "csv")]
+        for (name, csv) in std::mem::take(&mut self.csv) {
+            fields.extend(csv.into_iter().map(|f| NamedField::Csv(name.clone(), f)));
+        }
+
+        #[cfg(feature = "xml")]
+        for (name, xml) in std::mem::take(&mut self.xml) {
+            fields.extend(xml.into_iter().map(|f| NamedField::Xml(name.clone(), f)));
+        }
+
+        fields.sort_by_key(NamedField::index);
+
+        fields
+    }
+}
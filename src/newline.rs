@@ -0,0 +1,43 @@
+/// How the line breaks in the data of a text field are normalized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Newline {
+    /// Keep the line breaks as they are.
+    #[default]
+    Preserve,
+    /// Convert `\r\n` and `\r` to `\n`.
+    Lf,
+    /// Convert `\n` and `\r` to `\r\n`.
+    CrLf,
+}
+
+impl Newline {
+    /// Normalize the line breaks in `text`.
+    pub(crate) fn normalize(self, text: &mut String) {
+        let newline = match self {
+            Newline::Preserve => return,
+            Newline::Lf => "\n",
+            Newline::CrLf => "\r\n",
+        };
+
+        if !text.contains(['\r', '\n']) {
+            return;
+        }
+
+        let mut output = String::with_capacity(text.len());
+        let mut iter = text.chars().peekable();
+
+        while let Some(c) = iter.next() {
+            match c {
+                '\r' => {
+                    iter.next_if_eq(&'\n');
+
+                    output.push_str(newline);
+                },
+                '\n' => output.push_str(newline),
+                _ => output.push(c),
+            }
+        }
+
+        *text = output;
+    }
+}
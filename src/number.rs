@@ -0,0 +1,148 @@
+use std::{
+    cmp::Ordering,
+    fmt::{self, Display, Formatter},
+};
+
+/// A parsed numeric value.
+#[derive(Debug, Clone, Copy)]
+pub enum Number {
+    /// A signed integer.
+    Integer(i64),
+    /// An unsigned integer.
+    Unsigned(u64),
+    /// A floating-point number.
+    Float(f64),
+}
+
+impl Number {
+    /// Convert this number to a specific numeric type. Return `None` if it cannot be represented by that type.
+    #[inline]
+    pub fn get<T: Numeric>(self) -> Option<T> {
+        T::from_number(self)
+    }
+
+    #[inline]
+    fn as_f64(self) -> f64 {
+        match self {
+            Number::Integer(n) => n as f64,
+            Number::Unsigned(n) => n as f64,
+            Number::Float(n) => n,
+        }
+    }
+}
+
+impl PartialEq for Number {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
+    }
+}
+
+impl PartialOrd for Number {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (*self, *other) {
+            (Number::Integer(a), Number::Integer(b)) => a.partial_cmp(&b),
+            (Number::Unsigned(a), Number::Unsigned(b)) => a.partial_cmp(&b),
+            (Number::Integer(a), Number::Unsigned(b)) => (a as i128).partial_cmp(&(b as i128)),
+            (Number::Unsigned(a), Number::Integer(b)) => (a as i128).partial_cmp(&(b as i128)),
+            (a, b) => a.as_f64().partial_cmp(&b.as_f64()),
+        }
+    }
+}
+
+impl Display for Number {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        match self {
+            Number::Integer(n) => Display::fmt(n, f),
+            Number::Unsigned(n) => Display::fmt(n, f),
+            Number::Float(n) => Display::fmt(n, f),
+        }
+    }
+}
+
+/// Numeric types which can be used for number fields.
+pub trait Numeric: Copy + std::str::FromStr {
+    /// Convert this value to a `Number`.
+    fn into_number(self) -> Number;
+
+    /// Convert a `Number` to this type. Return `None` if it cannot be represented by this type.
+    fn from_number(number: Number) -> Option<Self>;
+}
+
+macro_rules! impl_numeric_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Numeric for $t {
+                #[inline]
+                fn into_number(self) -> Number {
+                    Number::Integer(self as i64)
+                }
+
+                #[inline]
+                fn from_number(number: Number) -> Option<Self> {
+                    match number {
+                        Number::Integer(n) => n.try_into().ok(),
+                        Number::Unsigned(n) => n.try_into().ok(),
+                        Number::Float(_) => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_numeric_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl Numeric for $t {
+                #[inline]
+                fn into_number(self) -> Number {
+                    Number::Unsigned(self as u64)
+                }
+
+                #[inline]
+                fn from_number(number: Number) -> Option<Self> {
+                    match number {
+                        Number::Integer(n) => n.try_into().ok(),
+                        Number::Unsigned(n) => n.try_into().ok(),
+                        Number::Float(_) => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_numeric_integer!(i8, i16, i32, i64, isize);
+impl_numeric_unsigned!(u8, u16, u32, u64, usize);
+
+impl Numeric for f32 {
+    #[inline]
+    fn into_number(self) -> Number {
+        Number::Float(self as f64)
+    }
+
+    #[inline]
+    fn from_number(number: Number) -> Option<Self> {
+        Some(number.as_f64() as f32)
+    }
+}
+
+impl Numeric for f64 {
+    #[inline]
+    fn into_number(self) -> Number {
+        Number::Float(self)
+    }
+
+    #[inline]
+    fn from_number(number: Number) -> Option<Self> {
+        Some(number.as_f64())
+    }
+}
+
+#[inline]
+pub(crate) fn parse_number<T: Numeric>(s: &str) -> Option<Number> {
+    s.trim().parse::<T>().ok().map(Numeric::into_number)
+}
@@ -0,0 +1,9 @@
+/// What to do when the data of a field exceeds its size limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OversizePolicy {
+    /// Fail with a `DataTooLargeError`.
+    #[default]
+    Error,
+    /// Keep the data up to the size limit and discard the rest. A `ParseWarning::DataTruncated` warning is recorded.
+    Truncate,
+}
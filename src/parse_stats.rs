@@ -0,0 +1,28 @@
+use std::time::Duration;
+
+/// Statistics of parsing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ParseStats {
+    /// The number of bytes which have been read from the HTTP body, including the headers of the parts and the parts which are skipped.
+    pub bytes_read: u64,
+    /// The number of parts without file names, including the ones of sub-forms.
+    pub text_parts: usize,
+    /// The number of parts with file names, including the ones of sub-forms.
+    pub file_parts: usize,
+    /// The time spent on parsing.
+    pub elapsed:    Duration,
+}
+
+impl ParseStats {
+    /// The measured reading rate in bytes per second.
+    #[inline]
+    pub fn bytes_per_second(&self) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+
+        if secs > 0.0 {
+            self.bytes_read as f64 / secs
+        } else {
+            0.0
+        }
+    }
+}
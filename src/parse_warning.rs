@@ -0,0 +1,56 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    sync::Arc,
+};
+
+/// Non-fatal anomalies found while parsing. They usually indicate bugs of clients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseWarning {
+    /// A part without a field name is skipped.
+    UnnamedPart,
+    /// A field which is not allowed is skipped.
+    UnknownField(String),
+    /// An empty file, which might be from an empty file input in a HTML form, is ignored.
+    EmptyFileIgnored(Arc<str>),
+    /// The content type of an ignored empty file does not match the field.
+    ContentTypeMismatchOnEmptyFile(Arc<str>),
+    /// A field is used more times than its repetition allows, so the extra one is dropped.
+    ExtraRepetitionDropped(Arc<str>),
+    /// The data of a field exceeds its size limit, so it is truncated.
+    DataTruncated(Arc<str>),
+    /// Active content is removed from the data of a field.
+    #[cfg(feature = "sanitize")]
+    ActiveContentStripped(Arc<str>),
+}
+
+impl Display for ParseWarning {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        match self {
+            ParseWarning::UnnamedPart => f.write_str("A part without a field name is skipped."),
+            ParseWarning::UnknownField(field) => {
+                f.write_fmt(format_args!("The unknown field `{}` is skipped.", field))
+            },
+            ParseWarning::EmptyFileIgnored(field) => {
+                f.write_fmt(format_args!("An empty file of field `{}` is ignored.", field))
+            },
+            ParseWarning::ContentTypeMismatchOnEmptyFile(field) => f.write_fmt(format_args!(
+                "The content type of an ignored empty file of field `{}` is incorrect.",
+                field
+            )),
+            ParseWarning::ExtraRepetitionDropped(field) => f.write_fmt(format_args!(
+                "The field `{}` is used too many times, so the extra one is dropped.",
+                field
+            )),
+            ParseWarning::DataTruncated(field) => {
+                f.write_fmt(format_args!("The data of field `{}` is truncated.", field))
+            },
+            #[cfg(feature = "sanitize")]
+            ParseWarning::ActiveContentStripped(field) => f.write_fmt(format_args!(
+                "Active content is removed from the data of field `{}`.",
+                field
+            )),
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use std::{
+    fmt::{self, Debug, Formatter},
+    io,
+    sync::Arc,
+};
+
+use crate::{
+    chunk_reader::ChunkReader,
+    multer::{bytes::Bytes, Field},
+    BoxFuture, FieldMeta, MultipartFormDataError,
+};
+
+/// A handler which can take over the parts of some fields from the standard handling, such as streaming the files of one field to an object store while the other fields are parsed as usual. It is used by the `MultipartFormData::parse_with` method.
+///
+/// The default implementation does not take over any part, so the current behavior is reproduced.
+pub trait PartHandler: Send + Sync {
+    /// Whether to take over a part which has been accepted by its field. The default implementation returns `false`.
+    fn accepts(&self, meta: &FieldMeta<'_>) -> bool {
+        let _ = meta;
+
+        false
+    }
+
+    /// Read the data of a part which has been taken over, and return an id of what it has been turned into. The part is put into the `stored` map with the id. The default implementation discards the data.
+    fn handle<'a>(
+        &'a self,
+        meta: &'a FieldMeta<'a>,
+        reader: &'a mut PartReader<'_>,
+    ) -> BoxFuture<'a, Result<String, MultipartFormDataError>> {
+        let _ = meta;
+
+        Box::pin(async move {
+            while reader.chunk().await?.is_some() {}
+
+            Ok(String::new())
+        })
+    }
+
+    /// Remove what a part has been turned into by its id. It is called for the parts which have been handled when parsing fails. The default implementation does nothing.
+    fn remove<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), io::Error>> {
+        let _ = id;
+
+        Box::pin(async { Ok(()) })
+    }
+}
+
+impl Debug for dyn PartHandler {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("PartHandler")
+    }
+}
+
+/// The standard handling of all parts.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultPartHandler;
+
+impl PartHandler for DefaultPartHandler {}
+
+pub(crate) trait ChunkSource: Send {
+    fn next_chunk(&mut self) -> BoxFuture<'_, Result<Option<Bytes>, MultipartFormDataError>>;
+}
+
+/// A part which is being read with the per-request accounting.
+pub(crate) struct PartSource<'a, 'r, 'q> {
+    pub(crate) entry:        &'a mut Field<'r>,
+    pub(crate) chunk_reader: &'a mut ChunkReader<'q>,
+}
+
+impl ChunkSource for PartSource<'_, '_, '_> {
+    #[inline]
+    fn next_chunk(&mut self) -> BoxFuture<'_, Result<Option<Bytes>, MultipartFormDataError>> {
+        Box::pin(self.chunk_reader.next_chunk(self.entry))
+    }
+}
+
+/// The reader of the data of a part which has been taken over by a `PartHandler`. The size limit of the field is applied.
+pub struct PartReader<'a> {
+    source:     &'a mut dyn ChunkSource,
+    field_name: Arc<str>,
+    size_limit: u64,
+    size:       u64,
+}
+
+impl<'a> PartReader<'a> {
+    #[inline]
+    pub(crate) fn new(
+        source: &'a mut dyn ChunkSource,
+        field_name: Arc<str>,
+        size_limit: u64,
+    ) -> PartReader<'a> {
+        PartReader {
+            source,
+            field_name,
+            size_limit,
+            size: 0,
+        }
+    }
+
+    /// Read the next chunk. Return `None` if all data of the part has been read.
+    pub async fn chunk(&mut self) -> Result<Option<Bytes>, MultipartFormDataError> {
+        let bytes = self.source.next_chunk().await?;
+
+        if let Some(bytes) = bytes.as_ref() {
+            self.size += bytes.len() as u64;
+
+            if self.size > self.size_limit {
+                return Err(MultipartFormDataError::DataTooLargeError(self.field_name.clone()));
+            }
+        }
+
+        Ok(bytes)
+    }
+
+    /// The number of bytes which have been read.
+    #[inline]
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+}
+
+impl Debug for PartReader<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("PartReader")
+            .field("field_name", &self.field_name)
+            .field("size_limit", &self.size_limit)
+            .field("size", &self.size)
+            .finish()
+    }
+}
@@ -0,0 +1,10 @@
+use std::ops::Range;
+
+/// The byte ranges of a part in the HTTP body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PartOffsets {
+    /// The range of the whole part, from the beginning of its boundary delimiter to the end of its data.
+    pub part: Range<u64>,
+    /// The range of the data of the part, after its headers.
+    pub data: Range<u64>,
+}
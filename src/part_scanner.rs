@@ -0,0 +1,232 @@
+use std::{
+    io,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll},
+};
+
+use rocket::tokio::io::{AsyncRead, ReadBuf};
+
+use crate::PartOffsets;
+
+const CRLF_CRLF: &[u8] = b"\r\n\r\n";
+
+/// Where the scanner is in the multipart body.
+#[derive(Debug, Clone, Copy)]
+enum Stage {
+    /// In the preamble or the data of a part. The number of bytes of the delimiter which have been matched.
+    Data(usize),
+    /// Right after a delimiter. Whether a `-` has been read, which may start a closing delimiter.
+    Delimiter(bool),
+    /// In the headers of a part. The number of bytes of the headers and the number of bytes of `\r\n\r\n` which have been matched.
+    Headers(u64, usize),
+    /// After the closing delimiter.
+    Epilogue,
+}
+
+/// A reader which follows the parts of a multipart body while it is being read. It fails as soon as the headers of a part exceed a size limit, even if they are split across many reads, so that they are not buffered without bounds. It can also record the byte ranges of the parts.
+pub(crate) struct PartScanner<R> {
+    inner:      R,
+    /// `\r\n--<boundary>`
+    delimiter:  Vec<u8>,
+    limit:      Option<u64>,
+    stage:      Stage,
+    exceeded:   Arc<AtomicBool>,
+    /// The offset of the next byte to scan.
+    offset:     u64,
+    /// The offset of the delimiter of the current part and the offset of its data, if its headers have ended.
+    part_start: u64,
+    data_start: Option<u64>,
+    offsets:    Option<Arc<Mutex<Vec<PartOffsets>>>>,
+    /// The number of bytes after the closing delimiter, and whether any of them is not whitespace.
+    epilogue:   (u64, bool),
+    trailing:   Option<Arc<AtomicU64>>,
+}
+
+impl<R> PartScanner<R> {
+    #[inline]
+    pub(crate) fn new(
+        inner: R,
+        boundary: &str,
+        limit: Option<u64>,
+        offsets: Option<Arc<Mutex<Vec<PartOffsets>>>>,
+    ) -> PartScanner<R> {
+        let mut delimiter = b"\r\n--".to_vec();
+        delimiter.extend_from_slice(boundary.as_bytes());
+
+        PartScanner {
+            inner,
+            delimiter,
+            limit,
+            // The first delimiter does not need to be preceded by a line break
+            stage: Stage::Data(2),
+            exceeded: Arc::new(AtomicBool::new(false)),
+            offset: 0,
+            part_start: 0,
+            data_start: None,
+            offsets,
+            epilogue: (0, false),
+            trailing: None,
+        }
+    }
+
+    /// Track the data after the closing delimiter. The returned counter is set to the number of bytes after the closing delimiter, if any of them is not whitespace.
+    #[inline]
+    pub(crate) fn trailing(&mut self) -> Arc<AtomicU64> {
+        self.trailing.get_or_insert_with(Arc::default).clone()
+    }
+
+    /// A flag which is set when the headers of a part exceed the size limit.
+    #[inline]
+    pub(crate) fn exceeded(&self) -> Arc<AtomicBool> {
+        self.exceeded.clone()
+    }
+
+    #[inline]
+    fn advance(&mut self, data: &mut &[u8], n: usize) {
+        *data = &data[n..];
+        self.offset += n as u64;
+    }
+
+    /// Scan the data which has been read. Return `false` if the headers of a part exceed the size limit.
+    fn scan(&mut self, mut data: &[u8]) -> bool {
+        let limit = self.limit.unwrap_or(u64::MAX);
+
+        while !data.is_empty() {
+            match self.stage {
+                Stage::Data(0) => {
+                    // Skip to the next byte which can start a delimiter
+                    match data.iter().position(|&b| b == b'\r') {
+                        Some(i) => {
+                            self.stage = Stage::Data(1);
+                            self.advance(&mut data, i + 1);
+                        },
+                        None => {
+                            self.offset += data.len() as u64;
+
+                            return true;
+                        },
+                    }
+                },
+                Stage::Data(matched) => {
+                    let b = data[0];
+                    self.advance(&mut data, 1);
+
+                    self.stage = if b == self.delimiter[matched] {
+                        if matched + 1 == self.delimiter.len() {
+                            self.end_part();
+
+                            Stage::Delimiter(false)
+                        } else {
+                            Stage::Data(matched + 1)
+                        }
+                    } else if b == b'\r' {
+                        Stage::Data(1)
+                    } else {
+                        Stage::Data(0)
+                    };
+                },
+                Stage::Delimiter(dash) => {
+                    if data[0] == b'-' {
+                        self.advance(&mut data, 1);
+
+                        self.stage = if dash { Stage::Epilogue } else { Stage::Delimiter(true) };
+                    } else {
+                        self.stage = Stage::Headers(dash as u64, 0);
+                    }
+                },
+                Stage::Headers(size, matched) => {
+                    let b = data[0];
+                    self.advance(&mut data, 1);
+
+                    let size = size + 1;
+
+                    if size > limit {
+                        return false;
+                    }
+
+                    let matched = if b == CRLF_CRLF[matched] {
+                        matched + 1
+                    } else if b == b'\r' {
+                        1
+                    } else {
+                        0
+                    };
+
+                    self.stage = if matched == CRLF_CRLF.len() {
+                        self.data_start = Some(self.offset);
+
+                        Stage::Data(0)
+                    } else {
+                        Stage::Headers(size, matched)
+                    };
+                },
+                Stage::Epilogue => {
+                    self.offset += data.len() as u64;
+
+                    if let Some(trailing) = self.trailing.as_ref() {
+                        self.epilogue.0 += data.len() as u64;
+                        self.epilogue.1 |= data.iter().any(|b| !b.is_ascii_whitespace());
+
+                        if self.epilogue.1 {
+                            trailing.store(self.epilogue.0, Ordering::Release);
+                        }
+                    }
+
+                    return true;
+                },
+            }
+        }
+
+        true
+    }
+
+    /// Record the part which ends with the delimiter which has just been scanned, and start the next one.
+    fn end_part(&mut self) {
+        // The delimiter begins with `--`, after the line break which belongs to it
+        let delimiter_start = (self.offset + 2).saturating_sub(self.delimiter.len() as u64);
+
+        if let (Some(offsets), Some(data_start)) = (self.offsets.as_ref(), self.data_start.take()) {
+            let data_end = delimiter_start.saturating_sub(2).max(data_start);
+
+            offsets
+                .lock()
+                .unwrap()
+                .push(PartOffsets {
+                    part: self.part_start..data_end, data: data_start..data_end
+                });
+        }
+
+        self.part_start = delimiter_start;
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for PartScanner<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        if self.limit.is_none() && self.offsets.is_none() && self.trailing.is_none() {
+            return Pin::new(&mut self.inner).poll_read(cx, buf);
+        }
+
+        let filled = buf.filled().len();
+
+        let result = Pin::new(&mut self.inner).poll_read(cx, buf);
+
+        if !self.scan(&buf.filled()[filled..]) {
+            self.exceeded.store(true, Ordering::Release);
+
+            return Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the headers of a part are too large",
+            )));
+        }
+
+        result
+    }
+}
@@ -0,0 +1,188 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug, Formatter},
+    io,
+    sync::{Arc, Mutex},
+};
+
+use rocket::{http::ContentType, Data};
+
+use crate::{
+    BoxFuture, FieldMeta, MultipartFormData, MultipartFormDataError, MultipartFormDataOptions,
+    PartHandler, PartReader,
+};
+
+/// An asynchronous consumer of the parts of a field, which reads the data while the part is streaming, such as uploading it to an object store or validating it.
+pub trait PartSink: Send + Sync {
+    /// Read the data of a part, and return an id of what it has been turned into. The part is put into the `stored` map with the id.
+    fn consume<'a>(
+        &'a self,
+        meta: &'a FieldMeta<'a>,
+        reader: &'a mut PartReader<'_>,
+    ) -> BoxFuture<'a, Result<String, MultipartFormDataError>>;
+
+    /// Remove what a part has been turned into by its id. It is called for the parts which have been consumed when parsing fails. The default implementation does nothing.
+    fn remove<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), io::Error>> {
+        let _ = id;
+
+        Box::pin(async { Ok(()) })
+    }
+}
+
+impl Debug for dyn PartSink {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("PartSink")
+    }
+}
+
+impl<F> PartSink for F
+where
+    F: for<'a, 'b> Fn(
+            &'a FieldMeta<'a>,
+            &'a mut PartReader<'b>,
+        ) -> BoxFuture<'a, Result<String, MultipartFormDataError>>
+        + Send
+        + Sync,
+{
+    #[inline]
+    fn consume<'a>(
+        &'a self,
+        meta: &'a FieldMeta<'a>,
+        reader: &'a mut PartReader<'_>,
+    ) -> BoxFuture<'a, Result<String, MultipartFormDataError>> {
+        self(meta, reader)
+    }
+}
+
+/// A set of `PartSink`s bound to the declared fields for a request. The parts of the bound fields are consumed by their sinks as they stream, without temporary files, and the other fields are parsed as usual.
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use rocket::{http::ContentType, Data};
+/// use rocket_multipart_form_data::{
+///     MultipartFormData, MultipartFormDataField, MultipartFormDataOptions,
+///     PartSinks,
+/// };
+///
+/// #[post("/", data = "<data>")]
+/// async fn index(content_type: &ContentType, data: Data<'_>) -> &'static str {
+///     let options =
+///         MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+///             MultipartFormDataField::file("photo"),
+///             MultipartFormDataField::text("title"),
+///         ]);
+///
+///     let sinks = PartSinks::new().sink("photo", |_meta, reader| {
+///         Box::pin(async move {
+///             while let Some(_chunk) = reader.chunk().await? {
+///                 // Upload the chunk.
+///             }
+///
+///             Ok(String::from("photo-id"))
+///         })
+///     });
+///
+///     let multipart_form_data = MultipartFormData::parse_with_sinks(
+///         content_type,
+///         data,
+///         options,
+///         sinks,
+///     )
+///     .await
+///     .unwrap();
+///
+///     let _photo_id = &multipart_form_data.stored.get("photo").unwrap()[0].id;
+///
+///     "ok"
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct PartSinks {
+    sinks:    HashMap<String, Arc<dyn PartSink>>,
+    consumed: Mutex<Vec<(Arc<dyn PartSink>, String)>>,
+}
+
+impl PartSinks {
+    /// Create an empty `PartSinks` instance.
+    #[inline]
+    pub fn new() -> PartSinks {
+        PartSinks::default()
+    }
+
+    /// Bind a closure to a field as its sink. The sink bound to the same field before is replaced.
+    #[inline]
+    pub fn sink<S: Into<String>, F>(self, field_name: S, sink: F) -> PartSinks
+    where
+        F: for<'a, 'b> Fn(
+                &'a FieldMeta<'a>,
+                &'a mut PartReader<'b>,
+            ) -> BoxFuture<'a, Result<String, MultipartFormDataError>>
+            + Send
+            + Sync
+            + 'static, {
+        self.bind(field_name, sink)
+    }
+
+    /// Bind a `PartSink` to a field. The sink bound to the same field before is replaced.
+    #[inline]
+    pub fn bind<S: Into<String>, K: PartSink + 'static>(
+        mut self,
+        field_name: S,
+        sink: K,
+    ) -> PartSinks {
+        self.sinks.insert(field_name.into(), Arc::new(sink));
+        self
+    }
+}
+
+impl PartHandler for PartSinks {
+    #[inline]
+    fn accepts(&self, meta: &FieldMeta<'_>) -> bool {
+        self.sinks.contains_key(meta.name)
+    }
+
+    fn handle<'a>(
+        &'a self,
+        meta: &'a FieldMeta<'a>,
+        reader: &'a mut PartReader<'_>,
+    ) -> BoxFuture<'a, Result<String, MultipartFormDataError>> {
+        Box::pin(async move {
+            let sink = self.sinks[meta.name].clone();
+
+            let id = sink.consume(meta, reader).await?;
+
+            self.consumed.lock().unwrap().push((sink, id.clone()));
+
+            Ok(id)
+        })
+    }
+
+    fn remove<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), io::Error>> {
+        Box::pin(async move {
+            let sink = {
+                let mut consumed = self.consumed.lock().unwrap();
+
+                match consumed.iter().position(|(_, i)| i == id) {
+                    Some(index) => consumed.remove(index).0,
+                    None => return Ok(()),
+                }
+            };
+
+            sink.remove(id).await
+        })
+    }
+}
+
+impl MultipartFormData {
+    /// Parse multipart/form-data from the HTTP body, and let the `sinks` consume the parts of the fields they are bound to. It resolves after every part has been consumed. The consumed parts are put into the `stored` map.
+    #[inline]
+    pub async fn parse_with_sinks(
+        content_type: &ContentType,
+        data: Data<'_>,
+        options: MultipartFormDataOptions<'_>,
+        sinks: PartSinks,
+    ) -> Result<MultipartFormData, MultipartFormDataError> {
+        MultipartFormData::parse_with(content_type, data, options, &sinks).await
+    }
+}
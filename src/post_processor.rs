@@ -0,0 +1,26 @@
+use std::{
+    error::Error,
+    fmt::{self, Debug, Formatter},
+    path::Path,
+};
+
+use crate::Derivative;
+
+/// A processor which is invoked after a file field has been completely received, in order to generate derived artifacts such as thumbnails or transcodes.
+///
+/// It is run on a blocking thread.
+pub trait FieldPostProcessor: Send + Sync {
+    /// Process the uploaded file at `source`. The derived artifacts should be created in `temporary_dir`. They will be deleted along with the uploaded file.
+    fn process(
+        &self,
+        source: &Path,
+        temporary_dir: &Path,
+    ) -> Result<Vec<Derivative>, Box<dyn Error + Send + Sync>>;
+}
+
+impl Debug for dyn FieldPostProcessor {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("FieldPostProcessor")
+    }
+}
@@ -0,0 +1,135 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use crate::{
+    mime::{self, Mime},
+    ContentTypeGroup, MultipartFormDataOptions, MultipartFormDataType,
+};
+
+const MIB: u64 = 1024 * 1024;
+
+/// A reusable set of restrictions on the file fields of a `MultipartFormDataOptions` instance. It is applied when parsing starts, so it also covers the fields which are added after the preset is set.
+///
+/// The size limit of every file field is reduced to the one of the preset. The content type filters of the preset are used by the file fields which do not have their own filters.
+///
+/// ```
+/// use rocket_multipart_form_data::{
+///     MultipartFormDataField, MultipartFormDataOptions, Preset,
+/// };
+///
+/// let mut options = MultipartFormDataOptions::preset(Preset::ImagesOnly {
+///     max_mb: 5,
+/// });
+///
+/// options.allowed_fields.push(MultipartFormDataField::file("avatar"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Preset {
+    /// Images (`image/*`) of at most `max_mb` MiB.
+    ImagesOnly { max_mb: u64 },
+    /// PDF, plain text, CSV, RTF, Microsoft Office and OpenDocument files of at most `max_mb` MiB.
+    Documents { max_mb: u64 },
+    /// Files of any type of at most `max_mb` MiB.
+    AnyFile { max_mb: u64 },
+    /// No files at all. Every part with a file name fails with a `TooManyFilePartsError`.
+    TextOnly,
+    /// Files of the given content types of at most `max_bytes` bytes. If `content_types` is empty, any type is accepted.
+    Custom { content_types: Vec<Mime>, max_bytes: u64 },
+}
+
+impl Preset {
+    /// Apply this preset to the file fields of `options`. Presets can be combined by applying them one after another, and the smallest size limit wins.
+    pub fn apply(&self, options: &mut MultipartFormDataOptions<'_>) {
+        let (content_types, max_bytes) = match self {
+            Preset::ImagesOnly {
+                max_mb,
+            } => (vec![mime::IMAGE_STAR], max_mb.saturating_mul(MIB)),
+            Preset::Documents {
+                max_mb,
+            } => (ContentTypeGroup::Documents.content_types(), max_mb.saturating_mul(MIB)),
+            Preset::AnyFile {
+                max_mb,
+            } => (Vec::new(), max_mb.saturating_mul(MIB)),
+            Preset::TextOnly => {
+                options.max_file_parts = 0;
+
+                return;
+            },
+            Preset::Custom {
+                content_types,
+                max_bytes,
+            } => (content_types.clone(), *max_bytes),
+        };
+
+        for field in
+            options.allowed_fields.iter_mut().filter(|f| f.typ == MultipartFormDataType::File)
+        {
+            field.size_limit = field.size_limit.min(max_bytes);
+
+            if field.content_type.is_none() && !content_types.is_empty() {
+                field.content_type = Some(content_types.clone());
+            }
+        }
+    }
+}
+
+impl MultipartFormDataOptions<'_> {
+    /// Create a `MultipartFormDataOptions` instance with a preset.
+    #[inline]
+    pub fn preset(preset: Preset) -> MultipartFormDataOptions<'static> {
+        MultipartFormDataOptions {
+            presets: vec![preset],
+            ..MultipartFormDataOptions::new()
+        }
+    }
+}
+
+/// Named presets registered by the application, which should be managed by Rocket, so that routes can share the same configuration.
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use rocket_multipart_form_data::{Preset, PresetRegistry};
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     let presets = PresetRegistry::new();
+///
+///     presets.register("avatar", Preset::ImagesOnly {
+///         max_mb: 2
+///     });
+///
+///     rocket::build().manage(presets)
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PresetRegistry {
+    presets: Arc<Mutex<HashMap<String, Preset>>>,
+}
+
+impl PresetRegistry {
+    /// Create a `PresetRegistry` instance.
+    #[inline]
+    pub fn new() -> PresetRegistry {
+        PresetRegistry::default()
+    }
+
+    /// Register a preset with a name. The preset which has been registered with the same name is replaced.
+    #[inline]
+    pub fn register<S: Into<String>>(&self, name: S, preset: Preset) {
+        self.presets.lock().unwrap().insert(name.into(), preset);
+    }
+
+    /// Get a registered preset.
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<Preset> {
+        self.presets.lock().unwrap().get(name).cloned()
+    }
+
+    /// Create a `MultipartFormDataOptions` instance with a registered preset.
+    #[inline]
+    pub fn options(&self, name: &str) -> Option<MultipartFormDataOptions<'static>> {
+        self.get(name).map(MultipartFormDataOptions::preset)
+    }
+}
@@ -0,0 +1,174 @@
+use std::{
+    fmt::{self, Debug, Formatter},
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use rocket::{
+    futures::Stream,
+    http::ContentType,
+    tokio::sync::mpsc::{self, UnboundedReceiver},
+    Data,
+};
+
+#[cfg(feature = "csv")]
+use crate::CsvField;
+#[cfg(feature = "chrono")]
+use crate::DateTimeField;
+#[cfg(feature = "xml")]
+use crate::XmlField;
+use crate::{
+    BoxFuture, FileField, LargeTextField, MultipartFormData, MultipartFormDataError,
+    MultipartFormDataOptions, NumberField, RawField, StoredField, TextField,
+};
+
+/// A field which has been completely received, with its field name. It is a copy of the field which is put into the `MultipartFormData` instance at the end, so the uploaded files are still deleted along with that instance.
+#[derive(Debug, Clone)]
+pub enum ParsedField {
+    File(Arc<str>, FileField),
+    Raw(Arc<str>, RawField),
+    Text(Arc<str>, TextField),
+    /// A checkbox field, with whether it is checked.
+    Checkbox(Arc<str>, bool),
+    Number(Arc<str>, NumberField),
+    #[cfg(feature = "chrono")]
+    DateTime(Arc<str>, DateTimeField),
+    Stored(Arc<str>, StoredField),
+    LargeText(Arc<str>, LargeTextField),
+    #[cfg(feature = "csv")]
+    Csv(Arc<str>, CsvField),
+    #[cfg(feature = "xml")]
+    Xml(Arc<str>, XmlField),
+}
+
+impl ParsedField {
+    /// The field name.
+    #[inline]
+    pub fn name(&self) -> &Arc<str> {
+        match self {
+            ParsedField::File(name, _)
+            | ParsedField::Raw(name, _)
+            | ParsedField::Text(name, _)
+            | ParsedField::Checkbox(name, _)
+            | ParsedField::Number(name, _)
+            | ParsedField::Stored(name, _)
+            | ParsedField::LargeText(name, _) => name,
+            #[cfg(feature = "chrono")]
+            ParsedField::DateTime(name, _) => name,
+            #[cfg(feature = "csv")]
+            ParsedField::Csv(name, _) => name,
+            #[cfg(feature = "xml")]
+            ParsedField::Xml(name, _) => name,
+        }
+    }
+}
+
+/// A parsing in progress, which is a stream of the fields which have been completely received while the later parts are still arriving. It is created by the `parse_progressive` method.
+///
+/// The parsing only advances while the stream or the `finish` method is polled. The parts of the sub-forms nested in raw fields are not reported.
+pub struct ProgressiveParse<'a> {
+    future:   Option<BoxFuture<'a, Result<MultipartFormData, MultipartFormDataError>>>,
+    result:   Option<Result<MultipartFormData, MultipartFormDataError>>,
+    receiver: UnboundedReceiver<ParsedField>,
+}
+
+impl<'a> Debug for ProgressiveParse<'a> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("ProgressiveParse").field("finished", &self.future.is_none()).finish()
+    }
+}
+
+impl<'a> ProgressiveParse<'a> {
+    /// Wait for the parsing to complete and get the result, which contains all fields, including the ones which have been reported by the stream.
+    pub async fn finish(mut self) -> Result<MultipartFormData, MultipartFormDataError> {
+        match self.future.take() {
+            Some(future) => future.await,
+            None => self.result.take().unwrap(),
+        }
+    }
+
+    /// Give up the parsing, such as when a field which has been reported is invalid. The rest of the HTTP body is not read, and the uploaded files are deleted.
+    pub async fn abort(mut self) {
+        // The parser stops reading as soon as it finds that nobody receives the fields
+        self.receiver.close();
+
+        if let Some(future) = self.future.take() {
+            let _ = future.await;
+        }
+    }
+}
+
+impl<'a> Stream for ProgressiveParse<'a> {
+    type Item = ParsedField;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(future) = self.future.as_mut() {
+            if let Poll::Ready(result) = Future::poll(future.as_mut(), cx) {
+                self.future = None;
+                self.result = Some(result);
+            }
+        }
+
+        // The sender is dropped along with the future, so the stream ends after the last field of a finished parsing
+        self.receiver.poll_recv(cx)
+    }
+}
+
+impl MultipartFormData {
+    /// Parse multipart/form-data from the HTTP body progressively. Every field is reported by the returned stream as soon as it has been completely received, so that the request can be validated and rejected by the `abort` method before the rest of the HTTP body is received. Use the `finish` method to get the result.
+    ///
+    /// ```rust,no_run
+    /// # #[macro_use] extern crate rocket;
+    /// use rocket::{futures::StreamExt, http::ContentType, Data};
+    /// use rocket_multipart_form_data::{
+    ///     MultipartFormData, MultipartFormDataField, MultipartFormDataOptions,
+    ///     ParsedField,
+    /// };
+    ///
+    /// #[post("/", data = "<data>")]
+    /// async fn index(content_type: &ContentType, data: Data<'_>) -> &'static str {
+    ///     let options =
+    ///         MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+    ///             MultipartFormDataField::text("metadata"),
+    ///             MultipartFormDataField::file("video").size_limit(u64::MAX),
+    ///         ]);
+    ///
+    ///     let mut parse =
+    ///         MultipartFormData::parse_progressive(content_type, data, options);
+    ///
+    ///     while let Some(field) = parse.next().await {
+    ///         if let ParsedField::Text(_, text) = field {
+    ///             if text.text.is_empty() {
+    ///                 parse.abort().await;
+    ///
+    ///                 return "rejected";
+    ///             }
+    ///         }
+    ///     }
+    ///
+    ///     let _multipart_form_data = parse.finish().await.unwrap();
+    ///
+    ///     "ok"
+    /// }
+    /// ```
+    pub fn parse_progressive<'a, 'r: 'a>(
+        content_type: &'a ContentType,
+        data: Data<'r>,
+        options: MultipartFormDataOptions<'a>,
+    ) -> ProgressiveParse<'a> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        let future = Box::pin(async move {
+            MultipartFormData::parse_progressive_with(content_type, data, options, sender).await
+        });
+
+        ProgressiveParse {
+            future: Some(future),
+            result: None,
+            receiver,
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use prost::{DecodeError, Message};
+
+use crate::RawField;
+
+impl RawField {
+    /// Decode the data as a protobuf message generated by `prost`.
+    #[inline]
+    pub fn decode_protobuf<M: Message + Default>(&self) -> Result<M, DecodeError> {
+        M::decode(self.raw.as_slice())
+    }
+}
+
+/// A function which checks whether the data can be decoded as a specific message type.
+pub(crate) type ProtobufCheck = fn(&[u8]) -> Result<(), DecodeError>;
+
+/// Check whether the data can be decoded as a message of type `M`.
+pub(crate) fn check_protobuf<M: Message + Default>(bytes: &[u8]) -> Result<(), DecodeError> {
+    M::decode(bytes).map(|_| ())
+}
@@ -0,0 +1,62 @@
+use std::{
+    fmt::{self, Debug, Formatter},
+    sync::Arc,
+};
+
+use crate::BoxFuture;
+
+const DEFAULT_RESERVATION_SIZE: u64 = 1024 * 1024;
+
+/// Bytes reserved by a `QuotaProvider`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Reservation {
+    /// The number of bytes granted. It can be bigger than the requested number of bytes.
+    pub bytes: u64,
+}
+
+/// A provider of upload quotas, which is invoked incrementally while the HTTP body is being read.
+pub trait QuotaProvider: Send + Sync {
+    /// Reserve at least `bytes` bytes for `identity`. Return `None` if the quota of `identity` is exhausted. A grant which is smaller than the bytes which have been read without a reservation is also treated as an exhausted quota.
+    fn check_and_reserve<'a>(
+        &'a self,
+        identity: &'a str,
+        bytes: u64,
+    ) -> BoxFuture<'a, Option<Reservation>>;
+
+    /// Give back the reserved bytes which have not been used when parsing ends. The default implementation does nothing.
+    fn release<'a>(&'a self, identity: &'a str, bytes: u64) -> BoxFuture<'a, ()> {
+        let _ = (identity, bytes);
+
+        Box::pin(async {})
+    }
+}
+
+impl Debug for dyn QuotaProvider {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("QuotaProvider")
+    }
+}
+
+/// A quota which is enforced while parsing.
+#[derive(Debug, Clone)]
+pub struct Quota {
+    /// The provider of quotas.
+    pub provider:         Arc<dyn QuotaProvider>,
+    /// The identity of the caller, such as an API key.
+    pub identity:         String,
+    /// The number of bytes to reserve at a time. The default value is 1 MiB.
+    pub reservation_size: u64,
+}
+
+impl Quota {
+    /// Create a `Quota` instance.
+    #[inline]
+    pub fn new<S: Into<String>>(provider: Arc<dyn QuotaProvider>, identity: S) -> Quota {
+        Quota {
+            provider,
+            identity: identity.into(),
+            reservation_size: DEFAULT_RESERVATION_SIZE,
+        }
+    }
+}
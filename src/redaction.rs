@@ -0,0 +1,346 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Debug, Formatter},
+    sync::Arc,
+};
+
+#[cfg(feature = "csv")]
+use crate::CsvField;
+#[cfg(feature = "chrono")]
+use crate::DateTimeField;
+#[cfg(feature = "xml")]
+use crate::XmlField;
+use crate::{
+    FileField, LargeTextField, MultipartFormData, MultipartFormDataError, NumberField,
+    OwnedMultipartFormData, RawField, StoredField, TextField,
+};
+
+/// The placeholder of the values of the fields set by the `redact_fields` option.
+pub(crate) const REDACTED: &str = "[redacted]";
+
+/// The max number of characters of a text which are shown by `Debug`.
+const MAX_DEBUG_TEXT_CHARS: usize = 64;
+
+/// A text shown by `Debug`, which is truncated if it is long.
+pub(crate) struct DebugText<'a>(pub(crate) &'a str);
+
+impl<'a> Debug for DebugText<'a> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        match self.0.char_indices().nth(MAX_DEBUG_TEXT_CHARS) {
+            Some((i, _)) => {
+                Debug::fmt(&self.0[..i], f)?;
+
+                f.write_fmt(format_args!("... ({} bytes)", self.0.len()))
+            },
+            None => Debug::fmt(self.0, f),
+        }
+    }
+}
+
+/// Binary data shown by `Debug`, which only shows its size.
+pub(crate) struct DebugBytes<'a>(pub(crate) &'a [u8]);
+
+impl<'a> Debug for DebugBytes<'a> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_fmt(format_args!("<{} bytes>", self.0.len()))
+    }
+}
+
+struct Redacted;
+
+impl Debug for Redacted {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str(REDACTED)
+    }
+}
+
+/// A parsed value whose contents can be left out of the `Debug` output.
+trait RedactedDebug: Debug {
+    /// Format this value without its contents. The value is formatted by `Debug` by default, for the ones which do not have contents in memory, such as uploaded files.
+    #[inline]
+    fn fmt_redacted(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        Debug::fmt(self, f)
+    }
+}
+
+impl RedactedDebug for FileField {}
+
+impl RedactedDebug for StoredField {}
+
+impl RedactedDebug for LargeTextField {}
+
+impl RedactedDebug for bool {
+    #[inline]
+    fn fmt_redacted(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        Debug::fmt(&Redacted, f)
+    }
+}
+
+impl<T: RedactedDebug> RedactedDebug for Vec<T> {
+    #[inline]
+    fn fmt_redacted(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_list().entries(self.iter().map(|value| Entry(value, true))).finish()
+    }
+}
+
+impl RedactedDebug for RawField {
+    fn fmt_redacted(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("RawField")
+            .field("content_type", &self.content_type)
+            .field("file_name", &self.file_name)
+            .field("disposition_params", &self.disposition_params)
+            .field("original_name", &self.original_name)
+            .field("index", &self.index)
+            .field("raw", &Redacted)
+            .finish()
+    }
+}
+
+impl RedactedDebug for TextField {
+    fn fmt_redacted(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("TextField")
+            .field("content_type", &self.content_type)
+            .field("file_name", &self.file_name)
+            .field("disposition_params", &self.disposition_params)
+            .field("original_name", &self.original_name)
+            .field("index", &self.index)
+            .field("text", &Redacted)
+            .field("raw_bytes", &self.raw_bytes.as_ref().map(|_| Redacted))
+            .finish()
+    }
+}
+
+impl RedactedDebug for NumberField {
+    fn fmt_redacted(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("NumberField")
+            .field("content_type", &self.content_type)
+            .field("file_name", &self.file_name)
+            .field("disposition_params", &self.disposition_params)
+            .field("original_name", &self.original_name)
+            .field("index", &self.index)
+            .field("number", &Redacted)
+            .finish()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl RedactedDebug for DateTimeField {
+    fn fmt_redacted(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("DateTimeField")
+            .field("content_type", &self.content_type)
+            .field("file_name", &self.file_name)
+            .field("disposition_params", &self.disposition_params)
+            .field("original_name", &self.original_name)
+            .field("index", &self.index)
+            .field("datetime", &Redacted)
+            .finish()
+    }
+}
+
+#[cfg(feature = "csv")]
+impl RedactedDebug for CsvField {
+    fn fmt_redacted(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("CsvField")
+            .field("content_type", &self.content_type)
+            .field("file_name", &self.file_name)
+            .field("disposition_params", &self.disposition_params)
+            .field("original_name", &self.original_name)
+            .field("index", &self.index)
+            .field("headers", &self.headers)
+            .field("records", &Redacted)
+            .finish()
+    }
+}
+
+#[cfg(feature = "xml")]
+impl RedactedDebug for XmlField {
+    fn fmt_redacted(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("XmlField")
+            .field("content_type", &self.content_type)
+            .field("file_name", &self.file_name)
+            .field("disposition_params", &self.disposition_params)
+            .field("original_name", &self.original_name)
+            .field("index", &self.index)
+            .field("root", &Redacted)
+            .finish()
+    }
+}
+
+/// A value in the `Debug` output, which is formatted without its contents if it is redacted.
+struct Entry<'a, T>(&'a T, bool);
+
+impl<'a, T: RedactedDebug> Debug for Entry<'a, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        if self.1 {
+            self.0.fmt_redacted(f)
+        } else {
+            Debug::fmt(self.0, f)
+        }
+    }
+}
+
+/// The fields of a map in the `Debug` output. The contents of the fields whose names are in `redacted` are left out.
+struct Fields<'a, T> {
+    fields:   &'a HashMap<Arc<str>, T>,
+    redacted: &'a [Arc<str>],
+}
+
+impl<'a, T: RedactedDebug> Debug for Fields<'a, T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_map()
+            .entries(
+                self.fields.iter().map(|(name, value)| {
+                    (name, Entry(value, self.redacted.iter().any(|n| n == name)))
+                }),
+            )
+            .finish()
+    }
+}
+
+macro_rules! impl_debug_form {
+    ($ty:ident) => {
+        impl RedactedDebug for $ty {
+            #[inline]
+            fn fmt_redacted(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+                Debug::fmt(&Redacted, f)
+            }
+        }
+
+        /// The texts are truncated and the raw data is shown by its size. The contents of the fields set by the `redact_fields` option are left out.
+        impl Debug for $ty {
+            fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+                let redacted = self.redacted.as_slice();
+
+                let mut s = f.debug_struct(stringify!($ty));
+
+                s.field("files", &Fields {
+                    fields: &self.files,
+                    redacted,
+                })
+                .field("raw", &Fields {
+                    fields: &self.raw,
+                    redacted,
+                })
+                .field("texts", &Fields {
+                    fields: &self.texts,
+                    redacted,
+                })
+                .field("bools", &Fields {
+                    fields: &self.bools,
+                    redacted,
+                })
+                .field("numbers", &Fields {
+                    fields: &self.numbers,
+                    redacted,
+                })
+                .field("stored", &Fields {
+                    fields: &self.stored,
+                    redacted,
+                })
+                .field("large_texts", &Fields {
+                    fields: &self.large_texts,
+                    redacted,
+                });
+
+                #[cfg(feature = "chrono")]
+                s.field("datetimes", &Fields {
+                    fields: &self.datetimes,
+                    redacted,
+                });
+
+                #[cfg(feature = "csv")]
+                s.field("csv", &Fields {
+                    fields: &self.csv,
+                    redacted,
+                });
+
+                #[cfg(feature = "xml")]
+                s.field("xml", &Fields {
+                    fields: &self.xml,
+                    redacted,
+                });
+
+                s.field("warnings", &self.warnings).field("nested", &Fields {
+                    fields: &self.nested,
+                    redacted,
+                });
+
+                #[cfg(feature = "sha2")]
+                s.field("body_digest", &self.body_digest);
+
+                s.field("stats", &self.stats).field("part_offsets", &self.part_offsets).finish()
+            }
+        }
+    };
+}
+
+impl_debug_form!(MultipartFormData);
+impl_debug_form!(OwnedMultipartFormData);
+
+impl Debug for RawField {
+    /// The data is shown by its size.
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("RawField")
+            .field("content_type", &self.content_type)
+            .field("file_name", &self.file_name)
+            .field("disposition_params", &self.disposition_params)
+            .field("original_name", &self.original_name)
+            .field("index", &self.index)
+            .field("raw", &DebugBytes(&self.raw))
+            .finish()
+    }
+}
+
+impl Debug for TextField {
+    /// The text is truncated if it is long, and the raw bytes are shown by their size.
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.debug_struct("TextField")
+            .field("content_type", &self.content_type)
+            .field("file_name", &self.file_name)
+            .field("disposition_params", &self.disposition_params)
+            .field("original_name", &self.original_name)
+            .field("index", &self.index)
+            .field("text", &DebugText(&self.text))
+            .field("raw_bytes", &self.raw_bytes.as_deref().map(DebugBytes))
+            .finish()
+    }
+}
+
+impl MultipartFormDataError {
+    /// Replace the values which this error has got with a placeholder, if they are of the fields in `redact_fields`. A number out of range is reported as an `InvalidValueError`, because the number cannot be replaced.
+    pub(crate) fn redact(self, redact_fields: &[&str]) -> MultipartFormDataError {
+        let redacted = |field: &Arc<str>| redact_fields.iter().any(|n| *n == field.as_ref());
+
+        match self {
+            MultipartFormDataError::InvalidValueError {
+                field, ..
+            } if redacted(&field) => MultipartFormDataError::InvalidValueError {
+                field,
+                got: REDACTED.to_string(),
+            },
+            MultipartFormDataError::InvalidNumberError {
+                field, ..
+            } if redacted(&field) => MultipartFormDataError::InvalidNumberError {
+                field,
+                got: REDACTED.to_string(),
+            },
+            MultipartFormDataError::NumberOutOfRangeError {
+                field, ..
+            } if redacted(&field) => MultipartFormDataError::InvalidValueError {
+                field,
+                got: REDACTED.to_string(),
+            },
+            #[cfg(feature = "chrono")]
+            MultipartFormDataError::InvalidDateTimeError {
+                field, ..
+            } if redacted(&field) => MultipartFormDataError::InvalidDateTimeError {
+                field,
+                got: REDACTED.to_string(),
+            },
+            err => err,
+        }
+    }
+}
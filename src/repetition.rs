@@ -1,12 +1,18 @@
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+
 #[derive(Debug, Clone, Copy)]
 pub(crate) enum RepetitionCounter {
     Fixed(u32),
     Infinite,
+    Budget { count: u32, bytes: u64 },
 }
 
 impl RepetitionCounter {
     #[inline]
-    pub fn decrease_check_is_over(&mut self) -> bool {
+    pub fn decrease_check_is_over(&mut self, size: u64) -> bool {
         match self {
             RepetitionCounter::Fixed(n) => {
                 debug_assert!(*n > 0);
@@ -15,6 +21,16 @@ impl RepetitionCounter {
                 *n == 0
             },
             RepetitionCounter::Infinite => false,
+            RepetitionCounter::Budget {
+                count,
+                bytes,
+            } => {
+                debug_assert!(*count > 0);
+
+                *count -= 1;
+                *bytes = bytes.saturating_sub(size);
+                *count == 0
+            },
         }
     }
 }
@@ -30,19 +46,39 @@ impl Default for RepetitionCounter {
 /// It can be used to define a `MultipartFormDataField` instance which can be used how many times.
 pub struct Repetition {
     counter: RepetitionCounter,
+    min:     u32,
+}
+
+/// Errors of invalid `Repetition` instances.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RepetitionError {
+    /// The count is `0`.
+    ZeroCountError,
+}
+
+impl Display for RepetitionError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        match self {
+            RepetitionError::ZeroCountError => f.write_str("The count of repetition is zero."),
+        }
+    }
 }
 
+impl Error for RepetitionError {}
+
 impl Repetition {
     #[inline]
     /// Create a `Repetition` instance for only one time.
     pub fn new() -> Repetition {
         Repetition {
-            counter: RepetitionCounter::Fixed(1)
+            counter: RepetitionCounter::Fixed(1), min: 0
         }
     }
 
     #[inline]
-    /// Create a `Repetition` instance for any fixed times.
+    /// Create a `Repetition` instance for any fixed times. If `count` is `0`, `1` is used instead. Use the `try_fixed` method to reject it.
     pub fn fixed(count: u32) -> Repetition {
         if count == 0 {
             eprintln!(
@@ -52,9 +88,33 @@ impl Repetition {
 
             Repetition::new()
         } else {
-            Repetition {
-                counter: RepetitionCounter::Fixed(count)
-            }
+            Repetition::at_most(count)
+        }
+    }
+
+    #[inline]
+    /// Create a `Repetition` instance for any fixed times. The `count` must be bigger than `0`.
+    pub fn try_fixed(count: u32) -> Result<Repetition, RepetitionError> {
+        if count == 0 {
+            Err(RepetitionError::ZeroCountError)
+        } else {
+            Ok(Repetition::fixed(count))
+        }
+    }
+
+    #[inline]
+    /// Create a `Repetition` instance for at most `max_count` times. The extra occurrences are dropped. It is the same as `fixed` except that a `max_count` of `0` is kept, which is rejected when the options are validated.
+    pub fn at_most(max_count: u32) -> Repetition {
+        Repetition {
+            counter: RepetitionCounter::Fixed(max_count), min: 0
+        }
+    }
+
+    #[inline]
+    /// Create a `Repetition` instance for infinite times, but the field must be sent at least `min_count` times, otherwise parsing fails with a `NotEnoughRepetitionsError`.
+    pub fn at_least(min_count: u32) -> Repetition {
+        Repetition {
+            counter: RepetitionCounter::Infinite, min: min_count
         }
     }
 
@@ -62,13 +122,128 @@ impl Repetition {
     /// Create a `Repetition` instance for infinite times.
     pub fn infinite() -> Repetition {
         Repetition {
-            counter: RepetitionCounter::Infinite
+            counter: RepetitionCounter::Infinite, min: 0
         }
     }
 
     #[inline]
-    pub(crate) fn decrease_check_is_over(&mut self) -> bool {
-        self.counter.decrease_check_is_over()
+    /// Create a `Repetition` instance for at most `max_count` times, whose data are at most `max_total_bytes` bytes in total. The size limit of the field still applies to every occurrence.
+    pub fn infinite_with_budget(max_total_bytes: u64, max_count: u32) -> Repetition {
+        Repetition {
+            counter: RepetitionCounter::Budget {
+                count: max_count, bytes: max_total_bytes
+            },
+            min:     0,
+        }
+    }
+
+    /// The number of times which the field must be sent at least.
+    #[inline]
+    pub(crate) fn min(&self) -> u32 {
+        self.min
+    }
+
+    /// Limit the number of occurrences to `max_count`, keeping the byte budget if any.
+    #[inline]
+    pub(crate) fn with_max_count(self, max_count: u32) -> Repetition {
+        let bytes = match self.counter {
+            RepetitionCounter::Budget {
+                bytes, ..
+            } => bytes,
+            _ => u64::MAX,
+        };
+
+        Repetition {
+            min: self.min,
+            ..Repetition::infinite_with_budget(bytes, max_count)
+        }
+    }
+
+    /// Limit the total size of all occurrences to `max_total_bytes`, keeping the count.
+    #[inline]
+    pub(crate) fn with_max_total_bytes(self, max_total_bytes: u64) -> Repetition {
+        let count = match self.counter {
+            RepetitionCounter::Fixed(count)
+            | RepetitionCounter::Budget {
+                count, ..
+            } => count,
+            RepetitionCounter::Infinite => u32::MAX,
+        };
+
+        Repetition {
+            min: self.min,
+            ..Repetition::infinite_with_budget(max_total_bytes, count)
+        }
+    }
+
+    /// Decrease the count by one occurrence whose data are `size` bytes. Return whether no more occurrences are allowed.
+    #[inline]
+    pub(crate) fn decrease_check_is_over(&mut self, size: u64) -> bool {
+        self.counter.decrease_check_is_over(size)
+    }
+
+    /// The size limit of the next occurrence, which is `size_limit` reduced to the remaining budget.
+    #[inline]
+    pub(crate) fn size_limit(&self, size_limit: u64) -> u64 {
+        match self.counter {
+            RepetitionCounter::Budget {
+                bytes, ..
+            } => size_limit.min(bytes),
+            _ => size_limit,
+        }
+    }
+
+    /// Whether this repetition allows no occurrence at all.
+    #[inline]
+    pub(crate) fn is_zero(&self) -> bool {
+        matches!(
+            self.counter,
+            RepetitionCounter::Fixed(0)
+                | RepetitionCounter::Budget {
+                    count: 0,
+                    ..
+                }
+        )
+    }
+
+    /// Sum up two repetitions.
+    #[inline]
+    pub(crate) fn merge(self, other: Repetition) -> Repetition {
+        let merged = match (self.counter, other.counter) {
+            (RepetitionCounter::Fixed(a), RepetitionCounter::Fixed(b)) => {
+                Repetition::at_most(a.saturating_add(b))
+            },
+            (
+                RepetitionCounter::Budget {
+                    count: a,
+                    bytes: x,
+                },
+                RepetitionCounter::Budget {
+                    count: b,
+                    bytes: y,
+                },
+            ) => Repetition::infinite_with_budget(x.saturating_add(y), a.saturating_add(b)),
+            (
+                RepetitionCounter::Fixed(a),
+                RepetitionCounter::Budget {
+                    count: b,
+                    bytes,
+                },
+            )
+            | (
+                RepetitionCounter::Budget {
+                    count: b,
+                    bytes,
+                },
+                RepetitionCounter::Fixed(a),
+            ) => Repetition::infinite_with_budget(bytes, a.saturating_add(b)),
+            _ => Repetition::infinite(),
+        };
+
+        Repetition {
+            min: self.min.saturating_add(other.min),
+            ..merged
+        }
     }
 }
 
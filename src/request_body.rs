@@ -0,0 +1,40 @@
+use rocket::{data::DataStream, http::ContentType, tokio::io::AsyncRead, Data};
+
+use crate::{MultipartFormData, MultipartFormDataError};
+
+/// The pieces of a multipart/form-data request which depend on the web framework.
+pub(crate) trait RequestBody {
+    type Reader: AsyncRead + Send + Unpin;
+
+    /// Extract the boundary from the content type of the request.
+    fn boundary(&self) -> Result<String, MultipartFormDataError>;
+
+    /// The length of the body, if it is known.
+    #[inline]
+    fn content_length(&self) -> Option<u64> {
+        None
+    }
+
+    /// Open the body, which is read at most `max_data_bytes` bytes.
+    fn open(self, max_data_bytes: u64) -> Self::Reader;
+}
+
+/// The content type and the body of a Rocket request.
+pub(crate) struct RocketBody<'a, 'r> {
+    pub(crate) content_type: &'a ContentType,
+    pub(crate) data:         Data<'r>,
+}
+
+impl<'a, 'r> RequestBody for RocketBody<'a, 'r> {
+    type Reader = DataStream<'r>;
+
+    #[inline]
+    fn boundary(&self) -> Result<String, MultipartFormDataError> {
+        MultipartFormData::boundary_from_content_type(self.content_type).map(|b| b.into_owned())
+    }
+
+    #[inline]
+    fn open(self, max_data_bytes: u64) -> Self::Reader {
+        self.data.open(max_data_bytes.into())
+    }
+}
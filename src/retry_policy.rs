@@ -0,0 +1,71 @@
+use std::{io, time::Duration};
+
+use rocket::tokio::time;
+
+/// How to retry the operations on the temporary files which fail with transient errors, which network filesystems such as NFS commonly return.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The max number of retries of an operation.
+    pub attempts: u32,
+    /// The delay before the first retry. It is doubled before every later retry.
+    pub backoff:  Duration,
+}
+
+impl RetryPolicy {
+    /// Create a `RetryPolicy` instance.
+    #[inline]
+    pub fn new(attempts: u32, backoff: Duration) -> RetryPolicy {
+        RetryPolicy {
+            attempts,
+            backoff,
+        }
+    }
+}
+
+/// The state of the retries of an operation.
+#[derive(Debug)]
+pub(crate) struct Retry {
+    policy:  Option<RetryPolicy>,
+    retried: u32,
+}
+
+impl Retry {
+    #[inline]
+    pub(crate) fn new(policy: Option<RetryPolicy>) -> Retry {
+        Retry {
+            policy,
+            retried: 0,
+        }
+    }
+
+    /// Wait before retrying the operation which fails with `err`. Return `err` if it should not be retried.
+    pub(crate) async fn wait(&mut self, err: io::Error) -> Result<(), io::Error> {
+        match self.policy {
+            Some(policy) if self.retried < policy.attempts && is_transient(&err) => {
+                time::sleep(policy.backoff.saturating_mul(1 << self.retried.min(16))).await;
+
+                self.retried += 1;
+
+                Ok(())
+            },
+            _ => Err(err),
+        }
+    }
+}
+
+/// Whether an error may not happen again if the operation is retried.
+#[inline]
+fn is_transient(err: &io::Error) -> bool {
+    !matches!(
+        err.kind(),
+        io::ErrorKind::NotFound
+            | io::ErrorKind::PermissionDenied
+            | io::ErrorKind::AlreadyExists
+            | io::ErrorKind::InvalidInput
+            | io::ErrorKind::InvalidData
+            | io::ErrorKind::WriteZero
+            | io::ErrorKind::Unsupported
+            | io::ErrorKind::UnexpectedEof
+            | io::ErrorKind::OutOfMemory
+    )
+}
@@ -0,0 +1,45 @@
+use std::{
+    collections::hash_map::RandomState,
+    fmt::{self, Debug, Formatter},
+    hash::{BuildHasher, Hasher},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// A source of random numbers, which can be replaced to make the names of uploaded files reproducible in tests.
+///
+/// It is implemented for closures of type `Fn() -> u64`.
+pub trait Rng: Send + Sync {
+    /// Get the next random number.
+    fn next_u64(&self) -> u64;
+}
+
+impl<F: Fn() -> u64 + Send + Sync> Rng for F {
+    #[inline]
+    fn next_u64(&self) -> u64 {
+        self()
+    }
+}
+
+impl Debug for dyn Rng {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("Rng")
+    }
+}
+
+/// The default source of random numbers, which hashes a counter with the randomly seeded hasher of the standard library. The numbers are not suitable for cryptography.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemRng;
+
+impl Rng for SystemRng {
+    #[inline]
+    fn next_u64(&self) -> u64 {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+        let mut hasher = RandomState::new().build_hasher();
+
+        hasher.write_u64(COUNTER.fetch_add(1, Ordering::Relaxed));
+
+        hasher.finish()
+    }
+}
@@ -0,0 +1,78 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+
+use crate::{FileField, RawField, StoredField, TextField};
+
+impl Serialize for FileField {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("FileField", 4)?;
+
+        s.serialize_field("content_type", &self.content_type.as_ref().map(|m| m.as_ref()))?;
+        s.serialize_field("file_name", &self.file_name)?;
+        s.serialize_field("path", &self.path)?;
+        s.serialize_field("size", &self.size)?;
+
+        s.end()
+    }
+}
+
+impl Serialize for RawField {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("RawField", 3)?;
+
+        s.serialize_field("content_type", &self.content_type.as_ref().map(|m| m.as_ref()))?;
+        s.serialize_field("file_name", &self.file_name)?;
+        s.serialize_field("raw", &self.raw)?;
+
+        s.end()
+    }
+}
+
+impl Serialize for TextField {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("TextField", 3)?;
+
+        s.serialize_field("content_type", &self.content_type.as_ref().map(|m| m.as_ref()))?;
+        s.serialize_field("file_name", &self.file_name)?;
+        s.serialize_field("text", &self.text)?;
+
+        s.end()
+    }
+}
+
+impl Serialize for StoredField {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("StoredField", 4)?;
+
+        s.serialize_field("content_type", &self.content_type.as_ref().map(|m| m.as_ref()))?;
+        s.serialize_field("file_name", &self.file_name)?;
+        s.serialize_field("id", &self.id)?;
+        s.serialize_field("size", &self.size)?;
+
+        s.end()
+    }
+}
+
+/// A `RawField` which is serialized with its data encoded in Base64. It is created by `RawField::as_base64`.
+#[derive(Debug, Clone, Copy)]
+pub struct Base64RawField<'a>(&'a RawField);
+
+impl RawField {
+    /// Serialize this field with its data encoded in Base64 instead of a sequence of bytes.
+    #[inline]
+    pub fn as_base64(&self) -> Base64RawField<'_> {
+        Base64RawField(self)
+    }
+}
+
+impl<'a> Serialize for Base64RawField<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut s = serializer.serialize_struct("RawField", 3)?;
+
+        s.serialize_field("content_type", &self.0.content_type.as_ref().map(|m| m.as_ref()))?;
+        s.serialize_field("file_name", &self.0.file_name)?;
+        s.serialize_field("raw", &STANDARD.encode(&self.0.raw))?;
+
+        s.end()
+    }
+}
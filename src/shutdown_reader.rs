@@ -0,0 +1,81 @@
+use std::{
+    future::Future,
+    io,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+};
+
+use rocket::{
+    tokio::{
+        io::{AsyncRead, ReadBuf},
+        sync::mpsc::UnboundedSender,
+    },
+    Shutdown,
+};
+
+use crate::ParsedField;
+
+/// A reader which fails as soon as the server begins shutting down, or the consumer of the progressive results has given up.
+pub(crate) struct ShutdownReader<R> {
+    inner:    R,
+    shutdown: Option<Shutdown>,
+    progress: Option<UnboundedSender<ParsedField>>,
+    aborted:  Arc<AtomicBool>,
+}
+
+impl<R> ShutdownReader<R> {
+    #[inline]
+    pub(crate) fn new(
+        inner: R,
+        shutdown: Option<Shutdown>,
+        progress: Option<UnboundedSender<ParsedField>>,
+    ) -> ShutdownReader<R> {
+        ShutdownReader {
+            inner,
+            shutdown,
+            progress,
+            aborted: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// A flag which is set when reading is aborted.
+    #[inline]
+    pub(crate) fn aborted(&self) -> Arc<AtomicBool> {
+        self.aborted.clone()
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for ShutdownReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        if let Some(shutdown) = self.shutdown.as_mut() {
+            // The waker is registered, so a pending read is woken up by the shutdown as well
+            if Pin::new(shutdown).poll(cx).is_ready() {
+                self.aborted.store(true, Ordering::Release);
+
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::Interrupted,
+                    "the server is shutting down",
+                )));
+            }
+        }
+
+        if self.progress.as_ref().map_or(false, |progress| progress.is_closed()) {
+            self.aborted.store(true, Ordering::Release);
+
+            return Poll::Ready(Err(io::Error::new(
+                io::ErrorKind::Interrupted,
+                "parsing is abandoned",
+            )));
+        }
+
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
@@ -0,0 +1,142 @@
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+use crate::{mime::Mime, MultipartFormDataField, MultipartFormDataOptions, Repetition};
+
+/// The error of parsing a field spec for `MultipartFormDataOptions::from_spec`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpecError {
+    /// The part of the spec which cannot be parsed.
+    pub fragment: String,
+    /// The reason.
+    pub message:  &'static str,
+}
+
+impl Display for SpecError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        f.write_fmt(format_args!("{} (at `{}`)", self.message, self.fragment))
+    }
+}
+
+impl Error for SpecError {}
+
+impl<'a> MultipartFormDataOptions<'a> {
+    /// Create a `MultipartFormDataOptions` instance from a field spec.
+    ///
+    /// Fields are separated by commas. Each field is written as `name: type modifiers...`, where `type` is one of `text`, `raw`, `bytes`, `file` and `checkbox`, and modifiers are separated by spaces or semicolons. A modifier can be
+    ///
+    /// * a content type filter, such as `image/*`
+    /// * a denied content type, such as `!image/svg+xml`
+    /// * a size limit, such as `<=32MiB` (supported units are `B`, `KB`, `KiB`, `MB`, `MiB`, `GB` and `GiB`)
+    /// * a repetition, such as `x3` or `x*` (infinite)
+    ///
+    /// ```
+    /// use rocket_multipart_form_data::MultipartFormDataOptions;
+    ///
+    /// let options = MultipartFormDataOptions::from_spec(
+    ///     "photo: file image/*; <=32MiB, name: text, email: text x3",
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(3, options.allowed_fields.len());
+    /// ```
+    pub fn from_spec(spec: &'a str) -> Result<MultipartFormDataOptions<'a>, SpecError> {
+        let mut allowed_fields = Vec::new();
+
+        for field_spec in spec.split(',') {
+            let field_spec = field_spec.trim();
+
+            if field_spec.is_empty() {
+                continue;
+            }
+
+            allowed_fields.push(parse_field_spec(field_spec)?);
+        }
+
+        Ok(MultipartFormDataOptions::with_multipart_form_data_fields(allowed_fields))
+    }
+}
+
+fn parse_field_spec(field_spec: &str) -> Result<MultipartFormDataField<'_>, SpecError> {
+    let error = |fragment: &str, message: &'static str| SpecError {
+        fragment: fragment.to_string(),
+        message,
+    };
+
+    let (field_name, rest) = field_spec
+        .split_once(':')
+        .ok_or_else(|| error(field_spec, "The field type is missing."))?;
+
+    let field_name = field_name.trim();
+
+    if field_name.is_empty() {
+        return Err(error(field_spec, "The field name is empty."));
+    }
+
+    let mut tokens = rest.split(|c: char| c.is_whitespace() || c == ';').filter(|s| !s.is_empty());
+
+    let typ = tokens.next().ok_or_else(|| error(field_spec, "The field type is missing."))?;
+
+    let mut field = match typ {
+        "text" => MultipartFormDataField::text(field_name),
+        "raw" | "bytes" => MultipartFormDataField::raw(field_name),
+        "file" => MultipartFormDataField::file(field_name),
+        "checkbox" => MultipartFormDataField::checkbox(field_name),
+        _ => return Err(error(typ, "The field type is unknown.")),
+    };
+
+    for token in tokens {
+        if let Some(size) = token.strip_prefix("<=") {
+            field.size_limit =
+                parse_size(size).ok_or_else(|| error(token, "Invalid size limit."))?;
+        } else if let Some(count) = token.strip_prefix('x') {
+            field.repetition = if count == "*" {
+                Repetition::infinite()
+            } else {
+                match count.parse::<u32>().ok().and_then(|c| Repetition::try_fixed(c).ok()) {
+                    Some(repetition) => repetition,
+                    None => return Err(error(token, "Invalid repetition.")),
+                }
+            };
+        } else if let Some(content_type) = token.strip_prefix('!') {
+            let content_type =
+                Mime::from_str(content_type).map_err(|_| error(token, "Invalid content type."))?;
+
+            field = field.deny_content_type(content_type);
+        } else if token.contains('/') {
+            let content_type =
+                Mime::from_str(token).map_err(|_| error(token, "Invalid content type."))?;
+
+            field = field.content_type(Some(content_type));
+        } else {
+            return Err(error(token, "The modifier is unknown."));
+        }
+    }
+
+    Ok(field)
+}
+
+fn parse_size(s: &str) -> Option<u64> {
+    let index = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+
+    let (number, unit) = s.split_at(index);
+
+    let number = number.parse::<u64>().ok()?;
+
+    let unit: u64 = match unit {
+        "" | "B" => 1,
+        "KB" => 1000,
+        "KiB" => 1024,
+        "MB" => 1000 * 1000,
+        "MiB" => 1024 * 1024,
+        "GB" => 1000 * 1000 * 1000,
+        "GiB" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+
+    number.checked_mul(unit)
+}
@@ -0,0 +1,38 @@
+use std::{
+    fmt::{self, Debug, Formatter},
+    io,
+};
+
+use crate::BoxFuture;
+
+/// A destination of uploaded files other than the temporary directory, such as a database or an object store.
+pub trait StorageBackend: Send + Sync {
+    /// Start storing an uploaded file of a field.
+    fn create<'a>(
+        &'a self,
+        field_name: &'a str,
+    ) -> BoxFuture<'a, Result<Box<dyn StorageWriter>, io::Error>>;
+
+    /// Remove a stored file by its id. It is called for the files which have been stored when parsing fails. The default implementation does nothing.
+    fn remove<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), io::Error>> {
+        let _ = id;
+
+        Box::pin(async { Ok(()) })
+    }
+}
+
+impl Debug for dyn StorageBackend {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str("StorageBackend")
+    }
+}
+
+/// The writer of a file which is being stored by a `StorageBackend`. Dropping it without calling the `finish` method should discard the data.
+pub trait StorageWriter: Send {
+    /// Write a chunk.
+    fn write<'a>(&'a mut self, data: &'a [u8]) -> BoxFuture<'a, Result<(), io::Error>>;
+
+    /// Finalize the file and return its id.
+    fn finish(self: Box<Self>) -> BoxFuture<'static, Result<String, io::Error>>;
+}
@@ -0,0 +1,90 @@
+use std::{
+    fs, io,
+    path::{Component, Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use crate::{Clock, Rng, SystemClock, SystemRng};
+
+/// A subdirectory of a temporary directory for the files of one request, such as `temporary_dir/<request id>/`. The subdirectory is deleted with everything in it when the handle is being dropped.
+///
+/// Concurrent parses can share one temporary directory safely, because the uploaded files are always created exclusively under unique names. Giving each request its own handle additionally isolates their files, so that everything a request leaves behind, including the files of a failed parse, is cleaned up at once. Set the `temporary_dir` of the options to the `path` of the handle, and keep the handle as long as the uploaded files are used.
+///
+/// ```rust,no_run
+/// use rocket_multipart_form_data::{MultipartFormDataOptions, TempDirHandle};
+///
+/// # fn run() -> std::io::Result<()> {
+/// let handle = TempDirHandle::create_unique(std::env::temp_dir())?;
+///
+/// let mut options = MultipartFormDataOptions::new();
+///
+/// options.temporary_dir = handle.path().to_path_buf();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct TempDirHandle {
+    path: Option<PathBuf>,
+}
+
+impl TempDirHandle {
+    /// Create the subdirectory named `request_id` in `parent`. It fails with `AlreadyExists` if the subdirectory exists, so that two requests never share one, and with `InvalidInput` if `request_id` is not a plain directory name.
+    pub fn create<P: AsRef<Path>>(parent: P, request_id: &str) -> io::Result<TempDirHandle> {
+        let mut components = Path::new(request_id).components();
+
+        match (components.next(), components.next()) {
+            (Some(Component::Normal(_)), None) => (),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("`{}` is not a plain directory name", request_id),
+                ));
+            },
+        }
+
+        let path = parent.as_ref().join(request_id);
+
+        fs::create_dir(&path)?;
+
+        Ok(TempDirHandle {
+            path: Some(path)
+        })
+    }
+
+    /// Create a subdirectory with a generated name in `parent`.
+    pub fn create_unique<P: AsRef<Path>>(parent: P) -> io::Result<TempDirHandle> {
+        let parent = parent.as_ref();
+
+        let nanos = SystemClock.now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos();
+
+        loop {
+            let request_id = format!("rs-req-{}-{:016x}", nanos, SystemRng.next_u64());
+
+            match TempDirHandle::create(parent, &request_id) {
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+                result => return result,
+            }
+        }
+    }
+
+    /// The path of the subdirectory.
+    #[inline]
+    pub fn path(&self) -> &Path {
+        self.path.as_deref().unwrap()
+    }
+
+    /// Disarm this handle and return the path of the subdirectory. It will not be deleted automatically anymore.
+    #[inline]
+    pub fn keep(mut self) -> PathBuf {
+        self.path.take().unwrap()
+    }
+}
+
+impl Drop for TempDirHandle {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(path) = self.path.take() {
+            if fs::remove_dir_all(path).is_err() {}
+        }
+    }
+}
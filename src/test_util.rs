@@ -0,0 +1,333 @@
+use std::{
+    collections::VecDeque,
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use proptest::{collection::vec, option, prelude::*, sample::select};
+use rocket::{
+    http::ContentType,
+    tokio::io::{AsyncRead, AsyncReadExt, ReadBuf, Take},
+};
+
+use crate::{
+    request_body::RequestBody, MultipartFormData, MultipartFormDataError, MultipartFormDataField,
+    MultipartFormDataOptions, Repetition,
+};
+
+const CONTENT_TYPES: [&str; 5] = [
+    "text/plain",
+    "text/plain; charset=utf-8",
+    "application/octet-stream",
+    "image/png",
+    "application/json",
+];
+
+/// A part of a generated multipart/form-data body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixturePart {
+    pub name:         String,
+    pub file_name:    Option<String>,
+    pub content_type: Option<String>,
+    pub data:         Vec<u8>,
+}
+
+/// How a generated multipart/form-data body is broken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Defect {
+    /// The closing delimiter is missing.
+    MissingTerminator,
+    /// The body is cut in the middle of the data of the last part.
+    Truncated,
+    /// A header line of the first part does not have a colon.
+    MalformedHeader,
+    /// The delimiters do not match the boundary in the content type.
+    WrongBoundary,
+}
+
+/// A generated multipart/form-data body. The `test-util` feature is required.
+///
+/// ```rust
+/// use proptest::{prop_assert_eq, test_runner::TestRunner};
+/// use rocket::tokio::runtime::Runtime;
+/// use rocket_multipart_form_data::{test_util, MultipartFormDataOptions};
+///
+/// let runtime = Runtime::new().unwrap();
+///
+/// TestRunner::default()
+///     .run(&test_util::fixture(), |fixture| {
+///         let options =
+///             MultipartFormDataOptions::with_multipart_form_data_fields(
+///                 fixture.fields(),
+///             );
+///
+///         let result = runtime.block_on(fixture.parse(options));
+///
+///         prop_assert_eq!(result.is_ok(), fixture.is_valid());
+///
+///         Ok(())
+///     })
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    pub boundary:    String,
+    pub parts:       Vec<FixturePart>,
+    pub defect:      Option<Defect>,
+    /// The sizes of the chunks in which the body is delivered to the parser, so that delimiters and CRLFs can be split across chunks. The rest of the body is delivered at once.
+    pub chunk_sizes: Vec<usize>,
+}
+
+impl Fixture {
+    /// Whether the body is well-formed.
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.defect.is_none()
+    }
+
+    /// The content type of the request, with the boundary quoted.
+    #[inline]
+    pub fn content_type(&self) -> ContentType {
+        ContentType::new("multipart", "form-data")
+            .with_params(("boundary", format!("\"{}\"", self.boundary)))
+    }
+
+    /// Encode the parts into the body.
+    pub fn body(&self) -> Vec<u8> {
+        let boundary = match self.defect {
+            // `!` is not allowed in boundaries, so the delimiters cannot match
+            Some(Defect::WrongBoundary) => format!("!{}", self.boundary),
+            _ => self.boundary.clone(),
+        };
+
+        let mut body = Vec::new();
+        let mut last_data_start = 0;
+
+        for (i, part) in self.parts.iter().enumerate() {
+            body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+            body.extend_from_slice(
+                format!("Content-Disposition: form-data; name=\"{}\"", part.name).as_bytes(),
+            );
+
+            if let Some(file_name) = part.file_name.as_ref() {
+                body.extend_from_slice(format!("; filename=\"{}\"", file_name).as_bytes());
+            }
+
+            body.extend_from_slice(b"\r\n");
+
+            if let Some(content_type) = part.content_type.as_ref() {
+                body.extend_from_slice(format!("Content-Type: {}\r\n", content_type).as_bytes());
+            }
+
+            if i == 0 && self.defect == Some(Defect::MalformedHeader) {
+                body.extend_from_slice(b"Malformed Header\r\n");
+            }
+
+            body.extend_from_slice(b"\r\n");
+
+            last_data_start = body.len();
+
+            body.extend_from_slice(&part.data);
+            body.extend_from_slice(b"\r\n");
+        }
+
+        match self.defect {
+            Some(Defect::MissingTerminator) => (),
+            Some(Defect::Truncated) => {
+                let data_length = self.parts.last().map_or(0, |part| part.data.len());
+
+                body.truncate(last_data_start + data_length / 2);
+            },
+            _ => body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes()),
+        }
+
+        body
+    }
+
+    /// Declare every part name as a raw field which accepts any number of parts of any size, in order to check the parsing loop without the limits of the fields.
+    pub fn fields(&self) -> Vec<MultipartFormDataField<'_>> {
+        self.parts
+            .iter()
+            .map(|part| {
+                MultipartFormDataField::raw(&part.name)
+                    .size_limit(u64::MAX)
+                    .repetition(Repetition::infinite())
+            })
+            .collect()
+    }
+
+    /// Parse the body in the same way as the `MultipartFormData::parse` method, delivering it in the chunks of `chunk_sizes`.
+    #[inline]
+    pub async fn parse(
+        &self,
+        options: MultipartFormDataOptions<'_>,
+    ) -> Result<MultipartFormData, MultipartFormDataError> {
+        MultipartFormData::parse_body(self, options).await
+    }
+}
+
+impl RequestBody for &Fixture {
+    type Reader = Take<ChunkedReader>;
+
+    #[inline]
+    fn boundary(&self) -> Result<String, MultipartFormDataError> {
+        MultipartFormData::boundary_from_content_type(&self.content_type()).map(|b| b.into_owned())
+    }
+
+    #[inline]
+    fn open(self, max_data_bytes: u64) -> Self::Reader {
+        ChunkedReader {
+            data:        self.body(),
+            position:    0,
+            chunk_sizes: self.chunk_sizes.iter().copied().collect(),
+        }
+        .take(max_data_bytes)
+    }
+}
+
+/// A reader which delivers data in chunks of the given sizes.
+pub(crate) struct ChunkedReader {
+    data:        Vec<u8>,
+    position:    usize,
+    chunk_sizes: VecDeque<usize>,
+}
+
+impl AsyncRead for ChunkedReader {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let rest = self.data.len() - self.position;
+        let chunk_size = self.chunk_sizes.pop_front().unwrap_or(rest).max(1);
+        let length = chunk_size.min(rest).min(buf.remaining());
+
+        buf.put_slice(&self.data[self.position..self.position + length]);
+        self.position += length;
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Generate boundaries made of any characters allowed by RFC 2046, including spaces which are not at the end.
+pub fn boundary() -> impl Strategy<Value = String> {
+    "[0-9A-Za-z'()+_,./:=? -]{0,69}[0-9A-Za-z'()+_,./:=?-]"
+}
+
+/// Generate parts with or without file names and content types.
+pub fn part() -> impl Strategy<Value = FixturePart> {
+    (
+        "[0-9A-Za-z_.\\[\\]-]{1,16}",
+        option::of("[0-9A-Za-z_. -]{0,15}[0-9A-Za-z_.-]"),
+        option::of(select(&CONTENT_TYPES[..]).prop_map(String::from)),
+        vec(any::<u8>(), 0..256),
+    )
+        .prop_map(|(name, file_name, content_type, data)| FixturePart {
+            name,
+            file_name,
+            content_type,
+            data,
+        })
+}
+
+fn fixture_with(defect: impl Strategy<Value = Option<Defect>>) -> impl Strategy<Value = Fixture> {
+    (boundary(), vec(part(), 0..5), defect, vec(1usize..64, 0..16))
+        .prop_map(|(boundary, parts, defect, chunk_sizes)| Fixture {
+            boundary,
+            parts,
+            defect,
+            chunk_sizes,
+        })
+        .prop_filter("the delimiter appears in the data", |fixture| {
+            let delimiter = format!("--{}", fixture.boundary);
+
+            !fixture
+                .parts
+                .iter()
+                .any(|part| part.data.windows(delimiter.len()).any(|w| w == delimiter.as_bytes()))
+        })
+}
+
+/// Generate well-formed bodies.
+#[inline]
+pub fn valid_fixture() -> impl Strategy<Value = Fixture> {
+    fixture_with(Just(None))
+}
+
+/// Generate broken bodies, which have at least one part.
+pub fn invalid_fixture() -> impl Strategy<Value = Fixture> {
+    fixture_with(
+        select(
+            &[
+                Defect::MissingTerminator,
+                Defect::Truncated,
+                Defect::MalformedHeader,
+                Defect::WrongBoundary,
+            ][..],
+        )
+        .prop_map(Some),
+    )
+    .prop_filter("no parts", |fixture| !fixture.parts.is_empty())
+}
+
+/// Generate well-formed and broken bodies.
+#[inline]
+pub fn fixture() -> impl Strategy<Value = Fixture> {
+    prop_oneof![valid_fixture(), invalid_fixture()]
+}
+
+#[cfg(test)]
+mod tests {
+    use rocket::tokio::runtime::Builder;
+
+    use super::*;
+
+    /// Run the body of a fixture through `MultipartFormData::parse_reader`, delivering it in the chunks of the fixture.
+    fn parse_reader(fixture: &Fixture) -> Result<MultipartFormData, MultipartFormDataError> {
+        let reader = ChunkedReader {
+            data:        fixture.body(),
+            position:    0,
+            chunk_sizes: fixture.chunk_sizes.iter().copied().collect(),
+        };
+
+        let options = MultipartFormDataOptions::with_multipart_form_data_fields(fixture.fields());
+
+        Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(MultipartFormData::parse_reader(reader, fixture.boundary.as_str(), options))
+    }
+
+    proptest! {
+        #[test]
+        fn valid_bodies_are_parsed(fixture in valid_fixture()) {
+            let multipart_form_data = match parse_reader(&fixture) {
+                Ok(multipart_form_data) => multipart_form_data,
+                Err(err) => return Err(TestCaseError::fail(err.to_string())),
+            };
+
+            for part in fixture.parts.iter() {
+                let expected: Vec<&[u8]> = fixture
+                    .parts
+                    .iter()
+                    .filter(|p| p.name == part.name)
+                    .map(|p| p.data.as_slice())
+                    .collect();
+
+                let parsed: Vec<&[u8]> = multipart_form_data.raw[part.name.as_str()]
+                    .iter()
+                    .map(|f| f.raw.as_slice())
+                    .collect();
+
+                prop_assert_eq!(parsed, expected);
+            }
+        }
+
+        #[test]
+        fn invalid_bodies_are_rejected(fixture in invalid_fixture()) {
+            prop_assert!(parse_reader(&fixture).is_err());
+        }
+    }
+}
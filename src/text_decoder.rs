@@ -0,0 +1,85 @@
+use std::string::FromUtf8Error;
+
+/// Decodes the data of a text field chunk by chunk, so that invalid UTF-8 is found as soon as it arrives and the text is built in place without an intermediate byte buffer.
+pub(crate) struct TextDecoder {
+    text:    String,
+    /// The first bytes of a character which is split between chunks.
+    pending: Vec<u8>,
+}
+
+impl TextDecoder {
+    #[inline]
+    pub(crate) fn with_capacity(capacity: usize) -> TextDecoder {
+        TextDecoder {
+            text: String::with_capacity(capacity), pending: Vec::new()
+        }
+    }
+
+    /// The number of bytes which have been pushed.
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        self.text.len() + self.pending.len()
+    }
+
+    /// Take the text which has been decoded, leaving an incomplete character at the end.
+    #[inline]
+    pub(crate) fn take_text(&mut self) -> String {
+        std::mem::take(&mut self.text)
+    }
+
+    /// Decode a chunk.
+    pub(crate) fn push(&mut self, mut bytes: &[u8]) -> Result<(), FromUtf8Error> {
+        // Complete the character which is split between chunks
+        while !self.pending.is_empty() {
+            let (&b, rest) = match bytes.split_first() {
+                Some(split) => split,
+                None => return Ok(()),
+            };
+
+            self.pending.push(b);
+            bytes = rest;
+
+            match std::str::from_utf8(&self.pending) {
+                Ok(s) => {
+                    self.text.push_str(s);
+                    self.pending.clear();
+                },
+                Err(err) if err.error_len().is_some() => return Err(invalid(&self.pending)),
+                Err(_) => (),
+            }
+        }
+
+        match std::str::from_utf8(bytes) {
+            Ok(s) => self.text.push_str(s),
+            Err(err) => {
+                let (valid, rest) = bytes.split_at(err.valid_up_to());
+
+                // It has been validated
+                self.text.push_str(unsafe { std::str::from_utf8_unchecked(valid) });
+
+                if err.error_len().is_some() {
+                    return Err(invalid(rest));
+                }
+
+                self.pending.extend_from_slice(rest);
+            },
+        }
+
+        Ok(())
+    }
+
+    /// Finish decoding. If the data has been truncated, an incomplete character at the end is dropped instead of being an error.
+    #[inline]
+    pub(crate) fn finish(self, truncated: bool) -> Result<String, FromUtf8Error> {
+        if self.pending.is_empty() || truncated {
+            Ok(self.text)
+        } else {
+            Err(invalid(&self.pending))
+        }
+    }
+}
+
+#[inline]
+fn invalid(bytes: &[u8]) -> FromUtf8Error {
+    String::from_utf8(bytes.to_vec()).unwrap_err()
+}
@@ -0,0 +1,11 @@
+/// What to do with a part of a text field which declares a file name in its `Content-Disposition` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextFileNamePolicy {
+    /// Parse the part as text. The file name is kept in the `file_name` of the `TextField`.
+    #[default]
+    Text,
+    /// Parse the part as a file, which is put into the `files` map.
+    TreatAsFile,
+    /// Fail with a `DataTypeError`.
+    Error,
+}
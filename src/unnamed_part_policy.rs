@@ -0,0 +1,11 @@
+/// What to do with a part which does not have a field name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UnnamedPartPolicy<'a> {
+    /// Skip the part. A `ParseWarning::UnnamedPart` warning is recorded.
+    #[default]
+    Ignore,
+    /// Stop parsing and fail with an `UnnamedPartError`.
+    Error,
+    /// Treat the part as a part of the specified field.
+    CollectAs(&'a str),
+}
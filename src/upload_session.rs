@@ -0,0 +1,844 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    error::Error,
+    fmt::{self, Display, Formatter},
+    fs as std_fs, io,
+    io::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use rocket::tokio::{
+    self,
+    fs::{self, File},
+    io::AsyncWriteExt,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    file_name_generator::create_new_file, manifest::move_file, mime::Mime, FileField,
+    FileNameGenerator, MultipartFormData, MultipartFormDataField, TimestampFileNameGenerator,
+};
+
+const DEFAULT_MAX_CHUNKS: u64 = 10000;
+const DEFAULT_MAX_SIZE: u64 = 4 * 1024 * 1024 * 1024;
+const MAX_SESSION_ID_LENGTH: usize = 128;
+/// The directory in `dir` where the chunks and the manifests of the sessions are kept. It cannot be taken by an assembled file because assembled files are only created with names which are not taken.
+const SESSIONS_DIR_NAME: &str = ".upload-sessions";
+const SESSION_MANIFEST_FILE_NAME: &str = "manifest.json";
+const SESSION_MANIFEST_VERSION: u32 = 1;
+
+/// A manager of upload sessions, which allows a file to be uploaded in numbered chunks across several requests, so that it can be larger than the size limit of a request. It should be managed by Rocket. It requires the `serde` feature, which is used to write the manifests of the sessions.
+///
+/// Every request is a multipart/form-data body with the following fields, which can be declared by the `fields` method.
+///
+/// * `session_id`: a text chosen by the client, made of ASCII letters, digits, `-` and `_`
+/// * `chunk_index`: the index of the chunk, starting from `0`
+/// * `chunk_count`: the number of chunks, which is required in at least one request of the session
+/// * `chunk`: the data of the chunk, which must not be encrypted
+///
+/// Any client which knows a session ID can add chunks to the session, so the handler should tie the session IDs to the authenticated user, such as by checking that the ID was issued to the user or by prefixing it with the ID of the user before the request is accepted, or generate the session IDs on the server and hand them out to the clients.
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use rocket::{http::ContentType, Data, State};
+/// use rocket_multipart_form_data::{
+///     MultipartFormData, MultipartFormDataOptions, UploadSession,
+///     UploadSessionStatus,
+/// };
+///
+/// #[post("/upload", data = "<data>")]
+/// async fn upload(
+///     sessions: &State<UploadSession>,
+///     content_type: &ContentType,
+///     data: Data<'_>,
+/// ) -> String {
+///     let options = MultipartFormDataOptions::with_multipart_form_data_fields(
+///         UploadSession::fields(8 * 1024 * 1024),
+///     );
+///
+///     let mut multipart_form_data =
+///         MultipartFormData::parse(content_type, data, options)
+///             .await
+///             .unwrap();
+///
+///     match sessions.accept(&mut multipart_form_data).await.unwrap() {
+///         UploadSessionStatus::Incomplete {
+///             received, ..
+///         } => format!("{} chunks received", received),
+///         UploadSessionStatus::Complete(file) => {
+///             format!("{} bytes uploaded", file.size)
+///         },
+///     }
+/// }
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     rocket::build()
+///         .manage(UploadSession::new(std::env::temp_dir()))
+///         .mount("/", routes![upload])
+/// }
+/// ```
+///
+/// When all chunks of a session have been received, they are concatenated into a new file in `dir` whose name is generated by the `file_name_generator`, so an existing file is never overwritten.
+///
+/// The received chunks are moved into the `.upload-sessions` directory in `dir`, along with a JSON manifest file per session which lists the chunks with their sizes (and their SHA-256 hashes if the `sha2` feature is enabled) and the expiry of the session. The manifest is replaced atomically and synced to the disk whenever a chunk is accepted, so the sessions can be resumed by the `recover` method after the process restarts.
+///
+/// The file operations of a chunk are done in a blocking thread, and only lock the session of the chunk, so the chunks of different sessions are accepted in parallel.
+#[derive(Debug, Clone)]
+pub struct UploadSession {
+    dir:                 PathBuf,
+    max_chunks:          u64,
+    max_size:            u64,
+    expiry:              Option<Duration>,
+    file_name_generator: Arc<dyn FileNameGenerator>,
+    sessions:            Arc<Mutex<HashMap<String, Arc<Mutex<Chunks>>>>>,
+}
+
+#[derive(Debug, Default)]
+struct Chunks {
+    count:   Option<u64>,
+    chunks:  BTreeMap<u64, FileField>,
+    /// The SHA-256 hashes of the chunks.
+    #[cfg(feature = "sha2")]
+    hashes:  HashMap<u64, [u8; 32]>,
+    /// When the session expires. It is extended whenever a chunk is accepted.
+    expires: Option<SystemTime>,
+    /// Whether the session has been assembled or deleted, and removed from the sessions. A chunk which was waiting for the session has to look the session up again.
+    removed: bool,
+}
+
+/// The manifest file of a session.
+#[derive(Serialize, Deserialize)]
+struct SessionManifest {
+    version: u32,
+    count:   Option<u64>,
+    /// The seconds since the Unix epoch.
+    expires: Option<u64>,
+    chunks:  Vec<ChunkManifest>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ChunkManifest {
+    index:        u64,
+    size:         u64,
+    /// The SHA-256 hash in hex.
+    sha256:       Option<String>,
+    content_type: Option<String>,
+    file_name:    Option<String>,
+}
+
+/// The fields of a chunk request.
+struct ChunkRequest {
+    session_id:  String,
+    chunk_index: u64,
+    chunk_count: Option<u64>,
+}
+
+enum Added {
+    Complete(String, Chunks),
+    Incomplete(u64, Option<u64>),
+}
+
+/// The status of an upload session after a chunk is accepted.
+#[derive(Debug)]
+pub enum UploadSessionStatus {
+    /// More chunks are expected.
+    Incomplete {
+        /// The number of different chunks which have been received.
+        received: u64,
+        /// The number of chunks, if it is known.
+        count:    Option<u64>,
+    },
+    /// All chunks have been received and assembled into one file, which is not deleted automatically.
+    Complete(Box<FileField>),
+}
+
+/// Errors of upload sessions.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum UploadSessionError {
+    /// A required field is missing.
+    MissingField(&'static str),
+    /// The session ID is empty, too long or contains disallowed characters.
+    InvalidSessionId,
+    /// The number of chunks is `0`, larger than the max number of chunks, or different from the one sent before.
+    InvalidChunkCount,
+    /// The chunk index is not smaller than the number of chunks.
+    ChunkIndexOutOfRange,
+    /// The chunk is encrypted.
+    EncryptedChunk,
+    /// The chunks of the session are larger than the max size of a session in total.
+    SessionTooLarge,
+    IOError(io::Error),
+}
+
+impl From<io::Error> for UploadSessionError {
+    #[inline]
+    fn from(err: io::Error) -> UploadSessionError {
+        UploadSessionError::IOError(err)
+    }
+}
+
+impl Display for UploadSessionError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        match self {
+            UploadSessionError::MissingField(field) => {
+                f.write_fmt(format_args!("The field `{}` is missing.", field))
+            },
+            UploadSessionError::InvalidSessionId => f.write_str("The session ID is invalid."),
+            UploadSessionError::InvalidChunkCount => {
+                f.write_str("The number of chunks is invalid.")
+            },
+            UploadSessionError::ChunkIndexOutOfRange => {
+                f.write_str("The chunk index is out of range.")
+            },
+            UploadSessionError::EncryptedChunk => {
+                f.write_str("Encrypted chunks are not supported.")
+            },
+            UploadSessionError::SessionTooLarge => f.write_str("The uploaded file is too large."),
+            UploadSessionError::IOError(err) => Display::fmt(err, f),
+        }
+    }
+}
+
+impl Error for UploadSessionError {}
+
+impl UploadSession {
+    /// Create an `UploadSession` instance. Assembled files are stored in `dir`, which should be created before.
+    #[inline]
+    pub fn new<P: Into<PathBuf>>(dir: P) -> UploadSession {
+        UploadSession {
+            dir:                 dir.into(),
+            max_chunks:          DEFAULT_MAX_CHUNKS,
+            max_size:            DEFAULT_MAX_SIZE,
+            expiry:              None,
+            file_name_generator: Arc::new(TimestampFileNameGenerator),
+            sessions:            Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Create an `UploadSession` instance with the sessions persisted in `dir` by a previous instance, such as before the process crashed. The chunks whose files are missing or do not match their sizes (or their hashes) are dropped, so that the clients can send them again. The expired sessions and the sessions whose manifests cannot be read are deleted.
+    ///
+    /// A session whose chunks have all been received but which has not been assembled is assembled when any of its chunks is sent again.
+    ///
+    /// This method does blocking I/O, so it should be called before the Rocket instance is launched, or in `rocket::tokio::task::spawn_blocking`.
+    pub fn recover<P: Into<PathBuf>>(dir: P) -> Result<UploadSession, io::Error> {
+        let upload_session = UploadSession::new(dir);
+
+        let sessions_dir = upload_session.dir.join(SESSIONS_DIR_NAME);
+
+        let entries = match std_fs::read_dir(&sessions_dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(upload_session),
+            Err(err) => return Err(err),
+        };
+
+        let now = SystemTime::now();
+
+        let mut sessions = upload_session.sessions.lock().unwrap();
+
+        for entry in entries {
+            let entry = entry?;
+
+            if !entry.file_type().map_or(false, |file_type| file_type.is_dir()) {
+                continue;
+            }
+
+            let session_dir = entry.path();
+
+            let session_id = match entry.file_name().into_string() {
+                Ok(session_id) => session_id,
+                Err(_) => continue,
+            };
+
+            let session = match load_session(&session_dir) {
+                Ok(session) => session,
+                // The session was being created when the process stopped, or its manifest is corrupt. The other sessions can still be recovered.
+                Err(_) => {
+                    let _ = std_fs::remove_dir_all(&session_dir);
+
+                    continue;
+                },
+            };
+
+            if session.expires.map_or(false, |expires| expires <= now) {
+                let _ = std_fs::remove_dir_all(&session_dir);
+
+                continue;
+            }
+
+            sessions.insert(session_id, Arc::new(Mutex::new(session)));
+        }
+
+        drop(sessions);
+
+        Ok(upload_session)
+    }
+
+    /// Set the max number of chunks of a session. The default value is `10000`.
+    #[inline]
+    pub fn max_chunks(mut self, max_chunks: u64) -> UploadSession {
+        self.max_chunks = max_chunks;
+        self
+    }
+
+    /// Set the max total size of the chunks of a session, which is the max size of an assembled file. The default value is 4 GiB.
+    #[inline]
+    pub fn max_size(mut self, max_size: u64) -> UploadSession {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Set the generator of the names of the assembled files. The default one is `TimestampFileNameGenerator`.
+    #[inline]
+    pub fn file_name_generator(
+        mut self,
+        file_name_generator: Arc<dyn FileNameGenerator>,
+    ) -> UploadSession {
+        self.file_name_generator = file_name_generator;
+        self
+    }
+
+    /// Set how long a session is kept after its last chunk is accepted. The chunks of an expired session are dropped when the next chunk of the session is sent, when the `remove_expired` method is called, or when the sessions are recovered. By default, sessions do not expire.
+    #[inline]
+    pub fn expiry(mut self, expiry: Duration) -> UploadSession {
+        self.expiry = Some(expiry);
+        self
+    }
+
+    /// The fields of a chunk request. The size of a chunk is limited by `chunk_size_limit`.
+    pub fn fields(chunk_size_limit: u64) -> Vec<MultipartFormDataField<'static>> {
+        vec![
+            MultipartFormDataField::text("session_id").size_limit(MAX_SESSION_ID_LENGTH as u64),
+            MultipartFormDataField::number::<u64>("chunk_index"),
+            MultipartFormDataField::number::<u64>("chunk_count"),
+            MultipartFormDataField::file("chunk").size_limit(chunk_size_limit),
+        ]
+    }
+
+    /// Take the chunk out of a parsed request and add it to its session. A chunk which has been received is replaced. When all chunks of the session have been received, they are assembled into one file.
+    pub async fn accept(
+        &self,
+        multipart_form_data: &mut MultipartFormData,
+    ) -> Result<UploadSessionStatus, UploadSessionError> {
+        let chunk = multipart_form_data
+            .remove_one_file("chunk")
+            .ok_or(UploadSessionError::MissingField("chunk"))?;
+
+        let request = match self.chunk_request(multipart_form_data, &chunk) {
+            Ok(request) => request,
+            Err(err) => {
+                delete_chunk(&chunk);
+
+                return Err(err);
+            },
+        };
+
+        #[cfg(feature = "sha2")]
+        let sha256 = match hash_file(&chunk.path).await {
+            Ok(sha256) => sha256,
+            Err(err) => {
+                delete_chunk(&chunk);
+
+                return Err(err.into());
+            },
+        };
+
+        let upload_session = self.clone();
+
+        let added = tokio::task::spawn_blocking(move || {
+            upload_session.add_chunk(
+                request,
+                chunk,
+                #[cfg(feature = "sha2")]
+                sha256,
+            )
+        })
+        .await
+        .map_err(|err| io::Error::new(io::ErrorKind::Other, err))??;
+
+        match added {
+            Added::Complete(session_id, chunks) => self
+                .assemble(&session_id, chunks)
+                .await
+                .map(|file| UploadSessionStatus::Complete(Box::new(file))),
+            Added::Incomplete(received, count) => Ok(UploadSessionStatus::Incomplete {
+                received,
+                count,
+            }),
+        }
+    }
+
+    /// Take the fields of a chunk request out of a parsed request and check them.
+    fn chunk_request(
+        &self,
+        multipart_form_data: &mut MultipartFormData,
+        chunk: &FileField,
+    ) -> Result<ChunkRequest, UploadSessionError> {
+        let session_id = match multipart_form_data.remove_one_text("session_id") {
+            Some(field) => field.text,
+            None => return Err(UploadSessionError::MissingField("session_id")),
+        };
+
+        if session_id.is_empty()
+            || session_id.len() > MAX_SESSION_ID_LENGTH
+            || !session_id.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+        {
+            return Err(UploadSessionError::InvalidSessionId);
+        }
+
+        let chunk_index = match multipart_form_data.remove_one_number("chunk_index") {
+            Some(field) => match field.number.get::<u64>() {
+                Some(chunk_index) => chunk_index,
+                None => return Err(UploadSessionError::ChunkIndexOutOfRange),
+            },
+            None => return Err(UploadSessionError::MissingField("chunk_index")),
+        };
+
+        let chunk_count = match multipart_form_data.remove_one_number("chunk_count") {
+            Some(field) => match field.number.get::<u64>() {
+                Some(chunk_count) if chunk_count > 0 && chunk_count <= self.max_chunks => {
+                    Some(chunk_count)
+                },
+                _ => return Err(UploadSessionError::InvalidChunkCount),
+            },
+            None => None,
+        };
+
+        if chunk.encryption.is_some() {
+            return Err(UploadSessionError::EncryptedChunk);
+        }
+
+        Ok(ChunkRequest {
+            session_id,
+            chunk_index,
+            chunk_count,
+        })
+    }
+
+    /// Get the session of a chunk, which is created if it does not exist, and lock it. The sessions are only locked while the session is being looked up.
+    fn lock_session<T>(
+        &self,
+        session_id: &str,
+        f: impl FnOnce(&Arc<Mutex<Chunks>>, &mut Chunks) -> T,
+    ) -> T {
+        loop {
+            let session =
+                self.sessions.lock().unwrap().entry(session_id.to_string()).or_default().clone();
+
+            let mut chunks = session.lock().unwrap();
+
+            // The session was assembled or deleted while the chunk was waiting for it
+            if chunks.removed {
+                continue;
+            }
+
+            return f(&session, &mut chunks);
+        }
+    }
+
+    /// Add a chunk to its session and persist the session. Return the session if it is complete, or the number of received chunks and the number of chunks if it is not. The chunk is deleted if it cannot be added.
+    ///
+    /// This method does blocking I/O, and only locks the session of the chunk while doing it.
+    fn add_chunk(
+        &self,
+        request: ChunkRequest,
+        mut chunk: FileField,
+        #[cfg(feature = "sha2")] sha256: [u8; 32],
+    ) -> Result<Added, UploadSessionError> {
+        let ChunkRequest {
+            session_id,
+            chunk_index,
+            chunk_count,
+        } = request;
+
+        let session_dir = self.dir.join(SESSIONS_DIR_NAME).join(&session_id);
+
+        self.lock_session(&session_id, |session_ref, session| {
+            macro_rules! fail {
+                ($err:expr) => {{
+                    delete_chunk(&chunk);
+
+                    return Err($err);
+                }};
+            }
+
+            let now = SystemTime::now();
+
+            if session.expires.map_or(false, |expires| expires <= now) {
+                for chunk in std::mem::take(session).chunks.values() {
+                    delete_chunk(chunk);
+                }
+            }
+
+            if let Some(chunk_count) = chunk_count {
+                match session.count {
+                    Some(count) if count != chunk_count => {
+                        fail!(UploadSessionError::InvalidChunkCount)
+                    },
+                    _ => session.count = Some(chunk_count),
+                }
+            }
+
+            if chunk_index >= session.count.unwrap_or(self.max_chunks) {
+                fail!(UploadSessionError::ChunkIndexOutOfRange);
+            }
+
+            // A chunk which has been received is replaced, so it is not counted
+            let size = session
+                .chunks
+                .iter()
+                .filter(|(index, _)| **index != chunk_index)
+                .fold(chunk.size, |size, (_, chunk)| size.saturating_add(chunk.size));
+
+            if size > self.max_size {
+                fail!(UploadSessionError::SessionTooLarge);
+            }
+
+            if let Some(old_chunk) = session.chunks.remove(&chunk_index) {
+                delete_chunk(&old_chunk);
+            }
+
+            let path = session_dir.join(format!("chunk-{}", chunk_index));
+
+            if let Err(err) = std_fs::create_dir_all(&session_dir)
+                .and_then(|_| move_file(&chunk.path, path.clone()))
+                .and_then(|_| std_fs::File::open(&path)?.sync_all())
+            {
+                fail!(err.into());
+            }
+
+            chunk.path = path;
+
+            session.chunks.insert(chunk_index, chunk);
+            #[cfg(feature = "sha2")]
+            session.hashes.insert(chunk_index, sha256);
+            session.expires = self.expiry.map(|expiry| now + expiry);
+
+            if let Err(err) = save_session(&session_dir, session) {
+                let chunk = session.chunks.remove(&chunk_index).unwrap();
+
+                delete_chunk(&chunk);
+
+                return Err(err.into());
+            }
+
+            let received = session.chunks.len() as u64;
+
+            if session.count == Some(received) {
+                let mut sessions = self.sessions.lock().unwrap();
+
+                if sessions.get(&session_id).map_or(false, |s| Arc::ptr_eq(s, session_ref)) {
+                    sessions.remove(&session_id);
+                }
+
+                drop(sessions);
+
+                let chunks = std::mem::take(session);
+
+                session.removed = true;
+
+                Ok(Added::Complete(session_id.clone(), chunks))
+            } else {
+                Ok(Added::Incomplete(received, session.count))
+            }
+        })
+    }
+
+    /// Concatenate the chunks into a new file in `dir` and delete them.
+    async fn assemble(
+        &self,
+        session_id: &str,
+        chunks: Chunks,
+    ) -> Result<FileField, UploadSessionError> {
+        let created =
+            create_new_file(&self.dir, self.file_name_generator.as_ref(), "chunk", None).await;
+
+        let result = match created {
+            Ok((file, path)) => match concatenate(file, &chunks).await {
+                Ok(size) => Ok((path, size)),
+                Err(err) => {
+                    let _ = fs::remove_file(&path).await;
+
+                    Err(err)
+                },
+            },
+            Err(err) => Err(err),
+        };
+
+        for chunk in chunks.chunks.values() {
+            delete_chunk(chunk);
+        }
+
+        let _ = fs::remove_dir_all(self.dir.join(SESSIONS_DIR_NAME).join(session_id)).await;
+
+        let (path, size) = result?;
+
+        let first = chunks.chunks.into_values().next().unwrap();
+
+        Ok(FileField {
+            content_type: first.content_type,
+            file_name: first.file_name,
+            disposition_params: first.disposition_params,
+            original_name: first.original_name,
+            index: 0,
+            path,
+            size,
+            content_length: None,
+            derivatives: Vec::new(),
+            was_deduplicated: false,
+            persistent: false,
+            encryption: None,
+        })
+    }
+
+    /// The indexes of the chunks of a session which have been received.
+    pub fn received(&self, session_id: &str) -> Option<Vec<u64>> {
+        let session = self.sessions.lock().unwrap().get(session_id)?.clone();
+
+        let session = session.lock().unwrap();
+
+        Some(session.chunks.keys().copied().collect())
+    }
+
+    /// Cancel a session and delete its chunks. The files are deleted in the current thread.
+    pub fn abort(&self, session_id: &str) {
+        let session = self.sessions.lock().unwrap().remove(session_id);
+
+        if let Some(session) = session {
+            let mut session = session.lock().unwrap();
+
+            session.removed = true;
+
+            for chunk in session.chunks.values() {
+                delete_chunk(chunk);
+            }
+
+            let _ = std_fs::remove_dir_all(self.dir.join(SESSIONS_DIR_NAME).join(session_id));
+        }
+    }
+
+    /// Delete the expired sessions along with their chunks. Return the number of deleted sessions. The files are deleted in the current thread.
+    pub fn remove_expired(&self) -> usize {
+        let now = SystemTime::now();
+
+        let mut sessions = self.sessions.lock().unwrap();
+
+        let mut expired = Vec::new();
+
+        sessions.retain(|session_id, session| {
+            // A session which is locked is accepting a chunk, so it is extended
+            let mut session = match session.try_lock() {
+                Ok(session) => session,
+                Err(_) => return true,
+            };
+
+            if session.expires.map_or(false, |expires| expires <= now) {
+                session.removed = true;
+
+                expired.push((session_id.clone(), std::mem::take(&mut session.chunks)));
+
+                false
+            } else {
+                true
+            }
+        });
+
+        drop(sessions);
+
+        for (session_id, chunks) in expired.iter() {
+            for chunk in chunks.values() {
+                delete_chunk(chunk);
+            }
+
+            let _ = std_fs::remove_dir_all(self.dir.join(SESSIONS_DIR_NAME).join(session_id));
+        }
+
+        expired.len()
+    }
+}
+
+/// Write the manifest of a session to a temporary file and rename it over the old one, so that a crash leaves either the old manifest or the new one.
+fn save_session(session_dir: &Path, session: &Chunks) -> Result<(), io::Error> {
+    let manifest = SessionManifest {
+        version: SESSION_MANIFEST_VERSION,
+        count:   session.count,
+        expires: session
+            .expires
+            .map(|expires| expires.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()),
+        chunks:  session
+            .chunks
+            .iter()
+            .map(|(index, chunk)| {
+                #[cfg(feature = "sha2")]
+                let sha256 = session
+                    .hashes
+                    .get(index)
+                    .map(|hash| hash.iter().map(|b| format!("{:02x}", b)).collect());
+                #[cfg(not(feature = "sha2"))]
+                let sha256 = None;
+
+                ChunkManifest {
+                    index: *index,
+                    size: chunk.size,
+                    sha256,
+                    content_type: chunk.content_type.as_ref().map(|m| m.to_string()),
+                    file_name: chunk.file_name.clone(),
+                }
+            })
+            .collect(),
+    };
+
+    let temporary_path = session_dir.join(format!("{}.tmp", SESSION_MANIFEST_FILE_NAME));
+
+    let mut file = std_fs::File::create(&temporary_path)?;
+
+    file.write_all(&serde_json::to_vec(&manifest)?)?;
+    file.sync_all()?;
+
+    std_fs::rename(&temporary_path, session_dir.join(SESSION_MANIFEST_FILE_NAME))?;
+
+    // Persist the rename
+    #[cfg(unix)]
+    std_fs::File::open(session_dir)?.sync_all()?;
+
+    Ok(())
+}
+
+/// Read the manifest of a session and check its chunks.
+fn load_session(session_dir: &Path) -> Result<Chunks, io::Error> {
+    let manifest: SessionManifest =
+        serde_json::from_slice(&std_fs::read(session_dir.join(SESSION_MANIFEST_FILE_NAME))?)?;
+
+    if manifest.version != SESSION_MANIFEST_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "The version of the upload session manifest is not supported.",
+        ));
+    }
+
+    let mut session = Chunks {
+        count: manifest.count,
+        expires: manifest.expires.map(|secs| UNIX_EPOCH + Duration::from_secs(secs)),
+        ..Chunks::default()
+    };
+
+    for chunk in manifest.chunks {
+        let content_type = match chunk.content_type {
+            Some(content_type) => Some(Mime::from_str(&content_type).map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "The content type of a chunk is invalid.",
+                )
+            })?),
+            None => None,
+        };
+
+        let path = session_dir.join(format!("chunk-{}", chunk.index));
+
+        // The chunk may not have been written completely
+        if std_fs::metadata(&path).map(|metadata| metadata.len()).ok() != Some(chunk.size) {
+            let _ = std_fs::remove_file(&path);
+
+            continue;
+        }
+
+        #[cfg(feature = "sha2")]
+        if let Some(sha256) = chunk.sha256 {
+            let hash = hash_file_sync(&path)?;
+
+            if hash.iter().map(|b| format!("{:02x}", b)).collect::<String>() != sha256 {
+                let _ = std_fs::remove_file(&path);
+
+                continue;
+            }
+
+            session.hashes.insert(chunk.index, hash);
+        }
+
+        let file = FileField {
+            content_type,
+            file_name: chunk.file_name,
+            disposition_params: HashMap::new(),
+            original_name: None,
+            index: 0,
+            path,
+            size: chunk.size,
+            content_length: None,
+            derivatives: Vec::new(),
+            was_deduplicated: false,
+            persistent: false,
+            encryption: None,
+        };
+
+        session.chunks.insert(chunk.index, file);
+    }
+
+    Ok(session)
+}
+
+/// Compute the SHA-256 hash of a chunk.
+#[cfg(feature = "sha2")]
+async fn hash_file(path: &Path) -> Result<[u8; 32], io::Error> {
+    use rocket::tokio::io::AsyncReadExt;
+    use sha2::{Digest, Sha256};
+
+    let mut file = File::open(path).await?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+
+    loop {
+        match file.read(&mut buffer).await? {
+            0 => break,
+            n => hasher.update(&buffer[..n]),
+        }
+    }
+
+    Ok(hasher.finalize().into())
+}
+
+/// Compute the SHA-256 hash of a recovered chunk.
+#[cfg(feature = "sha2")]
+fn hash_file_sync(path: &Path) -> Result<[u8; 32], io::Error> {
+    use std::io::Read;
+
+    use sha2::{Digest, Sha256};
+
+    let mut file = std_fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+
+    loop {
+        match file.read(&mut buffer)? {
+            0 => break,
+            n => hasher.update(&buffer[..n]),
+        }
+    }
+
+    Ok(hasher.finalize().into())
+}
+
+async fn concatenate(mut file: File, chunks: &Chunks) -> Result<u64, io::Error> {
+    let mut size = 0;
+
+    for chunk in chunks.chunks.values() {
+        let mut reader = chunk.open().await?;
+
+        size += tokio::io::copy(&mut reader, &mut file).await?;
+    }
+
+    file.flush().await?;
+
+    Ok(size)
+}
+
+#[inline]
+fn delete_chunk(chunk: &FileField) {
+    for path in chunk.paths() {
+        let _ = std_fs::remove_file(path);
+    }
+}
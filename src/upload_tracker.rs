@@ -0,0 +1,177 @@
+use std::{
+    collections::HashMap,
+    io,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use rocket::{
+    get,
+    http::ContentType,
+    response::stream::{Event, EventStream},
+    routes,
+    tokio::{
+        io::{AsyncRead, ReadBuf},
+        sync::watch,
+    },
+    Data, Route, State,
+};
+
+use crate::{MultipartFormData, MultipartFormDataError, MultipartFormDataOptions};
+
+/// The progress of an upload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct UploadProgress {
+    /// The number of bytes of the HTTP body which have been read.
+    pub bytes_read: u64,
+    /// Whether parsing has ended.
+    pub finished:   bool,
+    /// Whether parsing has failed.
+    pub failed:     bool,
+}
+
+/// The shared state of upload progresses, which should be managed by Rocket.
+///
+/// ```rust,no_run
+/// # #[macro_use] extern crate rocket;
+/// use rocket_multipart_form_data::UploadTrackerState;
+///
+/// #[launch]
+/// fn rocket() -> _ {
+///     rocket::build()
+///         .manage(UploadTrackerState::new())
+///         .mount("/uploads", UploadTrackerState::routes())
+/// }
+/// ```
+///
+/// The progress of an upload can then be received from `GET /uploads/progress/<tracker_id>` as server-sent events.
+#[derive(Debug, Clone, Default)]
+pub struct UploadTrackerState {
+    senders: Arc<Mutex<HashMap<String, watch::Sender<UploadProgress>>>>,
+}
+
+impl UploadTrackerState {
+    /// Create an `UploadTrackerState` instance.
+    #[inline]
+    pub fn new() -> UploadTrackerState {
+        UploadTrackerState::default()
+    }
+
+    /// The routes which stream upload progresses as server-sent events.
+    #[inline]
+    pub fn routes() -> Vec<Route> {
+        routes![upload_progress]
+    }
+
+    /// Subscribe to the progress of an upload. The upload does not need to be started yet.
+    pub fn subscribe(&self, tracker_id: &str) -> watch::Receiver<UploadProgress> {
+        self.sender(tracker_id).subscribe()
+    }
+
+    /// Get the current progress of an upload.
+    pub fn progress(&self, tracker_id: &str) -> Option<UploadProgress> {
+        self.senders.lock().unwrap().get(tracker_id).map(|sender| *sender.borrow())
+    }
+
+    /// Stop tracking an upload.
+    pub fn remove(&self, tracker_id: &str) {
+        self.senders.lock().unwrap().remove(tracker_id);
+    }
+
+    fn sender(&self, tracker_id: &str) -> watch::Sender<UploadProgress> {
+        self.senders
+            .lock()
+            .unwrap()
+            .entry(tracker_id.to_string())
+            .or_insert_with(|| watch::channel(UploadProgress::default()).0)
+            .clone()
+    }
+}
+
+impl MultipartFormData {
+    /// Parse multipart/form-data from the HTTP body and report the progress to `tracker` under `tracker_id`.
+    ///
+    /// The progress is removed from `tracker` when parsing ends if nobody is subscribing to it.
+    pub async fn parse_tracked(
+        content_type: &ContentType,
+        data: Data<'_>,
+        options: MultipartFormDataOptions<'_>,
+        tracker: &UploadTrackerState,
+        tracker_id: &str,
+    ) -> Result<MultipartFormData, MultipartFormDataError> {
+        let boundary = MultipartFormData::boundary_from_content_type(content_type)?;
+
+        let sender = tracker.sender(tracker_id);
+
+        let reader = TrackedReader {
+            inner:  data.open(options.read_limit().into()),
+            sender: &sender,
+        };
+
+        let result = MultipartFormData::parse_reader(reader, boundary, options).await;
+
+        sender.send_modify(|progress| {
+            progress.finished = true;
+            progress.failed = result.is_err();
+        });
+
+        if sender.receiver_count() == 0 {
+            tracker.remove(tracker_id);
+        }
+
+        result
+    }
+}
+
+/// Count the bytes read from the inner reader.
+struct TrackedReader<'a, R> {
+    inner:  R,
+    sender: &'a watch::Sender<UploadProgress>,
+}
+
+impl<'a, R: AsyncRead + Unpin> AsyncRead for TrackedReader<'a, R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<Result<(), io::Error>> {
+        let this = self.get_mut();
+        let before = buf.filled().len();
+
+        let result = Pin::new(&mut this.inner).poll_read(cx, buf);
+
+        let n = (buf.filled().len() - before) as u64;
+
+        if n > 0 {
+            this.sender.send_modify(|progress| progress.bytes_read += n);
+        }
+
+        result
+    }
+}
+
+#[get("/progress/<tracker_id>")]
+fn upload_progress(tracker_id: &str, tracker: &State<UploadTrackerState>) -> EventStream![] {
+    let mut receiver = tracker.subscribe(tracker_id);
+    let tracker = tracker.inner().clone();
+    let tracker_id = tracker_id.to_string();
+
+    EventStream! {
+        loop {
+            let progress = *receiver.borrow_and_update();
+
+            yield Event::data(format!(
+                "{{\"bytes_read\":{},\"finished\":{},\"failed\":{}}}",
+                progress.bytes_read, progress.finished, progress.failed
+            ))
+            .event("progress");
+
+            if progress.finished || receiver.changed().await.is_err() {
+                break;
+            }
+        }
+
+        tracker.remove(&tracker_id);
+    }
+}
@@ -0,0 +1,581 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+
+use quick_xml::{
+    events::{BytesStart, Event},
+    Reader,
+};
+use serde::de::{
+    value, DeserializeOwned, DeserializeSeed, Deserializer, Error as _, IntoDeserializer,
+    MapAccess, SeqAccess, Visitor,
+};
+
+use crate::{mime::Mime, value_deserializer::ValueDeserializer};
+
+/// The max depth of the nested elements of an XML document.
+const MAX_DEPTH: usize = 256;
+
+/// A node in the content of an XML element. Comments and processing instructions are not kept, and CDATA sections are merged into the text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XmlNode {
+    Element(XmlElement),
+    Text(String),
+}
+
+/// An XML element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XmlElement {
+    /// The name of the element, including its namespace prefix if any.
+    pub name:       String,
+    /// The attributes in the order in which they appear. The references in the values are resolved.
+    pub attributes: Vec<(String, String)>,
+    pub children:   Vec<XmlNode>,
+}
+
+impl XmlElement {
+    /// Get the value of an attribute.
+    #[inline]
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(n, _)| n == name).map(|(_, value)| value.as_str())
+    }
+
+    /// Get the child elements.
+    #[inline]
+    pub fn elements(&self) -> impl Iterator<Item = &XmlElement> {
+        self.children.iter().filter_map(|node| match node {
+            XmlNode::Element(element) => Some(element),
+            XmlNode::Text(_) => None,
+        })
+    }
+
+    /// Get the first child element of a specific name.
+    #[inline]
+    pub fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.elements().find(|element| element.name == name)
+    }
+
+    /// Get the text directly in this element, without the text of the child elements.
+    #[inline]
+    pub fn text(&self) -> String {
+        self.children
+            .iter()
+            .filter_map(|node| match node {
+                XmlNode::Text(text) => Some(text.as_str()),
+                XmlNode::Element(_) => None,
+            })
+            .collect()
+    }
+
+    /// Deserialize this element. The attributes and the child elements are the fields of a struct or a map, and the child elements of the same name can be collected into a sequence. The text of an element which has attributes or child elements is the `$text` field.
+    #[inline]
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, XmlError> {
+        T::deserialize(ElementDeserializer(self))
+            .map_err(|err| XmlError {
+                line: None, message: err.to_string()
+            })
+    }
+}
+
+/// An error of an XML field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct XmlError {
+    /// The line where the document is malformed, starting from `1`. It is `None` for the errors of deserialization.
+    pub line:    Option<usize>,
+    pub message: String,
+}
+
+impl Display for XmlError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        match self.line {
+            Some(line) => f.write_fmt(format_args!("line {}: {}", line, self.message)),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl Error for XmlError {}
+
+/// A parsed XML field.
+#[derive(Debug, Clone)]
+pub struct XmlField {
+    pub content_type:       Option<Mime>,
+    pub file_name:          Option<String>,
+    /// The extra parameters of the `Content-Disposition` header.
+    pub disposition_params: HashMap<String, String>,
+    /// The field name sent by the client, if it has been changed by the key normalization.
+    pub original_name:      Option<String>,
+    /// The position of this part in the HTTP body, starting from `0`.
+    pub index:              usize,
+    /// The root element of the document.
+    pub root:               XmlElement,
+}
+
+impl XmlField {
+    /// Deserialize the root element.
+    #[inline]
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T, XmlError> {
+        self.root.deserialize()
+    }
+}
+
+/// Parse an XML document by `quick-xml`. Document type declarations are rejected, so no entities other than the predefined ones and the character references can be used, and nothing outside the document is ever loaded.
+pub(crate) fn parse_xml(text: &str) -> Result<XmlElement, XmlError> {
+    let text = text.strip_prefix('\u{FEFF}').unwrap_or(text);
+
+    let mut reader = Reader::from_str(text);
+
+    let error = |position: u64, message: String| {
+        let position = usize::try_from(position).unwrap_or(usize::MAX).min(text.len());
+
+        XmlError {
+            line: Some(text.as_bytes()[..position].iter().filter(|&&b| b == b'\n').count() + 1),
+            message,
+        }
+    };
+
+    let mut stack: Vec<XmlElement> = Vec::new();
+    let mut root = None;
+
+    loop {
+        let event =
+            reader.read_event().map_err(|err| error(reader.error_position(), err.to_string()))?;
+
+        let position = reader.buffer_position();
+
+        match event {
+            Event::Start(start) => {
+                if root.is_some() {
+                    return Err(error(position, "There is content after the root element.".into()));
+                }
+
+                if stack.len() >= MAX_DEPTH {
+                    return Err(error(position, "The elements are nested too deeply.".into()));
+                }
+
+                stack.push(start_element(&start).map_err(|message| error(position, message))?);
+            },
+            Event::Empty(start) => {
+                if root.is_some() {
+                    return Err(error(position, "There is content after the root element.".into()));
+                }
+
+                let element = start_element(&start).map_err(|message| error(position, message))?;
+
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(XmlNode::Element(element)),
+                    None => root = Some(element),
+                }
+            },
+            Event::End(_) => {
+                // The names of the end tags are checked by `quick-xml`
+                let element = match stack.pop() {
+                    Some(element) => element,
+                    None => return Err(error(position, "An end tag is not expected.".into())),
+                };
+
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(XmlNode::Element(element)),
+                    None => root = Some(element),
+                }
+            },
+            Event::Text(t) => {
+                let t = t.unescape().map_err(|err| error(position, err.to_string()))?;
+
+                match stack.last_mut() {
+                    Some(element) => push_text(element, &t),
+                    None if t.trim().is_empty() => (),
+                    None if root.is_some() => {
+                        return Err(error(
+                            position,
+                            "There is content after the root element.".into(),
+                        ))
+                    },
+                    None => return Err(error(position, "The root element is missing.".into())),
+                }
+            },
+            Event::CData(t) => match stack.last_mut() {
+                Some(element) => push_text(element, &String::from_utf8_lossy(&t)),
+                None => {
+                    return Err(error(
+                        position,
+                        "A CDATA section is outside of the root element.".into(),
+                    ))
+                },
+            },
+            Event::DocType(_) => {
+                return Err(error(
+                    position,
+                    "Document type declarations are not allowed because they can declare entities."
+                        .into(),
+                ));
+            },
+            Event::Comment(_) | Event::PI(_) | Event::Decl(_) => (),
+            Event::Eof => {
+                if let Some(element) = stack.last() {
+                    return Err(error(
+                        position,
+                        format!("The element `<{}>` is not closed.", element.name),
+                    ));
+                }
+
+                return root.ok_or_else(|| error(position, "The root element is missing.".into()));
+            },
+        }
+    }
+}
+
+/// Create an element from its start tag. The references in the attribute values are resolved, and duplicated attributes are rejected by `quick-xml`.
+fn start_element(start: &BytesStart) -> Result<XmlElement, String> {
+    let mut attributes = Vec::new();
+
+    for attribute in start.attributes() {
+        let attribute = attribute.map_err(|err| err.to_string())?;
+
+        if attribute.value.contains(&b'<') {
+            return Err("An attribute value contains `<`.".to_string());
+        }
+
+        let value = attribute.unescape_value().map_err(|err| err.to_string())?;
+
+        attributes.push((
+            String::from_utf8_lossy(attribute.key.as_ref()).into_owned(),
+            value.into_owned(),
+        ));
+    }
+
+    Ok(XmlElement {
+        name: String::from_utf8_lossy(start.name().as_ref()).into_owned(),
+        attributes,
+        children: Vec::new(),
+    })
+}
+
+#[inline]
+fn push_text(element: &mut XmlElement, text: &str) {
+    match element.children.last_mut() {
+        Some(XmlNode::Text(t)) => t.push_str(text),
+        _ => element.children.push(XmlNode::Text(text.to_string())),
+    }
+}
+
+/// Deserialize an element.
+struct ElementDeserializer<'a>(&'a XmlElement);
+
+impl<'a> ElementDeserializer<'a> {
+    /// Whether the element only has text.
+    #[inline]
+    fn is_text(&self) -> bool {
+        self.0.attributes.is_empty() && self.0.elements().next().is_none()
+    }
+}
+
+macro_rules! deserialize_text {
+    ($($method:ident)*) => {
+        $(
+            #[inline]
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                ValueDeserializer(self.0.text().trim()).$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> Deserializer<'de> for ElementDeserializer<'a> {
+    type Error = value::Error;
+
+    deserialize_text! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char deserialize_str
+        deserialize_string deserialize_bytes deserialize_byte_buf deserialize_identifier
+    }
+
+    #[inline]
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.is_text() {
+            visitor.visit_str(self.0.text().trim())
+        } else {
+            self.deserialize_map(visitor)
+        }
+    }
+
+    #[inline]
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.is_text() && self.0.text().trim().is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    #[inline]
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    #[inline]
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+
+    #[inline]
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    #[inline]
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(ElementSeq(self.0.elements().collect::<Vec<_>>().into_iter()))
+    }
+
+    #[inline]
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    #[inline]
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let mut entries: Vec<(&str, Entry)> = self
+            .0
+            .attributes
+            .iter()
+            .map(|(name, value)| (name.as_str(), Entry::Attribute(value)))
+            .collect();
+
+        for element in self.0.elements() {
+            let name = element.name.as_str();
+
+            match entries.iter_mut().find(|(n, _)| *n == name) {
+                Some((_, Entry::Elements(elements))) => elements.push(element),
+                _ => entries.push((name, Entry::Elements(vec![element]))),
+            }
+        }
+
+        let text = self.0.text();
+
+        if !text.trim().is_empty() {
+            entries.push(("$text", Entry::Text(text)));
+        }
+
+        visitor.visit_map(ElementMap {
+            entries: entries.into_iter(), value: None
+        })
+    }
+
+    #[inline]
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_map(visitor)
+    }
+
+    #[inline]
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        ValueDeserializer(self.0.text().trim()).deserialize_enum(name, variants, visitor)
+    }
+
+    #[inline]
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_unit()
+    }
+}
+
+/// A field of an element which is deserialized as a struct or a map.
+enum Entry<'a> {
+    Attribute(&'a str),
+    /// The child elements of the same name.
+    Elements(Vec<&'a XmlElement>),
+    Text(String),
+}
+
+struct ElementMap<'a> {
+    entries: std::vec::IntoIter<(&'a str, Entry<'a>)>,
+    value:   Option<Entry<'a>>,
+}
+
+impl<'de, 'a> MapAccess<'de> for ElementMap<'a> {
+    type Error = value::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        match self.entries.next() {
+            Some((name, entry)) => {
+                self.value = Some(entry);
+
+                seed.deserialize(name.into_deserializer()).map(Some)
+            },
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        match self.value.take() {
+            Some(Entry::Attribute(value)) => seed.deserialize(ValueDeserializer(value)),
+            Some(Entry::Text(text)) => seed.deserialize(ValueDeserializer(text.trim())),
+            Some(Entry::Elements(elements)) => seed.deserialize(ElementsDeserializer(elements)),
+            None => Err(value::Error::custom("the value is missing")),
+        }
+    }
+}
+
+struct ElementSeq<'a>(std::vec::IntoIter<&'a XmlElement>);
+
+impl<'de, 'a> SeqAccess<'de> for ElementSeq<'a> {
+    type Error = value::Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        match self.0.next() {
+            Some(element) => seed.deserialize(ElementDeserializer(element)).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Deserialize the child elements of the same name, which are a sequence or the first one of them.
+struct ElementsDeserializer<'a>(Vec<&'a XmlElement>);
+
+macro_rules! deserialize_first {
+    ($($method:ident)*) => {
+        $(
+            #[inline]
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+                ElementDeserializer(self.0[0]).$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> Deserializer<'de> for ElementsDeserializer<'a> {
+    type Error = value::Error;
+
+    deserialize_first! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64
+        deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char deserialize_str
+        deserialize_string deserialize_bytes deserialize_byte_buf deserialize_identifier
+        deserialize_unit deserialize_map deserialize_ignored_any
+    }
+
+    #[inline]
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        let element = ElementDeserializer(self.0[0]);
+
+        if self.0.len() == 1 && element.is_text() && element.0.text().trim().is_empty() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    #[inline]
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        if self.0.len() > 1 {
+            self.deserialize_seq(visitor)
+        } else {
+            ElementDeserializer(self.0[0]).deserialize_any(visitor)
+        }
+    }
+
+    #[inline]
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_seq(ElementSeq(self.0.into_iter()))
+    }
+
+    #[inline]
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    #[inline]
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    #[inline]
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        ElementDeserializer(self.0[0]).deserialize_unit_struct(name, visitor)
+    }
+
+    #[inline]
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        ElementDeserializer(self.0[0]).deserialize_newtype_struct(name, visitor)
+    }
+
+    #[inline]
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        ElementDeserializer(self.0[0]).deserialize_struct(name, fields, visitor)
+    }
+
+    #[inline]
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        ElementDeserializer(self.0[0]).deserialize_enum(name, variants, visitor)
+    }
+}
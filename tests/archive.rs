@@ -0,0 +1,249 @@
+mod common;
+
+use rocket_multipart_form_data::{
+    ArchiveLimits, ArchiveViolation, MultipartFormDataError, MultipartFormDataField,
+    MultipartFormDataOptions,
+};
+
+fn zip_local_header(name: &str, data: &[u8]) -> Vec<u8> {
+    let mut header = Vec::new();
+
+    header.extend_from_slice(b"PK\x03\x04");
+    header.extend_from_slice(&[20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    header.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    header.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    header.extend_from_slice(&(name.len() as u16).to_le_bytes());
+    header.extend_from_slice(&[0, 0]);
+    header.extend_from_slice(name.as_bytes());
+    header.extend_from_slice(data);
+
+    header
+}
+
+fn zip_central_directory_header(name: &str, data: &[u8], offset: u32) -> Vec<u8> {
+    let mut header = Vec::new();
+
+    header.extend_from_slice(b"PK\x01\x02");
+    header.extend_from_slice(&[20, 0, 20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+    header.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    header.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    header.extend_from_slice(&(name.len() as u16).to_le_bytes());
+    header.extend_from_slice(&[0; 12]);
+    header.extend_from_slice(&offset.to_le_bytes());
+    header.extend_from_slice(name.as_bytes());
+
+    header
+}
+
+/// Build a stored ZIP archive whose central directory lists `directory` instead of the local entries.
+fn zip(entries: &[(&str, &[u8])], directory: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut archive = Vec::new();
+    let mut offsets = Vec::new();
+
+    for (name, data) in entries {
+        offsets.push(archive.len() as u32);
+        archive.extend(zip_local_header(name, data));
+    }
+
+    let directory_offset = archive.len() as u32;
+
+    for (i, (name, data)) in directory.iter().enumerate() {
+        archive.extend(zip_central_directory_header(
+            name,
+            data,
+            offsets.get(i).copied().unwrap_or(0),
+        ));
+    }
+
+    let directory_size = archive.len() as u32 - directory_offset;
+
+    archive.extend_from_slice(b"PK\x05\x06");
+    archive.extend_from_slice(&[0, 0, 0, 0]);
+    archive.extend_from_slice(&(directory.len() as u16).to_le_bytes());
+    archive.extend_from_slice(&(directory.len() as u16).to_le_bytes());
+    archive.extend_from_slice(&directory_size.to_le_bytes());
+    archive.extend_from_slice(&directory_offset.to_le_bytes());
+    archive.extend_from_slice(&[0, 0]);
+
+    archive
+}
+
+fn tar_header(name: &str, typ: u8, link: &str, size: usize) -> Vec<u8> {
+    let mut header = vec![0; 512];
+
+    header[..name.len()].copy_from_slice(name.as_bytes());
+    header[124..135].copy_from_slice(format!("{:011o}", size).as_bytes());
+    header[156] = typ;
+    header[157..157 + link.len()].copy_from_slice(link.as_bytes());
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+
+    header
+}
+
+fn tar(entries: &[(&str, u8, &str, &[u8])]) -> Vec<u8> {
+    let mut archive = Vec::new();
+
+    for (name, typ, link, data) in entries {
+        archive.extend(tar_header(name, *typ, link, data.len()));
+        archive.extend_from_slice(data);
+        archive.resize(archive.len() + (512 - data.len() % 512) % 512, 0);
+    }
+
+    archive.resize(archive.len() + 1024, 0);
+
+    archive
+}
+
+async fn inspect(
+    name: &str,
+    archive: &[u8],
+    limits: ArchiveLimits,
+) -> Result<(), ArchiveViolation> {
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::file("f").archive_limits(limits),
+    ]);
+
+    let result = common::parse(
+        &format!("archive-{}", name),
+        common::body(&[common::file_part("f", "a", None, archive)]),
+        options,
+    )
+    .await;
+
+    match result {
+        Ok(_) => Ok(()),
+        Err(MultipartFormDataError::ArchiveRejectedError {
+            violation, ..
+        }) => Err(violation),
+        Err(err) => panic!("{:?}", err),
+    }
+}
+
+#[rocket::async_test]
+async fn zip_is_accepted() {
+    let entries: &[(&str, &[u8])] = &[("a.txt", b"hello"), ("dir/b.txt", b"world")];
+
+    assert_eq!(inspect("zip", &zip(entries, entries), ArchiveLimits::default()).await, Ok(()));
+}
+
+#[rocket::async_test]
+async fn zip_traversal_is_rejected() {
+    let entries: &[(&str, &[u8])] = &[("a.txt", b"hello"), ("../evil", b"world")];
+
+    assert_eq!(
+        inspect("zip-traversal", &zip(entries, entries), ArchiveLimits::default()).await,
+        Err(ArchiveViolation::PathTraversal("../evil".to_string()))
+    );
+}
+
+#[rocket::async_test]
+async fn zip_central_directory_traversal_is_rejected() {
+    assert_eq!(
+        inspect(
+            "zip-directory-traversal",
+            &zip(&[("a.txt", b"hello")], &[("/etc/passwd", b"hello")]),
+            ArchiveLimits::default()
+        )
+        .await,
+        Err(ArchiveViolation::PathTraversal("/etc/passwd".to_string()))
+    );
+}
+
+#[rocket::async_test]
+async fn zip_central_directory_mismatch_is_rejected() {
+    assert_eq!(
+        inspect(
+            "zip-directory-name",
+            &zip(&[("a.txt", b"hello")], &[("b.txt", b"hello")]),
+            ArchiveLimits::default()
+        )
+        .await,
+        Err(ArchiveViolation::CentralDirectoryMismatch("b.txt".to_string()))
+    );
+
+    // An entry which is only in the local headers
+    assert_eq!(
+        inspect(
+            "zip-directory-hidden",
+            &zip(&[("a.txt", b"hello"), ("b.txt", b"world")], &[("a.txt", b"hello")]),
+            ArchiveLimits::default()
+        )
+        .await,
+        Err(ArchiveViolation::CentralDirectoryMismatch("b.txt".to_string()))
+    );
+
+    // An entry which is only in the central directory
+    assert_eq!(
+        inspect(
+            "zip-directory-extra",
+            &zip(&[("a.txt", b"hello")], &[("a.txt", b"hello"), ("b.txt", b"world")]),
+            ArchiveLimits::default()
+        )
+        .await,
+        Err(ArchiveViolation::CentralDirectoryMismatch("b.txt".to_string()))
+    );
+}
+
+#[rocket::async_test]
+async fn zip_with_too_many_entries_is_rejected() {
+    let limits = ArchiveLimits {
+        max_entries: 1,
+        ..ArchiveLimits::default()
+    };
+
+    let entries: &[(&str, &[u8])] = &[("a.txt", b"hello"), ("b.txt", b"world")];
+
+    assert_eq!(
+        inspect("zip-entries", &zip(entries, entries), limits).await,
+        Err(ArchiveViolation::TooManyEntries)
+    );
+
+    // The central directory is counted even if the local headers are within the limit
+    assert_eq!(
+        inspect("zip-directory-entries", &zip(&entries[..1], entries), limits).await,
+        Err(ArchiveViolation::TooManyEntries)
+    );
+}
+
+#[rocket::async_test]
+async fn tar_is_accepted() {
+    let archive = tar(&[("a.txt", b'0', "", b"hello"), ("link", b'2', "dir/a.txt", b"")]);
+
+    assert_eq!(inspect("tar", &archive, ArchiveLimits::default()).await, Ok(()));
+}
+
+#[rocket::async_test]
+async fn tar_traversal_is_rejected() {
+    assert_eq!(
+        inspect(
+            "tar-traversal",
+            &tar(&[("../a.txt", b'0', "", b"hello")]),
+            ArchiveLimits::default()
+        )
+        .await,
+        Err(ArchiveViolation::PathTraversal("../a.txt".to_string()))
+    );
+
+    assert_eq!(
+        inspect("tar-link", &tar(&[("link", b'2', "/etc/passwd", b"")]), ArchiveLimits::default())
+            .await,
+        Err(ArchiveViolation::PathTraversal("/etc/passwd".to_string()))
+    );
+
+    // The name in a GNU long name entry
+    let long_name = format!("{}/../../a.txt", "d".repeat(100));
+
+    assert_eq!(
+        inspect(
+            "tar-long-name",
+            &tar(&[
+                ("././@LongLink", b'L', "", long_name.as_bytes()),
+                ("a.txt", b'0', "", b"hello")
+            ]),
+            ArchiveLimits::default()
+        )
+        .await,
+        Err(ArchiveViolation::PathTraversal(long_name))
+    );
+}
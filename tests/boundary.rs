@@ -0,0 +1,43 @@
+use rocket::http::ContentType;
+use rocket_multipart_form_data::{MultipartFormData, MultipartFormDataError};
+
+fn boundary(content_type: &str) -> Result<String, MultipartFormDataError> {
+    let content_type = ContentType::parse_flexible(content_type).unwrap();
+
+    MultipartFormData::boundary_from_content_type(&content_type).map(|b| b.into_owned())
+}
+
+#[test]
+fn boundaries() {
+    for content_type in [
+        "multipart/form-data; boundary=----x",
+        "multipart/form-data; boundary=\"----x\"",
+        "multipart/form-data; BOUNDARY=----x",
+        "Multipart/Form-Data; charset=utf-8; Boundary=\"----x\"",
+        "multipart/form-data; boundary=\"\"; boundary=----x",
+    ] {
+        assert_eq!("----x", boundary(content_type).unwrap(), "{}", content_type);
+    }
+
+    assert_eq!("a b", boundary("multipart/form-data; boundary=\"a b\"").unwrap());
+}
+
+#[test]
+fn errors() {
+    assert!(matches!(
+        boundary("application/x-www-form-urlencoded; boundary=x"),
+        Err(MultipartFormDataError::WrongContentTypeError(_))
+    ));
+
+    for content_type in [
+        "multipart/form-data",
+        "multipart/form-data; charset=utf-8",
+        "multipart/form-data; boundary=\"\"",
+    ] {
+        assert!(
+            matches!(boundary(content_type), Err(MultipartFormDataError::MissingBoundaryError)),
+            "{}",
+            content_type
+        );
+    }
+}
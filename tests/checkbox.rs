@@ -0,0 +1,36 @@
+mod common;
+
+use rocket_multipart_form_data::{MultipartFormDataField, MultipartFormDataOptions};
+
+async fn checked(name: &str, value: Option<&str>) -> bool {
+    let mut parts = Vec::new();
+
+    if let Some(value) = value {
+        parts.push(common::text_part("c", value));
+    }
+
+    parts.push(common::text_part("t", "x"));
+
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::checkbox("c"),
+        MultipartFormDataField::text("t"),
+    ]);
+
+    let multipart_form_data =
+        common::parse(&format!("checkbox-{}", name), common::body(&parts), options).await.unwrap();
+
+    multipart_form_data.bools["c"]
+}
+
+#[rocket::async_test]
+async fn checkbox_values() {
+    assert!(checked("on", Some("on")).await);
+    assert!(checked("yes", Some("yes")).await);
+    assert!(checked("custom", Some("newsletter")).await);
+
+    assert!(!checked("absent", None).await);
+    assert!(!checked("off", Some("off")).await);
+    assert!(!checked("false", Some("False")).await);
+    assert!(!checked("zero", Some("0")).await);
+    assert!(!checked("empty", Some("")).await);
+}
@@ -0,0 +1,35 @@
+mod common;
+
+use rocket_multipart_form_data::{MultipartFormDataField, MultipartFormDataOptions};
+
+#[rocket::async_test]
+async fn chunk_sizes() {
+    let data: String = (0..10_000).map(|i| char::from(b'a' + (i % 26) as u8)).collect();
+
+    for (read_chunk_size, write_chunk_size) in [(0, 0), (1, 3), (7, 0), (4096, 1), (4096, 65536)] {
+        let name = format!("chunk-size-{}-{}", read_chunk_size, write_chunk_size);
+
+        let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+            MultipartFormDataField::file("f"),
+            MultipartFormDataField::text("t"),
+        ]);
+
+        options.temporary_dir = common::temporary_dir(&name);
+        options.read_chunk_size = read_chunk_size;
+        options.write_chunk_size = write_chunk_size;
+
+        let multipart_form_data = common::parse(
+            &name,
+            common::body(&[
+                common::file_part("f", "a.txt", None, &data),
+                common::text_part("t", "hello"),
+            ]),
+            options,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(data, std::fs::read_to_string(&multipart_form_data.files["f"][0].path).unwrap());
+        assert_eq!("hello", multipart_form_data.texts["t"][0].text);
+    }
+}
@@ -0,0 +1,78 @@
+//! Helpers shared by the integration tests. The bodies are delimited by the `XB` boundary.
+
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+
+use rocket_multipart_form_data::{
+    MultipartFormData, MultipartFormDataError, MultipartFormDataOptions,
+};
+
+pub const BOUNDARY: &str = "XB";
+
+/// A part with the parameters of its `Content-Disposition` header, and its content type if any.
+pub fn part(disposition: &str, content_type: Option<&str>, data: impl AsRef<[u8]>) -> Vec<u8> {
+    let mut part = format!("--{}\r\nContent-Disposition: form-data; {}\r\n", BOUNDARY, disposition)
+        .into_bytes();
+
+    if let Some(content_type) = content_type {
+        part.extend_from_slice(format!("Content-Type: {}\r\n", content_type).as_bytes());
+    }
+
+    part.extend_from_slice(b"\r\n");
+    part.extend_from_slice(data.as_ref());
+    part.extend_from_slice(b"\r\n");
+
+    part
+}
+
+/// A part without a file name.
+pub fn text_part(name: &str, value: impl AsRef<[u8]>) -> Vec<u8> {
+    part(&format!("name=\"{}\"", name), None, value)
+}
+
+/// A part with a file name.
+pub fn file_part(
+    name: &str,
+    file_name: &str,
+    content_type: Option<&str>,
+    data: impl AsRef<[u8]>,
+) -> Vec<u8> {
+    part(&format!("name=\"{}\"; filename=\"{}\"", name, file_name), content_type, data)
+}
+
+/// The parts followed by the closing boundary.
+pub fn body<P: AsRef<[u8]>>(parts: &[P]) -> Vec<u8> {
+    let mut body: Vec<u8> = parts.iter().flat_map(|part| part.as_ref().iter().copied()).collect();
+
+    body.extend_from_slice(format!("--{}--\r\n", BOUNDARY).as_bytes());
+
+    body
+}
+
+/// An empty temporary directory for a test.
+pub fn temporary_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rocket-multipart-form-data-{}", name));
+
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    dir
+}
+
+/// Parse a body as if it had been captured to a file. The file is removed afterwards.
+pub async fn parse(
+    name: &str,
+    body: impl AsRef<[u8]>,
+    options: MultipartFormDataOptions<'_>,
+) -> Result<MultipartFormData, MultipartFormDataError> {
+    let path = std::env::temp_dir().join(format!("rocket-multipart-form-data-{}.body", name));
+
+    std::fs::write(&path, body).unwrap();
+
+    let result = MultipartFormData::parse_file(&path, BOUNDARY, options).await;
+
+    std::fs::remove_file(&path).unwrap();
+
+    result
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "sha2")]
+
+mod common;
+
+use rocket_multipart_form_data::{
+    FileStorageMode, MultipartFormDataField, MultipartFormDataOptions, Repetition,
+};
+
+const HELLO_SHA256: &str = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+#[rocket::async_test]
+async fn deduplicated_files() {
+    let dir = common::temporary_dir("content-addressed");
+
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::file("f").repetition(Repetition::infinite()),
+    ]);
+
+    options.temporary_dir = dir.clone();
+    options.file_storage_mode = FileStorageMode::ContentAddressed;
+
+    let multipart_form_data = common::parse(
+        "content-addressed",
+        common::body(&[
+            common::file_part("f", "a.txt", None, "hello"),
+            common::file_part("f", "b.txt", None, "hello"),
+            common::file_part("f", "c.txt", None, "world"),
+        ]),
+        options,
+    )
+    .await
+    .unwrap();
+
+    let files = &multipart_form_data.files["f"];
+
+    assert_eq!(dir.join(HELLO_SHA256), files[0].path);
+    assert!(!files[0].was_deduplicated);
+
+    assert_eq!(files[0].path, files[1].path);
+    assert!(files[1].was_deduplicated);
+    assert_eq!(Some("b.txt"), files[1].file_name.as_deref());
+
+    assert_ne!(files[0].path, files[2].path);
+    assert!(!files[2].was_deduplicated);
+
+    let paths = [files[0].path.clone(), files[2].path.clone()];
+
+    drop(multipart_form_data);
+
+    // Shared files are not deleted automatically
+    assert_eq!("hello", std::fs::read_to_string(&paths[0]).unwrap());
+    assert_eq!("world", std::fs::read_to_string(&paths[1]).unwrap());
+    assert_eq!(2, std::fs::read_dir(&dir).unwrap().count());
+}
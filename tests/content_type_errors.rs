@@ -0,0 +1,62 @@
+use rocket::{
+    http::{ContentType, Status},
+    local::asynchronous::Client,
+    post, routes, Data,
+};
+use rocket_multipart_form_data::{
+    MultipartFormData, MultipartFormDataError, MultipartFormDataField, MultipartFormDataOptions,
+};
+
+#[post("/", data = "<data>")]
+async fn upload(content_type: &ContentType, data: Data<'_>) -> (Status, String) {
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("t"),
+    ]);
+
+    match MultipartFormData::parse(content_type, data, options).await {
+        Ok(_) => (Status::Ok, String::new()),
+        Err(err @ MultipartFormDataError::WrongContentTypeError(_)) => {
+            (Status::UnsupportedMediaType, err.to_string())
+        },
+        Err(err @ MultipartFormDataError::MissingBoundaryError) => {
+            (Status::BadRequest, err.to_string())
+        },
+        Err(err) => (Status::InternalServerError, err.to_string()),
+    }
+}
+
+async fn post(content_type: ContentType) -> (Status, String) {
+    let client = Client::tracked(rocket::build().mount("/", routes![upload])).await.unwrap();
+
+    let response = client.post("/").header(content_type).body("").dispatch().await;
+
+    (response.status(), response.into_string().await.unwrap())
+}
+
+#[rocket::async_test]
+async fn wrong_content_type() {
+    let (status, message) = post(ContentType::JSON).await;
+
+    assert_eq!(Status::UnsupportedMediaType, status);
+
+    // The offending content type is reported
+    assert!(message.contains("application/json"), "{}", message);
+}
+
+#[rocket::async_test]
+async fn missing_boundary() {
+    let (status, _) = post(ContentType::FormData).await;
+
+    assert_eq!(Status::BadRequest, status);
+}
+
+#[test]
+fn client_errors() {
+    assert!(MultipartFormDataError::WrongContentTypeError(Box::new(ContentType::JSON))
+        .is_client_error());
+    assert!(MultipartFormDataError::MissingBoundaryError.is_client_error());
+    assert!(MultipartFormDataError::DataTooLargeError("f".into()).is_client_error());
+
+    assert!(!MultipartFormDataError::IOError(std::io::ErrorKind::Other.into()).is_client_error());
+    assert!(!MultipartFormDataError::ShutdownError.is_client_error());
+}
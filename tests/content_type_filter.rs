@@ -0,0 +1,75 @@
+mod common;
+
+use rocket_multipart_form_data::{
+    mime, MultipartFormDataError, MultipartFormDataField, MultipartFormDataOptions,
+};
+
+/// Whether `field` accepts a file part of `content_type`.
+async fn accepts(name: &str, field: MultipartFormDataField<'_>, content_type: &str) -> bool {
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![field]);
+
+    options.temporary_dir = common::temporary_dir(name);
+
+    match common::parse(
+        name,
+        common::body(&[common::file_part("f", "a", Some(content_type), "data")]),
+        options,
+    )
+    .await
+    {
+        Ok(_) => true,
+        Err(MultipartFormDataError::DataTypeError(_)) => false,
+        Err(err) => panic!("{:?}", err),
+    }
+}
+
+#[rocket::async_test]
+async fn denied() {
+    let field = || {
+        MultipartFormDataField::file("f")
+            .content_type(Some(mime::IMAGE_STAR))
+            .deny_content_type(mime::IMAGE_SVG)
+    };
+
+    assert!(accepts("content-type-png", field(), "image/png").await);
+    assert!(!accepts("content-type-svg", field(), "image/svg+xml").await);
+    assert!(!accepts("content-type-text", field(), "text/plain").await);
+
+    // Only denied content types
+    let field =
+        || MultipartFormDataField::file("f").deny_content_type(mime::APPLICATION_OCTET_STREAM);
+
+    assert!(accepts("content-type-any", field(), "text/plain").await);
+    assert!(!accepts("content-type-octet", field(), "application/octet-stream").await);
+}
+
+#[rocket::async_test]
+async fn suffix() {
+    let field = || {
+        MultipartFormDataField::file("f")
+            .content_type_by_string(Some("application/*+json"))
+            .unwrap()
+    };
+
+    assert!(accepts("content-type-suffix", field(), "application/vnd.api+json").await);
+    assert!(accepts("content-type-suffix-json", field(), "application/json").await);
+    assert!(!accepts("content-type-suffix-xml", field(), "application/atom+xml").await);
+
+    let field = |match_suffix| {
+        MultipartFormDataField::file("f")
+            .content_type(Some(mime::APPLICATION_JSON))
+            .match_suffix(match_suffix)
+    };
+
+    assert!(accepts("content-type-match-suffix", field(true), "application/vnd.api+json").await);
+    assert!(!accepts("content-type-no-suffix", field(false), "application/vnd.api+json").await);
+}
+
+#[rocket::async_test]
+async fn parameters() {
+    let field = || MultipartFormDataField::file("f").content_type(Some(mime::TEXT_PLAIN_UTF_8));
+
+    assert!(accepts("content-type-charset", field(), "text/plain; charset=UTF-8").await);
+    assert!(!accepts("content-type-latin1", field(), "text/plain; charset=latin1").await);
+    assert!(!accepts("content-type-no-charset", field(), "text/plain").await);
+}
@@ -0,0 +1,69 @@
+#![cfg(feature = "csv")]
+
+mod common;
+
+use rocket_multipart_form_data::{
+    MultipartFormData, MultipartFormDataError, MultipartFormDataField, MultipartFormDataOptions,
+};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Row {
+    name: String,
+    age:  u32,
+    note: Option<String>,
+}
+
+async fn parse(name: &str, csv: &str) -> Result<MultipartFormData, MultipartFormDataError> {
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::csv("rows"),
+    ]);
+
+    // Values and quotes are split across chunks
+    options.read_chunk_size = 3;
+
+    common::parse(
+        &format!("csv-{}", name),
+        common::body(&[common::file_part("rows", "a.csv", None, csv)]),
+        options,
+    )
+    .await
+}
+
+#[rocket::async_test]
+async fn quoted_values_across_chunks() {
+    let multipart_form_data = parse(
+        "quoted",
+        "\u{FEFF}name,age,note\r\n\"Smith, J\",42,\r\nAmy,7,\"said \"\"hi\"\"\nbye\"\r\n",
+    )
+    .await
+    .unwrap();
+
+    let field = &multipart_form_data.csv["rows"][0];
+
+    assert_eq!(field.headers, ["name", "age", "note"]);
+    assert_eq!(field.deserialize::<Row>().unwrap(), [
+        Row {
+            name: "Smith, J".to_string(), age: 42, note: None
+        },
+        Row {
+            name: "Amy".to_string(), age: 7, note: Some("said \"hi\"\nbye".to_string())
+        },
+    ]);
+}
+
+#[rocket::async_test]
+async fn row_errors() {
+    let multipart_form_data = parse("typed", "name,age,note\na,x,\nb,3,").await.unwrap();
+
+    let errors = multipart_form_data.csv["rows"][0].deserialize::<Row>().unwrap_err();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].row, 1);
+
+    let err = parse("columns", "a,b\n1,2\n3\n").await.unwrap_err();
+
+    assert!(
+        matches!(err, MultipartFormDataError::InvalidCsvError { ref error, .. } if error.row == 2)
+    );
+}
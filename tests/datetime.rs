@@ -0,0 +1,40 @@
+#![cfg(feature = "chrono")]
+
+mod common;
+
+use rocket_multipart_form_data::{
+    MultipartFormData, MultipartFormDataError, MultipartFormDataField, MultipartFormDataOptions,
+};
+
+async fn parse(
+    name: &str,
+    format: &'static str,
+    value: &str,
+) -> Result<MultipartFormData, MultipartFormDataError> {
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::datetime("d").format(format),
+    ]);
+
+    common::parse(
+        &format!("datetime-{}", name),
+        common::body(&[common::text_part("d", value)]),
+        options,
+    )
+    .await
+}
+
+async fn datetime(name: &str, format: &'static str, value: &str) -> String {
+    parse(name, format, value).await.unwrap().datetimes["d"][0].datetime.to_string()
+}
+
+#[rocket::async_test]
+async fn date_and_time_formats() {
+    assert_eq!("2024-05-01 10:30:00", datetime("both", "%d/%m/%Y %H:%M", "01/05/2024 10:30").await);
+    assert_eq!("2024-05-01 00:00:00", datetime("date", "%Y-%m-%d", "2024-05-01").await);
+    assert_eq!("1970-01-01 10:30:00", datetime("time", "%H:%M", "10:30").await);
+
+    assert!(matches!(
+        parse("invalid", "%Y-%m-%d", "2024-13-01").await,
+        Err(MultipartFormDataError::InvalidDateTimeError { .. })
+    ));
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use std::collections::HashMap;
+
+use rocket_multipart_form_data::{MultipartFormDataField, MultipartFormDataOptions};
+
+#[rocket::async_test]
+async fn extra_parameters() {
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::file("f"),
+        MultipartFormDataField::text("t"),
+    ]);
+
+    options.temporary_dir = common::temporary_dir("disposition-params");
+
+    let multipart_form_data = common::parse(
+        "disposition-params",
+        common::body(&[
+            common::part(
+                "name=\"f\"; filename=\"a.txt\"; SIZE=5; creation-date=\"Wed, 12 Feb 1997 \
+                 16:29:51 -0500\"; note=\"a \\\"quoted\\\"; value\"; flag",
+                None,
+                "hello",
+            ),
+            common::text_part("t", "text"),
+        ]),
+        options,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        HashMap::from([
+            ("size".to_string(), "5".to_string()),
+            ("creation-date".to_string(), "Wed, 12 Feb 1997 16:29:51 -0500".to_string()),
+            ("note".to_string(), "a \"quoted\"; value".to_string()),
+        ]),
+        multipart_form_data.files["f"][0].disposition_params
+    );
+
+    // The name and the file name are not included
+    assert!(multipart_form_data.texts["t"][0].disposition_params.is_empty());
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use rocket_multipart_form_data::{
+    MultipartFormData, MultipartFormDataError, MultipartFormDataField, MultipartFormDataOptions,
+    OptionsValidationError, Repetition,
+};
+
+async fn parse(
+    name: &str,
+    options: MultipartFormDataOptions<'_>,
+) -> Result<MultipartFormData, MultipartFormDataError> {
+    let parts: Vec<Vec<u8>> = (0..5).map(|i| common::text_part("email", i.to_string())).collect();
+
+    common::parse(&format!("duplicate-{}", name), common::body(&parts), options).await
+}
+
+#[rocket::async_test]
+async fn duplicate_fields_are_merged() {
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("email").repetition(Repetition::fixed(3)),
+        MultipartFormDataField::text("email").size_limit(1),
+    ]);
+
+    let multipart_form_data = parse("merged", options).await.unwrap();
+
+    let emails: Vec<&str> =
+        multipart_form_data.texts["email"].iter().map(|f| f.text.as_str()).collect();
+
+    assert_eq!(vec!["0", "1", "2", "3"], emails);
+
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("email").repetition(Repetition::fixed(3)),
+        MultipartFormDataField::text("email"),
+    ]);
+
+    options.merge_duplicate_fields().unwrap();
+
+    assert_eq!(1, options.allowed_fields.len());
+}
+
+#[rocket::async_test]
+async fn conflicting_duplicate_fields_are_rejected() {
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("email"),
+        MultipartFormDataField::raw("email"),
+    ]);
+
+    assert!(matches!(
+        parse("conflicting-types", options).await,
+        Err(MultipartFormDataError::OptionsError(OptionsValidationError::ConflictingTypesError(
+            name
+        ))) if name == "email"
+    ));
+
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("email").content_type_by_string(Some("text/plain")).unwrap(),
+        MultipartFormDataField::text("email").content_type_by_string(Some("image/png")).unwrap(),
+    ]);
+
+    assert!(matches!(
+        parse("conflicting-content-types", options).await,
+        Err(MultipartFormDataError::OptionsError(OptionsValidationError::EmptyContentTypeError(
+            name
+        ))) if name == "email"
+    ));
+}
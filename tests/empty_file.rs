@@ -0,0 +1,62 @@
+mod common;
+
+use std::sync::Arc;
+
+use rocket_multipart_form_data::{
+    mime, EmptyFilePolicy, MultipartFormData, MultipartFormDataError, MultipartFormDataField,
+    MultipartFormDataOptions, ParseWarning,
+};
+
+async fn parse(
+    name: &str,
+    policy: EmptyFilePolicy,
+) -> Result<MultipartFormData, MultipartFormDataError> {
+    let body = common::body(&[
+        common::file_part("f", "", Some("application/octet-stream"), ""),
+        common::file_part("r", "", Some("application/octet-stream"), ""),
+    ]);
+
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::file("f")
+            .content_type(Some(mime::IMAGE_STAR))
+            .on_empty_file(policy),
+        MultipartFormDataField::raw("r").content_type(Some(mime::IMAGE_STAR)).on_empty_file(policy),
+    ]);
+
+    options.temporary_dir = common::temporary_dir(&format!("empty-{}", name));
+
+    common::parse(&format!("empty-{}", name), body, options).await
+}
+
+#[rocket::async_test]
+async fn ignore() {
+    let multipart_form_data = parse("ignore", EmptyFilePolicy::Ignore).await.unwrap();
+
+    assert!(multipart_form_data.files.is_empty());
+    assert!(multipart_form_data.raw.is_empty());
+    assert_eq!(
+        vec![
+            ParseWarning::ContentTypeMismatchOnEmptyFile(Arc::from("f")),
+            ParseWarning::EmptyFileIgnored(Arc::from("f")),
+            ParseWarning::ContentTypeMismatchOnEmptyFile(Arc::from("r")),
+            ParseWarning::EmptyFileIgnored(Arc::from("r")),
+        ],
+        multipart_form_data.warnings
+    );
+}
+
+#[rocket::async_test]
+async fn keep_empty() {
+    let multipart_form_data = parse("keep", EmptyFilePolicy::KeepEmpty).await.unwrap();
+
+    assert_eq!(0, multipart_form_data.files["f"][0].size);
+    assert!(multipart_form_data.raw["r"][0].raw.is_empty());
+}
+
+#[rocket::async_test]
+async fn error() {
+    assert!(matches!(
+        parse("error", EmptyFilePolicy::Error).await,
+        Err(MultipartFormDataError::EmptyFileError(field)) if field.as_ref() == "f"
+    ));
+}
@@ -0,0 +1,141 @@
+#![cfg(feature = "encryption")]
+
+mod common;
+
+use std::{io, sync::Arc};
+
+use rocket_multipart_form_data::{
+    MultipartFormData, MultipartFormDataField, MultipartFormDataOptions,
+};
+
+/// Upload `data` to an encrypted file field.
+async fn upload(name: &str, data: &[u8]) -> MultipartFormData {
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::file("f").size_limit(1024 * 1024),
+    ]);
+
+    options.temporary_dir = common::temporary_dir(&format!("encryption-{}", name));
+    options.encryption = Some(Arc::new([7u8; 32]));
+
+    common::parse(
+        &format!("encryption-{}", name),
+        common::body(&[common::file_part("f", "a.bin", None, data)]),
+        options,
+    )
+    .await
+    .unwrap()
+}
+
+/// The offsets of the frames of an encrypted file.
+fn frames(encrypted: &[u8]) -> Vec<usize> {
+    let mut offsets = Vec::new();
+    let mut i = 8;
+
+    while i < encrypted.len() {
+        offsets.push(i);
+
+        i += 5 + u32::from_be_bytes(encrypted[i + 1..i + 5].try_into().unwrap()) as usize;
+    }
+
+    offsets
+}
+
+fn plaintext() -> Vec<u8> {
+    (0..150_000u32).map(|i| (i % 251) as u8).collect()
+}
+
+#[rocket::async_test]
+async fn round_trip() {
+    let data = plaintext();
+
+    let multipart_form_data = upload("round-trip", &data).await;
+
+    let file = &multipart_form_data.files["f"][0];
+
+    let encrypted = std::fs::read(&file.path).unwrap();
+
+    assert_eq!(3, frames(&encrypted).len());
+    assert!(!encrypted.windows(64).any(|w| w == &data[..64]));
+    assert_eq!(data, file.read_to_bytes().await.unwrap());
+
+    // An empty file has one empty frame
+    let multipart_form_data = upload("empty", b"").await;
+
+    assert!(multipart_form_data.files["f"][0].read_to_bytes().await.unwrap().is_empty());
+}
+
+#[rocket::async_test]
+async fn truncated_file_is_rejected() {
+    let multipart_form_data = upload("truncated", &plaintext()).await;
+
+    let file = &multipart_form_data.files["f"][0];
+
+    let encrypted = std::fs::read(&file.path).unwrap();
+
+    // Cut in the middle of the last frame
+    std::fs::write(&file.path, &encrypted[..encrypted.len() - 10]).unwrap();
+
+    assert_eq!(io::ErrorKind::UnexpectedEof, file.read_to_bytes().await.unwrap_err().kind());
+
+    // Drop the last frame, so the file ends after a complete frame
+    let last = *frames(&encrypted).last().unwrap();
+
+    std::fs::write(&file.path, &encrypted[..last]).unwrap();
+
+    assert_eq!(io::ErrorKind::UnexpectedEof, file.read_to_bytes().await.unwrap_err().kind());
+}
+
+#[rocket::async_test]
+async fn appended_data_is_rejected() {
+    let multipart_form_data = upload("appended", &plaintext()).await;
+
+    let file = &multipart_form_data.files["f"][0];
+
+    let mut encrypted = std::fs::read(&file.path).unwrap();
+
+    encrypted.extend_from_slice(b"appended");
+
+    std::fs::write(&file.path, &encrypted).unwrap();
+
+    assert_eq!(io::ErrorKind::InvalidData, file.read_to_bytes().await.unwrap_err().kind());
+}
+
+#[rocket::async_test]
+async fn tampered_file_is_rejected() {
+    let multipart_form_data = upload("tampered", &plaintext()).await;
+
+    let file = &multipart_form_data.files["f"][0];
+
+    let encrypted = std::fs::read(&file.path).unwrap();
+
+    let offsets = frames(&encrypted);
+
+    // Flip a byte of the ciphertext
+    let mut tampered = encrypted.clone();
+
+    tampered[offsets[1] + 100] ^= 1;
+
+    std::fs::write(&file.path, &tampered).unwrap();
+
+    assert_eq!(io::ErrorKind::InvalidData, file.read_to_bytes().await.unwrap_err().kind());
+
+    // Mark the first frame as the last one
+    let mut tampered = encrypted.clone();
+
+    tampered[offsets[0]] = 1;
+
+    std::fs::write(&file.path, &tampered).unwrap();
+
+    assert_eq!(io::ErrorKind::InvalidData, file.read_to_bytes().await.unwrap_err().kind());
+
+    // Swap the first two frames, which have the same length
+    let mut tampered = encrypted[..offsets[0]].to_vec();
+
+    tampered.extend_from_slice(&encrypted[offsets[1]..offsets[2]]);
+    tampered.extend_from_slice(&encrypted[offsets[0]..offsets[1]]);
+    tampered.extend_from_slice(&encrypted[offsets[2]..]);
+
+    std::fs::write(&file.path, &tampered).unwrap();
+
+    assert_eq!(io::ErrorKind::InvalidData, file.read_to_bytes().await.unwrap_err().kind());
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use rocket_multipart_form_data::{
+    ErrorReport, MultipartFormDataError, MultipartFormDataField, MultipartFormDataOptions,
+};
+
+#[test]
+fn reports() {
+    assert_eq!(
+        ErrorReport {
+            code:    "data_too_large",
+            field:   Some("photo".to_string()),
+            message: MultipartFormDataError::DataTooLargeError("photo".into()).to_string(),
+            details: None,
+        },
+        MultipartFormDataError::DataTooLargeError("photo".into()).to_report()
+    );
+
+    let report = MultipartFormDataError::MissingBoundaryError.to_report();
+
+    assert_eq!("boundary_not_found", report.code);
+    assert_eq!(None, report.field);
+}
+
+#[test]
+fn catalog_message() {
+    let catalog = |_: &MultipartFormDataError| Some("Try again.".to_string());
+
+    let report = MultipartFormDataError::QuotaExceededError.to_report_with(&catalog);
+
+    assert_eq!("Try again.", report.message);
+    assert_eq!(MultipartFormDataError::QuotaExceededError.to_report().code, report.code);
+}
+
+#[rocket::async_test]
+async fn from_parsing() {
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::number::<u8>("age"),
+    ]);
+
+    let report =
+        common::parse("error-report", common::body(&[common::text_part("age", "old")]), options)
+            .await
+            .unwrap_err()
+            .to_report();
+
+    assert_eq!("invalid_number", report.code);
+    assert_eq!(Some("age"), report.field.as_deref());
+    assert_eq!(Some("old"), report.details.as_deref());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn json() {
+    let report = MultipartFormDataError::InvalidValueError {
+        field: "color".into(),
+        got:   "purple".to_string(),
+    }
+    .to_report();
+
+    let json = serde_json::to_value(&report).unwrap();
+
+    assert_eq!("invalid_value", json["code"]);
+    assert_eq!("color", json["field"]);
+    assert_eq!("purple", json["details"]);
+    assert_eq!(report.message, json["message"]);
+
+    // Absent fields are skipped
+    let json =
+        serde_json::to_value(MultipartFormDataError::QuotaExceededError.to_report()).unwrap();
+
+    assert_eq!(
+        ["code", "message"],
+        json.as_object().unwrap().keys().map(|k| k.as_str()).collect::<Vec<_>>()[..]
+    );
+}
@@ -0,0 +1,107 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use rocket_multipart_form_data::{
+    FieldDecision, FieldMeta, MultipartFormDataError, MultipartFormDataField,
+    MultipartFormDataOptions, Repetition,
+};
+
+fn options(name: &str) -> MultipartFormDataOptions<'static> {
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::file("f").repetition(Repetition::infinite()),
+        MultipartFormDataField::text("t"),
+    ]);
+
+    options.temporary_dir = common::temporary_dir(name);
+    options.on_field = Some(Arc::new(|meta: &FieldMeta<'_>| match meta.file_name {
+        Some(file_name) if file_name.ends_with(".exe") => FieldDecision::Reject,
+        Some(file_name) if file_name.ends_with(".tmp") => FieldDecision::Skip,
+        _ => FieldDecision::Accept,
+    }));
+
+    options
+}
+
+#[rocket::async_test]
+async fn skip() {
+    let multipart_form_data = common::parse(
+        "field-filter",
+        common::body(&[
+            common::file_part("f", "a.tmp", None, "skipped"),
+            common::file_part("f", "b.txt", None, "kept"),
+            common::text_part("t", "text"),
+        ]),
+        options("field-filter"),
+    )
+    .await
+    .unwrap();
+
+    let files = &multipart_form_data.files["f"];
+
+    assert_eq!(1, files.len());
+    assert_eq!(Some("b.txt"), files[0].file_name.as_deref());
+    assert_eq!("text", multipart_form_data.texts["t"][0].text);
+}
+
+#[rocket::async_test]
+async fn reject() {
+    let result = common::parse(
+        "field-filter-reject",
+        common::body(&[
+            common::file_part("f", "b.txt", None, "kept"),
+            common::file_part("f", "a.exe", None, "MZ"),
+        ]),
+        options("field-filter-reject"),
+    )
+    .await;
+
+    assert!(
+        matches!(result, Err(MultipartFormDataError::FieldRejectedError(field)) if field.as_ref() == "f")
+    );
+}
+
+#[rocket::async_test]
+async fn meta() {
+    let metas = Arc::new(Mutex::new(Vec::new()));
+
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::file("f"),
+    ]);
+
+    options.temporary_dir = common::temporary_dir("field-filter-meta");
+    options.on_field = Some(Arc::new({
+        let metas = metas.clone();
+
+        move |meta: &FieldMeta<'_>| {
+            metas.lock().unwrap().push((
+                meta.name.to_string(),
+                meta.file_name.map(String::from),
+                meta.content_type.map(|m| m.to_string()),
+                meta.headers.len(),
+            ));
+
+            FieldDecision::Accept
+        }
+    }));
+
+    common::parse(
+        "field-filter-meta",
+        common::body(&[
+            common::file_part("f", "a.png", Some("image/png"), "png"),
+            common::text_part("u", "unknown"),
+        ]),
+        options,
+    )
+    .await
+    .unwrap();
+
+    // Fields which are not allowed are passed to the filter as well
+    assert_eq!(
+        vec![
+            ("f".to_string(), Some("a.png".to_string()), Some("image/png".to_string()), 2),
+            ("u".to_string(), None, None, 1),
+        ],
+        *metas.lock().unwrap()
+    );
+}
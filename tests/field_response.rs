@@ -0,0 +1,103 @@
+mod common;
+
+use rocket::{get, local::asynchronous::Client, routes};
+use rocket_multipart_form_data::{
+    FileFieldResponse, MultipartFormData, MultipartFormDataField, MultipartFormDataOptions,
+    RawFieldResponse,
+};
+
+async fn parse(name: &str) -> MultipartFormData {
+    let body = common::body(&[
+        common::file_part("r", "a.html", Some("text/html"), "<script></script>"),
+        common::file_part("f", "b.html", Some("text/html"), "<script></script>"),
+    ]);
+
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::raw("r"),
+        MultipartFormDataField::file("f"),
+    ]);
+
+    common::parse(name, body, options).await.unwrap()
+}
+
+async fn raw_field(name: &str) -> RawFieldResponse {
+    parse(name).await.raw.remove("r").unwrap().remove(0).into_response_body()
+}
+
+async fn file_field(name: &str) -> FileFieldResponse {
+    parse(name).await.remove_one_file("f").unwrap().into_response().await.unwrap()
+}
+
+#[get("/raw")]
+async fn raw() -> RawFieldResponse {
+    raw_field("response-raw").await
+}
+
+#[get("/raw/inline")]
+async fn raw_inline() -> RawFieldResponse {
+    raw_field("response-raw-inline").await.inline()
+}
+
+#[get("/file")]
+async fn file() -> FileFieldResponse {
+    file_field("response-file").await
+}
+
+#[get("/file/inline")]
+async fn file_inline() -> FileFieldResponse {
+    file_field("response-file-inline").await.inline()
+}
+
+async fn headers(uri: &'static str) -> (String, String) {
+    let client =
+        Client::tracked(rocket::build().mount("/", routes![raw, raw_inline, file, file_inline]))
+            .await
+            .unwrap();
+
+    let response = client.get(uri).dispatch().await;
+
+    let headers = response.headers();
+
+    let disposition = headers.get_one("Content-Disposition").unwrap().to_string();
+    let nosniff = headers.get_one("X-Content-Type-Options").unwrap().to_string();
+
+    assert_eq!("<script></script>", response.into_string().await.unwrap());
+
+    (disposition, nosniff)
+}
+
+#[rocket::async_test]
+async fn attachment_by_default() {
+    assert_eq!(
+        (
+            r#"attachment; filename="a.html"; filename*=UTF-8''a.html"#.to_string(),
+            "nosniff".to_string()
+        ),
+        headers("/raw").await
+    );
+    assert_eq!(
+        (
+            r#"attachment; filename="b.html"; filename*=UTF-8''b.html"#.to_string(),
+            "nosniff".to_string()
+        ),
+        headers("/file").await
+    );
+}
+
+#[rocket::async_test]
+async fn inline_opt_in() {
+    assert_eq!(
+        (
+            r#"inline; filename="a.html"; filename*=UTF-8''a.html"#.to_string(),
+            "nosniff".to_string()
+        ),
+        headers("/raw/inline").await
+    );
+    assert_eq!(
+        (
+            r#"inline; filename="b.html"; filename*=UTF-8''b.html"#.to_string(),
+            "nosniff".to_string()
+        ),
+        headers("/file/inline").await
+    );
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use rocket::tokio::io::AsyncReadExt;
+use rocket_multipart_form_data::{
+    MultipartFormData, MultipartFormDataField, MultipartFormDataOptions, Repetition,
+};
+
+async fn upload(name: &str) -> MultipartFormData {
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::file("f").repetition(Repetition::fixed(2)),
+    ]);
+
+    options.temporary_dir = common::temporary_dir(name);
+
+    common::parse(
+        name,
+        common::body(&[
+            common::file_part("f", "a.txt", None, "hello"),
+            common::file_part("f", "b.txt", None, ""),
+        ]),
+        options,
+    )
+    .await
+    .unwrap()
+}
+
+#[rocket::async_test]
+async fn read_helpers() {
+    let multipart_form_data = upload("file-field").await;
+
+    let file = &multipart_form_data.files["f"][0];
+
+    assert_eq!(b"hello", &file.read_to_bytes().await.unwrap()[..]);
+    assert_eq!(5, file.len().await.unwrap());
+    assert!(!file.is_empty().await.unwrap());
+
+    let mut content = String::new();
+
+    file.open().await.unwrap().read_to_string(&mut content).await.unwrap();
+
+    assert_eq!("hello", content);
+
+    let empty = &multipart_form_data.files["f"][1];
+
+    assert!(empty.read_to_bytes().await.unwrap().is_empty());
+    assert_eq!(0, empty.len().await.unwrap());
+    assert!(empty.is_empty().await.unwrap());
+}
+
+#[rocket::async_test]
+async fn deleted_file() {
+    let multipart_form_data = upload("file-field-deleted").await;
+
+    let file = &multipart_form_data.files["f"][0];
+
+    std::fs::remove_file(&file.path).unwrap();
+
+    assert_eq!(std::io::ErrorKind::NotFound, file.open().await.unwrap_err().kind());
+    assert_eq!(std::io::ErrorKind::NotFound, file.read_to_bytes().await.unwrap_err().kind());
+    assert_eq!(std::io::ErrorKind::NotFound, file.len().await.unwrap_err().kind());
+}
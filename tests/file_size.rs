@@ -0,0 +1,44 @@
+mod common;
+
+use rocket_multipart_form_data::{
+    EmptyFilePolicy, MultipartFormDataField, MultipartFormDataOptions, OversizePolicy, Repetition,
+};
+
+#[rocket::async_test]
+async fn sizes() {
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::file("f")
+            .repetition(Repetition::infinite())
+            .on_empty_file(EmptyFilePolicy::KeepEmpty),
+        MultipartFormDataField::file("t").size_limit(4).on_oversize(OversizePolicy::Truncate),
+    ]);
+
+    options.temporary_dir = common::temporary_dir("file-size");
+    options.read_chunk_size = 16;
+
+    let multipart_form_data = common::parse(
+        "file-size",
+        common::body(&[
+            common::file_part("f", "a.txt", None, "a".repeat(1000)),
+            common::file_part("f", "b.txt", None, ""),
+            common::file_part("t", "c.txt", None, "abcdefgh"),
+        ]),
+        options,
+    )
+    .await
+    .unwrap();
+
+    let files = &multipart_form_data.files["f"];
+
+    assert_eq!(1000, files[0].size);
+    assert_eq!(0, files[1].size);
+
+    // The size of a truncated file is the size of the stored data
+    let truncated = &multipart_form_data.files["t"][0];
+
+    assert_eq!(4, truncated.size);
+
+    for file in files.iter().chain([truncated]) {
+        assert_eq!(std::fs::metadata(&file.path).unwrap().len(), file.size);
+    }
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use rocket::{
+    form::{error::ErrorKind, Errors},
+    http::{ContentType, Status},
+};
+use rocket_multipart_form_data::{
+    MultipartFormDataError, MultipartFormDataField, MultipartFormDataOptions,
+};
+
+#[test]
+fn request_errors() {
+    let error = MultipartFormDataError::WrongContentTypeError(Box::new(ContentType::Plain))
+        .into_form_error();
+
+    assert_eq!(Status::UnsupportedMediaType, error.status());
+    assert_eq!(None, error.name);
+    assert_eq!(Some("text/plain; charset=utf-8"), error.value.as_deref());
+
+    assert_eq!(
+        Status::BadRequest,
+        MultipartFormDataError::MissingBoundaryError.into_form_error().status()
+    );
+    assert_eq!(
+        Status::PayloadTooLarge,
+        MultipartFormDataError::QuotaExceededError.into_form_error().status()
+    );
+}
+
+#[test]
+fn field_errors() {
+    let error = MultipartFormDataError::DataTooLargeError("photo".into()).into_form_error();
+
+    assert_eq!(Status::PayloadTooLarge, error.status());
+    assert_eq!("photo", error.name.unwrap().to_string());
+
+    let error = MultipartFormDataError::InvalidNumberError {
+        field: "age".into(),
+        got:   "old".to_string(),
+    }
+    .into_form_error();
+
+    assert_eq!(Status::UnprocessableEntity, error.status());
+    assert_eq!("age", error.name.unwrap().to_string());
+    assert_eq!(Some("old"), error.value.as_deref());
+    assert!(matches!(error.kind, ErrorKind::Validation(_)));
+}
+
+#[test]
+fn catalog_message() {
+    let catalog = |error: &MultipartFormDataError| match error {
+        MultipartFormDataError::DataTypeError(field) => {
+            Some(format!("「{}」の形式が違います。", field))
+        },
+        _ => None,
+    };
+
+    let error =
+        MultipartFormDataError::DataTypeError("photo".into()).into_form_error_with(&catalog);
+
+    match error.kind {
+        ErrorKind::Validation(message) => assert_eq!("「photo」の形式が違います。", message),
+        kind => panic!("{:?}", kind),
+    }
+
+    // Custom errors keep their own status and are not rewritten
+    let error = MultipartFormDataError::ShutdownError.into_form_error_with(&catalog);
+
+    assert_eq!(Status::ServiceUnavailable, error.status());
+}
+
+#[rocket::async_test]
+async fn from_parsing() {
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::number::<u8>("age"),
+    ]);
+
+    let error =
+        common::parse("form-errors", common::body(&[common::text_part("age", "old")]), options)
+            .await
+            .unwrap_err();
+
+    let errors = Errors::from(error);
+
+    assert_eq!(1, errors.len());
+    assert_eq!(Status::UnprocessableEntity, errors.status());
+    assert_eq!("age", errors[0].name.as_ref().unwrap().to_string());
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use rocket_multipart_form_data::{
+    MultipartFormData, MultipartFormDataField, MultipartFormDataOptions,
+};
+
+async fn upload(name: &str) -> MultipartFormData {
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::file("f"),
+        MultipartFormDataField::text("t"),
+    ]);
+
+    options.temporary_dir = common::temporary_dir(name);
+
+    common::parse(
+        name,
+        common::body(&[
+            common::file_part("f", "a.txt", None, "hello"),
+            common::text_part("t", "world"),
+        ]),
+        options,
+    )
+    .await
+    .unwrap()
+}
+
+#[rocket::async_test]
+async fn files_live_as_long_as_the_guard() {
+    let (data, guard) = upload("into-background").await.into_background();
+
+    let path = data.files["f"][0].path.clone();
+
+    assert_eq!(guard.paths(), std::slice::from_ref(&path));
+
+    // The data and the guard can be moved into a task which outlives the request
+    let (text, content) = rocket::tokio::spawn(async move {
+        let content = std::fs::read_to_string(&data.files["f"][0].path).unwrap();
+
+        drop(guard);
+
+        (data.texts["t"][0].text.clone(), content)
+    })
+    .await
+    .unwrap();
+
+    assert_eq!("world", text);
+    assert_eq!("hello", content);
+    assert!(!path.exists());
+}
+
+#[rocket::async_test]
+async fn kept_files_are_not_deleted() {
+    let (data, guard) = upload("into-background-keep").await.into_background();
+
+    let path = data.files["f"][0].path.clone();
+
+    assert_eq!(guard.keep(), std::slice::from_ref(&path));
+
+    drop(data);
+
+    assert_eq!("hello", std::fs::read_to_string(&path).unwrap());
+
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use rocket_multipart_form_data::{
+    MultipartFormDataField, MultipartFormDataOptions, NamedField, Repetition,
+};
+
+#[rocket::async_test]
+async fn arrival_order() {
+    let dir = common::temporary_dir("into-fields");
+
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("t").repetition(Repetition::infinite()),
+        MultipartFormDataField::raw("r"),
+        MultipartFormDataField::file("f"),
+        MultipartFormDataField::number::<u8>("n"),
+    ]);
+
+    options.temporary_dir = dir.clone();
+
+    let multipart_form_data = common::parse(
+        "into-fields",
+        common::body(&[
+            common::text_part("t", "a"),
+            common::file_part("f", "a.txt", None, "file"),
+            common::text_part("n", "7"),
+            common::text_part("r", "raw"),
+            common::text_part("t", "b"),
+        ]),
+        options,
+    )
+    .await
+    .unwrap();
+
+    let fields = multipart_form_data.into_fields();
+
+    assert_eq!(
+        ["t", "f", "n", "r", "t"],
+        fields.iter().map(|f| f.name().as_ref()).collect::<Vec<_>>()[..]
+    );
+    assert_eq!([0, 1, 2, 3, 4], fields.iter().map(NamedField::index).collect::<Vec<_>>()[..]);
+
+    match (&fields[0], &fields[4]) {
+        (NamedField::Text(_, a), NamedField::Text(_, b)) => {
+            assert_eq!("a", a.text);
+            assert_eq!("b", b.text);
+        },
+        fields => panic!("{:?}", fields),
+    }
+
+    let path = match &fields[1] {
+        NamedField::File(_, f) => f.path.clone(),
+        field => panic!("{:?}", field),
+    };
+
+    assert!(matches!(&fields[3], NamedField::Raw(_, f) if f.raw == b"raw"));
+
+    // The uploaded files are not deleted automatically anymore
+    drop(fields);
+
+    assert_eq!("file", std::fs::read_to_string(&path).unwrap());
+
+    std::fs::remove_file(&path).unwrap();
+}
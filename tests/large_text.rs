@@ -0,0 +1,76 @@
+#[cfg(feature = "encryption")]
+mod common;
+
+#[cfg(feature = "encryption")]
+use std::sync::Arc;
+
+#[cfg(feature = "encryption")]
+use rocket_multipart_form_data::MultipartFormData;
+use rocket_multipart_form_data::{
+    MultipartFormDataField, MultipartFormDataOptions, OptionsValidationError,
+};
+
+#[test]
+fn raw_bytes_with_large_text() {
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("log").keep_raw_bytes(true).large_text(1024),
+    ]);
+
+    assert_eq!(
+        Err(OptionsValidationError::RawBytesUnavailableError("log".to_string())),
+        options.validate()
+    );
+
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("log").large_text(1024),
+    ]);
+
+    assert_eq!(Ok(()), options.validate());
+}
+
+#[cfg(feature = "encryption")]
+#[rocket::async_test]
+async fn large_text_is_encrypted() {
+    let dir = std::env::temp_dir().join("rocket-multipart-form-data-large-text-encrypted-dir");
+
+    let _ = std::fs::remove_dir_all(&dir);
+
+    // More than one frame of the encryption
+    let text = "secret line\n".repeat(8000);
+
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("log").size_limit(1024 * 1024).large_text(1024),
+    ]);
+
+    options.encryption = Some(Arc::new([7u8; 32]));
+
+    let multipart_form_data = common::parse(
+        "large-text-encrypted",
+        common::body(&[common::text_part("log", &text)]),
+        options,
+    )
+    .await
+    .unwrap();
+
+    let large_text = &multipart_form_data.large_texts["log"][0];
+
+    let stored = std::fs::read(&large_text.path).unwrap();
+
+    assert!(!stored.windows(6).any(|w| w == b"secret"));
+    assert_eq!(text.len() as u64, large_text.len);
+    assert_eq!(text, large_text.read_to_string().await.unwrap());
+
+    multipart_form_data.save(&dir).unwrap();
+
+    let multipart_form_data =
+        MultipartFormData::load_with_key_provider(&dir, Arc::new([7u8; 32])).unwrap();
+
+    let large_text = &multipart_form_data.large_texts["log"][0];
+
+    assert_eq!(text.len() as u64, large_text.len);
+    assert_eq!(text, large_text.read_to_string().await.unwrap());
+
+    drop(multipart_form_data);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
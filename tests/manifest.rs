@@ -0,0 +1,129 @@
+mod common;
+
+use rocket_multipart_form_data::{
+    MultipartFormData, MultipartFormDataField, MultipartFormDataOptions, Number, ParseWarning,
+};
+
+#[rocket::async_test]
+async fn save_and_load() {
+    let dir = std::env::temp_dir().join("rocket-multipart-form-data-manifest-dir");
+
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let body = common::body(&[
+        common::part("name=\"f\"; filename=\"a.txt\"; lang=\"en\"", Some("text/plain"), "hello"),
+        common::text_part("t", "ab\tc"),
+        common::text_part("n", "-3"),
+        common::text_part("c", "on"),
+        common::part(
+            "name=\"s\"",
+            Some("multipart/mixed; boundary=IB"),
+            "--IB\r\nContent-Disposition: form-data; name=\"i\"\r\n\r\ninner\r\n--IB--\r\n",
+        ),
+        common::text_part("u", "x"),
+    ]);
+
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::file("f"),
+        MultipartFormDataField::text("t").keep_raw_bytes(true),
+        MultipartFormDataField::number::<i32>("n"),
+        MultipartFormDataField::checkbox("c"),
+        MultipartFormDataField::raw("s").nested(vec![MultipartFormDataField::text("i")]),
+    ]);
+
+    options.max_nesting_depth = 1;
+
+    let multipart_form_data = common::parse("manifest", body, options).await.unwrap();
+
+    let stats = multipart_form_data.stats;
+
+    multipart_form_data.save(&dir).unwrap();
+
+    let multipart_form_data = MultipartFormData::load(&dir).unwrap();
+
+    let file = &multipart_form_data.files["f"][0];
+
+    assert_eq!(std::fs::read(&file.path).unwrap(), b"hello");
+    assert_eq!(file.file_name.as_deref(), Some("a.txt"));
+    assert_eq!(file.disposition_params["lang"], "en");
+    assert_eq!(file.content_type.as_ref().map(|m| m.as_ref()), Some("text/plain"));
+
+    let text = &multipart_form_data.texts["t"][0];
+
+    assert_eq!(text.text, "ab\tc");
+    assert_eq!(text.raw_bytes.as_deref(), Some(b"ab\tc".as_slice()));
+
+    assert!(matches!(multipart_form_data.numbers["n"][0].number, Number::Integer(-3)));
+    assert!(multipart_form_data.bools["c"]);
+    assert_eq!(multipart_form_data.nested["s"][0].texts["i"][0].text, "inner");
+    assert!(
+        matches!(&multipart_form_data.warnings[..], [ParseWarning::UnknownField(name)] if name == "u")
+    );
+    assert_eq!(multipart_form_data.stats, stats);
+
+    drop(multipart_form_data);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(feature = "csv")]
+#[rocket::async_test]
+async fn csv_is_unsupported() {
+    let dir = std::env::temp_dir().join("rocket-multipart-form-data-manifest-csv-dir");
+
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::csv("c"),
+    ]);
+
+    let multipart_form_data = common::parse(
+        "manifest-csv",
+        common::body(&[common::text_part("c", "a,b\r\n1,2")]),
+        options,
+    )
+    .await
+    .unwrap();
+
+    let err = multipart_form_data.save(&dir).unwrap_err();
+
+    assert_eq!(err.kind(), std::io::ErrorKind::Unsupported);
+    assert!(!dir.exists());
+}
+
+#[cfg(feature = "encryption")]
+#[rocket::async_test]
+async fn encrypted_files_need_the_key() {
+    use std::sync::Arc;
+
+    let dir = std::env::temp_dir().join("rocket-multipart-form-data-manifest-encrypted-dir");
+
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::file("f"),
+    ]);
+
+    options.encryption = Some(Arc::new([7u8; 32]));
+
+    let multipart_form_data = common::parse(
+        "manifest-encrypted",
+        common::body(&[common::file_part("f", "a.txt", None, "secret")]),
+        options,
+    )
+    .await
+    .unwrap();
+
+    multipart_form_data.save(&dir).unwrap();
+
+    let err = MultipartFormData::load(&dir).unwrap_err();
+
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    let multipart_form_data =
+        MultipartFormData::load_with_key_provider(&dir, Arc::new([7u8; 32])).unwrap();
+
+    assert_eq!(multipart_form_data.files["f"][0].read_to_bytes().await.unwrap(), b"secret");
+
+    drop(multipart_form_data);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use rocket_multipart_form_data::{
+    MultipartFormData, MultipartFormDataError, MultipartFormDataField, MultipartFormDataOptions,
+    Repetition,
+};
+
+async fn parse(
+    name: &str,
+    max_text_parts: usize,
+    max_file_parts: usize,
+) -> Result<MultipartFormData, MultipartFormDataError> {
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("t").repetition(Repetition::infinite()),
+        MultipartFormDataField::file("f").repetition(Repetition::infinite()),
+    ]);
+
+    options.temporary_dir = common::temporary_dir(name);
+    options.max_text_parts = max_text_parts;
+    options.max_file_parts = max_file_parts;
+
+    // Parts of unknown fields are counted as well
+    common::parse(
+        name,
+        common::body(&[
+            common::text_part("t", "a"),
+            common::file_part("f", "a.txt", None, "a"),
+            common::text_part("u", "b"),
+            common::file_part("u", "b.txt", None, "b"),
+        ]),
+        options,
+    )
+    .await
+}
+
+#[rocket::async_test]
+async fn within_limits() {
+    let multipart_form_data = parse("max-parts", 2, 2).await.unwrap();
+
+    assert_eq!(2, multipart_form_data.stats.text_parts);
+    assert_eq!(2, multipart_form_data.stats.file_parts);
+}
+
+#[rocket::async_test]
+async fn too_many_text_parts() {
+    assert!(matches!(
+        parse("max-parts-text", 1, usize::MAX).await,
+        Err(MultipartFormDataError::TooManyTextPartsError)
+    ));
+}
+
+#[rocket::async_test]
+async fn too_many_file_parts() {
+    assert!(matches!(
+        parse("max-parts-file", usize::MAX, 1).await,
+        Err(MultipartFormDataError::TooManyFilePartsError)
+    ));
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use rocket_multipart_form_data::{
+    multer::{self, Multipart},
+    MultipartFormDataField, MultipartFormDataOptions,
+};
+
+fn multipart(body: Vec<u8>, fields: Vec<MultipartFormDataField>) -> Multipart<'static> {
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(fields);
+
+    Multipart::with_constraints(
+        tokio_util::io::ReaderStream::new(std::io::Cursor::new(body)),
+        common::BOUNDARY,
+        options.to_multer_constraints(),
+    )
+}
+
+fn fields() -> Vec<MultipartFormDataField<'static>> {
+    vec![MultipartFormDataField::text("t").size_limit(4), MultipartFormDataField::file("f")]
+}
+
+#[rocket::async_test]
+async fn allowed_fields() {
+    let mut multipart = multipart(
+        common::body(&[common::text_part("t", "abc"), common::text_part("u", "x")]),
+        fields(),
+    );
+
+    let field = multipart.next_field().await.unwrap().unwrap();
+
+    assert_eq!("abc", field.text().await.unwrap());
+
+    assert!(matches!(
+        multipart.next_field().await,
+        Err(multer::Error::UnknownField { field_name: Some(name) }) if name == "u"
+    ));
+}
+
+#[rocket::async_test]
+async fn size_limits() {
+    let mut multipart = multipart(common::body(&[common::text_part("t", "abcde")]), fields());
+
+    let field = multipart.next_field().await.unwrap().unwrap();
+
+    assert!(matches!(
+        field.text().await,
+        Err(multer::Error::FieldSizeExceeded { field_name: Some(name), .. }) if name == "t"
+    ));
+}
+
+#[rocket::async_test]
+async fn size_limit_of_duplicate_fields() {
+    let mut multipart = multipart(common::body(&[common::text_part("t", "abcdefgh")]), vec![
+        MultipartFormDataField::text("t").size_limit(4),
+        MultipartFormDataField::text("t").size_limit(16),
+    ]);
+
+    // The largest limit of the fields with the same name is used
+    let field = multipart.next_field().await.unwrap().unwrap();
+
+    assert_eq!("abcdefgh", field.text().await.unwrap());
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use rocket_multipart_form_data::{
+    MultipartFormData, MultipartFormDataError, MultipartFormDataField, MultipartFormDataOptions,
+    Number,
+};
+
+async fn parse(
+    name: &str,
+    field: MultipartFormDataField<'_>,
+    value: &str,
+) -> Result<MultipartFormData, MultipartFormDataError> {
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![field]);
+
+    common::parse(
+        &format!("number-{}", name),
+        common::body(&[common::text_part("n", value)]),
+        options,
+    )
+    .await
+}
+
+async fn number(name: &str, field: MultipartFormDataField<'_>, value: &str) -> Number {
+    parse(name, field, value).await.unwrap().numbers["n"][0].number
+}
+
+#[rocket::async_test]
+async fn numbers() {
+    assert_eq!(
+        Number::Integer(-3),
+        number("integer", MultipartFormDataField::number::<i32>("n"), "-3").await
+    );
+    assert_eq!(
+        Number::Unsigned(u64::MAX),
+        number("unsigned", MultipartFormDataField::number::<u64>("n"), "18446744073709551615")
+            .await
+    );
+    assert_eq!(
+        Number::Float(2.5),
+        number("float", MultipartFormDataField::number::<f64>("n"), " 2.5 ").await
+    );
+
+    let n = number("get", MultipartFormDataField::number::<u16>("n"), "300").await;
+
+    assert_eq!(Some(300u16), n.get());
+    assert_eq!(None, n.get::<u8>());
+}
+
+#[rocket::async_test]
+async fn invalid_numbers() {
+    for (name, field, value) in [
+        ("text", MultipartFormDataField::number::<i32>("n"), "three"),
+        ("overflow", MultipartFormDataField::number::<u8>("n"), "300"),
+        ("negative", MultipartFormDataField::number::<u32>("n"), "-1"),
+        ("fraction", MultipartFormDataField::number::<i64>("n"), "1.5"),
+    ] {
+        match parse(name, field, value).await {
+            Err(MultipartFormDataError::InvalidNumberError {
+                field,
+                got,
+            }) => {
+                assert_eq!("n", field.as_ref());
+                assert_eq!(value, got);
+            },
+            result => panic!("{}: {:?}", name, result.map(|_| ())),
+        }
+    }
+}
+
+#[rocket::async_test]
+async fn range() {
+    let field = || MultipartFormDataField::number::<i32>("n").range(1..=10);
+
+    assert_eq!(Number::Integer(1), number("range-start", field(), "1").await);
+    assert_eq!(Number::Integer(10), number("range-end", field(), "10").await);
+
+    for (name, value) in [("range-below", "0"), ("range-above", "11")] {
+        assert!(matches!(
+            parse(name, field(), value).await,
+            Err(MultipartFormDataError::NumberOutOfRangeError { field, got })
+                if field.as_ref() == "n" && got.to_string() == value
+        ));
+    }
+
+    // The end of a half-open range is excluded, and the bounds can have another numeric type
+    let field = || MultipartFormDataField::number::<f64>("n").range(0u8..1);
+
+    assert_eq!(Number::Float(0.5), number("half-open", field(), "0.5").await);
+    assert!(matches!(
+        parse("half-open-end", field(), "1").await,
+        Err(MultipartFormDataError::NumberOutOfRangeError { .. })
+    ));
+}
@@ -0,0 +1,41 @@
+mod common;
+
+use rocket_multipart_form_data::{
+    MultipartFormData, MultipartFormDataError, MultipartFormDataField, MultipartFormDataOptions,
+};
+
+async fn parse(name: &str, value: &str) -> Result<MultipartFormData, MultipartFormDataError> {
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("color").one_of(["red", "green", "blue"]),
+    ]);
+
+    common::parse(
+        &format!("one-of-{}", name),
+        common::body(&[common::text_part("color", value)]),
+        options,
+    )
+    .await
+}
+
+#[rocket::async_test]
+async fn allowed_value() {
+    let multipart_form_data = parse("allowed", "green").await.unwrap();
+
+    assert_eq!("green", multipart_form_data.texts["color"][0].text);
+}
+
+#[rocket::async_test]
+async fn other_values_are_rejected() {
+    for (name, value) in [("other", "purple"), ("case", "Red"), ("empty", "")] {
+        match parse(name, value).await {
+            Err(MultipartFormDataError::InvalidValueError {
+                field,
+                got,
+            }) => {
+                assert_eq!("color", field.as_ref());
+                assert_eq!(value, got);
+            },
+            result => panic!("{:?}", result.map(|_| ())),
+        }
+    }
+}
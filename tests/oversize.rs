@@ -0,0 +1,62 @@
+mod common;
+
+use std::sync::Arc;
+
+use rocket_multipart_form_data::{
+    MultipartFormDataError, MultipartFormDataField, MultipartFormDataOptions, OversizePolicy,
+    ParseWarning,
+};
+
+#[rocket::async_test]
+async fn truncate() {
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("t").size_limit(5).on_oversize(OversizePolicy::Truncate),
+        MultipartFormDataField::raw("r").size_limit(3).on_oversize(OversizePolicy::Truncate),
+        MultipartFormDataField::file("f").size_limit(4).on_oversize(OversizePolicy::Truncate),
+    ]);
+
+    options.temporary_dir = common::temporary_dir("oversize");
+
+    let multipart_form_data = common::parse(
+        "oversize",
+        common::body(&[
+            common::text_part("t", "hello world"),
+            common::file_part("r", "a.bin", None, "abc"),
+            common::file_part("f", "a.txt", None, "abcdefgh"),
+        ]),
+        options,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!("hello", multipart_form_data.texts["t"][0].text);
+    assert_eq!(b"abc", &multipart_form_data.raw["r"][0].raw[..]);
+    assert_eq!("abcd", std::fs::read_to_string(&multipart_form_data.files["f"][0].path).unwrap());
+
+    // Data which fits the limit exactly is not truncated
+    assert_eq!(
+        vec![
+            ParseWarning::DataTruncated(Arc::from("t")),
+            ParseWarning::DataTruncated(Arc::from("f"))
+        ],
+        multipart_form_data.warnings
+    );
+}
+
+#[rocket::async_test]
+async fn error_by_default() {
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("t").size_limit(5),
+    ]);
+
+    let result = common::parse(
+        "oversize-error",
+        common::body(&[common::text_part("t", "hello world")]),
+        options,
+    )
+    .await;
+
+    assert!(
+        matches!(result, Err(MultipartFormDataError::DataTooLargeError(field)) if field.as_ref() == "t")
+    );
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use rocket::{
+    http::{ContentType, MediaType},
+    tokio::task,
+};
+use rocket_multipart_form_data::{
+    MultipartFormData, MultipartFormDataError, MultipartFormDataField, MultipartFormDataOptions,
+};
+
+fn content_type() -> ContentType {
+    ContentType(
+        MediaType::new("multipart", "form-data").with_params(("boundary", common::BOUNDARY)),
+    )
+}
+
+fn options(name: &str) -> MultipartFormDataOptions<'static> {
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("t"),
+        MultipartFormDataField::file("f"),
+    ]);
+
+    options.temporary_dir = common::temporary_dir(name);
+
+    options
+}
+
+fn body() -> Vec<u8> {
+    common::body(&[common::text_part("t", "hello"), common::file_part("f", "a.txt", None, "file")])
+}
+
+#[test]
+fn without_runtime() {
+    let multipart_form_data =
+        MultipartFormData::parse_blocking(&content_type(), &body()[..], options("parse-blocking"))
+            .unwrap();
+
+    assert_eq!("hello", multipart_form_data.texts["t"][0].text);
+    assert_eq!("file", std::fs::read_to_string(&multipart_form_data.files["f"][0].path).unwrap());
+}
+
+#[rocket::async_test]
+async fn in_spawn_blocking() {
+    let multipart_form_data = task::spawn_blocking(|| {
+        MultipartFormData::parse_blocking(
+            &content_type(),
+            std::io::Cursor::new(body()),
+            options("parse-blocking-spawn"),
+        )
+    })
+    .await
+    .unwrap()
+    .unwrap();
+
+    assert_eq!("hello", multipart_form_data.texts["t"][0].text);
+}
+
+#[test]
+fn wrong_content_type() {
+    assert!(matches!(
+        MultipartFormData::parse_blocking(
+            &ContentType::Plain,
+            &body()[..],
+            options("parse-blocking-wrong"),
+        ),
+        Err(MultipartFormDataError::WrongContentTypeError(_))
+    ));
+}
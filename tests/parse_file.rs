@@ -0,0 +1,86 @@
+mod common;
+
+use rocket_multipart_form_data::{
+    DataLimitSource, MultipartFormData, MultipartFormDataError, MultipartFormDataField,
+    MultipartFormDataOptions,
+};
+
+fn options(name: &str) -> MultipartFormDataOptions<'static> {
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::file("f").size_limit(64),
+        MultipartFormDataField::text("t").size_limit(64),
+    ]);
+
+    options.temporary_dir = common::temporary_dir(name);
+
+    options
+}
+
+#[rocket::async_test]
+async fn captured_body() {
+    let path = std::env::temp_dir().join("rocket-multipart-form-data-parse-file.body");
+
+    std::fs::write(
+        &path,
+        common::body(&[
+            common::text_part("t", "hello"),
+            common::file_part("f", "a.txt", None, "file"),
+        ]),
+    )
+    .unwrap();
+
+    let multipart_form_data =
+        MultipartFormData::parse_file(&path, common::BOUNDARY, options("parse-file"))
+            .await
+            .unwrap();
+
+    // The captured body is left untouched
+    assert!(path.exists());
+
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!("hello", multipart_form_data.texts["t"][0].text);
+    assert_eq!("file", std::fs::read_to_string(&multipart_form_data.files["f"][0].path).unwrap());
+}
+
+#[rocket::async_test]
+async fn missing_file() {
+    let path = std::env::temp_dir().join("rocket-multipart-form-data-parse-file-missing.body");
+
+    let _ = std::fs::remove_file(&path);
+
+    match MultipartFormData::parse_file(&path, common::BOUNDARY, options("parse-file-missing"))
+        .await
+    {
+        Err(MultipartFormDataError::IOError(err)) => {
+            assert_eq!(std::io::ErrorKind::NotFound, err.kind())
+        },
+        result => panic!("{:?}", result.map(|_| ())),
+    }
+}
+
+#[rocket::async_test]
+async fn data_limit() {
+    let mut options = options("parse-file-limit");
+
+    options.max_data_bytes = 128;
+
+    let result = common::parse(
+        "parse-file-limit",
+        common::body(&[
+            common::text_part("t", "a".repeat(60)),
+            common::file_part("f", "a.txt", None, "a".repeat(60)),
+        ]),
+        options,
+    )
+    .await;
+
+    // The same limits are applied as parsing a live request
+    assert!(matches!(
+        result,
+        Err(MultipartFormDataError::DataLimitExceededError {
+            limit:  128,
+            source: DataLimitSource::Options,
+        })
+    ));
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use rocket::{
+    data::{Limits, ToByteUnit},
+    http::ContentType,
+    local::asynchronous::Client,
+    post, routes, Data,
+};
+use rocket_multipart_form_data::{
+    MultipartFormData, MultipartFormDataError, MultipartFormDataField, MultipartFormDataOptions,
+};
+
+#[post("/", data = "<data>")]
+async fn upload(content_type: &ContentType, limits: &Limits, data: Data<'_>) -> String {
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::file("f"),
+    ]);
+
+    options.temporary_dir = std::env::temp_dir();
+
+    match MultipartFormData::parse_limited(content_type, data, options, limits, None).await {
+        Ok(multipart_form_data) => {
+            let file = &multipart_form_data.files["f"][0];
+
+            format!("{} {:?}", file.size, file.file_name)
+        },
+        Err(MultipartFormDataError::DataLimitExceededError {
+            limit,
+            source,
+        }) => format!("{} {:?}", limit, source),
+        Err(err) => format!("error: {}", err),
+    }
+}
+
+async fn post(limit: u64) -> String {
+    let figment = rocket::Config::figment()
+        .merge(("limits", Limits::new().limit("data-form", limit.bytes())));
+
+    let client =
+        Client::tracked(rocket::custom(figment).mount("/", routes![upload])).await.unwrap();
+
+    client
+        .post("/")
+        .header(
+            ContentType::new("multipart", "form-data").with_params(("boundary", common::BOUNDARY)),
+        )
+        .body(common::body(&[common::file_part("f", "a.txt", Some("text/plain"), "hello")]))
+        .dispatch()
+        .await
+        .into_string()
+        .await
+        .unwrap()
+}
+
+#[rocket::async_test]
+async fn default_file_field_under_rocket_limit() {
+    // the default `size_limit` of a file field (8 MiB) is bigger than the default `data-form` limit (2 MiB)
+    assert_eq!("5 Some(\"a.txt\")", post(Limits::DATA_FORM.as_u64()).await);
+}
+
+#[rocket::async_test]
+async fn body_over_rocket_limit() {
+    assert_eq!("20 RocketLimits", post(20).await);
+}
@@ -0,0 +1,109 @@
+mod common;
+
+use std::{error::Error, path::Path};
+
+use rocket_multipart_form_data::{
+    mime, Derivative, FieldPostProcessor, MultipartFormDataError, MultipartFormDataField,
+    MultipartFormDataOptions,
+};
+
+/// A processor which derives an upper-case copy of a text file.
+struct Upper;
+
+impl FieldPostProcessor for Upper {
+    fn process(
+        &self,
+        source: &Path,
+        temporary_dir: &Path,
+    ) -> Result<Vec<Derivative>, Box<dyn Error + Send + Sync>> {
+        let text = std::fs::read_to_string(source)?;
+
+        let path =
+            temporary_dir.join(format!("{}-upper", source.file_name().unwrap().to_string_lossy()));
+
+        std::fs::write(&path, text.to_uppercase())?;
+
+        Ok(vec![Derivative {
+            name: "upper".to_string(),
+            content_type: Some(mime::TEXT_PLAIN),
+            path,
+        }])
+    }
+}
+
+/// A processor which always fails.
+struct Failing;
+
+impl FieldPostProcessor for Failing {
+    fn process(
+        &self,
+        _source: &Path,
+        _temporary_dir: &Path,
+    ) -> Result<Vec<Derivative>, Box<dyn Error + Send + Sync>> {
+        Err("unsupported format".into())
+    }
+}
+
+#[rocket::async_test]
+async fn derivatives_are_deleted_with_the_file() {
+    let dir = common::temporary_dir("post-processor");
+
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::file("f").post_process(Box::new(Upper)),
+    ]);
+
+    options.temporary_dir = dir.clone();
+
+    let multipart_form_data = common::parse(
+        "post-processor",
+        common::body(&[common::file_part("f", "a.txt", None, "hello")]),
+        options,
+    )
+    .await
+    .unwrap();
+
+    let file = &multipart_form_data.files["f"][0];
+
+    assert_eq!(1, file.derivatives.len());
+
+    let derivative = &file.derivatives[0];
+
+    assert_eq!("upper", derivative.name);
+    assert_eq!(Some(mime::TEXT_PLAIN), derivative.content_type);
+    assert_eq!("HELLO", std::fs::read_to_string(&derivative.path).unwrap());
+
+    drop(multipart_form_data);
+
+    assert_eq!(0, std::fs::read_dir(&dir).unwrap().count());
+}
+
+#[rocket::async_test]
+async fn failure_removes_the_file() {
+    let dir = common::temporary_dir("post-processor-failure");
+
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::file("f").post_process(Box::new(Failing)),
+    ]);
+
+    options.temporary_dir = dir.clone();
+
+    let result = common::parse(
+        "post-processor-failure",
+        common::body(&[common::file_part("f", "a.txt", None, "hello")]),
+        options,
+    )
+    .await;
+
+    match result {
+        Err(MultipartFormDataError::PostProcessError {
+            field,
+            error,
+        }) => {
+            assert_eq!("f", field.as_ref());
+            assert_eq!("unsupported format", error.to_string());
+        },
+        result => panic!("{:?}", result.map(|_| ())),
+    }
+
+    assert_eq!(0, std::fs::read_dir(&dir).unwrap().count());
+}
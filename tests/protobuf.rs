@@ -0,0 +1,32 @@
+#![cfg(feature = "prost")]
+
+mod common;
+
+use rocket_multipart_form_data::{
+    MultipartFormData, MultipartFormDataError, MultipartFormDataField, MultipartFormDataOptions,
+};
+
+async fn parse(name: &str, data: &[u8]) -> Result<MultipartFormData, MultipartFormDataError> {
+    // `u32` is the message of `google.protobuf.UInt32Value` in `prost`
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::raw("m").protobuf::<u32>(),
+    ]);
+
+    common::parse(
+        &format!("protobuf-{}", name),
+        common::body(&[common::text_part("m", data)]),
+        options,
+    )
+    .await
+}
+
+#[rocket::async_test]
+async fn decode_protobuf() {
+    let multipart_form_data = parse("valid", &[0x08, 0x07]).await.unwrap();
+
+    assert_eq!(multipart_form_data.raw["m"][0].decode_protobuf::<u32>().unwrap(), 7);
+
+    let err = parse("invalid", &[0x0F]).await.unwrap_err();
+
+    assert!(matches!(err, MultipartFormDataError::InvalidProtobufError { .. }));
+}
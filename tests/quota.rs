@@ -0,0 +1,109 @@
+mod common;
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use rocket::tokio::time;
+use rocket_multipart_form_data::{
+    BoxFuture, MultipartFormData, MultipartFormDataError, MultipartFormDataField,
+    MultipartFormDataOptions, Quota, QuotaProvider, Reservation,
+};
+
+/// A provider which grants at most `remaining` bytes, and the requested bytes if it can.
+struct Budget {
+    remaining: AtomicU64,
+}
+
+impl QuotaProvider for Budget {
+    fn check_and_reserve<'a>(
+        &'a self,
+        _identity: &'a str,
+        bytes: u64,
+    ) -> BoxFuture<'a, Option<Reservation>> {
+        let remaining = self.remaining.load(Ordering::SeqCst);
+        let bytes = bytes.min(remaining);
+
+        self.remaining.store(remaining - bytes, Ordering::SeqCst);
+
+        Box::pin(async move {
+            Some(Reservation {
+                bytes,
+            })
+        })
+    }
+
+    fn release<'a>(&'a self, _identity: &'a str, bytes: u64) -> BoxFuture<'a, ()> {
+        self.remaining.fetch_add(bytes, Ordering::SeqCst);
+
+        Box::pin(async {})
+    }
+}
+
+fn body(unknown_size: usize) -> Vec<u8> {
+    common::body(&[
+        common::text_part("unknown", "x".repeat(unknown_size)),
+        common::text_part("t", "hello"),
+    ])
+}
+
+async fn parse(
+    name: &str,
+    body: &[u8],
+    budget: &Arc<Budget>,
+) -> Result<MultipartFormData, MultipartFormDataError> {
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("t"),
+    ]);
+
+    let mut quota = Quota::new(budget.clone(), "user");
+
+    quota.reservation_size = 16;
+
+    options.quota = Some(quota);
+
+    // a zero grant must not make the parser wait for another one forever
+    time::timeout(Duration::from_secs(10), common::parse(&format!("quota-{}", name), body, options))
+        .await
+        .expect("parsing should not hang")
+}
+
+#[rocket::async_test]
+async fn zero_grant() {
+    let budget = Arc::new(Budget {
+        remaining: AtomicU64::new(0)
+    });
+
+    assert!(matches!(
+        parse("zero", &body(0), &budget).await,
+        Err(MultipartFormDataError::QuotaExceededError)
+    ));
+}
+
+#[rocket::async_test]
+async fn skipped_parts_are_charged() {
+    let body = body(4096);
+
+    // enough for the accepted field, but not for the unknown one
+    let budget = Arc::new(Budget {
+        remaining: AtomicU64::new(1024)
+    });
+
+    assert!(matches!(
+        parse("short", &body, &budget).await,
+        Err(MultipartFormDataError::QuotaExceededError)
+    ));
+
+    let budget = Arc::new(Budget {
+        remaining: AtomicU64::new(body.len() as u64)
+    });
+
+    let multipart_form_data = parse("enough", &body, &budget).await.unwrap();
+
+    assert_eq!(body.len() as u64, multipart_form_data.stats.bytes_read);
+    assert_eq!(0, budget.remaining.load(Ordering::SeqCst));
+}
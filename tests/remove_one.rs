@@ -0,0 +1,61 @@
+mod common;
+
+use rocket_multipart_form_data::{
+    MultipartFormData, MultipartFormDataField, MultipartFormDataOptions, Repetition,
+};
+
+async fn parse(name: &str) -> MultipartFormData {
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("t").repetition(Repetition::infinite()),
+        MultipartFormDataField::raw("r"),
+        MultipartFormDataField::file("f"),
+    ]);
+
+    options.temporary_dir = common::temporary_dir(name);
+
+    common::parse(
+        name,
+        common::body(&[
+            common::text_part("t", "a"),
+            common::text_part("r", "raw"),
+            common::text_part("t", "b"),
+            common::file_part("f", "a.txt", None, "file"),
+        ]),
+        options,
+    )
+    .await
+    .unwrap()
+}
+
+#[rocket::async_test]
+async fn remove_in_order() {
+    let mut multipart_form_data = parse("remove-one").await;
+
+    // The result maps are looked up by `&str`
+    assert_eq!(2, multipart_form_data.texts.get("t").unwrap().len());
+
+    assert_eq!("a", multipart_form_data.remove_one_text("t").unwrap().text);
+    assert_eq!("b", multipart_form_data.remove_one_text("t").unwrap().text);
+    assert!(multipart_form_data.remove_one_text("t").is_none());
+    assert!(!multipart_form_data.texts.contains_key("t"));
+
+    assert_eq!(b"raw", &multipart_form_data.remove_one_raw("r").unwrap().raw[..]);
+    assert!(multipart_form_data.raw.is_empty());
+
+    assert!(multipart_form_data.remove_one_text("unknown").is_none());
+}
+
+#[rocket::async_test]
+async fn removed_file_is_kept() {
+    let mut multipart_form_data = parse("remove-one-file").await;
+
+    let file = multipart_form_data.remove_one_file("f").unwrap();
+
+    assert!(multipart_form_data.files.is_empty());
+
+    drop(multipart_form_data);
+
+    assert_eq!("file", std::fs::read_to_string(&file.path).unwrap());
+
+    std::fs::remove_file(&file.path).unwrap();
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use rocket_multipart_form_data::{
+    MultipartFormDataField, MultipartFormDataOptions, OptionsValidationError, Repetition,
+    RepetitionError,
+};
+
+#[rocket::async_test]
+async fn fixed_zero_is_one() {
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("t").repetition(Repetition::fixed(0)),
+    ]);
+
+    assert_eq!(Ok(()), options.validate());
+
+    let multipart_form_data = common::parse(
+        "repetition-fixed-zero",
+        common::body(&[common::text_part("t", "a"), common::text_part("t", "b")]),
+        options,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(1, multipart_form_data.texts["t"].len());
+}
+
+#[test]
+fn try_fixed_zero() {
+    assert_eq!(Err(RepetitionError::ZeroCountError), Repetition::try_fixed(0).map(|_| ()));
+    assert!(Repetition::try_fixed(2).is_ok());
+}
+
+#[test]
+fn at_most_zero() {
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("t").repetition(Repetition::at_most(0)),
+    ]);
+
+    assert_eq!(
+        Err(OptionsValidationError::ZeroRepetitionError("t".to_string())),
+        options.validate()
+    );
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use std::sync::Arc;
+
+use rocket_multipart_form_data::{
+    MultipartFormData, MultipartFormDataError, MultipartFormDataField, MultipartFormDataOptions,
+    ParseWarning, Repetition,
+};
+
+async fn parse(name: &str, values: &[&str]) -> Result<MultipartFormData, MultipartFormDataError> {
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("t")
+            .size_limit(8)
+            .repetition(Repetition::infinite_with_budget(10, 3)),
+    ]);
+
+    let parts: Vec<Vec<u8>> = values.iter().map(|v| common::text_part("t", v)).collect();
+
+    common::parse(name, common::body(&parts), options).await
+}
+
+#[rocket::async_test]
+async fn within_budget() {
+    let multipart_form_data = parse("repetition-budget", &["abcd", "efgh", "ij"]).await.unwrap();
+
+    assert_eq!(
+        ["abcd", "efgh", "ij"],
+        multipart_form_data.texts["t"].iter().map(|f| f.text.as_str()).collect::<Vec<_>>()[..]
+    );
+}
+
+#[rocket::async_test]
+async fn count() {
+    let multipart_form_data =
+        parse("repetition-budget-count", &["a", "b", "c", "d"]).await.unwrap();
+
+    assert_eq!(3, multipart_form_data.texts["t"].len());
+    assert_eq!(
+        vec![ParseWarning::ExtraRepetitionDropped(Arc::from("t"))],
+        multipart_form_data.warnings
+    );
+}
+
+#[rocket::async_test]
+async fn total_bytes() {
+    // Every occurrence fits the size limit, but not the rest of the budget
+    let result = parse("repetition-budget-bytes", &["abcdefgh", "ijk"]).await;
+
+    assert!(
+        matches!(result, Err(MultipartFormDataError::DataTooLargeError(field)) if field.as_ref() == "t")
+    );
+}
@@ -0,0 +1,117 @@
+#![cfg(feature = "sanitize")]
+
+mod common;
+
+use rocket_multipart_form_data::{
+    ActiveContentPolicy, MultipartFormData, MultipartFormDataError, MultipartFormDataField,
+    MultipartFormDataOptions,
+};
+
+async fn parse(
+    name: &str,
+    max_in_memory_bytes: u64,
+) -> Result<MultipartFormData, MultipartFormDataError> {
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::file("f").active_content(ActiveContentPolicy::Strip),
+    ]);
+
+    options.temporary_dir = common::temporary_dir(&format!("sanitize-{}", name));
+    options.max_in_memory_bytes = Some(max_in_memory_bytes);
+
+    common::parse(
+        &format!("sanitize-{}", name),
+        common::body(&[common::file_part(
+            "f",
+            "a.svg",
+            Some("image/svg+xml"),
+            format!("<svg>{}</svg>", "x".repeat(4096)),
+        )]),
+        options,
+    )
+    .await
+}
+
+#[rocket::async_test]
+async fn markup_counts_against_memory_limit() {
+    assert!(matches!(
+        parse("over", 1024).await,
+        Err(MultipartFormDataError::MemoryLimitExceededError)
+    ));
+
+    let multipart_form_data = parse("under", 8192).await.unwrap();
+
+    assert_eq!(4107, multipart_form_data.files["f"][0].size);
+}
+
+async fn sanitize(
+    name: &str,
+    markup: &str,
+    policy: ActiveContentPolicy,
+) -> Result<Vec<u8>, MultipartFormDataError> {
+    let dir = common::temporary_dir(&format!("sanitize-{}", name));
+
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::file("f").active_content(policy),
+    ]);
+
+    options.temporary_dir = dir.clone();
+
+    let result = common::parse(
+        &format!("sanitize-{}", name),
+        common::body(&[common::file_part("f", "a.svg", Some("image/svg+xml"), markup)]),
+        options,
+    )
+    .await
+    .map(|multipart_form_data| std::fs::read(&multipart_form_data.files["f"][0].path).unwrap());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    result
+}
+
+const BYPASSES: [&str; 8] = [
+    "<svg/onload=alert(1)>",
+    "<img/src=x/onerror=alert(1)>",
+    "<svg><a/href=\"javascript:alert(1)\">x</a></svg>",
+    "<svg x=\"1\"/onload=alert(1)/>",
+    "<svg \"x\" onload=alert(1)>",
+    "<svg><animate attributeName=\"href\" values=\"javascript:alert(1)\"/></svg>",
+    "<svg><set attributeName=\"xlink:href\" to=\"javascript:alert(1)\"></set></svg>",
+    "<svg><animate attributeName=\"onbegin\" from=\"x\" to=\"alert(1)\"/></svg>",
+];
+
+#[rocket::async_test]
+async fn bypasses_are_rejected() {
+    for (i, markup) in BYPASSES.iter().enumerate() {
+        assert!(
+            matches!(
+                sanitize(&format!("reject-{}", i), markup, ActiveContentPolicy::Reject).await,
+                Err(MultipartFormDataError::ActiveContentError(_))
+            ),
+            "{}",
+            markup
+        );
+    }
+}
+
+#[rocket::async_test]
+async fn bypasses_are_stripped() {
+    for (i, markup) in BYPASSES.iter().enumerate() {
+        let stripped =
+            sanitize(&format!("strip-{}", i), markup, ActiveContentPolicy::Strip).await.unwrap();
+        let stripped = String::from_utf8(stripped).unwrap().to_ascii_lowercase();
+
+        assert!(!stripped.contains("alert"), "{} => {}", markup, stripped);
+    }
+}
+
+#[rocket::async_test]
+async fn safe_markup_is_kept() {
+    let markup = "<svg width=\"10\"/><svg><animate attributeName=\"opacity\" from=\"0\" \
+                  to=\"1\"/><a href=\"#a\">x</a></svg>";
+
+    assert_eq!(
+        markup.as_bytes(),
+        sanitize("safe", markup, ActiveContentPolicy::Strip).await.unwrap()
+    );
+}
@@ -0,0 +1,62 @@
+#![cfg(feature = "serde")]
+
+mod common;
+
+use rocket_multipart_form_data::{MultipartFormDataField, MultipartFormDataOptions};
+use serde_json::json;
+
+#[rocket::async_test]
+async fn fields() {
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::file("f"),
+        MultipartFormDataField::raw("r"),
+        MultipartFormDataField::text("t"),
+    ]);
+
+    options.temporary_dir = common::temporary_dir("serialize");
+
+    let multipart_form_data = common::parse(
+        "serialize",
+        common::body(&[
+            common::file_part("f", "a.txt", Some("text/plain"), "hello"),
+            common::file_part("r", "b.bin", Some("application/octet-stream"), [0u8, 255]),
+            common::text_part("t", "text"),
+        ]),
+        options,
+    )
+    .await
+    .unwrap();
+
+    let file = &multipart_form_data.files["f"][0];
+
+    assert_eq!(
+        json!({
+            "content_type": "text/plain",
+            "file_name": "a.txt",
+            "path": file.path,
+            "size": 5,
+        }),
+        serde_json::to_value(file).unwrap()
+    );
+
+    let raw = &multipart_form_data.raw["r"][0];
+
+    assert_eq!(
+        json!({
+            "content_type": "application/octet-stream",
+            "file_name": "b.bin",
+            "raw": [0, 255],
+        }),
+        serde_json::to_value(raw).unwrap()
+    );
+    assert_eq!(json!("AP8="), serde_json::to_value(raw.as_base64()).unwrap()["raw"]);
+
+    assert_eq!(
+        json!({
+            "content_type": null,
+            "file_name": null,
+            "text": "text",
+        }),
+        serde_json::to_value(&multipart_form_data.texts["t"][0]).unwrap()
+    );
+}
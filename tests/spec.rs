@@ -0,0 +1,105 @@
+mod common;
+
+use rocket_multipart_form_data::{
+    mime, MultipartFormDataError, MultipartFormDataOptions, MultipartFormDataType, SpecError,
+};
+
+#[test]
+fn fields() {
+    let options = MultipartFormDataOptions::from_spec(
+        "photo: file image/*; !image/svg+xml <=32MiB, name: text, , data: bytes <=10KB, agree: \
+         checkbox, tags: text x*",
+    )
+    .unwrap();
+
+    let fields = &options.allowed_fields;
+
+    assert_eq!(
+        ["photo", "name", "data", "agree", "tags"],
+        fields.iter().map(|f| f.field_name).collect::<Vec<_>>()[..]
+    );
+    assert_eq!(
+        [
+            MultipartFormDataType::File,
+            MultipartFormDataType::Text,
+            MultipartFormDataType::Raw,
+            MultipartFormDataType::Checkbox,
+            MultipartFormDataType::Text
+        ],
+        fields.iter().map(|f| f.typ).collect::<Vec<_>>()[..]
+    );
+
+    assert_eq!(Some(vec![mime::IMAGE_STAR]), fields[0].content_type);
+    assert_eq!(
+        ["image/svg+xml"],
+        fields[0].denied_content_types.iter().map(|m| m.as_ref()).collect::<Vec<_>>()[..]
+    );
+    assert_eq!(32 * 1024 * 1024, fields[0].size_limit);
+    assert_eq!(10_000, fields[2].size_limit);
+}
+
+#[rocket::async_test]
+async fn repetition() {
+    let options = MultipartFormDataOptions::from_spec("email: text x2").unwrap();
+
+    let multipart_form_data = common::parse(
+        "spec-repetition",
+        common::body(&[
+            common::text_part("email", "a"),
+            common::text_part("email", "b"),
+            common::text_part("email", "c"),
+        ]),
+        options,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        ["a", "b"],
+        multipart_form_data.texts["email"].iter().map(|f| f.text.as_str()).collect::<Vec<_>>()[..]
+    );
+}
+
+#[rocket::async_test]
+async fn denied_content_type() {
+    let options =
+        MultipartFormDataOptions::from_spec("photo: file image/* !image/svg+xml").unwrap();
+
+    let result = common::parse(
+        "spec-denied",
+        common::body(&[common::file_part("photo", "a.svg", Some("image/svg+xml"), "<svg/>")]),
+        options,
+    )
+    .await;
+
+    assert!(
+        matches!(result, Err(MultipartFormDataError::DataTypeError(field)) if field.as_ref() == "photo")
+    );
+}
+
+#[test]
+fn errors() {
+    for (spec, fragment, message) in [
+        ("photo", "photo", "The field type is missing."),
+        ("photo:", "photo:", "The field type is missing."),
+        (": text", ": text", "The field name is empty."),
+        ("photo: image", "image", "The field type is unknown."),
+        ("photo: file <=32PiB", "<=32PiB", "Invalid size limit."),
+        ("photo: file <=99999999999GiB", "<=99999999999GiB", "Invalid size limit."),
+        ("email: text x0", "x0", "Invalid repetition."),
+        ("email: text xx", "xx", "Invalid repetition."),
+        ("photo: file /png", "/png", "Invalid content type."),
+        ("photo: file !image", "!image", "Invalid content type."),
+        ("photo: file large", "large", "The modifier is unknown."),
+    ] {
+        assert_eq!(
+            Err(SpecError {
+                fragment: fragment.to_string(),
+                message,
+            }),
+            MultipartFormDataOptions::from_spec(spec).map(|_| ()),
+            "{}",
+            spec
+        );
+    }
+}
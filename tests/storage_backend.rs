@@ -0,0 +1,121 @@
+mod common;
+
+use std::{
+    collections::HashMap,
+    io,
+    sync::{Arc, Mutex},
+};
+
+use rocket_multipart_form_data::{
+    mime, BoxFuture, MultipartFormDataError, MultipartFormDataField, MultipartFormDataOptions,
+    StorageBackend, StorageWriter,
+};
+
+/// A storage backend which keeps the files in memory.
+#[derive(Default)]
+struct MemoryStorage {
+    files: Arc<Mutex<HashMap<String, Vec<u8>>>>,
+}
+
+struct MemoryWriter {
+    files: Arc<Mutex<HashMap<String, Vec<u8>>>>,
+    id:    String,
+    data:  Vec<u8>,
+}
+
+impl StorageBackend for MemoryStorage {
+    fn create<'a>(
+        &'a self,
+        field_name: &'a str,
+    ) -> BoxFuture<'a, Result<Box<dyn StorageWriter>, io::Error>> {
+        Box::pin(async move {
+            let id = format!("{}-{}", field_name, self.files.lock().unwrap().len());
+
+            Ok(Box::new(MemoryWriter {
+                files: self.files.clone(),
+                id,
+                data: Vec::new(),
+            }) as Box<dyn StorageWriter>)
+        })
+    }
+
+    fn remove<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), io::Error>> {
+        Box::pin(async move {
+            self.files.lock().unwrap().remove(id);
+
+            Ok(())
+        })
+    }
+}
+
+impl StorageWriter for MemoryWriter {
+    fn write<'a>(&'a mut self, data: &'a [u8]) -> BoxFuture<'a, Result<(), io::Error>> {
+        self.data.extend_from_slice(data);
+
+        Box::pin(async { Ok(()) })
+    }
+
+    fn finish(self: Box<Self>) -> BoxFuture<'static, Result<String, io::Error>> {
+        Box::pin(async move {
+            self.files.lock().unwrap().insert(self.id.clone(), self.data);
+
+            Ok(self.id)
+        })
+    }
+}
+
+fn options(storage: Arc<MemoryStorage>) -> MultipartFormDataOptions<'static> {
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::file("f"),
+        MultipartFormDataField::number::<u8>("n"),
+    ]);
+
+    options.storage = Some(storage);
+
+    options
+}
+
+#[rocket::async_test]
+async fn stored_files() {
+    let storage = Arc::new(MemoryStorage::default());
+
+    let multipart_form_data = common::parse(
+        "storage-backend",
+        common::body(&[
+            common::file_part("f", "a.txt", Some("text/plain"), "hello"),
+            common::text_part("n", "1"),
+        ]),
+        options(storage.clone()),
+    )
+    .await
+    .unwrap();
+
+    // Files are not written to the temporary directory
+    assert!(multipart_form_data.files.is_empty());
+
+    let stored = &multipart_form_data.stored["f"][0];
+
+    assert_eq!("f-0", stored.id);
+    assert_eq!(5, stored.size);
+    assert_eq!(Some("a.txt"), stored.file_name.as_deref());
+    assert_eq!(Some(mime::TEXT_PLAIN), stored.content_type);
+    assert_eq!(b"hello", &storage.files.lock().unwrap()["f-0"][..]);
+}
+
+#[rocket::async_test]
+async fn removed_on_failure() {
+    let storage = Arc::new(MemoryStorage::default());
+
+    let result = common::parse(
+        "storage-backend-failure",
+        common::body(&[
+            common::file_part("f", "a.txt", None, "hello"),
+            common::text_part("n", "x"),
+        ]),
+        options(storage.clone()),
+    )
+    .await;
+
+    assert!(matches!(result, Err(MultipartFormDataError::InvalidNumberError { .. })));
+    assert!(storage.files.lock().unwrap().is_empty());
+}
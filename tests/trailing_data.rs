@@ -0,0 +1,80 @@
+mod common;
+
+use std::{
+    io,
+    sync::{Arc, Mutex},
+};
+
+use rocket_multipart_form_data::{
+    BoxFuture, MultipartFormDataError, MultipartFormDataField, MultipartFormDataOptions,
+    StorageBackend, StorageWriter,
+};
+
+/// A storage backend which keeps the ids of the stored files.
+#[derive(Default)]
+struct Ids(Arc<Mutex<Vec<String>>>);
+
+struct Writer(Arc<Mutex<Vec<String>>>);
+
+impl StorageBackend for Ids {
+    fn create<'a>(
+        &'a self,
+        _field_name: &'a str,
+    ) -> BoxFuture<'a, Result<Box<dyn StorageWriter>, io::Error>> {
+        let writer: Box<dyn StorageWriter> = Box::new(Writer(self.0.clone()));
+
+        Box::pin(async move { Ok(writer) })
+    }
+
+    fn remove<'a>(&'a self, id: &'a str) -> BoxFuture<'a, Result<(), io::Error>> {
+        self.0.lock().unwrap().retain(|stored| stored != id);
+
+        Box::pin(async { Ok(()) })
+    }
+}
+
+impl StorageWriter for Writer {
+    fn write<'a>(&'a mut self, _data: &'a [u8]) -> BoxFuture<'a, Result<(), io::Error>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn finish(self: Box<Self>) -> BoxFuture<'static, Result<String, io::Error>> {
+        Box::pin(async move {
+            let mut ids = self.0.lock().unwrap();
+
+            let id = format!("id{}", ids.len());
+
+            ids.push(id.clone());
+
+            Ok(id)
+        })
+    }
+}
+
+#[rocket::async_test]
+async fn trailing_data_removes_stored_files() {
+    let tail = common::body(&[common::text_part("f", "x")]);
+
+    let mut body = common::body(&[common::file_part("f", "a", None, "hello")]);
+
+    body.extend_from_slice(&tail);
+
+    let ids = Ids::default();
+    let stored = ids.0.clone();
+
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::file("f"),
+    ]);
+
+    options.storage = Some(Arc::new(ids));
+    options.reject_trailing_data = true;
+
+    let result = common::parse("trailing-data", body, options).await;
+
+    // The line break after the closing boundary is counted as well
+    assert!(matches!(
+        result,
+        Err(MultipartFormDataError::TrailingDataError(bytes)) if bytes == tail.len() as u64 + 2
+    ));
+    assert!(stored.lock().unwrap().is_empty());
+}
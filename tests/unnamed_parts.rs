@@ -0,0 +1,57 @@
+mod common;
+
+use rocket_multipart_form_data::{
+    MultipartFormData, MultipartFormDataError, MultipartFormDataField, MultipartFormDataOptions,
+    ParseWarning, Repetition, UnnamedPartPolicy,
+};
+
+async fn parse(
+    name: &str,
+    unnamed_parts: UnnamedPartPolicy<'static>,
+) -> Result<MultipartFormData, MultipartFormDataError> {
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("t"),
+        MultipartFormDataField::file("unnamed").repetition(Repetition::infinite()),
+    ]);
+
+    options.temporary_dir = common::temporary_dir(name);
+    options.unnamed_parts = unnamed_parts;
+
+    common::parse(
+        name,
+        common::body(&[
+            common::text_part("t", "text"),
+            common::part("filename=\"a.txt\"", None, "hello"),
+        ]),
+        options,
+    )
+    .await
+}
+
+#[rocket::async_test]
+async fn ignore() {
+    let multipart_form_data = parse("unnamed-ignore", UnnamedPartPolicy::Ignore).await.unwrap();
+
+    assert!(multipart_form_data.files.is_empty());
+    assert_eq!(vec![ParseWarning::UnnamedPart], multipart_form_data.warnings);
+}
+
+#[rocket::async_test]
+async fn error() {
+    assert!(matches!(
+        parse("unnamed-error", UnnamedPartPolicy::Error).await,
+        Err(MultipartFormDataError::UnnamedPartError)
+    ));
+}
+
+#[rocket::async_test]
+async fn collect_as() {
+    let multipart_form_data =
+        parse("unnamed-collect", UnnamedPartPolicy::CollectAs("unnamed")).await.unwrap();
+
+    let file = &multipart_form_data.files["unnamed"][0];
+
+    assert_eq!(Some("a.txt"), file.file_name.as_deref());
+    assert_eq!("hello", std::fs::read_to_string(&file.path).unwrap());
+    assert!(multipart_form_data.warnings.is_empty());
+}
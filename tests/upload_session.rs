@@ -0,0 +1,151 @@
+#![cfg(feature = "serde")]
+
+mod common;
+
+use std::path::Path;
+
+use rocket_multipart_form_data::{
+    MultipartFormDataOptions, UploadSession, UploadSessionError, UploadSessionStatus,
+};
+
+async fn send(
+    upload_session: &UploadSession,
+    dir: &Path,
+    session_id: &str,
+    chunk_index: u64,
+    data: &str,
+) -> Result<UploadSessionStatus, UploadSessionError> {
+    let body = common::body(&[
+        common::text_part("session_id", session_id),
+        common::text_part("chunk_index", chunk_index.to_string()),
+        common::text_part("chunk_count", "2"),
+        common::file_part("chunk", "f.bin", None, data),
+    ]);
+
+    let mut options =
+        MultipartFormDataOptions::with_multipart_form_data_fields(UploadSession::fields(1024));
+
+    options.temporary_dir = dir.to_path_buf();
+
+    let mut multipart_form_data =
+        common::parse(&format!("upload-session-{}-{}", session_id, chunk_index), body, options)
+            .await
+            .unwrap();
+
+    upload_session.accept(&mut multipart_form_data).await
+}
+
+#[rocket::async_test]
+async fn parallel_sessions() {
+    let dir = std::env::temp_dir().join("rocket-multipart-form-data-upload-session-parallel");
+
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let upload_session = UploadSession::new(&dir);
+
+    rocket::futures::future::join_all(
+        ["a", "b", "c"].iter().map(|session_id| send(&upload_session, &dir, session_id, 0, "x")),
+    )
+    .await;
+
+    let statuses = rocket::futures::future::join_all(
+        ["a", "b", "c"].iter().map(|session_id| send(&upload_session, &dir, session_id, 1, "y")),
+    )
+    .await;
+
+    for status in statuses {
+        match status.unwrap() {
+            UploadSessionStatus::Complete(file) => {
+                assert_eq!(std::fs::read_to_string(&file.path).unwrap(), "xy")
+            },
+            status => panic!("{:?}", status),
+        }
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[rocket::async_test]
+async fn recover_skips_corrupt_sessions() {
+    let dir = std::env::temp_dir().join("rocket-multipart-form-data-upload-session-recover");
+
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let upload_session = UploadSession::new(&dir);
+
+    send(&upload_session, &dir, "good", 0, "x").await.unwrap();
+    send(&upload_session, &dir, "bad", 0, "x").await.unwrap();
+
+    drop(upload_session);
+
+    let sessions_dir = dir.join(".upload-sessions");
+
+    std::fs::write(sessions_dir.join("bad").join("manifest.json"), "{not json").unwrap();
+
+    let upload_session = UploadSession::recover(&dir).unwrap();
+
+    assert_eq!(upload_session.received("good"), Some(vec![0]));
+    assert_eq!(upload_session.received("bad"), None);
+    assert!(!sessions_dir.join("bad").exists());
+
+    match send(&upload_session, &dir, "good", 1, "y").await.unwrap() {
+        UploadSessionStatus::Complete(file) => {
+            assert_eq!(std::fs::read_to_string(&file.path).unwrap(), "xy")
+        },
+        status => panic!("{:?}", status),
+    }
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[rocket::async_test]
+async fn assembled_file_does_not_overwrite() {
+    let dir = std::env::temp_dir().join("rocket-multipart-form-data-upload-session-overwrite");
+
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    std::fs::write(dir.join("a"), "keep").unwrap();
+
+    let upload_session = UploadSession::new(&dir);
+
+    send(&upload_session, &dir, "a", 0, "x").await.unwrap();
+
+    match send(&upload_session, &dir, "a", 1, "y").await.unwrap() {
+        UploadSessionStatus::Complete(file) => {
+            assert_ne!(dir.join("a"), file.path);
+            assert_eq!(std::fs::read_to_string(&file.path).unwrap(), "xy");
+        },
+        status => panic!("{:?}", status),
+    }
+
+    assert_eq!(std::fs::read_to_string(dir.join("a")).unwrap(), "keep");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[rocket::async_test]
+async fn session_too_large() {
+    let dir = std::env::temp_dir().join("rocket-multipart-form-data-upload-session-too-large");
+
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let upload_session = UploadSession::new(&dir).max_size(5);
+
+    send(&upload_session, &dir, "a", 0, "xyz").await.unwrap();
+
+    // A chunk which is sent again replaces the old one
+    send(&upload_session, &dir, "a", 0, "xyz").await.unwrap();
+
+    assert!(matches!(
+        send(&upload_session, &dir, "a", 1, "xyz").await,
+        Err(UploadSessionError::SessionTooLarge)
+    ));
+
+    assert_eq!(Some(vec![0]), upload_session.received("a"));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
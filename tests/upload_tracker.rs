@@ -0,0 +1,123 @@
+mod common;
+
+use rocket::{
+    http::{ContentType, MediaType, Status},
+    local::asynchronous::Client,
+    post, routes, Data, State,
+};
+use rocket_multipart_form_data::{
+    MultipartFormData, MultipartFormDataField, MultipartFormDataOptions, UploadTrackerState,
+};
+
+#[post("/upload/<tracker_id>", data = "<data>")]
+async fn upload(
+    content_type: &ContentType,
+    data: Data<'_>,
+    tracker: &State<UploadTrackerState>,
+    tracker_id: &str,
+) -> Status {
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("t"),
+    ]);
+
+    match MultipartFormData::parse_tracked(content_type, data, options, tracker, tracker_id).await {
+        Ok(_) => Status::Ok,
+        Err(_) => Status::BadRequest,
+    }
+}
+
+async fn client(tracker: &UploadTrackerState) -> Client {
+    Client::tracked(
+        rocket::build()
+            .manage(tracker.clone())
+            .mount("/", routes![upload])
+            .mount("/uploads", UploadTrackerState::routes()),
+    )
+    .await
+    .unwrap()
+}
+
+async fn post(client: &Client, tracker_id: &str, body: Vec<u8>) -> Status {
+    client
+        .post(format!("/upload/{}", tracker_id))
+        .header(ContentType(
+            MediaType::new("multipart", "form-data").with_params(("boundary", common::BOUNDARY)),
+        ))
+        .body(body)
+        .dispatch()
+        .await
+        .status()
+}
+
+#[rocket::async_test]
+async fn progress() {
+    let tracker = UploadTrackerState::new();
+    let client = client(&tracker).await;
+
+    let receiver = tracker.subscribe("a");
+
+    let body = common::body(&[common::text_part("t", "hello")]);
+    let length = body.len() as u64;
+
+    assert_eq!(Status::Ok, post(&client, "a", body).await);
+
+    let progress = *receiver.borrow();
+
+    assert_eq!(length, progress.bytes_read);
+    assert!(progress.finished);
+    assert!(!progress.failed);
+    assert_eq!(Some(progress), tracker.progress("a"));
+
+    // The progress is not kept if nobody is subscribing to it
+    assert_eq!(Status::Ok, post(&client, "b", common::body(&[common::text_part("t", "b")])).await);
+    assert_eq!(None, tracker.progress("b"));
+}
+
+#[rocket::async_test]
+async fn failure() {
+    let tracker = UploadTrackerState::new();
+    let client = client(&tracker).await;
+
+    let receiver = tracker.subscribe("c");
+
+    assert_eq!(Status::BadRequest, post(&client, "c", b"--XB\r\nbroken".to_vec()).await);
+
+    let progress = *receiver.borrow();
+
+    assert!(progress.finished);
+    assert!(progress.failed);
+}
+
+#[rocket::async_test]
+async fn events() {
+    let tracker = UploadTrackerState::new();
+    let client = client(&tracker).await;
+
+    let receiver = tracker.subscribe("d");
+
+    let body = common::body(&[common::text_part("t", "hello")]);
+    let length = body.len();
+
+    assert_eq!(Status::Ok, post(&client, "d", body).await);
+
+    let response = client.get("/uploads/progress/d").dispatch().await;
+
+    assert_eq!(Some(ContentType::EventStream), response.content_type());
+    let events = response.into_string().await.unwrap();
+
+    // The stream ends with the finished progress
+    assert!(events.starts_with("event:progress\n"), "{}", events);
+    assert!(
+        events.ends_with(&format!(
+            "data:{{\"bytes_read\":{},\"finished\":true,\"failed\":false}}\n\n",
+            length
+        )),
+        "{}",
+        events
+    );
+
+    // The progress is removed after the stream ends
+    assert_eq!(None, tracker.progress("d"));
+
+    drop(receiver);
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use rocket_multipart_form_data::{
+    MultipartFormDataError, MultipartFormDataField, MultipartFormDataOptions,
+    OptionsValidationError,
+};
+
+#[test]
+fn valid_options() {
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::file("f"),
+        MultipartFormDataField::text("t"),
+        MultipartFormDataField::text("t"),
+    ]);
+
+    assert_eq!(Ok(()), options.validate());
+}
+
+#[test]
+fn size_limit_too_large() {
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("t").size_limit(1024),
+        MultipartFormDataField::file("f").size_limit(4096),
+    ]);
+
+    options.max_data_bytes = 2048;
+
+    assert_eq!(
+        Err(OptionsValidationError::SizeLimitTooLargeError("f".to_string())),
+        options.validate()
+    );
+}
+
+#[test]
+fn conflicting_types() {
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("a"),
+        MultipartFormDataField::file("a"),
+    ]);
+
+    assert_eq!(
+        Err(OptionsValidationError::ConflictingTypesError("a".to_string())),
+        options.validate()
+    );
+}
+
+#[test]
+fn missing_temporary_directory() {
+    let dir = std::env::temp_dir().join("rocket-multipart-form-data-validate-missing");
+
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("t"),
+    ]);
+
+    options.temporary_dir = dir.clone();
+
+    // The temporary directory is only needed by file fields
+    assert_eq!(Ok(()), options.validate());
+
+    options.allowed_fields.push(MultipartFormDataField::file("f"));
+
+    assert_eq!(Err(OptionsValidationError::TemporaryDirectoryError(dir)), options.validate());
+}
+
+#[rocket::async_test]
+async fn checked_before_parsing() {
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::file("f"),
+    ]);
+
+    options.temporary_dir = std::env::temp_dir().join("rocket-multipart-form-data-validate-parse");
+
+    let _ = std::fs::remove_dir_all(&options.temporary_dir);
+
+    let result = common::parse(
+        "validate-parse",
+        common::body(&[common::file_part("f", "a.txt", None, "hello")]),
+        options,
+    )
+    .await;
+
+    assert!(matches!(
+        result,
+        Err(MultipartFormDataError::OptionsError(OptionsValidationError::TemporaryDirectoryError(
+            _
+        )))
+    ));
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use std::sync::Arc;
+
+use rocket_multipart_form_data::{
+    MultipartFormDataField, MultipartFormDataOptions, ParseWarning, Repetition,
+};
+
+#[rocket::async_test]
+async fn anomalies() {
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("t").repetition(Repetition::fixed(1)),
+    ]);
+
+    let multipart_form_data = common::parse(
+        "warnings",
+        common::body(&[
+            common::text_part("t", "a"),
+            common::text_part("u", "unknown"),
+            common::part("filename=\"a.txt\"", None, "unnamed"),
+            common::text_part("t", "b"),
+        ]),
+        options,
+    )
+    .await
+    .unwrap();
+
+    assert_eq!("a", multipart_form_data.texts["t"][0].text);
+    assert_eq!(
+        vec![
+            ParseWarning::UnknownField("u".to_string()),
+            ParseWarning::UnnamedPart,
+            ParseWarning::ExtraRepetitionDropped(Arc::from("t")),
+        ],
+        multipart_form_data.warnings
+    );
+}
+
+#[rocket::async_test]
+async fn no_anomalies() {
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::text("t"),
+    ]);
+
+    let multipart_form_data =
+        common::parse("warnings-none", common::body(&[common::text_part("t", "a")]), options)
+            .await
+            .unwrap();
+
+    assert!(multipart_form_data.warnings.is_empty());
+}
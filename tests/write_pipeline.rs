@@ -0,0 +1,65 @@
+mod common;
+
+use rocket_multipart_form_data::{
+    MultipartFormDataError, MultipartFormDataField, MultipartFormDataOptions, Repetition,
+};
+
+fn options(name: &str, write_pipeline_depth: usize) -> MultipartFormDataOptions<'static> {
+    let mut options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::file("f").size_limit(100_000).repetition(Repetition::infinite()),
+    ]);
+
+    options.temporary_dir = common::temporary_dir(name);
+    options.read_chunk_size = 512;
+    options.write_pipeline_depth = write_pipeline_depth;
+
+    options
+}
+
+#[rocket::async_test]
+async fn pipelined_files() {
+    let a = "a".repeat(50_000);
+    let b = "b".repeat(30_000);
+
+    for depth in [1, 4] {
+        let name = format!("write-pipeline-{}", depth);
+
+        let multipart_form_data = common::parse(
+            &name,
+            common::body(&[
+                common::file_part("f", "a.txt", None, &a),
+                common::file_part("f", "b.txt", None, &b),
+                common::file_part("f", "c.txt", None, ""),
+            ]),
+            options(&name, depth),
+        )
+        .await
+        .unwrap();
+
+        let files = &multipart_form_data.files["f"];
+
+        assert_eq!(a, std::fs::read_to_string(&files[0].path).unwrap());
+        assert_eq!(b, std::fs::read_to_string(&files[1].path).unwrap());
+        assert_eq!("", std::fs::read_to_string(&files[2].path).unwrap());
+    }
+}
+
+#[rocket::async_test]
+async fn pipelined_file_too_large() {
+    let options = options("write-pipeline-too-large", 2);
+
+    let dir = options.temporary_dir.clone();
+
+    let result = common::parse(
+        "write-pipeline-too-large",
+        common::body(&[common::file_part("f", "a.txt", None, "a".repeat(100_001))]),
+        options,
+    )
+    .await;
+
+    assert!(
+        matches!(result, Err(MultipartFormDataError::DataTooLargeError(field)) if field.as_ref() == "f")
+    );
+
+    assert_eq!(0, std::fs::read_dir(dir).unwrap().count());
+}
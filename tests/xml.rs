@@ -0,0 +1,48 @@
+#![cfg(feature = "xml")]
+
+mod common;
+
+use rocket_multipart_form_data::{
+    MultipartFormData, MultipartFormDataError, MultipartFormDataField, MultipartFormDataOptions,
+};
+
+async fn parse(name: &str, xml: &str) -> Result<MultipartFormData, MultipartFormDataError> {
+    let options = MultipartFormDataOptions::with_multipart_form_data_fields(vec![
+        MultipartFormDataField::xml("m"),
+    ]);
+
+    common::parse(&format!("xml-{}", name), common::body(&[common::text_part("m", xml)]), options)
+        .await
+}
+
+#[rocket::async_test]
+async fn parse_document() {
+    let multipart_form_data = parse(
+        "valid",
+        "<?xml version=\"1.0\"?>\n<!-- c -->\n<m v=\"&quot;2&quot;\">A &amp; B \
+         &#x41;<![CDATA[<c>]]><e/></m>\n",
+    )
+    .await
+    .unwrap();
+
+    let root = &multipart_form_data.xml["m"][0].root;
+
+    assert_eq!(root.name, "m");
+    assert_eq!(root.attribute("v"), Some("\"2\""));
+    assert_eq!(root.text(), "A & B A<c>");
+    assert!(root.child("e").is_some());
+}
+
+#[rocket::async_test]
+async fn reject_entities() {
+    for (name, xml) in [
+        ("doctype", "<!DOCTYPE x [<!ENTITY e SYSTEM \"file:///etc/passwd\">]><x>&e;</x>"),
+        ("entity", "<x>&e;</x>"),
+        ("mismatch", "<x><y></x>"),
+        ("roots", "<x/><y/>"),
+    ] {
+        let err = parse(name, xml).await.unwrap_err();
+
+        assert!(matches!(err, MultipartFormDataError::InvalidXmlError { .. }), "{}", name);
+    }
+}